@@ -0,0 +1,21 @@
+DROP TABLE metadata_state;
+DROP TABLE metadata_log;
+DROP TABLE scrub_report;
+DROP TABLE placement_moves;
+DROP TABLE placement_ring;
+DROP TABLE replication_tasks;
+DROP TABLE nodes;
+DROP TABLE upload_parts;
+DROP TABLE uploads;
+DROP TABLE access_keys;
+DROP TABLE object_metadata;
+DROP TABLE objects;
+DROP TABLE boxes;
+DROP TABLE init_state;
+DROP TABLE sessions;
+DROP TABLE role_permissions;
+DROP TABLE permissions;
+DROP TABLE role_users;
+DROP TABLE default_role;
+DROP TABLE roles;
+DROP TABLE users;
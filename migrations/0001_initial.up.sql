@@ -0,0 +1,181 @@
+-- Timestamps are RFC 3339 text, structured values are JSON text
+
+CREATE TABLE users (
+    id TEXT PRIMARY KEY NOT NULL,
+    username TEXT NOT NULL,
+    hashed_password TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX users_username ON users (username COLLATE NOCASE);
+
+CREATE TABLE roles (
+    id TEXT PRIMARY KEY NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT
+);
+
+CREATE INDEX roles_title ON roles (title COLLATE NOCASE);
+
+CREATE TABLE default_role (
+    id INTEGER PRIMARY KEY NOT NULL CHECK (id = 0),
+    role_id TEXT NOT NULL REFERENCES roles (id) ON DELETE CASCADE
+);
+
+CREATE TABLE role_users (
+    role_id TEXT NOT NULL REFERENCES roles (id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    PRIMARY KEY (role_id, user_id)
+);
+
+CREATE INDEX role_users_user_id ON role_users (user_id);
+
+CREATE TABLE permissions (
+    id TEXT PRIMARY KEY NOT NULL,
+    tag TEXT NOT NULL
+);
+
+CREATE INDEX permissions_tag ON permissions (tag);
+
+CREATE TABLE role_permissions (
+    role_id TEXT NOT NULL REFERENCES roles (id) ON DELETE CASCADE,
+    permission_id TEXT NOT NULL REFERENCES permissions (id) ON DELETE CASCADE,
+    PRIMARY KEY (role_id, permission_id)
+);
+
+CREATE TABLE sessions (
+    token TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    permissions TEXT NOT NULL,
+    expires_at TEXT NOT NULL
+);
+
+CREATE INDEX sessions_user_id ON sessions (user_id);
+
+CREATE TABLE init_state (
+    id INTEGER PRIMARY KEY NOT NULL CHECK (id = 0),
+    start_date TEXT NOT NULL
+);
+
+CREATE TABLE boxes (
+    id TEXT PRIMARY KEY NOT NULL,
+    duplicate_names TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+-- Objects may arrive from peers before their box, so there is no reference to boxes
+CREATE TABLE objects (
+    id TEXT PRIMARY KEY NOT NULL,
+    box_id TEXT NOT NULL,
+    name TEXT,
+    path TEXT,
+    -- Path joined with the name, or the id for objects without a name
+    key TEXT NOT NULL,
+    hash TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    content_type TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT
+);
+
+CREATE INDEX objects_box_id_key ON objects (box_id, key, id);
+CREATE INDEX objects_box_id_path_name ON objects (box_id, path, name);
+CREATE INDEX objects_hash ON objects (hash);
+
+CREATE TABLE object_metadata (
+    object_id TEXT NOT NULL REFERENCES objects (id) ON DELETE CASCADE,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (object_id, key)
+);
+
+CREATE INDEX object_metadata_key_value ON object_metadata (key, value);
+
+CREATE TABLE access_keys (
+    id TEXT PRIMARY KEY NOT NULL,
+    secret TEXT NOT NULL,
+    user_id TEXT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX access_keys_user_id ON access_keys (user_id);
+
+CREATE TABLE uploads (
+    id TEXT PRIMARY KEY NOT NULL,
+    box_id TEXT NOT NULL,
+    name TEXT,
+    path TEXT,
+    metadata TEXT NOT NULL,
+    user_id TEXT,
+    created_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL
+);
+
+CREATE INDEX uploads_expires_at ON uploads (expires_at);
+
+CREATE TABLE upload_parts (
+    upload_id TEXT NOT NULL REFERENCES uploads (id) ON DELETE CASCADE,
+    number INTEGER NOT NULL,
+    hash TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (upload_id, number)
+);
+
+CREATE TABLE nodes (
+    id TEXT PRIMARY KEY NOT NULL,
+    address TEXT NOT NULL,
+    version TEXT NOT NULL,
+    capacity INTEGER NOT NULL,
+    status TEXT NOT NULL,
+    last_heartbeat TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX nodes_address ON nodes (address);
+
+CREATE TABLE replication_tasks (
+    id TEXT PRIMARY KEY NOT NULL,
+    object_id TEXT NOT NULL,
+    node_id TEXT,
+    attempts INTEGER NOT NULL,
+    next_attempt_at TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX replication_tasks_next_attempt_at ON replication_tasks (next_attempt_at);
+
+CREATE TABLE placement_ring (
+    id INTEGER PRIMARY KEY NOT NULL CHECK (id = 0),
+    members TEXT NOT NULL
+);
+
+CREATE TABLE placement_moves (
+    id TEXT PRIMARY KEY NOT NULL,
+    object_id TEXT NOT NULL,
+    targets TEXT NOT NULL,
+    release INTEGER NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX placement_moves_created_at ON placement_moves (created_at);
+
+CREATE TABLE scrub_report (
+    id INTEGER PRIMARY KEY NOT NULL CHECK (id = 0),
+    report TEXT NOT NULL
+);
+
+CREATE TABLE metadata_log (
+    "index" INTEGER PRIMARY KEY NOT NULL,
+    term INTEGER NOT NULL,
+    command TEXT NOT NULL
+);
+
+CREATE TABLE metadata_state (
+    id INTEGER PRIMARY KEY NOT NULL CHECK (id = 0),
+    term INTEGER NOT NULL,
+    voted_for TEXT,
+    applied INTEGER NOT NULL
+);
@@ -0,0 +1,11 @@
+-- Tokens are not recoverable from their hashes, sessions are dropped
+DROP TABLE sessions;
+
+CREATE TABLE sessions (
+    token TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    permissions TEXT NOT NULL,
+    expires_at TEXT NOT NULL
+);
+
+CREATE INDEX sessions_user_id ON sessions (user_id);
@@ -0,0 +1,23 @@
+-- Sessions get an id to be listed and revoked by, and the client they were created from.
+-- A primary key can not be added to a table, it is recreated.
+CREATE TABLE sessions_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    user_id TEXT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    permissions TEXT NOT NULL,
+    ip TEXT,
+    client TEXT,
+    os TEXT,
+    device TEXT,
+    created_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL
+);
+
+INSERT INTO sessions_new (id, token_hash, user_id, permissions, created_at, expires_at)
+SELECT lower(hex(randomblob(8))), token_hash, user_id, permissions, created_at, expires_at FROM sessions;
+
+DROP TABLE sessions;
+ALTER TABLE sessions_new RENAME TO sessions;
+
+CREATE INDEX sessions_user_id ON sessions (user_id);
+CREATE INDEX sessions_expires_at ON sessions (expires_at);
@@ -0,0 +1,2 @@
+-- Existing boxes stay private, the policy is stored as json like duplicate_names
+ALTER TABLE boxes ADD COLUMN access TEXT NOT NULL DEFAULT '"private"';
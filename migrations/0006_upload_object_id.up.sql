@@ -0,0 +1,2 @@
+-- Sessions started before stay without an id, their object gets a generated one
+ALTER TABLE uploads ADD COLUMN object_id TEXT;
@@ -0,0 +1,40 @@
+use crate::adapters::auth::token::TokenProcessor;
+use crate::application::common::id_provider::IdProvider;
+use crate::domain::models::user::UserId;
+
+/// Caller authenticated by an api key sent as `Authorization: Bearer <key>`
+pub struct IdApiKeyProvider {
+    user_id: UserId,
+    permissions: Vec<String>,
+    is_auth: bool
+}
+
+impl IdApiKeyProvider {
+    pub async fn new(
+        key: &str,
+        token_processor: &TokenProcessor
+    ) -> Result<Self, String> {
+        let (api_key, permissions) = token_processor.get_api_key(key).await?;
+        Ok(Self {
+            user_id: api_key.user_id,
+            permissions,
+            is_auth: true
+        })
+    }
+}
+
+impl IdProvider for IdApiKeyProvider {
+    /// Api keys are not sessions
+    fn token(&self) -> Option<&String> {
+        None
+    }
+    fn user_id(&self) -> Option<&UserId> {
+        Some(&self.user_id)
+    }
+    fn permissions(&self) -> &Vec<String> {
+        &self.permissions
+    }
+    fn is_auth(&self) -> &bool {
+        &self.is_auth
+    }
+}
@@ -0,0 +1,58 @@
+use crate::application::common::id_provider::IdProvider;
+use crate::domain::models::user::UserId;
+
+/// Headers of a request proxied by an intermediate node
+pub const FORWARDED_USER_HEADER: &str = "X-Forwarded-User";
+/// Json array of permission tags
+pub const FORWARDED_PERMISSIONS_HEADER: &str = "X-Forwarded-Permissions";
+/// Id of a new object, chosen by the intermediate node to stream it to an owner
+pub const FORWARDED_OBJECT_ID_HEADER: &str = "X-Forwarded-Object-Id";
+
+/// Caller authenticated by an intermediate node
+///
+/// The identity is trusted only together with the cluster secret,
+/// which is checked before the provider is created.
+pub struct IdForwardedProvider {
+    user_id: Option<UserId>,
+    permissions: Vec<String>,
+    is_auth: bool
+}
+
+impl IdForwardedProvider {
+    pub fn new(user_id: Option<&str>, permissions: &str) -> Result<Self, String> {
+        let permissions = serde_json::from_str::<Vec<String>>(permissions)
+            .map_err(|error| format!("Invalid forwarded permissions: {}", error))?;
+        Ok(Self {
+            user_id: user_id.map(str::to_string),
+            permissions,
+            is_auth: user_id.is_some()
+        })
+    }
+    
+    /// Headers passing the caller identity to a storage node
+    pub fn headers(id_provider: &dyn IdProvider) -> Vec<(&'static str, String)> {
+        let mut headers = vec![(
+            FORWARDED_PERMISSIONS_HEADER,
+            serde_json::to_string(id_provider.permissions()).unwrap()
+        )];
+        if let (true, Some(user_id)) = (id_provider.is_auth(), id_provider.user_id()) {
+            headers.push((FORWARDED_USER_HEADER, user_id.clone()));
+        }
+        headers
+    }
+}
+
+impl IdProvider for IdForwardedProvider {
+    fn token(&self) -> Option<&String> {
+        None
+    }
+    fn user_id(&self) -> Option<&UserId> {
+        self.user_id.as_ref()
+    }
+    fn permissions(&self) -> &Vec<String> {
+        &self.permissions
+    }
+    fn is_auth(&self) -> &bool {
+        &self.is_auth
+    }
+}
@@ -1 +1,6 @@
 pub mod token;
+pub mod sigv4;
+pub mod api_key;
+pub mod presign;
+
+pub mod forwarded;
\ No newline at end of file
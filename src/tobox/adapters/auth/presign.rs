@@ -0,0 +1,194 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::adapters::auth::sigv4::uri_encode;
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::signer::Signer;
+use crate::domain::models::presign::{PresignedAction, PresignedGrant};
+use crate::domain::models::r#box::BoxId;
+use crate::domain::models::user::UserId;
+use crate::domain::services::presign::PresignService;
+
+/// Query parameters of a presigned url
+pub const PRESIGN_BOX_PARAM: &str = "X-Tobox-Box";
+pub const PRESIGN_USER_PARAM: &str = "X-Tobox-User";
+/// Unix time in seconds
+pub const PRESIGN_EXPIRES_PARAM: &str = "X-Tobox-Expires";
+pub const PRESIGN_MAX_LENGTH_PARAM: &str = "X-Tobox-Max-Length";
+pub const PRESIGN_SIGNATURE_PARAM: &str = "X-Tobox-Signature";
+
+#[derive(Debug, Deserialize)]
+pub struct PresignedQuery {
+    #[serde(rename = "X-Tobox-Box")]
+    pub box_id: BoxId,
+    #[serde(rename = "X-Tobox-User")]
+    pub user_id: UserId,
+    #[serde(rename = "X-Tobox-Expires")]
+    pub expires: i64,
+    #[serde(rename = "X-Tobox-Max-Length")]
+    pub max_length: Option<u64>,
+    #[serde(rename = "X-Tobox-Signature")]
+    pub signature: String
+}
+
+impl PresignedQuery {
+    /// Query string of the url, the object id of a download is a part of the path
+    pub fn encode(grant: &PresignedGrant, signature: &str) -> String {
+        let mut params = vec![
+            (PRESIGN_BOX_PARAM, grant.box_id.clone()),
+            (PRESIGN_USER_PARAM, grant.user_id.clone()),
+            (PRESIGN_EXPIRES_PARAM, grant.expires_at.timestamp().to_string())
+        ];
+        if let Some(max_length) = grant.max_length {
+            params.push((PRESIGN_MAX_LENGTH_PARAM, max_length.to_string()));
+        }
+        params.push((PRESIGN_SIGNATURE_PARAM, signature.to_string()));
+        params.iter()
+            .map(|(name, value)| format!("{}={}", name, uri_encode(value)))
+            .collect::<Vec<String>>()
+            .join("&")
+    }
+}
+
+/// Request made with a presigned url
+pub struct PresignedRequest<'a> {
+    pub method: &'a str,
+    pub action: PresignedAction,
+    pub content_length: Option<u64>
+}
+
+/// Holder of a presigned url, acting on behalf of its issuer
+///
+/// The permissions are limited to the box of the url and the request is checked
+/// to be the one the url was signed for, so nothing but this request is allowed.
+pub struct IdPresignedProvider {
+    user_id: UserId,
+    permissions: Vec<String>,
+    is_auth: bool
+}
+
+impl IdPresignedProvider {
+    pub fn new(
+        query: PresignedQuery,
+        request: &PresignedRequest,
+        signer: &dyn Signer,
+        presign_service: &PresignService
+    ) -> Result<Self, String> {
+        let grant = PresignedGrant {
+            action: request.action.clone(),
+            box_id: query.box_id,
+            user_id: query.user_id,
+            expires_at: DateTime::from_timestamp(query.expires, 0).ok_or("Invalid url expiry")?,
+            max_length: query.max_length
+        };
+        if !signer.verify(&presign_service.string_to_sign(&grant), &query.signature) {
+            return Err("Invalid url signature".to_string())
+        }
+        presign_service.check_request(
+            &grant,
+            request.method,
+            &request.action,
+            request.content_length,
+            &Utc::now()
+        )?;
+
+        Ok(Self {
+            permissions: presign_service.grant_permissions(&grant),
+            user_id: grant.user_id,
+            is_auth: true
+        })
+    }
+}
+
+impl IdProvider for IdPresignedProvider {
+    fn token(&self) -> Option<&String> {
+        None
+    }
+    fn user_id(&self) -> Option<&UserId> {
+        Some(&self.user_id)
+    }
+    fn permissions(&self) -> &Vec<String> {
+        &self.permissions
+    }
+    fn is_auth(&self) -> &bool {
+        &self.is_auth
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use crate::adapters::hmac_signer::HmacSigner;
+
+    use super::*;
+
+    #[test]
+    fn test_presigned_provider() {
+        let signer = HmacSigner::new("secret", "presign");
+        let service = PresignService {};
+        let grant = service.create_grant(
+            PresignedAction::GetObject("object".to_string()),
+            "box".to_string(),
+            "user".to_string(),
+            Some(60),
+            None,
+            &Utc::now()
+        ).unwrap();
+        let signature = signer.sign(&service.string_to_sign(&grant));
+        let query = || PresignedQuery {
+            box_id: grant.box_id.clone(),
+            user_id: grant.user_id.clone(),
+            expires: grant.expires_at.timestamp(),
+            max_length: None,
+            signature: signature.clone()
+        };
+        let request = |object_id: &str| PresignedRequest {
+            method: "GET",
+            action: PresignedAction::GetObject(object_id.to_string()),
+            content_length: None
+        };
+
+        let provider = IdPresignedProvider::new(query(), &request("object"), &signer, &service).unwrap();
+        assert_eq!(provider.user_id(), Some(&"user".to_string()));
+        assert_eq!(provider.permissions(), &vec!["GetSpecificObject(box)".to_string()]);
+
+        // Another object, user or expiry than signed
+        assert!(IdPresignedProvider::new(query(), &request("other"), &signer, &service).is_err());
+        assert!(IdPresignedProvider::new(
+            PresignedQuery { user_id: "admin".to_string(), ..query() },
+            &request("object"),
+            &signer,
+            &service
+        ).is_err());
+        assert!(IdPresignedProvider::new(
+            PresignedQuery { expires: (grant.expires_at + TimeDelta::days(1)).timestamp(), ..query() },
+            &request("object"),
+            &signer,
+            &service
+        ).is_err());
+        // An upload to the box with the signature of a download
+        assert!(IdPresignedProvider::new(
+            query(),
+            &PresignedRequest { method: "POST", action: PresignedAction::CreateObject, content_length: None },
+            &signer,
+            &service
+        ).is_err());
+    }
+
+    #[test]
+    fn test_encode() {
+        let grant = PresignedGrant {
+            action: PresignedAction::CreateObject,
+            box_id: "box".to_string(),
+            user_id: "user".to_string(),
+            expires_at: DateTime::from_timestamp(1700000000, 0).unwrap(),
+            max_length: Some(1024)
+        };
+        assert_eq!(
+            PresignedQuery::encode(&grant, "abc"),
+            "X-Tobox-Box=box&X-Tobox-User=user&X-Tobox-Expires=1700000000\
+            &X-Tobox-Max-Length=1024&X-Tobox-Signature=abc"
+        );
+    }
+}
@@ -1,31 +1,39 @@
-use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::sync::Arc;
 
+use chrono::Utc;
+
+use crate::application::common::api_key_gateway::ApiKeyReader;
+use crate::application::common::hasher::Hasher;
 use crate::application::common::id_provider::IdProvider;
+use crate::application::common::permission_gateway::PermissionReader;
+use crate::application::common::session_gateway::SessionGateway;
+use crate::domain::models::api_key::ApiKey;
 use crate::domain::models::permission::PermissionTag;
 use crate::domain::models::session::Session;
 use crate::domain::models::user::UserId;
+use crate::domain::services::api_key::ApiKeyService;
+use crate::domain::services::session::SessionService;
 
 pub struct IdTokenProvider {
     token: Option<String>,
     user_id: Option<UserId>,
-    permissions: Vec<PermissionTag>,
+    permissions: Vec<String>,
     is_auth: bool
 }
 
 
 impl IdTokenProvider {
-    pub fn new(
+    pub async fn new(
         token: Option<String>,
         token_processor: &TokenProcessor,
     ) -> Result<Self, String> {
         match token {
             Some(token) => {
-                let session = token_processor.get_token_session(&token)?;
+                let session = token_processor.get_token_session(&token).await?;
                 Ok(Self {
                     token: Some(token),
                     user_id: Option::from(session.user_id),
-                    permissions: session.permissions,
+                    permissions: session.permissions.iter().map(|tag| tag.to_string()).collect(),
                     is_auth: true
                 })
             }
@@ -33,7 +41,7 @@ impl IdTokenProvider {
                 Ok(Self {
                     token,
                     user_id: None,
-                    permissions: PermissionTag::guest_tags(),
+                    permissions: PermissionTag::guest_tags().iter().map(|tag| tag.to_string()).collect(),
                     is_auth: false
                 })
             }
@@ -48,7 +56,7 @@ impl IdProvider for IdTokenProvider {
     fn user_id(&self) -> Option<&UserId> {
         self.user_id.as_ref()
     }
-    fn permissions(&self) -> &Vec<PermissionTag> {
+    fn permissions(&self) -> &Vec<String> {
         &self.permissions
     }
     fn is_auth(&self) -> &bool {
@@ -57,35 +65,248 @@ impl IdProvider for IdTokenProvider {
 }
 
 
+/// Resolves session tokens and api keys of requests
+///
+/// Both are looked up by their hash. A session used in the second half
+/// of its lifetime is renewed.
+///
+/// Permissions of both are read from the roles of the user on every request,
+/// so a revoked permission is not kept by a session that is still in use.
 pub struct TokenProcessor {
-    data: Arc<RwLock<HashMap<String, Session>>>,
+    session_gateway: Arc<dyn SessionGateway + Send + Sync>,
+    api_key_reader: Arc<dyn ApiKeyReader + Send + Sync>,
+    permission_reader: Arc<dyn PermissionReader + Send + Sync>,
+    token_hasher: Arc<dyn Hasher + Send + Sync>,
+    session_service: SessionService,
+    api_key_service: ApiKeyService
 }
 
 impl TokenProcessor {
-    pub fn new() -> Self {
+    pub fn new(
+        session_gateway: Arc<dyn SessionGateway + Send + Sync>,
+        api_key_reader: Arc<dyn ApiKeyReader + Send + Sync>,
+        permission_reader: Arc<dyn PermissionReader + Send + Sync>,
+        token_hasher: Arc<dyn Hasher + Send + Sync>,
+        session_service: SessionService
+    ) -> Self {
         Self {
-            data: Arc::new(RwLock::new(HashMap::new())),
+            session_gateway,
+            api_key_reader,
+            permission_reader,
+            token_hasher,
+            session_service,
+            api_key_service: ApiKeyService {}
         }
     }
-    
-    pub fn set_token_session(&self, session: &Session) -> String {
-        let token = session.token.clone();
-        let mut data = self.data.write().unwrap();
-        data.insert(token.clone(), session.clone());
-        token
+
+    pub async fn get_token_session(&self, token: &str) -> Result<Session, String> {
+        let token_hash = self.token_hasher.hash(token).await;
+        let mut session = match self.session_gateway.get_session(&token_hash).await {
+            Some(session) => session,
+            None => return Err("Token not found".to_string())
+        };
+
+        let now = Utc::now();
+        if self.session_service.is_expired(&session, &now) {
+            return Err("Token expired".to_string())
+        }
+        session.permissions = self.permission_reader
+            .get_user_permissions(&session.user_id).await
+            .into_iter()
+            .map(|permission| permission.tag)
+            .collect();
+        if self.session_service.renew(&mut session, &now) {
+            self.session_gateway.save_session(&session).await;
+        }
+        Ok(session)
     }
-    
-    pub fn get_token_session(&self, token: &str) -> Result<Session, String> {
-        let data = self.data.read().unwrap();
-        match data.get(token) {
-            Some(session) => {
-                if session.expires_at < chrono::Utc::now() {
-                    Ok(session.clone())
-                } else {
-                    Err("Token expired".to_string())
-                }
-            }
-            None => Err("Token not found".to_string())
+
+    /// * return: the key and the permissions it acts with
+    pub async fn get_api_key(&self, key: &str) -> Result<(ApiKey, Vec<String>), String> {
+        let key_hash = self.token_hasher.hash(key).await;
+        let api_key = match self.api_key_reader.get_api_key_by_hash(&key_hash).await {
+            Some(api_key) => api_key,
+            None => return Err("Api key not found".to_string())
+        };
+
+        if self.api_key_service.is_expired(&api_key, &Utc::now()) {
+            return Err("Api key expired".to_string())
         }
+        let owner_permissions: Vec<PermissionTag> = self.permission_reader
+            .get_user_permissions(&api_key.user_id).await
+            .into_iter()
+            .map(|permission| permission.tag)
+            .collect();
+        let permissions = self.api_key_service.effective_permissions(&api_key, &owner_permissions);
+        Ok((api_key, permissions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use crate::adapters::database::api_key_db::ApiKeyGateway;
+    use crate::adapters::database::permission_db::PermissionGateway;
+    use crate::adapters::database::role_db::RoleGateway;
+    use crate::adapters::database::session_db::SessionGateway as SqliteSessionGateway;
+    use crate::adapters::database::sqlite::connect_in_memory;
+    use crate::adapters::sha256_session_hasher::Sha256SessionHasher;
+    use crate::application::common::api_key_gateway::ApiKeyWriter;
+    use crate::application::common::permission_gateway::{PermissionLinker, PermissionWriter};
+    use crate::application::common::role_gateway::{RoleLinker, RoleWriter};
+    use crate::application::common::session_gateway::{SessionReader, SessionRemover, SessionWriter};
+    use crate::domain::models::permission::Permission;
+    use crate::domain::models::role::Role;
+    use crate::domain::models::session::SessionOrigin;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_token_session() {
+        let db = connect_in_memory().await;
+        sqlx::query("INSERT INTO users (id, username, hashed_password, created_at) VALUES ('user', 'user', '', ?)")
+            .bind(Utc::now())
+            .execute(&db)
+            .await
+            .unwrap();
+        let gateway = Arc::new(SqliteSessionGateway::new(db.clone()));
+        let processor = TokenProcessor::new(
+            gateway.clone(),
+            Arc::new(ApiKeyGateway::new(db.clone())),
+            Arc::new(PermissionGateway::new(db)),
+            Arc::new(Sha256SessionHasher {}),
+            SessionService::new(3600)
+        );
+        let hasher = Sha256SessionHasher {};
+        let service = SessionService::new(3600);
+
+        let token = service.generate_token();
+        let now = Utc::now();
+        let mut session = service.create_session(
+            hasher.hash(&token).await,
+            "user".to_string(),
+            vec![],
+            SessionOrigin::default(),
+            &now
+        );
+        gateway.save_session(&session).await;
+        assert_eq!(processor.get_token_session(&token).await.unwrap().user_id, "user");
+        // The hash is not a token
+        assert!(processor.get_token_session(&session.token_hash).await.is_err());
+
+        // Renewed in the second half of the lifetime
+        session.expires_at = now + TimeDelta::minutes(20);
+        gateway.save_session(&session).await;
+        let renewed = processor.get_token_session(&token).await.unwrap();
+        assert!(renewed.expires_at > session.expires_at);
+        assert_eq!(gateway.get_session(&session.token_hash).await.unwrap().expires_at, renewed.expires_at);
+
+        session.expires_at = now - TimeDelta::hours(1);
+        gateway.save_session(&session).await;
+        assert_eq!(processor.get_token_session(&token).await.unwrap_err(), "Token expired");
+
+        // A revoked session is refused on the next request
+        gateway.remove_session(&session.id).await;
+        assert_eq!(processor.get_token_session(&token).await.unwrap_err(), "Token not found");
+    }
+
+    #[tokio::test]
+    async fn test_session_permissions() {
+        let db = connect_in_memory().await;
+        sqlx::query("INSERT INTO users (id, username, hashed_password, created_at) VALUES ('user', 'user', '', ?)")
+            .bind(Utc::now())
+            .execute(&db)
+            .await
+            .unwrap();
+        let roles = RoleGateway::new(db.clone());
+        let permissions = PermissionGateway::new(db.clone());
+        roles.save_role(&Role {
+            id: "role".to_string(),
+            title: "Reader".to_string(),
+            description: None,
+            created_at: Utc::now(),
+            updated_at: None
+        }).await;
+        roles.link_role_to_user(&"role".to_string(), &"user".to_string()).await;
+        permissions.save_permissions(&vec![
+            Permission { id: "get".to_string(), tag: PermissionTag::GetObject },
+            Permission { id: "delete".to_string(), tag: PermissionTag::DeleteObject },
+        ]).await;
+        permissions.link_permissions_to_role(
+            &"role".to_string(),
+            &vec!["get".to_string(), "delete".to_string()]
+        ).await;
+
+        let gateway = Arc::new(SqliteSessionGateway::new(db.clone()));
+        let processor = TokenProcessor::new(
+            gateway.clone(),
+            Arc::new(ApiKeyGateway::new(db.clone())),
+            Arc::new(PermissionGateway::new(db)),
+            Arc::new(Sha256SessionHasher {}),
+            SessionService::new(3600)
+        );
+        let service = SessionService::new(3600);
+        let token = service.generate_token();
+        let now = Utc::now();
+        let mut session = service.create_session(
+            Sha256SessionHasher {}.hash(&token).await,
+            "user".to_string(),
+            vec![PermissionTag::GetObject, PermissionTag::DeleteObject],
+            SessionOrigin::default(),
+            &now
+        );
+        gateway.save_session(&session).await;
+        let provider = IdTokenProvider::new(Some(token.clone()), &processor).await.unwrap();
+        assert!(provider.permissions().contains(&PermissionTag::DeleteObject.to_string()));
+
+        // Revoked while the session is in use, the renewed session loses it
+        permissions.unlink_permission_from_role(&"role".to_string(), &"delete".to_string()).await;
+        session.expires_at = now + TimeDelta::minutes(20);
+        gateway.save_session(&session).await;
+        let provider = IdTokenProvider::new(Some(token), &processor).await.unwrap();
+        assert_eq!(provider.permissions(), &vec![PermissionTag::GetObject.to_string()]);
+        let renewed = gateway.get_session(&session.token_hash).await.unwrap();
+        assert!(renewed.expires_at > session.expires_at);
+        assert_eq!(renewed.permissions.len(), 1);
+        assert!(matches!(renewed.permissions[0], PermissionTag::GetObject));
+    }
+
+    #[tokio::test]
+    async fn test_get_api_key() {
+        let db = connect_in_memory().await;
+        sqlx::query("INSERT INTO users (id, username, hashed_password, created_at) VALUES ('user', 'user', '', ?)")
+            .bind(Utc::now())
+            .execute(&db)
+            .await
+            .unwrap();
+        let gateway = Arc::new(ApiKeyGateway::new(db.clone()));
+        let processor = TokenProcessor::new(
+            Arc::new(SqliteSessionGateway::new(db.clone())),
+            gateway.clone(),
+            Arc::new(PermissionGateway::new(db)),
+            Arc::new(Sha256SessionHasher {}),
+            SessionService::new(3600)
+        );
+        let service = ApiKeyService {};
+
+        let key = service.generate_key();
+        let mut api_key = service.create_api_key(
+            "backup".to_string(),
+            Sha256SessionHasher {}.hash(&key).await,
+            "user".to_string(),
+            vec![PermissionTag::GetObject],
+            None
+        );
+        gateway.save_api_key(&api_key).await;
+        let (found, permissions) = processor.get_api_key(&key).await.unwrap();
+        assert_eq!(found.id, api_key.id);
+        // The owner has no roles, the key has no rights either
+        assert!(permissions.is_empty());
+
+        api_key.expires_at = Some(Utc::now());
+        gateway.save_api_key(&api_key).await;
+        assert_eq!(processor.get_api_key(&key).await.unwrap_err(), "Api key expired");
+        assert_eq!(processor.get_api_key(&service.generate_key()).await.unwrap_err(), "Api key not found");
     }
 }
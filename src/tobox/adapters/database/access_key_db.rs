@@ -0,0 +1,94 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+
+use crate::application::common::access_key_gateway::{
+    AccessKeyGateway as AccessKeyGatewayTrait,
+    AccessKeyReader,
+    AccessKeyRemover,
+    AccessKeyWriter
+};
+use crate::domain::models::access_key::{AccessKey, AccessKeyId};
+use crate::domain::models::user::UserId;
+
+#[derive(FromRow)]
+struct AccessKeyModel {
+    id: String,
+    secret: String,
+    user_id: String,
+    created_at: DateTime<Utc>
+}
+
+pub struct AccessKeyGateway {
+    db: SqlitePool,
+}
+
+impl AccessKeyGateway {
+    pub fn new(db: SqlitePool) -> Self {
+        AccessKeyGateway { db }
+    }
+}
+
+#[async_trait]
+impl AccessKeyReader for AccessKeyGateway {
+    async fn get_access_key(&self, access_key_id: &AccessKeyId) -> Option<AccessKey> {
+        sqlx::query_as::<_, AccessKeyModel>("SELECT * FROM access_keys WHERE id = ?")
+            .bind(access_key_id)
+            .fetch_optional(&self.db)
+            .await
+            .unwrap()
+            .map(map_access_key_model_to_domain)
+    }
+
+    async fn get_user_access_keys(&self, user_id: &UserId) -> Vec<AccessKey> {
+        sqlx::query_as::<_, AccessKeyModel>(
+            "SELECT * FROM access_keys WHERE user_id = ? ORDER BY created_at, id"
+        )
+            .bind(user_id)
+            .fetch_all(&self.db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(map_access_key_model_to_domain)
+            .collect()
+    }
+}
+
+#[async_trait]
+impl AccessKeyWriter for AccessKeyGateway {
+    async fn save_access_key(&self, data: &AccessKey) {
+        sqlx::query(
+            "INSERT INTO access_keys (id, secret, user_id, created_at) VALUES (?, ?, ?, ?)
+            ON CONFLICT (id) DO UPDATE SET secret = excluded.secret"
+        )
+            .bind(&data.id)
+            .bind(&data.secret)
+            .bind(&data.user_id)
+            .bind(data.created_at)
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+}
+
+#[async_trait]
+impl AccessKeyRemover for AccessKeyGateway {
+    async fn remove_access_key(&self, access_key_id: &AccessKeyId) {
+        sqlx::query("DELETE FROM access_keys WHERE id = ?")
+            .bind(access_key_id)
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+}
+
+fn map_access_key_model_to_domain(access_key: AccessKeyModel) -> AccessKey {
+    AccessKey {
+        id: access_key.id,
+        secret: access_key.secret,
+        user_id: access_key.user_id,
+        created_at: access_key.created_at
+    }
+}
+
+impl AccessKeyGatewayTrait for AccessKeyGateway {}
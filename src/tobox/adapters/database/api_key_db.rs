@@ -0,0 +1,159 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+use sqlx::types::Json;
+
+use crate::application::common::api_key_gateway::{
+    ApiKeyGateway as ApiKeyGatewayTrait,
+    ApiKeyReader,
+    ApiKeyRemover,
+    ApiKeyWriter
+};
+use crate::domain::models::api_key::{ApiKey, ApiKeyId};
+use crate::domain::models::permission::PermissionTag;
+use crate::domain::models::user::UserId;
+
+#[derive(FromRow)]
+struct ApiKeyModel {
+    id: String,
+    name: String,
+    key_hash: String,
+    user_id: String,
+    permissions: Json<Vec<PermissionTag>>,
+    created_at: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>
+}
+
+pub struct ApiKeyGateway {
+    db: SqlitePool,
+}
+
+impl ApiKeyGateway {
+    pub fn new(db: SqlitePool) -> Self {
+        ApiKeyGateway { db }
+    }
+}
+
+#[async_trait]
+impl ApiKeyReader for ApiKeyGateway {
+    async fn get_api_key(&self, api_key_id: &ApiKeyId) -> Option<ApiKey> {
+        sqlx::query_as::<_, ApiKeyModel>("SELECT * FROM api_keys WHERE id = ?")
+            .bind(api_key_id)
+            .fetch_optional(&self.db)
+            .await
+            .unwrap()
+            .map(map_api_key_model_to_domain)
+    }
+
+    async fn get_api_key_by_hash(&self, key_hash: &str) -> Option<ApiKey> {
+        sqlx::query_as::<_, ApiKeyModel>("SELECT * FROM api_keys WHERE key_hash = ?")
+            .bind(key_hash)
+            .fetch_optional(&self.db)
+            .await
+            .unwrap()
+            .map(map_api_key_model_to_domain)
+    }
+
+    async fn get_user_api_keys(&self, user_id: &UserId) -> Vec<ApiKey> {
+        sqlx::query_as::<_, ApiKeyModel>(
+            "SELECT * FROM api_keys WHERE user_id = ? ORDER BY created_at, id"
+        )
+            .bind(user_id)
+            .fetch_all(&self.db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(map_api_key_model_to_domain)
+            .collect()
+    }
+}
+
+#[async_trait]
+impl ApiKeyWriter for ApiKeyGateway {
+    async fn save_api_key(&self, data: &ApiKey) {
+        sqlx::query(
+            "INSERT INTO api_keys (id, name, key_hash, user_id, permissions, created_at, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (id) DO UPDATE SET
+                name = excluded.name,
+                permissions = excluded.permissions,
+                expires_at = excluded.expires_at"
+        )
+            .bind(&data.id)
+            .bind(&data.name)
+            .bind(&data.key_hash)
+            .bind(&data.user_id)
+            .bind(Json(&data.permissions))
+            .bind(data.created_at)
+            .bind(data.expires_at)
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+}
+
+#[async_trait]
+impl ApiKeyRemover for ApiKeyGateway {
+    async fn remove_api_key(&self, api_key_id: &ApiKeyId) {
+        sqlx::query("DELETE FROM api_keys WHERE id = ?")
+            .bind(api_key_id)
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+}
+
+fn map_api_key_model_to_domain(api_key: ApiKeyModel) -> ApiKey {
+    ApiKey {
+        id: api_key.id,
+        name: api_key.name,
+        key_hash: api_key.key_hash,
+        user_id: api_key.user_id,
+        permissions: api_key.permissions.0,
+        created_at: api_key.created_at,
+        expires_at: api_key.expires_at
+    }
+}
+
+impl ApiKeyGatewayTrait for ApiKeyGateway {}
+
+#[cfg(test)]
+mod tests {
+    use crate::adapters::database::sqlite::connect_in_memory;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_api_keys() {
+        let db = connect_in_memory().await;
+        sqlx::query("INSERT INTO users (id, username, hashed_password, created_at) VALUES ('user', 'user', '', ?)")
+            .bind(Utc::now())
+            .execute(&db)
+            .await
+            .unwrap();
+        let gateway = ApiKeyGateway::new(db);
+        let expires_at = Utc::now();
+        for (id, expires_at) in [("a", None), ("b", Some(expires_at))] {
+            gateway.save_api_key(&ApiKey {
+                id: id.to_string(),
+                name: format!("key {}", id),
+                key_hash: format!("hash {}", id),
+                user_id: "user".to_string(),
+                permissions: vec![PermissionTag::GetSpecificObject("box".to_string())],
+                created_at: Utc::now(),
+                expires_at
+            }).await;
+        }
+
+        let b = gateway.get_api_key_by_hash("hash b").await.unwrap();
+        assert_eq!(b.id, "b");
+        assert_eq!(b.expires_at, Some(expires_at));
+        assert_eq!(b.permissions[0].to_string(), "GetSpecificObject(box)");
+        assert!(gateway.get_api_key(&"a".to_string()).await.unwrap().expires_at.is_none());
+
+        gateway.remove_api_key(&"a".to_string()).await;
+        let keys = gateway.get_user_api_keys(&"user".to_string()).await;
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].name, "key b");
+    }
+}
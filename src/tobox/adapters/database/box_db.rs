@@ -0,0 +1,141 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+use sqlx::types::Json;
+
+use crate::application::common::box_gateway::{
+    BoxGateway as BoxGatewayTrait,
+    BoxReader,
+    BoxRemover,
+    BoxWriter
+};
+use crate::domain::models::r#box::{Box as BoxDomain, BoxAccessPolicy, BoxId, DuplicateNamePolicy};
+
+#[derive(FromRow)]
+struct BoxModel {
+    id: String,
+    duplicate_names: Json<DuplicateNamePolicy>,
+    access: Json<BoxAccessPolicy>,
+    created_at: DateTime<Utc>
+}
+
+pub struct BoxGateway {
+    db: SqlitePool,
+}
+
+impl BoxGateway {
+    pub fn new(db: SqlitePool) -> Self {
+        BoxGateway { db }
+    }
+}
+
+#[async_trait]
+impl BoxReader for BoxGateway {
+    async fn get_box(&self, box_id: &BoxId) -> Option<BoxDomain> {
+        sqlx::query_as::<_, BoxModel>("SELECT * FROM boxes WHERE id = ?")
+            .bind(box_id)
+            .fetch_optional(&self.db)
+            .await
+            .unwrap()
+            .map(map_box_model_to_domain)
+    }
+
+    async fn get_boxes(&self) -> Vec<BoxDomain> {
+        sqlx::query_as::<_, BoxModel>("SELECT * FROM boxes ORDER BY id")
+            .fetch_all(&self.db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(map_box_model_to_domain)
+            .collect()
+    }
+
+    async fn get_boxes_range(&self, limit: &u64, offset: &u64) -> Vec<BoxDomain> {
+        sqlx::query_as::<_, BoxModel>("SELECT * FROM boxes ORDER BY id LIMIT ? OFFSET ?")
+            .bind(*limit as i64)
+            .bind(*offset as i64)
+            .fetch_all(&self.db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(map_box_model_to_domain)
+            .collect()
+    }
+}
+
+#[async_trait]
+impl BoxWriter for BoxGateway {
+    async fn save_box(&self, data: &BoxDomain) {
+        sqlx::query(
+            "INSERT INTO boxes (id, duplicate_names, access, created_at) VALUES (?, ?, ?, ?)
+            ON CONFLICT (id) DO UPDATE SET
+                duplicate_names = excluded.duplicate_names,
+                access = excluded.access"
+        )
+            .bind(&data.id)
+            .bind(Json(&data.duplicate_names))
+            .bind(Json(&data.access))
+            .bind(data.created_at)
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+}
+
+#[async_trait]
+impl BoxRemover for BoxGateway {
+    async fn remove_box(&self, box_id: &BoxId) {
+        sqlx::query("DELETE FROM boxes WHERE id = ?")
+            .bind(box_id)
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+}
+
+fn map_box_model_to_domain(r#box: BoxModel) -> BoxDomain {
+    BoxDomain {
+        id: r#box.id,
+        duplicate_names: r#box.duplicate_names.0,
+        access: r#box.access.0,
+        created_at: r#box.created_at
+    }
+}
+
+impl BoxGatewayTrait for BoxGateway {}
+
+#[cfg(test)]
+mod tests {
+    use crate::adapters::database::sqlite::connect_in_memory;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_boxes() {
+        let gateway = BoxGateway::new(connect_in_memory().await);
+        for id in ["b", "a", "c"] {
+            gateway.save_box(&BoxDomain {
+                id: id.to_string(),
+                duplicate_names: DuplicateNamePolicy::Allow,
+                access: BoxAccessPolicy::Private,
+                created_at: Utc::now()
+            }).await;
+        }
+        gateway.save_box(&BoxDomain {
+            id: "a".to_string(),
+            duplicate_names: DuplicateNamePolicy::Reject,
+            access: BoxAccessPolicy::PublicRead,
+            created_at: Utc::now()
+        }).await;
+
+        let a = gateway.get_box(&"a".to_string()).await.unwrap();
+        assert_eq!(a.duplicate_names, DuplicateNamePolicy::Reject);
+        assert_eq!(a.access, BoxAccessPolicy::PublicRead);
+        let page: Vec<BoxId> = gateway.get_boxes_range(&2, &1).await.into_iter().map(|r#box| r#box.id).collect();
+        assert_eq!(page, vec!["b".to_string(), "c".to_string()]);
+
+        gateway.remove_box(&"a".to_string()).await;
+        assert!(gateway.get_box(&"a".to_string()).await.is_none());
+        assert_eq!(gateway.get_boxes().await.len(), 2);
+    }
+}
@@ -0,0 +1,581 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use tokio::fs::File;
+use tokio::io;
+use tokio_stream::Stream;
+
+use crate::adapters::database::file_storage::{FileStorage, hash_file};
+use crate::application::common::file_storage_manager::{
+    BlobHealth,
+    FileStorageError,
+    FileStorageManager,
+    FileStorageReader,
+    FileStorageRemover,
+    FileStorageWriter
+};
+use crate::domain::id_generator::generate_id;
+use crate::domain::models::file_info::FileInfo;
+use crate::domain::models::file_stream::FileStream;
+
+const VERIFIED_CACHE_SIZE: usize = 100_000;
+
+/// Every volume is a `FileStorage` of its own, a blob is copied to `replicas` of them
+///
+/// New blobs go to the volumes with the most available space, so an added disk is
+/// filled first. Reads take the first replica whose content matches its hash and fall
+/// back to the next one when a replica is missing or damaged. Blobs never change in
+/// place, so a replica is hashed on its first read only.
+///
+/// Volume directories must exist, a missing one is treated as an unmounted disk
+/// and skipped.
+pub struct CombinedFileStorage {
+    volumes: Vec<(PathBuf, FileStorage)>,
+    replicas: usize,
+    verified: Mutex<HashSet<(usize, String)>>,
+}
+
+impl CombinedFileStorage {
+    /// * replicas: copies of every blob, a copy on each volume when not set
+    pub fn new(volumes: &[PathBuf], replicas: Option<u8>) -> Result<Self, String> {
+        if volumes.is_empty() {
+            return Err("At least one volume is required".to_string())
+        }
+        let replicas = replicas.map_or(volumes.len(), usize::from);
+        if replicas == 0 || replicas > volumes.len() {
+            return Err(format!("Replicas should be between 1 and {}", volumes.len()))
+        }
+
+        Ok(Self {
+            volumes: volumes.iter().map(|path| (path.clone(), FileStorage::new(path))).collect(),
+            replicas,
+            verified: Mutex::new(HashSet::new()),
+        })
+    }
+
+    fn storage(&self, index: usize) -> &FileStorage {
+        &self.volumes[index].1
+    }
+
+    /// Indexes of available volumes, the most available space first
+    fn volumes_by_space(&self) -> Vec<usize> {
+        let mut spaces: Vec<(usize, u64)> = self.volumes.iter().enumerate()
+            .filter_map(|(index, (path, _))| match fs4::available_space(path) {
+                Ok(space) => Some((index, space)),
+                Err(error) => {
+                    log::warn!("Volume {} is unavailable: {}", path.display(), error);
+                    None
+                }
+            })
+            .collect();
+        // Stable, volumes with the same space keep the config order
+        spaces.sort_by_key(|(_, space)| Reverse(*space));
+        spaces.into_iter().map(|(index, _)| index).collect()
+    }
+
+    async fn replica_volumes(&self, hash: &str) -> Vec<usize> {
+        let mut indexes = Vec::new();
+        for index in 0..self.volumes.len() {
+            if self.storage(index).is_file_exists(hash).await {
+                indexes.push(index);
+            }
+        }
+        indexes
+    }
+
+    /// Volume of the first replica matching its hash
+    async fn find_replica(&self, hash: &str) -> Option<usize> {
+        for index in self.replica_volumes(hash).await {
+            let key = (index, hash.to_string());
+            if self.verified.lock().unwrap().contains(&key) {
+                return Some(index)
+            }
+
+            match hash_file(&self.storage(index).blob_path(hash)).await {
+                Ok(actual) if actual == hash => {
+                    let mut verified = self.verified.lock().unwrap();
+                    if verified.len() >= VERIFIED_CACHE_SIZE {
+                        verified.clear();
+                    }
+                    verified.insert(key);
+                    return Some(index)
+                },
+                Ok(_) => log::warn!(
+                    "Blob {} on volume {} does not match its hash",
+                    hash,
+                    self.volumes[index].0.display()
+                ),
+                Err(error) => log::warn!(
+                    "Blob {} on volume {} is not readable: {}",
+                    hash,
+                    self.volumes[index].0.display(),
+                    error
+                )
+            }
+        }
+        None
+    }
+
+    async fn find_staged(&self, filename: &str) -> Option<usize> {
+        for index in 0..self.volumes.len() {
+            let path = self.storage(index).staging_path(filename);
+            if tokio::fs::try_exists(path).await.unwrap_or(false) {
+                return Some(index)
+            }
+        }
+        None
+    }
+
+    async fn find_upload(&self, upload_id: &str) -> Option<usize> {
+        for index in 0..self.volumes.len() {
+            let path = self.storage(index).upload_path(upload_id);
+            if tokio::fs::try_exists(path).await.unwrap_or(false) {
+                return Some(index)
+            }
+        }
+        None
+    }
+
+    fn volume_for_new_file(&self) -> Result<usize, FileStorageError> {
+        self.volumes_by_space().first().copied().ok_or(
+            FileStorageError::Interrupted("No volume is available".to_string())
+        )
+    }
+}
+
+/// Copy a file with its content synced to disk
+async fn copy_file(from: &Path, to: &Path) -> io::Result<()> {
+    tokio::fs::create_dir_all(to.parent().unwrap()).await?;
+    tokio::fs::copy(from, to).await?;
+    File::open(to).await?.sync_all().await
+}
+
+#[async_trait]
+impl FileStorageReader for CombinedFileStorage {
+    async fn read_file(&self, hash: &str) -> Result<Box<dyn FileStream>, FileStorageError> {
+        match self.find_replica(hash).await {
+            Some(index) => self.storage(index).read_file(hash).await,
+            None => Err(FileStorageError::NotFound(format!("No valid replica of blob {}", hash)))
+        }
+    }
+
+    async fn read_file_range(
+        &self,
+        hash: &str,
+        offset: u64,
+        length: u64
+    ) -> Result<Box<dyn FileStream>, FileStorageError> {
+        match self.find_replica(hash).await {
+            Some(index) => self.storage(index).read_file_range(hash, offset, length).await,
+            None => Err(FileStorageError::NotFound(format!("No valid replica of blob {}", hash)))
+        }
+    }
+
+    async fn is_file_exists(&self, hash: &str) -> bool {
+        for index in 0..self.volumes.len() {
+            if self.storage(index).is_file_exists(hash).await {
+                return true
+            }
+        }
+        false
+    }
+
+    async fn get_files(&self) -> Vec<String> {
+        let mut hashes = BTreeSet::new();
+        for index in 0..self.volumes.len() {
+            hashes.extend(self.storage(index).get_files().await);
+        }
+        hashes.into_iter().collect()
+    }
+
+    /// A blob must fit on each of its replica volumes
+    async fn available_space(&self) -> u64 {
+        let mut spaces: Vec<u64> = self.volumes.iter()
+            .filter_map(|(path, _)| fs4::available_space(path).ok())
+            .collect();
+        spaces.sort_by_key(|space| Reverse(*space));
+        spaces.truncate(self.replicas);
+        spaces.last().copied().unwrap_or(0)
+    }
+}
+
+#[async_trait]
+impl FileStorageWriter for CombinedFileStorage {
+    async fn save_file(
+        &self,
+        filename: &str,
+        content_type: Option<&str>,
+        size_range: Option<(u64, u64)>,
+        bytes: &mut dyn FileStream
+    ) -> Result<FileInfo, FileStorageError> {
+        let index = self.volume_for_new_file()?;
+        self.storage(index).save_file(filename, content_type, size_range, bytes).await
+    }
+
+    /// The staged file is copied to the other chosen volumes, then moved into the blobs
+    /// of its own volume or removed if that one is not chosen
+    async fn commit_file(&self, filename: &str, hash: &str) -> Result<(), FileStorageError> {
+        let source = match self.find_staged(filename).await {
+            Some(source) => source,
+            // The volume of the staged file may have been unmounted since it was saved
+            None => return Err(FileStorageError::Interrupted(
+                format!("Blob {} is not committed: staged file {} not found", hash, filename)
+            ))
+        };
+        let existing = self.replica_volumes(hash).await;
+        let targets: Vec<usize> = self.volumes_by_space().into_iter()
+            .filter(|index| !existing.contains(index))
+            .take(self.replicas.saturating_sub(existing.len()))
+            .collect();
+
+        let staged_path = self.storage(source).staging_path(filename);
+        let mut stored = existing.len();
+        for &index in targets.iter().filter(|index| **index != source) {
+            let storage = self.storage(index);
+            let copied = match copy_file(&staged_path, &storage.staging_path(filename)).await {
+                Ok(()) => storage.commit_file(filename, hash).await,
+                Err(error) => Err(FileStorageError::Interrupted(error.to_string()))
+            };
+            match copied {
+                Ok(()) => stored += 1,
+                Err(error) => {
+                    log::warn!(
+                        "Blob {} is not copied to volume {}: {:?}",
+                        hash,
+                        self.volumes[index].0.display(),
+                        error
+                    );
+                    storage.discard_file(filename).await;
+                }
+            }
+        }
+
+        if !targets.contains(&source) {
+            self.storage(source).discard_file(filename).await;
+        } else if let Err(error) = self.storage(source).commit_file(filename, hash).await {
+            log::warn!(
+                "Blob {} is not committed to volume {}: {:?}",
+                hash,
+                self.volumes[source].0.display(),
+                error
+            );
+            self.storage(source).discard_file(filename).await;
+        } else {
+            stored += 1;
+        }
+
+        if stored == 0 {
+            return Err(FileStorageError::Interrupted(
+                format!("Blob {} is not stored on any volume", hash)
+            ))
+        }
+        if stored < self.replicas {
+            log::warn!("Blob {} is stored with {} of {} replicas", hash, stored, self.replicas);
+        }
+        Ok(())
+    }
+
+    async fn discard_file(&self, filename: &str) {
+        for index in 0..self.volumes.len() {
+            self.storage(index).discard_file(filename).await;
+        }
+    }
+
+    /// Parts of an upload are kept on one volume to be assembled there
+    async fn save_part(
+        &self,
+        upload_id: &str,
+        number: u32,
+        bytes: &mut dyn FileStream
+    ) -> Result<FileInfo, FileStorageError> {
+        let index = match self.find_upload(upload_id).await {
+            Some(index) => index,
+            None => self.volume_for_new_file()?
+        };
+        self.storage(index).save_part(upload_id, number, bytes).await
+    }
+
+    async fn assemble_parts(
+        &self,
+        upload_id: &str,
+        numbers: &[u32],
+        filename: &str,
+        content_type: Option<&str>,
+        size_range: Option<(u64, u64)>
+    ) -> Result<FileInfo, FileStorageError> {
+        let index = self.find_upload(upload_id).await.ok_or(
+            FileStorageError::Interrupted(format!("Parts of upload {} not found", upload_id))
+        )?;
+        self.storage(index).assemble_parts(upload_id, numbers, filename, content_type, size_range).await
+    }
+
+    /// Damaged replicas are overwritten by a healthy one, lost ones are copied
+    /// to the volumes with the most available space
+    async fn repair_file(&self, hash: &str) -> BlobHealth {
+        let mut healthy = Vec::new();
+        let mut damaged = Vec::new();
+        for index in self.replica_volumes(hash).await {
+            self.verified.lock().unwrap().remove(&(index, hash.to_string()));
+            match hash_file(&self.storage(index).blob_path(hash)).await {
+                Ok(actual) if actual == hash => healthy.push(index),
+                _ => {
+                    log::warn!(
+                        "Blob {} on volume {} is damaged",
+                        hash,
+                        self.volumes[index].0.display()
+                    );
+                    damaged.push(index)
+                }
+            }
+        }
+        let source = match healthy.first() {
+            Some(index) => self.storage(*index).blob_path(hash),
+            None if damaged.is_empty() => return BlobHealth::Missing,
+            None => return BlobHealth::Corrupted
+        };
+
+        let mut targets = damaged.clone();
+        targets.extend(
+            self.volumes_by_space().into_iter()
+                .filter(|index| !healthy.contains(index) && !damaged.contains(index))
+                .take(self.replicas.saturating_sub(healthy.len() + damaged.len()))
+        );
+        if targets.is_empty() {
+            return BlobHealth::Healthy
+        }
+
+        let mut stored = healthy.len();
+        for index in targets {
+            let storage = self.storage(index);
+            let filename = format!("{}.{}", hash, generate_id(8));
+            let restored = match copy_file(&source, &storage.staging_path(&filename)).await {
+                Ok(()) => {
+                    // A damaged blob would be taken for the same content
+                    storage.remove_file(hash).await;
+                    storage.commit_file(&filename, hash).await
+                },
+                Err(error) => Err(FileStorageError::Interrupted(error.to_string()))
+            };
+            match restored {
+                Ok(()) => stored += 1,
+                Err(error) => {
+                    log::warn!(
+                        "Blob {} is not restored on volume {}: {:?}",
+                        hash,
+                        self.volumes[index].0.display(),
+                        error
+                    );
+                    storage.discard_file(&filename).await;
+                }
+            }
+        }
+        if stored < self.replicas {
+            log::warn!("Blob {} is stored with {} of {} replicas", hash, stored, self.replicas);
+        }
+        BlobHealth::Repaired
+    }
+}
+
+#[async_trait]
+impl FileStorageRemover for CombinedFileStorage {
+    async fn remove_file(&self, hash: &str) {
+        for index in 0..self.volumes.len() {
+            self.storage(index).remove_file(hash).await;
+            self.verified.lock().unwrap().remove(&(index, hash.to_string()));
+        }
+    }
+
+    async fn remove_staged_files(&self) {
+        for index in 0..self.volumes.len() {
+            self.storage(index).remove_staged_files().await;
+        }
+    }
+
+    async fn remove_parts(&self, upload_id: &str) {
+        for index in 0..self.volumes.len() {
+            self.storage(index).remove_parts(upload_id).await;
+        }
+    }
+}
+
+impl FileStorageManager for CombinedFileStorage {}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use tokio_stream::StreamExt;
+
+    use super::*;
+
+    struct VecStream(VecDeque<Bytes>);
+
+    impl Stream for VecStream {
+        type Item = io::Result<Bytes>;
+
+        fn poll_next(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>
+        ) -> Poll<Option<Self::Item>> {
+            Poll::Ready(self.0.pop_front().map(Ok))
+        }
+    }
+
+    impl FileStream for VecStream {}
+
+    async fn volumes(dir: &Path, count: usize) -> Vec<PathBuf> {
+        let volumes: Vec<PathBuf> = (0..count)
+            .map(|index| dir.join(format!("volume{}", index)))
+            .collect();
+        for volume in &volumes {
+            tokio::fs::create_dir_all(volume).await.unwrap();
+        }
+        volumes
+    }
+
+    async fn save(storage: &CombinedFileStorage, filename: &str, content: &'static [u8]) -> String {
+        let mut stream = VecStream(VecDeque::from([Bytes::from_static(content)]));
+        let hash = match storage.save_file(filename, None, None, &mut stream).await {
+            Ok(file_info) => file_info.hash,
+            Err(_) => panic!("save_file failed")
+        };
+        storage.commit_file(filename, &hash).await.unwrap();
+        hash
+    }
+
+    async fn read(
+        stream: Result<Box<dyn FileStream>, FileStorageError>
+    ) -> io::Result<Vec<u8>> {
+        let mut stream = stream.map_err(|error| io::Error::other(format!("{:?}", error)))?;
+        let mut buf = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            buf.extend_from_slice(&chunk?);
+        }
+        Ok(buf)
+    }
+
+    #[test]
+    fn test_new() {
+        let volumes = [PathBuf::from("a"), PathBuf::from("b")];
+        assert!(CombinedFileStorage::new(&[], None).is_err());
+        assert!(CombinedFileStorage::new(&volumes, Some(0)).is_err());
+        assert!(CombinedFileStorage::new(&volumes, Some(3)).is_err());
+        assert!(CombinedFileStorage::new(&volumes, Some(1)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_replica_fallback() {
+        let dir = tempfile::tempdir().unwrap();
+        let volumes = volumes(dir.path(), 3).await;
+        let storage = CombinedFileStorage::new(&volumes, None).unwrap();
+
+        let hash = save(&storage, "object", b"mirrored content").await;
+        assert_eq!(storage.replica_volumes(&hash).await, vec![0, 1, 2]);
+        assert!(storage.find_staged("object").await.is_none());
+
+        // Damaged replica is skipped
+        tokio::fs::write(storage.storage(0).blob_path(&hash), b"mirrored contenT").await.unwrap();
+        assert_eq!(read(storage.read_file(&hash).await).await.unwrap(), b"mirrored content");
+        assert_eq!(read(storage.read_file_range(&hash, 9, 4).await).await.unwrap(), b"cont");
+
+        // So is a missing one
+        tokio::fs::remove_dir_all(&volumes[1]).await.unwrap();
+        assert_eq!(read(storage.read_file(&hash).await).await.unwrap(), b"mirrored content");
+
+        tokio::fs::remove_dir_all(&volumes[2]).await.unwrap();
+        assert!(matches!(storage.read_file(&hash).await, Err(FileStorageError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_repair_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let volumes = volumes(dir.path(), 3).await;
+        let storage = CombinedFileStorage::new(&volumes, Some(2)).unwrap();
+
+        let hash = save(&storage, "object", b"repaired content").await;
+        assert_eq!(storage.repair_file(&hash).await, BlobHealth::Healthy);
+        let replicas = storage.replica_volumes(&hash).await;
+        assert_eq!(replicas.len(), 2);
+
+        // Read once, a cached replica is still hashed again
+        assert_eq!(read(storage.read_file(&hash).await).await.unwrap(), b"repaired content");
+        tokio::fs::write(storage.storage(replicas[0]).blob_path(&hash), b"rotten").await.unwrap();
+        assert_eq!(storage.repair_file(&hash).await, BlobHealth::Repaired);
+        assert_eq!(
+            tokio::fs::read(storage.storage(replicas[0]).blob_path(&hash)).await.unwrap(),
+            b"repaired content"
+        );
+
+        // Lost replica is copied to another volume
+        storage.storage(replicas[1]).remove_file(&hash).await;
+        assert_eq!(storage.repair_file(&hash).await, BlobHealth::Repaired);
+        assert_eq!(storage.replica_volumes(&hash).await.len(), 2);
+
+        for index in storage.replica_volumes(&hash).await {
+            tokio::fs::write(storage.storage(index).blob_path(&hash), b"rotten").await.unwrap();
+        }
+        assert_eq!(storage.repair_file(&hash).await, BlobHealth::Corrupted);
+        storage.remove_file(&hash).await;
+        assert_eq!(storage.repair_file(&hash).await, BlobHealth::Missing);
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_volume() {
+        let dir = tempfile::tempdir().unwrap();
+        let volumes = volumes(dir.path(), 3).await;
+        tokio::fs::remove_dir_all(&volumes[0]).await.unwrap();
+        let storage = CombinedFileStorage::new(&volumes, Some(1)).unwrap();
+
+        let hash = save(&storage, "object", b"single copy").await;
+        assert_eq!(storage.replica_volumes(&hash).await.len(), 1);
+        assert!(!volumes[0].exists());
+
+        // Same content is not stored again
+        assert_eq!(save(&storage, "again", b"single copy").await, hash);
+        assert_eq!(storage.replica_volumes(&hash).await.len(), 1);
+        assert_eq!(storage.get_files().await, vec![hash.clone()]);
+
+        storage.remove_file(&hash).await;
+        assert!(!storage.is_file_exists(&hash).await);
+
+        // The staged file is lost with its volume
+        assert!(matches!(
+            storage.commit_file("lost", &hash).await,
+            Err(FileStorageError::Interrupted(_))
+        ));
+        assert!(!storage.is_file_exists(&hash).await);
+    }
+
+    #[tokio::test]
+    async fn test_assemble_parts() {
+        let dir = tempfile::tempdir().unwrap();
+        let volumes = volumes(dir.path(), 2).await;
+        let storage = CombinedFileStorage::new(&volumes, None).unwrap();
+
+        for (number, content) in [(1, &b"first "[..]), (2, &b"second"[..])] {
+            let mut stream = VecStream(VecDeque::from([Bytes::copy_from_slice(content)]));
+            assert!(storage.save_part("upload", number, &mut stream).await.is_ok());
+        }
+        let index = storage.find_upload("upload").await.unwrap();
+        assert!(!storage.storage(1 - index).upload_path("upload").exists());
+
+        let file_info = match storage.assemble_parts("upload", &[1, 2], "object", None, None).await {
+            Ok(file_info) => file_info,
+            Err(_) => panic!("assemble_parts failed")
+        };
+        storage.commit_file("object", &file_info.hash).await.unwrap();
+        assert_eq!(storage.replica_volumes(&file_info.hash).await, vec![0, 1]);
+        assert_eq!(read(storage.read_file(&file_info.hash).await).await.unwrap(), b"first second");
+
+        storage.remove_parts("upload").await;
+        assert!(storage.find_upload("upload").await.is_none());
+        assert!(storage.assemble_parts("upload", &[1], "missing", None, None).await.is_err());
+    }
+}
@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::adapters::database::combined_file_storage::CombinedFileStorage;
+use crate::adapters::database::ec_file_storage::EcFileStorage;
+use crate::application::common::file_storage_manager::FileStorageManager;
+use crate::config::{ClusterMode, DiskConfig};
+
+/// Open the blob storage of the node on the volumes of the config
+///
+/// Blobs are copied between the volumes in the combined mode and split into
+/// Reed-Solomon shards in the `ec` mode. Volumes not fitting the mode are refused.
+pub fn open(config: &DiskConfig) -> Result<Arc<dyn FileStorageManager + Send + Sync>, String> {
+    let volumes: Vec<PathBuf> = config.volumes.iter().map(PathBuf::from).collect();
+    Ok(match config.mode {
+        ClusterMode::Combined => Arc::new(CombinedFileStorage::new(&volumes, config.replicas)?),
+        ClusterMode::Ec(parity_shards) => {
+            if config.replicas.is_some() {
+                return Err("Replicas are set only in the combined mode".to_string())
+            }
+            Arc::new(EcFileStorage::new(&volumes, parity_shards)?)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(mode: ClusterMode, volumes: &[PathBuf], replicas: Option<u8>) -> DiskConfig {
+        DiskConfig {
+            mode,
+            volumes: volumes.iter().map(|path| path.to_string_lossy().to_string()).collect(),
+            replicas
+        }
+    }
+
+    #[test]
+    fn test_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let volumes: Vec<PathBuf> = (0..3).map(|index| dir.path().join(index.to_string())).collect();
+
+        assert!(open(&config(ClusterMode::Combined, &volumes, None)).is_ok());
+        assert!(open(&config(ClusterMode::Combined, &volumes, Some(4))).is_err());
+        assert!(open(&config(ClusterMode::Combined, &[], None)).is_err());
+        assert!(open(&config(ClusterMode::Ec(1), &volumes, None)).is_ok());
+        assert!(open(&config(ClusterMode::Ec(3), &volumes, None)).is_err());
+        assert!(open(&config(ClusterMode::Ec(0), &volumes, None)).is_err());
+        assert!(open(&config(ClusterMode::Ec(1), &volumes, Some(2))).is_err());
+    }
+}
@@ -0,0 +1,851 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use reed_solomon_erasure::galois_8::ReedSolomon;
+use sha2::{Digest, Sha256};
+use tokio::fs::File;
+use tokio::io::{self, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, SeekFrom};
+use tokio::sync::mpsc;
+use tokio_stream::Stream;
+
+use crate::adapters::database::file_storage::{FileStorage, read_dir_names, sync_dir};
+use crate::application::common::file_storage_manager::{
+    BlobHealth,
+    FileStorageError,
+    FileStorageManager,
+    FileStorageReader,
+    FileStorageRemover,
+    FileStorageWriter
+};
+use crate::domain::id_generator::generate_id;
+use crate::domain::models::file_info::FileInfo;
+use crate::domain::models::file_stream::FileStream;
+
+const SHARDS_DIR: &str = "shards";
+/// Bytes of every shard in a stripe
+const CHUNK_SIZE: usize = 64 * 1024;
+const HEADER_SIZE: usize = 16;
+const SHARD_MAGIC: &[u8; 4] = b"TBEC";
+const CHANNEL_CAPACITY: usize = 4;
+
+/// Leading bytes of a shard file, any surviving shard tells the blob size
+struct ShardHeader {
+    index: u8,
+    data_shards: u8,
+    parity_shards: u8,
+    size: u64,
+}
+
+impl ShardHeader {
+    fn to_bytes(&self) -> [u8; HEADER_SIZE] {
+        let mut bytes = [0; HEADER_SIZE];
+        bytes[..4].copy_from_slice(SHARD_MAGIC);
+        bytes[4] = self.index;
+        bytes[5] = self.data_shards;
+        bytes[6] = self.parity_shards;
+        bytes[8..].copy_from_slice(&self.size.to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8; HEADER_SIZE]) -> Option<Self> {
+        if &bytes[..4] != SHARD_MAGIC {
+            return None
+        }
+        Some(Self {
+            index: bytes[4],
+            data_shards: bytes[5],
+            parity_shards: bytes[6],
+            size: u64::from_le_bytes(bytes[8..].try_into().unwrap()),
+        })
+    }
+}
+
+/// Blobs are split into stripes of `CHUNK_SIZE` bytes per data shard, the last one
+/// is padded with zeros. Every stripe is extended with parity chunks by Reed-Solomon
+/// coding and chunk `i` of each stripe is appended to the shard file on volume `i`:
+/// `<volume>/shards/9f/86/9f86d081...`.
+///
+/// A blob is readable while at most `parity_shards` of its shards are lost, a shard
+/// is lost when its volume is gone or the file is truncated or not a shard.
+///
+/// Staged files and parts of multipart uploads are plain files on the first volume,
+/// they are encoded only when committed.
+pub struct EcFileStorage {
+    volumes: Arc<[PathBuf]>,
+    data_shards: usize,
+    parity_shards: usize,
+    codec: Arc<ReedSolomon>,
+    staging: FileStorage,
+}
+
+impl EcFileStorage {
+    pub fn new(volumes: &[PathBuf], parity_shards: u8) -> Result<Self, String> {
+        let parity_shards = parity_shards as usize;
+        if parity_shards == 0 {
+            return Err("Erasure coding requires at least one parity shard".to_string())
+        }
+        if volumes.len() <= parity_shards {
+            return Err(format!(
+                "Erasure coding with {} parity shards requires more than {} volumes",
+                parity_shards,
+                parity_shards
+            ))
+        }
+        if volumes.len() > u8::MAX as usize {
+            return Err(format!("Erasure coding supports at most {} volumes", u8::MAX))
+        }
+
+        let data_shards = volumes.len() - parity_shards;
+        let codec = ReedSolomon::new(data_shards, parity_shards).map_err(
+            |error| error.to_string()
+        )?;
+        Ok(Self {
+            volumes: volumes.into(),
+            data_shards,
+            parity_shards,
+            codec: Arc::new(codec),
+            staging: FileStorage::new(&volumes[0]),
+        })
+    }
+
+    fn header(&self, index: usize, size: u64) -> ShardHeader {
+        ShardHeader {
+            index: index as u8,
+            data_shards: self.data_shards as u8,
+            parity_shards: self.parity_shards as u8,
+            size,
+        }
+    }
+
+    /// Encode the staged file into shard files
+    ///
+    /// Shards are written next to their final path and renamed once complete. The blob
+    /// is committed when at least `data_shards` shards are stored, the rest are logged.
+    async fn write_shards(&self, source: &Path, hash: &str) -> io::Result<()> {
+        let mut source = File::open(source).await?;
+        let size = source.metadata().await?.len();
+        let temp_name = format!("{}.{}.tmp", hash, generate_id(8));
+
+        let mut writers: Vec<Option<File>> = Vec::with_capacity(self.volumes.len());
+        for (index, volume) in self.volumes.iter().enumerate() {
+            let temp_path = shard_path(volume, hash).with_file_name(&temp_name);
+            writers.push(match create_shard(&temp_path, &self.header(index, size)).await {
+                Ok(file) => Some(file),
+                Err(error) => {
+                    log::warn!("Shard {} of blob {} is not written: {}", index, hash, error);
+                    None
+                }
+            });
+        }
+
+        let mut result = Ok(());
+        let mut stripe = vec![0; self.data_shards * CHUNK_SIZE];
+        for _ in 0..stripe_count(size, self.data_shards) {
+            result = self.write_stripe(&mut source, &mut stripe, &mut writers, hash).await;
+            if result.is_err() {
+                break;
+            }
+        }
+
+        let mut committed = Vec::new();
+        for (index, (volume, writer)) in self.volumes.iter().zip(writers).enumerate() {
+            let path = shard_path(volume, hash);
+            let temp_path = path.with_file_name(&temp_name);
+            let mut file = match writer {
+                Some(file) => file,
+                None => {
+                    tokio::fs::remove_file(&temp_path).await.ok();
+                    continue
+                }
+            };
+            if result.is_err() {
+                drop(file);
+                tokio::fs::remove_file(&temp_path).await.ok();
+                continue
+            }
+            let renamed = async {
+                file.flush().await?;
+                file.sync_all().await?;
+                tokio::fs::rename(&temp_path, &path).await?;
+                sync_dir(path.parent().unwrap()).await
+            }.await;
+            match renamed {
+                Ok(()) => committed.push(path),
+                Err(error) => {
+                    log::warn!("Shard {} of blob {} is not written: {}", index, hash, error);
+                    tokio::fs::remove_file(&temp_path).await.ok();
+                }
+            }
+        }
+        result?;
+
+        if committed.len() < self.data_shards {
+            for path in committed {
+                tokio::fs::remove_file(path).await.ok();
+            }
+            return Err(io::Error::other(
+                format!("Less than {} volumes are available", self.data_shards)
+            ))
+        }
+        Ok(())
+    }
+
+    async fn write_stripe(
+        &self,
+        source: &mut File,
+        stripe: &mut [u8],
+        writers: &mut [Option<File>],
+        hash: &str
+    ) -> io::Result<()> {
+        let filled = read_full(source, stripe).await?;
+        stripe[filled..].fill(0);
+
+        let mut chunks: Vec<Vec<u8>> = stripe.chunks(CHUNK_SIZE).map(<[u8]>::to_vec).collect();
+        chunks.resize(self.volumes.len(), vec![0; CHUNK_SIZE]);
+        self.codec.encode(&mut chunks).map_err(
+            |error| io::Error::other(error.to_string())
+        )?;
+
+        for (index, (writer, chunk)) in writers.iter_mut().zip(&chunks).enumerate() {
+            if let Some(file) = writer {
+                if let Err(error) = file.write_all(chunk).await {
+                    log::warn!("Shard {} of blob {} is not written: {}", index, hash, error);
+                    *writer = None;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn shard_path(volume: &Path, hash: &str) -> PathBuf {
+    volume.join(SHARDS_DIR).join(&hash[..2]).join(&hash[2..4]).join(hash)
+}
+
+fn stripe_count(size: u64, data_shards: usize) -> u64 {
+    size.div_ceil((data_shards * CHUNK_SIZE) as u64)
+}
+
+async fn create_shard(path: &Path, header: &ShardHeader) -> io::Result<File> {
+    tokio::fs::create_dir_all(path.parent().unwrap()).await?;
+    let mut file = File::create(path).await?;
+    file.write_all(&header.to_bytes()).await?;
+    Ok(file)
+}
+
+/// Read until the buffer is full or the file ends
+async fn read_full(file: &mut File, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match file.read(&mut buffer[filled..]).await? {
+            0 => break,
+            read => filled += read
+        }
+    }
+    Ok(filled)
+}
+
+/// Open the shards of a blob, a shard not matching the codec or the blob size is lost
+///
+/// * return: shard files by index and the blob size, unknown when no shard is found
+async fn open_shards(
+    volumes: &[PathBuf],
+    hash: &str,
+    data_shards: usize,
+    parity_shards: usize
+) -> (Vec<Option<File>>, Option<u64>) {
+    let mut shards = Vec::with_capacity(volumes.len());
+    let mut blob_size = None;
+    for (index, volume) in volumes.iter().enumerate() {
+        let opened = async {
+            let mut file = File::open(shard_path(volume, hash)).await?;
+            let mut bytes = [0; HEADER_SIZE];
+            file.read_exact(&mut bytes).await?;
+            let file_size = file.metadata().await?.len();
+            io::Result::Ok((file, ShardHeader::from_bytes(&bytes), file_size))
+        }.await;
+
+        let shard = match opened {
+            Ok((file, Some(header), file_size))
+                if header.index as usize == index
+                    && header.data_shards as usize == data_shards
+                    && header.parity_shards as usize == parity_shards
+                    && blob_size.is_none_or(|size| size == header.size)
+                    && file_size == HEADER_SIZE as u64
+                        + stripe_count(header.size, data_shards) * CHUNK_SIZE as u64 => {
+                blob_size = Some(header.size);
+                Some(file)
+            },
+            Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+            _ => {
+                log::warn!("Shard {} of blob {} is damaged", index, hash);
+                None
+            }
+        };
+        shards.push(shard);
+    }
+    (shards, blob_size)
+}
+
+/// Read the data chunks of a stripe, missing ones are reconstructed from parity
+///
+/// Data shards are preferred, parity shards are read only in place of lost ones.
+async fn read_stripe(
+    shards: &mut [Option<File>],
+    codec: &ReedSolomon,
+    data_shards: usize,
+    stripe: u64
+) -> io::Result<Vec<u8>> {
+    let position = HEADER_SIZE as u64 + stripe * CHUNK_SIZE as u64;
+    let mut chunks: Vec<Option<Vec<u8>>> = vec![None; shards.len()];
+    let mut read = 0;
+    for (index, shard) in shards.iter_mut().enumerate() {
+        if read == data_shards {
+            break;
+        }
+        let file = match shard {
+            Some(file) => file,
+            None => continue
+        };
+        let mut chunk = vec![0; CHUNK_SIZE];
+        let result = async {
+            file.seek(SeekFrom::Start(position)).await?;
+            file.read_exact(&mut chunk).await
+        }.await;
+        match result {
+            Ok(_) => {
+                chunks[index] = Some(chunk);
+                read += 1;
+            },
+            Err(error) => {
+                log::warn!("Shard {} is lost while reading: {}", index, error);
+                *shard = None;
+            }
+        }
+    }
+
+    if read < data_shards {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "Not enough shards are available to reconstruct the blob"
+        ))
+    }
+    if chunks[..data_shards].iter().any(Option::is_none) {
+        codec.reconstruct_data(&mut chunks).map_err(
+            |error| io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+        )?;
+    }
+    Ok(chunks.into_iter().take(data_shards).flat_map(Option::unwrap).collect())
+}
+
+/// Send the bytes of the blob in `offset..offset + length` stripe by stripe
+async fn read_blob(
+    volumes: &[PathBuf],
+    codec: &ReedSolomon,
+    hash: &str,
+    offset: u64,
+    length: u64,
+    sender: &mpsc::Sender<io::Result<Bytes>>
+) -> io::Result<()> {
+    let data_shards = codec.data_shard_count();
+    let (mut shards, size) = open_shards(
+        volumes,
+        hash,
+        data_shards,
+        codec.parity_shard_count()
+    ).await;
+    let size = size.ok_or_else(
+        || io::Error::new(io::ErrorKind::NotFound, format!("Blob {} not found", hash))
+    )?;
+
+    let end = offset.saturating_add(length).min(size);
+    if offset >= end {
+        return Ok(())
+    }
+    let stripe_size = (data_shards * CHUNK_SIZE) as u64;
+    for stripe in offset / stripe_size..=(end - 1) / stripe_size {
+        let data = read_stripe(&mut shards, codec, data_shards, stripe).await?;
+        let stripe_start = stripe * stripe_size;
+        let from = (offset.max(stripe_start) - stripe_start) as usize;
+        let to = (end.min(stripe_start + stripe_size) - stripe_start) as usize;
+        if sender.send(Ok(Bytes::from(data).slice(from..to))).await.is_err() {
+            // Reader is gone
+            return Ok(())
+        }
+    }
+    Ok(())
+}
+
+/// Decode the whole blob into a file, leaving out the shard `skip`
+///
+/// * return: sha256 of the decoded content
+async fn decode_blob(
+    volumes: &[PathBuf],
+    codec: &ReedSolomon,
+    hash: &str,
+    skip: Option<usize>,
+    target: &Path
+) -> io::Result<String> {
+    let data_shards = codec.data_shard_count();
+    let (mut shards, size) = open_shards(
+        volumes,
+        hash,
+        data_shards,
+        codec.parity_shard_count()
+    ).await;
+    let size = size.ok_or_else(
+        || io::Error::new(io::ErrorKind::NotFound, format!("Blob {} not found", hash))
+    )?;
+    if let Some(index) = skip {
+        shards[index] = None;
+    }
+
+    tokio::fs::create_dir_all(target.parent().unwrap()).await?;
+    let mut file = File::create(target).await?;
+    let mut hasher = Sha256::new();
+    let mut remaining = size;
+    for stripe in 0..stripe_count(size, data_shards) {
+        let data = read_stripe(&mut shards, codec, data_shards, stripe).await?;
+        let length = remaining.min(data.len() as u64) as usize;
+        hasher.update(&data[..length]);
+        file.write_all(&data[..length]).await?;
+        remaining -= length as u64;
+    }
+    file.flush().await?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Whether every shard of the blob is present and every stripe matches its parity
+async fn verify_parity(volumes: &[PathBuf], codec: &ReedSolomon, hash: &str) -> io::Result<bool> {
+    let data_shards = codec.data_shard_count();
+    let (mut shards, size) = open_shards(
+        volumes,
+        hash,
+        data_shards,
+        codec.parity_shard_count()
+    ).await;
+    let size = match size {
+        Some(size) if shards.iter().all(Option::is_some) => size,
+        _ => return Ok(false)
+    };
+
+    for stripe in 0..stripe_count(size, data_shards) {
+        let position = HEADER_SIZE as u64 + stripe * CHUNK_SIZE as u64;
+        let mut chunks = Vec::with_capacity(shards.len());
+        for file in shards.iter_mut().flatten() {
+            let mut chunk = vec![0; CHUNK_SIZE];
+            file.seek(SeekFrom::Start(position)).await?;
+            file.read_exact(&mut chunk).await?;
+            chunks.push(chunk);
+        }
+        if !codec.verify(&chunks).map_err(|error| io::Error::other(error.to_string()))? {
+            return Ok(false)
+        }
+    }
+    Ok(true)
+}
+
+/// Blob content decoded by a background task
+struct ShardStream {
+    receiver: mpsc::Receiver<io::Result<Bytes>>,
+}
+
+impl Stream for ShardStream {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>
+    ) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl FileStream for ShardStream {}
+
+#[async_trait]
+impl FileStorageReader for EcFileStorage {
+    async fn read_file(&self, hash: &str) -> Result<Box<dyn FileStream>, FileStorageError> {
+        self.read_file_range(hash, 0, u64::MAX).await
+    }
+
+    async fn read_file_range(
+        &self,
+        hash: &str,
+        offset: u64,
+        length: u64
+    ) -> Result<Box<dyn FileStream>, FileStorageError> {
+        if !self.is_file_exists(hash).await {
+            return Err(FileStorageError::NotFound(
+                format!("Not enough shards of blob {} are left", hash)
+            ))
+        }
+        let (sender, receiver) = mpsc::channel(CHANNEL_CAPACITY);
+        let volumes = self.volumes.clone();
+        let codec = self.codec.clone();
+        let hash = hash.to_string();
+        tokio::spawn(async move {
+            let result = read_blob(&volumes, &codec, &hash, offset, length, &sender).await;
+            if let Err(error) = result {
+                sender.send(Err(error)).await.ok();
+            }
+        });
+        Ok(Box::new(ShardStream { receiver }))
+    }
+
+    async fn is_file_exists(&self, hash: &str) -> bool {
+        let (shards, _) = open_shards(
+            &self.volumes,
+            hash,
+            self.data_shards,
+            self.parity_shards
+        ).await;
+        shards.iter().flatten().count() >= self.data_shards
+    }
+
+    async fn get_files(&self) -> Vec<String> {
+        let mut hashes = BTreeSet::new();
+        for volume in self.volumes.iter() {
+            let shards_path = volume.join(SHARDS_DIR);
+            for first in read_dir_names(&shards_path).await {
+                for second in read_dir_names(&shards_path.join(&first)).await {
+                    hashes.extend(
+                        read_dir_names(&shards_path.join(&first).join(&second)).await
+                            .into_iter()
+                            // Shards being written
+                            .filter(|name| !name.contains('.'))
+                    );
+                }
+            }
+        }
+        hashes.into_iter().collect()
+    }
+
+    /// Every volume holds a shard of the blob, the fullest one is the limit
+    async fn available_space(&self) -> u64 {
+        self.volumes.iter()
+            .filter_map(|volume| fs4::available_space(volume).ok())
+            .min()
+            .map_or(0, |space| space.saturating_mul(self.data_shards as u64))
+    }
+}
+
+#[async_trait]
+impl FileStorageWriter for EcFileStorage {
+    async fn save_file(
+        &self,
+        filename: &str,
+        content_type: Option<&str>,
+        size_range: Option<(u64, u64)>,
+        bytes: &mut dyn FileStream
+    ) -> Result<FileInfo, FileStorageError> {
+        self.staging.save_file(filename, content_type, size_range, bytes).await
+    }
+
+    async fn commit_file(&self, filename: &str, hash: &str) -> Result<(), FileStorageError> {
+        let path = self.staging.staging_path(filename);
+        let commit_error = |error: io::Error| FileStorageError::Interrupted(
+            format!("Blob {} is not committed: {}", hash, error)
+        );
+
+        // Same content is already stored, deduplicate
+        if !self.is_file_exists(hash).await {
+            self.write_shards(&path, hash).await.map_err(commit_error)?;
+        }
+        tokio::fs::remove_file(path).await.map_err(commit_error)
+    }
+
+    async fn discard_file(&self, filename: &str) {
+        self.staging.discard_file(filename).await
+    }
+
+    async fn save_part(
+        &self,
+        upload_id: &str,
+        number: u32,
+        bytes: &mut dyn FileStream
+    ) -> Result<FileInfo, FileStorageError> {
+        self.staging.save_part(upload_id, number, bytes).await
+    }
+
+    async fn assemble_parts(
+        &self,
+        upload_id: &str,
+        numbers: &[u32],
+        filename: &str,
+        content_type: Option<&str>,
+        size_range: Option<(u64, u64)>
+    ) -> Result<FileInfo, FileStorageError> {
+        self.staging.assemble_parts(upload_id, numbers, filename, content_type, size_range).await
+    }
+
+    /// Lost and damaged shards are encoded again from the content decoded from the rest
+    ///
+    /// A shard with rotten content still looks valid, so when the decoded content does
+    /// not match its hash, every shard in turn is left out of the decoding.
+    async fn repair_file(&self, hash: &str) -> BlobHealth {
+        let (shards, size) = open_shards(
+            &self.volumes,
+            hash,
+            self.data_shards,
+            self.parity_shards
+        ).await;
+        if size.is_none() {
+            return BlobHealth::Missing
+        }
+        let is_complete = shards.iter().all(Option::is_some);
+        drop(shards);
+
+        let path = self.staging.staging_path(&format!("{}.{}", hash, generate_id(8)));
+        let mut health = BlobHealth::Corrupted;
+        for skip in std::iter::once(None).chain((0..self.volumes.len()).map(Some)) {
+            match decode_blob(&self.volumes, &self.codec, hash, skip, &path).await {
+                Ok(actual) if actual == hash => (),
+                Ok(_) => continue,
+                // Too few shards are left without this one
+                Err(_) => continue
+            }
+            if skip.is_none() && is_complete
+                && verify_parity(&self.volumes, &self.codec, hash).await.unwrap_or(false) {
+                health = BlobHealth::Healthy;
+                break
+            }
+            health = match self.write_shards(&path, hash).await {
+                Ok(()) => BlobHealth::Repaired,
+                Err(error) => {
+                    log::warn!("Shards of blob {} are not restored: {}", hash, error);
+                    BlobHealth::Corrupted
+                }
+            };
+            break
+        }
+        tokio::fs::remove_file(&path).await.ok();
+        health
+    }
+}
+
+#[async_trait]
+impl FileStorageRemover for EcFileStorage {
+    async fn remove_file(&self, hash: &str) {
+        for volume in self.volumes.iter() {
+            let path = shard_path(volume, hash);
+            match tokio::fs::remove_file(&path).await {
+                Ok(_) => (),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+                // The volume may be gone, the blob is removed from the rest
+                Err(error) => {
+                    log::warn!("Failed to remove shard {}: {}", path.display(), error);
+                    continue
+                }
+            }
+
+            // Shard directories are removed only when empty
+            let shard = path.parent().unwrap();
+            if tokio::fs::remove_dir(shard).await.is_ok() {
+                tokio::fs::remove_dir(shard.parent().unwrap()).await.ok();
+            }
+        }
+    }
+
+    async fn remove_staged_files(&self) {
+        self.staging.remove_staged_files().await;
+
+        // Shards left by interrupted commits
+        for volume in self.volumes.iter() {
+            let shards_path = volume.join(SHARDS_DIR);
+            for first in read_dir_names(&shards_path).await {
+                for second in read_dir_names(&shards_path.join(&first)).await {
+                    let path = shards_path.join(&first).join(&second);
+                    for name in read_dir_names(&path).await {
+                        if name.ends_with(".tmp") {
+                            tokio::fs::remove_file(path.join(&name)).await.ok();
+                            log::debug!("Shard {} removed", name);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    async fn remove_parts(&self, upload_id: &str) {
+        self.staging.remove_parts(upload_id).await
+    }
+}
+
+impl FileStorageManager for EcFileStorage {}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use sha2::{Digest, Sha256};
+    use tokio_stream::StreamExt;
+
+    use super::*;
+
+    struct VecStream(VecDeque<Bytes>);
+
+    impl Stream for VecStream {
+        type Item = io::Result<Bytes>;
+
+        fn poll_next(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>
+        ) -> Poll<Option<Self::Item>> {
+            Poll::Ready(self.0.pop_front().map(Ok))
+        }
+    }
+
+    impl FileStream for VecStream {}
+
+    fn volumes(dir: &Path, count: usize) -> Vec<PathBuf> {
+        (0..count).map(|index| dir.join(format!("volume{}", index))).collect()
+    }
+
+    /// Content spanning several stripes, with a partial last one
+    fn content(size: usize) -> Vec<u8> {
+        (0..size).map(|i| (i * 31 % 251) as u8).collect()
+    }
+
+    async fn save(storage: &EcFileStorage, filename: &str, content: &[u8]) -> String {
+        let mut stream = VecStream(
+            content.chunks(100_000).map(Bytes::copy_from_slice).collect()
+        );
+        let hash = match storage.save_file(filename, None, None, &mut stream).await {
+            Ok(file_info) => file_info.hash,
+            Err(_) => panic!("save_file failed")
+        };
+        storage.commit_file(filename, &hash).await.unwrap();
+        hash
+    }
+
+    async fn read(
+        stream: Result<Box<dyn FileStream>, FileStorageError>
+    ) -> io::Result<Vec<u8>> {
+        let mut stream = stream.map_err(|error| io::Error::other(format!("{:?}", error)))?;
+        let mut buf = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            buf.extend_from_slice(&chunk?);
+        }
+        Ok(buf)
+    }
+
+    #[test]
+    fn test_new() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(EcFileStorage::new(&volumes(dir.path(), 3), 0).is_err());
+        assert!(EcFileStorage::new(&volumes(dir.path(), 2), 2).is_err());
+        assert!(EcFileStorage::new(&volumes(dir.path(), 3), 2).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_lost_volumes() {
+        let dir = tempfile::tempdir().unwrap();
+        let volumes = volumes(dir.path(), 5);
+        let storage = EcFileStorage::new(&volumes, 2).unwrap();
+
+        let content = content(3 * CHUNK_SIZE * 2 + 12345);
+        let hash = save(&storage, "object", &content).await;
+        assert_eq!(hash, format!("{:x}", Sha256::digest(&content)));
+        assert_eq!(storage.get_files().await, vec![hash.clone()]);
+        for volume in &volumes {
+            assert!(shard_path(volume, &hash).exists());
+        }
+        assert_eq!(read(storage.read_file(&hash).await).await.unwrap(), content);
+
+        // A data shard and a parity shard are lost
+        tokio::fs::remove_dir_all(&volumes[1]).await.unwrap();
+        tokio::fs::remove_dir_all(&volumes[4]).await.unwrap();
+        assert!(storage.is_file_exists(&hash).await);
+        assert_eq!(read(storage.read_file(&hash).await).await.unwrap(), content);
+
+        // Range crossing stripes
+        let offset = 3 * CHUNK_SIZE as u64 - 10;
+        let range = read(storage.read_file_range(&hash, offset, 100).await).await.unwrap();
+        assert_eq!(range, &content[offset as usize..offset as usize + 100]);
+
+        tokio::fs::remove_dir_all(&volumes[0]).await.unwrap();
+        assert!(!storage.is_file_exists(&hash).await);
+        assert!(matches!(storage.read_file(&hash).await, Err(FileStorageError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_damaged_shards() {
+        let dir = tempfile::tempdir().unwrap();
+        let volumes = volumes(dir.path(), 3);
+        let storage = EcFileStorage::new(&volumes, 1).unwrap();
+
+        let content = content(CHUNK_SIZE + 1);
+        let hash = save(&storage, "object", &content).await;
+
+        // Truncated shard is lost, the blob is reconstructed from the others
+        let file = std::fs::OpenOptions::new().write(true).open(shard_path(&volumes[0], &hash)).unwrap();
+        file.set_len(HEADER_SIZE as u64 + 10).unwrap();
+        assert_eq!(read(storage.read_file(&hash).await).await.unwrap(), content);
+
+        // Shard of another volume
+        std::fs::copy(shard_path(&volumes[2], &hash), shard_path(&volumes[1], &hash)).unwrap();
+        assert!(!storage.is_file_exists(&hash).await);
+    }
+
+    #[tokio::test]
+    async fn test_repair_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let volumes = volumes(dir.path(), 4);
+        let storage = EcFileStorage::new(&volumes, 1).unwrap();
+
+        let content = content(3 * CHUNK_SIZE + 100);
+        let hash = save(&storage, "object", &content).await;
+        assert_eq!(storage.repair_file(&hash).await, BlobHealth::Healthy);
+
+        // Lost shard is written again
+        tokio::fs::remove_file(shard_path(&volumes[2], &hash)).await.unwrap();
+        assert_eq!(storage.repair_file(&hash).await, BlobHealth::Repaired);
+        assert!(shard_path(&volumes[2], &hash).exists());
+        assert_eq!(storage.repair_file(&hash).await, BlobHealth::Healthy);
+
+        // Rotten data and parity shards keep their size and header
+        for index in [1, 3] {
+            let path = shard_path(&volumes[index], &hash);
+            let mut bytes = std::fs::read(&path).unwrap();
+            bytes[HEADER_SIZE + 7] ^= 0xff;
+            std::fs::write(&path, bytes).unwrap();
+            assert_eq!(storage.repair_file(&hash).await, BlobHealth::Repaired);
+            assert_eq!(storage.repair_file(&hash).await, BlobHealth::Healthy);
+        }
+        assert_eq!(read(storage.read_file(&hash).await).await.unwrap(), content);
+
+        tokio::fs::remove_dir_all(&volumes[0]).await.unwrap();
+        tokio::fs::remove_dir_all(&volumes[1]).await.unwrap();
+        assert_eq!(storage.repair_file(&hash).await, BlobHealth::Corrupted);
+        storage.remove_file(&hash).await;
+        assert_eq!(storage.repair_file(&hash).await, BlobHealth::Missing);
+    }
+
+    #[tokio::test]
+    async fn test_deduplication_and_removal() {
+        let dir = tempfile::tempdir().unwrap();
+        let volumes = volumes(dir.path(), 3);
+        let storage = EcFileStorage::new(&volumes, 1).unwrap();
+
+        let hash = save(&storage, "first", b"same content").await;
+        assert_eq!(save(&storage, "second", b"same content").await, hash);
+        assert!(storage.get_files().await == vec![hash.clone()]);
+        assert_eq!(read(storage.read_file(&hash).await).await.unwrap(), b"same content");
+
+        let empty = save(&storage, "empty", b"").await;
+        assert!(read(storage.read_file(&empty).await).await.unwrap().is_empty());
+
+        // Volume lost before removal
+        tokio::fs::remove_dir_all(&volumes[2]).await.unwrap();
+        storage.remove_file(&hash).await;
+        assert!(!storage.is_file_exists(&hash).await);
+        assert_eq!(storage.get_files().await, vec![empty]);
+    }
+}
@@ -0,0 +1,171 @@
+use async_trait::async_trait;
+use sqlx::{FromRow, SqlitePool};
+use sqlx::types::Json;
+
+use crate::application::common::metadata_gateway::{
+    MetadataLogGateway as MetadataLogGatewayTrait,
+    MetadataLogReader,
+    MetadataLogRemover,
+    MetadataLogWriter
+};
+use crate::domain::models::metadata::{HardState, LogEntry, LogIndex, MetadataCommand};
+
+#[derive(FromRow)]
+struct LogEntryModel {
+    index: i64,
+    term: i64,
+    command: Json<MetadataCommand>
+}
+
+#[derive(FromRow)]
+struct HardStateModel {
+    term: i64,
+    voted_for: Option<String>,
+    applied: i64
+}
+
+pub struct MetadataLogGateway {
+    db: SqlitePool,
+}
+
+impl MetadataLogGateway {
+    pub fn new(db: SqlitePool) -> Self {
+        MetadataLogGateway { db }
+    }
+}
+
+#[async_trait]
+impl MetadataLogReader for MetadataLogGateway {
+    async fn get_hard_state(&self) -> HardState {
+        sqlx::query_as::<_, HardStateModel>("SELECT term, voted_for, applied FROM metadata_state")
+            .fetch_optional(&self.db)
+            .await
+            .unwrap()
+            .map(|state| HardState {
+                term: state.term as u64,
+                voted_for: state.voted_for,
+                applied: state.applied as u64
+            })
+            .unwrap_or_default()
+    }
+
+    async fn get_entry(&self, index: LogIndex) -> Option<LogEntry> {
+        sqlx::query_as::<_, LogEntryModel>(r#"SELECT * FROM metadata_log WHERE "index" = ?"#)
+            .bind(index as i64)
+            .fetch_optional(&self.db)
+            .await
+            .unwrap()
+            .map(map_log_entry_model_to_domain)
+    }
+
+    async fn get_entries(&self, from: LogIndex, limit: u64) -> Vec<LogEntry> {
+        sqlx::query_as::<_, LogEntryModel>(
+            r#"SELECT * FROM metadata_log WHERE "index" >= ? ORDER BY "index" LIMIT ?"#
+        )
+            .bind(from as i64)
+            .bind(limit as i64)
+            .fetch_all(&self.db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(map_log_entry_model_to_domain)
+            .collect()
+    }
+
+    async fn get_last_entry(&self) -> Option<LogEntry> {
+        sqlx::query_as::<_, LogEntryModel>(r#"SELECT * FROM metadata_log ORDER BY "index" DESC LIMIT 1"#)
+            .fetch_optional(&self.db)
+            .await
+            .unwrap()
+            .map(map_log_entry_model_to_domain)
+    }
+}
+
+#[async_trait]
+impl MetadataLogWriter for MetadataLogGateway {
+    async fn save_hard_state(&self, data: &HardState) {
+        sqlx::query(
+            "INSERT INTO metadata_state (id, term, voted_for, applied) VALUES (0, ?, ?, ?)
+            ON CONFLICT (id) DO UPDATE SET
+                term = excluded.term,
+                voted_for = excluded.voted_for,
+                applied = excluded.applied"
+        )
+            .bind(data.term as i64)
+            .bind(&data.voted_for)
+            .bind(data.applied as i64)
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+
+    async fn append_entries(&self, entries: &[LogEntry]) {
+        let mut transaction = self.db.begin().await.unwrap();
+        for entry in entries {
+            sqlx::query(r#"INSERT INTO metadata_log ("index", term, command) VALUES (?, ?, ?)"#)
+                .bind(entry.index as i64)
+                .bind(entry.term as i64)
+                .bind(Json(&entry.command))
+                .execute(&mut *transaction)
+                .await
+                .unwrap();
+        }
+        transaction.commit().await.unwrap();
+    }
+}
+
+#[async_trait]
+impl MetadataLogRemover for MetadataLogGateway {
+    async fn truncate_entries(&self, from: LogIndex) {
+        sqlx::query(r#"DELETE FROM metadata_log WHERE "index" >= ?"#)
+            .bind(from as i64)
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+}
+
+fn map_log_entry_model_to_domain(entry: LogEntryModel) -> LogEntry {
+    LogEntry {
+        index: entry.index as u64,
+        term: entry.term as u64,
+        command: entry.command.0
+    }
+}
+
+impl MetadataLogGatewayTrait for MetadataLogGateway {}
+
+#[cfg(test)]
+mod tests {
+    use crate::adapters::database::sqlite::connect_in_memory;
+
+    use super::*;
+
+    fn entry(index: LogIndex, term: u64) -> LogEntry {
+        LogEntry { index, term, command: MetadataCommand::RemoveBox { box_id: format!("box-{}", index) } }
+    }
+
+    #[tokio::test]
+    async fn test_metadata_log() {
+        let gateway = MetadataLogGateway::new(connect_in_memory().await);
+        assert_eq!(gateway.get_hard_state().await, HardState::default());
+        let state = HardState { term: 3, voted_for: Some("a".to_string()), applied: 1 };
+        gateway.save_hard_state(&state).await;
+        assert_eq!(gateway.get_hard_state().await, state);
+
+        gateway.append_entries(&[entry(1, 1), entry(2, 1), entry(3, 2)]).await;
+        assert_eq!(gateway.get_last_entry().await.unwrap().index, 3);
+        let entries = gateway.get_entries(2, 10).await;
+        assert_eq!(entries.iter().map(|entry| entry.index).collect::<Vec<_>>(), vec![2, 3]);
+        assert!(matches!(
+            gateway.get_entry(2).await.unwrap().command,
+            MetadataCommand::RemoveBox { box_id } if box_id == "box-2"
+        ));
+
+        // A conflicting suffix is replaced by the entries of the leader
+        gateway.truncate_entries(2).await;
+        gateway.append_entries(&[entry(2, 3)]).await;
+        assert_eq!(gateway.get_last_entry().await.unwrap().term, 3);
+        assert!(gateway.get_entry(3).await.is_none());
+    }
+}
@@ -0,0 +1,255 @@
+use std::fmt;
+
+use sqlx::migrate::{Migrate, MigrateError, Migration, Migrator};
+use sqlx::SqlitePool;
+
+/// Schema migrations from `migrations/`, built into the binary
+///
+/// Every migration has an up and a down script, so the schema can be taken back to any release.
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// Version of a database without any migration applied
+pub const EMPTY_SCHEMA: i64 = 0;
+
+#[derive(Debug)]
+pub enum MigrationError {
+    /// The database was migrated by a newer release, this binary must not touch it
+    SchemaTooNew { version: i64, latest: i64 },
+    /// A migration was applied by another build and is unknown to this one
+    UnknownMigration(i64),
+    /// An applied migration differs from the one built into the binary
+    ModifiedMigration(i64),
+    UnknownTarget(i64),
+    Failed(MigrateError)
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::SchemaTooNew { version, latest } => write!(
+                f,
+                "database schema version {} is newer than the latest known to this release ({})",
+                version,
+                latest
+            ),
+            MigrationError::UnknownMigration(version) => write!(
+                f,
+                "migration {} is applied to the database but unknown to this release",
+                version
+            ),
+            MigrationError::ModifiedMigration(version) => write!(
+                f,
+                "migration {} is applied to the database but differs from the one of this release",
+                version
+            ),
+            MigrationError::UnknownTarget(version) => write!(f, "there is no migration {}", version),
+            MigrationError::Failed(error) => write!(f, "{}", error)
+        }
+    }
+}
+
+impl From<MigrateError> for MigrationError {
+    fn from(error: MigrateError) -> Self {
+        MigrationError::Failed(error)
+    }
+}
+
+impl From<sqlx::Error> for MigrationError {
+    fn from(error: sqlx::Error) -> Self {
+        MigrationError::Failed(MigrateError::Execute(error))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Up,
+    Down
+}
+
+/// Migration as it stands in the database
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub applied: bool
+}
+
+/// Migration to be applied or reverted
+#[derive(Debug, Clone)]
+pub struct MigrationStep {
+    pub version: i64,
+    pub description: String,
+    pub direction: Direction
+}
+
+/// Latest schema version known to this release
+pub fn latest_version() -> i64 {
+    MIGRATOR.iter()
+        .filter(|migration| migration.migration_type.is_up_migration())
+        .map(|migration| migration.version)
+        .max()
+        .unwrap_or(EMPTY_SCHEMA)
+}
+
+/// Migrations of this release and whether each one is applied
+pub async fn status(db: &SqlitePool) -> Result<Vec<MigrationStatus>, MigrationError> {
+    let applied = applied_versions(db).await?;
+    Ok(up_migrations()
+        .map(|migration| MigrationStatus {
+            version: migration.version,
+            description: migration.description.to_string(),
+            applied: applied.contains(&migration.version)
+        })
+        .collect())
+}
+
+/// Steps taking the schema to the target version, the latest one when not set
+///
+/// Migrations above the target are reverted newest first, pending ones up to it are applied oldest first.
+pub async fn plan(db: &SqlitePool, target: Option<i64>) -> Result<Vec<MigrationStep>, MigrationError> {
+    let applied = applied_versions(db).await?;
+    let target = target.unwrap_or_else(latest_version);
+    if target != EMPTY_SCHEMA && !up_migrations().any(|migration| migration.version == target) {
+        return Err(MigrationError::UnknownTarget(target))
+    }
+
+    let mut steps: Vec<MigrationStep> = down_migrations()
+        .rev()
+        .filter(|migration| migration.version > target && applied.contains(&migration.version))
+        .map(|migration| step(migration, Direction::Down))
+        .collect();
+    steps.extend(up_migrations()
+        .filter(|migration| migration.version <= target && !applied.contains(&migration.version))
+        .map(|migration| step(migration, Direction::Up)));
+    Ok(steps)
+}
+
+/// Take the schema to the target version, the latest one when not set
+///
+/// * return: steps that were made
+pub async fn migrate(db: &SqlitePool, target: Option<i64>) -> Result<Vec<MigrationStep>, MigrationError> {
+    let steps = plan(db, target).await?;
+    let mut connection = db.acquire().await?;
+    for entry in &steps {
+        let migration = MIGRATOR.iter()
+            .find(|migration| migration.version == entry.version && match entry.direction {
+                Direction::Up => migration.migration_type.is_up_migration(),
+                Direction::Down => migration.migration_type.is_down_migration()
+            })
+            .expect("Planned migration is not built into the binary");
+        match entry.direction {
+            Direction::Up => connection.apply(migration).await?,
+            Direction::Down => connection.revert(migration).await?
+        };
+        log::info!(
+            "Migration {} {} is {}",
+            entry.version,
+            entry.description,
+            match entry.direction {
+                Direction::Up => "applied",
+                Direction::Down => "reverted"
+            }
+        );
+    }
+    Ok(steps)
+}
+
+/// Versions applied to the database, checked against the migrations of this release
+async fn applied_versions(db: &SqlitePool) -> Result<Vec<i64>, MigrationError> {
+    let mut connection = db.acquire().await?;
+    connection.ensure_migrations_table().await?;
+    if let Some(version) = connection.dirty_version().await? {
+        return Err(MigrateError::Dirty(version).into())
+    }
+
+    let applied = connection.list_applied_migrations().await?;
+    let latest = latest_version();
+    for migration in &applied {
+        match up_migrations().find(|known| known.version == migration.version) {
+            Some(known) if known.checksum != migration.checksum => {
+                return Err(MigrationError::ModifiedMigration(migration.version))
+            },
+            Some(_) => (),
+            None if migration.version > latest => return Err(MigrationError::SchemaTooNew {
+                version: applied.iter().map(|migration| migration.version).max().unwrap(),
+                latest
+            }),
+            None => return Err(MigrationError::UnknownMigration(migration.version))
+        }
+    }
+    Ok(applied.into_iter().map(|migration| migration.version).collect())
+}
+
+fn up_migrations() -> impl DoubleEndedIterator<Item = &'static Migration> {
+    MIGRATOR.iter().filter(|migration| migration.migration_type.is_up_migration())
+}
+
+fn down_migrations() -> impl DoubleEndedIterator<Item = &'static Migration> {
+    MIGRATOR.iter().filter(|migration| migration.migration_type.is_down_migration())
+}
+
+fn step(migration: &Migration, direction: Direction) -> MigrationStep {
+    MigrationStep {
+        version: migration.version,
+        description: migration.description.to_string(),
+        direction
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adapters::database::sqlite::open_in_memory;
+
+    use super::*;
+
+    async fn has_table(db: &SqlitePool, name: &str) -> bool {
+        sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?)")
+            .bind(name)
+            .fetch_one(db)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_migrate() {
+        let db = open_in_memory().await;
+        let latest = latest_version();
+
+        // A dry run changes nothing
+        let steps = plan(&db, None).await.unwrap();
+        assert_eq!(steps.len(), status(&db).await.unwrap().len());
+        assert!(steps.iter().all(|step| step.direction == Direction::Up));
+        assert!(!has_table(&db, "users").await);
+
+        migrate(&db, None).await.unwrap();
+        assert!(status(&db).await.unwrap().iter().all(|migration| migration.applied));
+        assert!(has_table(&db, "users").await);
+        assert!(plan(&db, Some(latest)).await.unwrap().is_empty());
+
+        let steps = migrate(&db, Some(EMPTY_SCHEMA)).await.unwrap();
+        assert_eq!(steps.first().unwrap().version, latest);
+        assert!(steps.iter().all(|step| step.direction == Direction::Down));
+        assert!(!has_table(&db, "users").await);
+
+        assert!(matches!(plan(&db, Some(latest + 1)).await, Err(MigrationError::UnknownTarget(_))));
+    }
+
+    #[tokio::test]
+    async fn test_schema_too_new() {
+        let db = open_in_memory().await;
+        migrate(&db, None).await.unwrap();
+        sqlx::query(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+            VALUES (?, 'future', TRUE, x'00', 0)"
+        )
+            .bind(latest_version() + 1)
+            .execute(&db)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            migrate(&db, None).await,
+            Err(MigrationError::SchemaTooNew { version, latest }) if version == latest + 1
+        ));
+    }
+}
@@ -1,9 +1,21 @@
-pub mod models;
+pub mod sqlite;
+pub mod migration;
 pub mod user_db;
 pub mod session_db;
 pub mod role_db;
-pub mod service_db;
 pub mod permission_db;
 pub mod init_state_db;
-pub mod access_log_db;
-pub mod file_storage;
\ No newline at end of file
+pub mod box_db;
+pub mod object_db;
+pub mod access_key_db;
+pub mod api_key_db;
+pub mod upload_db;
+pub mod node_db;
+pub mod replication_db;
+pub mod placement_db;
+pub mod scrub_db;
+pub mod metadata_db;
+pub mod file_storage;
+pub mod ec_file_storage;
+pub mod combined_file_storage;
+pub mod disk;
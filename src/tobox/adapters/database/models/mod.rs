@@ -1,14 +0,0 @@
-//! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.15
-
-
-pub mod permissions;
-pub mod role_permissions;
-pub mod role_user;
-pub mod roles;
-pub mod sea_orm_active_enums;
-pub mod services;
-pub mod sessions;
-pub mod users;
-pub mod default_role;
-pub mod init_state;
-pub mod access_logs;
@@ -1,55 +0,0 @@
-//! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.15
-
-use chrono::{DateTime, Utc};
-use sea_orm::entity::prelude::*;
-
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
-#[sea_orm(table_name = "permission")]
-pub struct Model {
-    #[sea_orm(primary_key, auto_increment = false)]
-    pub id: Uuid,
-    pub text_id: String,
-    pub service_id: Uuid,
-    #[sea_orm(unique)]
-    pub title: String,
-    pub description: Option<String>,
-    pub created_at: DateTime<Utc>,
-    pub updated_at: Option<DateTime<Utc>>,
-}
-
-#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {
-    #[sea_orm(has_many = "super::role_permissions::Entity")]
-    RolePermissions,
-    #[sea_orm(
-        belongs_to = "super::services::Entity",
-        from = "Column::ServiceId",
-        to = "super::services::Column::Id",
-        on_update = "NoAction",
-        on_delete = "Cascade"
-    )]
-    Services,
-}
-
-impl Related<super::role_permissions::Entity> for Entity {
-    fn to() -> RelationDef {
-        Relation::RolePermissions.def()
-    }
-}
-
-impl Related<super::services::Entity> for Entity {
-    fn to() -> RelationDef {
-        Relation::Services.def()
-    }
-}
-
-impl Related<super::roles::Entity> for Entity {
-    fn to() -> RelationDef {
-        super::role_permissions::Relation::Roles.def()
-    }
-    fn via() -> Option<RelationDef> {
-        Some(super::role_permissions::Relation::Permissions.def().rev())
-    }
-}
-
-impl ActiveModelBehavior for ActiveModel {}
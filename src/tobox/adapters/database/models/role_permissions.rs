@@ -1,46 +0,0 @@
-//! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.15
-
-use sea_orm::entity::prelude::*;
-
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
-#[sea_orm(table_name = "role_permissions")]
-pub struct Model {
-    #[sea_orm(primary_key, auto_increment = false)]
-    pub role_id: Uuid,
-    #[sea_orm(primary_key, auto_increment = false)]
-    pub permission_id: Uuid,
-}
-
-#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {
-    #[sea_orm(
-        belongs_to = "super::permission::Entity",
-        from = "Column::PermissionId",
-        to = "super::permission::Column::Id",
-        on_update = "NoAction",
-        on_delete = "Cascade"
-    )]
-    Permissions,
-    #[sea_orm(
-        belongs_to = "super::role::Entity",
-        from = "Column::RoleId",
-        to = "super::role::Column::Id",
-        on_update = "NoAction",
-        on_delete = "Cascade"
-    )]
-    Roles,
-}
-
-impl Related<super::permissions::Entity> for Entity {
-    fn to() -> RelationDef {
-        Relation::Permissions.def()
-    }
-}
-
-impl Related<super::roles::Entity> for Entity {
-    fn to() -> RelationDef {
-        Relation::Roles.def()
-    }
-}
-
-impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,115 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+use sqlx::types::Json;
+
+use crate::application::common::node_gateway::{
+    NodeGateway as NodeGatewayTrait,
+    NodeReader,
+    NodeRemover,
+    NodeWriter
+};
+use crate::domain::models::node::{Node, NodeId, NodeStatus};
+
+#[derive(FromRow)]
+struct NodeModel {
+    id: String,
+    address: String,
+    version: String,
+    capacity: i64,
+    status: Json<NodeStatus>,
+    last_heartbeat: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>
+}
+
+pub struct NodeGateway {
+    db: SqlitePool,
+}
+
+impl NodeGateway {
+    pub fn new(db: SqlitePool) -> Self {
+        NodeGateway { db }
+    }
+}
+
+#[async_trait]
+impl NodeReader for NodeGateway {
+    async fn get_node(&self, node_id: &NodeId) -> Option<Node> {
+        sqlx::query_as::<_, NodeModel>("SELECT * FROM nodes WHERE id = ?")
+            .bind(node_id)
+            .fetch_optional(&self.db)
+            .await
+            .unwrap()
+            .map(map_node_model_to_domain)
+    }
+
+    async fn get_node_by_address(&self, address: &str) -> Option<Node> {
+        sqlx::query_as::<_, NodeModel>("SELECT * FROM nodes WHERE address = ?")
+            .bind(address)
+            .fetch_optional(&self.db)
+            .await
+            .unwrap()
+            .map(map_node_model_to_domain)
+    }
+
+    async fn get_nodes(&self) -> Vec<Node> {
+        sqlx::query_as::<_, NodeModel>("SELECT * FROM nodes ORDER BY id")
+            .fetch_all(&self.db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(map_node_model_to_domain)
+            .collect()
+    }
+}
+
+#[async_trait]
+impl NodeWriter for NodeGateway {
+    async fn save_node(&self, data: &Node) {
+        sqlx::query(
+            "INSERT INTO nodes (id, address, version, capacity, status, last_heartbeat, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (id) DO UPDATE SET
+                address = excluded.address,
+                version = excluded.version,
+                capacity = excluded.capacity,
+                status = excluded.status,
+                last_heartbeat = excluded.last_heartbeat"
+        )
+            .bind(&data.id)
+            .bind(&data.address)
+            .bind(&data.version)
+            .bind(data.capacity as i64)
+            .bind(Json(&data.status))
+            .bind(data.last_heartbeat)
+            .bind(data.created_at)
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+}
+
+#[async_trait]
+impl NodeRemover for NodeGateway {
+    async fn remove_node(&self, node_id: &NodeId) {
+        sqlx::query("DELETE FROM nodes WHERE id = ?")
+            .bind(node_id)
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+}
+
+fn map_node_model_to_domain(node: NodeModel) -> Node {
+    Node {
+        id: node.id,
+        address: node.address,
+        version: node.version,
+        capacity: node.capacity as u64,
+        status: node.status.0,
+        last_heartbeat: node.last_heartbeat,
+        created_at: node.created_at
+    }
+}
+
+impl NodeGatewayTrait for NodeGateway {}
@@ -0,0 +1,354 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, QueryBuilder, Sqlite, SqlitePool};
+
+use crate::application::common::object_gateway::{
+    ObjectCursor,
+    ObjectFilter,
+    ObjectGateway as ObjectGatewayTrait,
+    ObjectReader,
+    ObjectRemover,
+    ObjectWriter
+};
+use crate::domain::models::object::{Object as ObjectDomain, ObjectId};
+use crate::domain::models::r#box::BoxId;
+
+#[derive(FromRow)]
+struct ObjectModel {
+    id: String,
+    box_id: String,
+    name: Option<String>,
+    path: Option<String>,
+    hash: String,
+    size: i64,
+    content_type: String,
+    created_at: DateTime<Utc>,
+    updated_at: Option<DateTime<Utc>>
+}
+
+#[derive(FromRow)]
+struct ObjectMetadataModel {
+    object_id: String,
+    key: String,
+    value: String
+}
+
+pub struct ObjectGateway {
+    db: SqlitePool,
+}
+
+impl ObjectGateway {
+    pub fn new(db: SqlitePool) -> Self {
+        ObjectGateway { db }
+    }
+
+    /// Attach the metadata of the objects, loaded by a single query
+    async fn with_metadata(&self, objects: Vec<ObjectModel>) -> Vec<ObjectDomain> {
+        let mut objects: Vec<ObjectDomain> = objects.into_iter().map(map_object_model_to_domain).collect();
+        if objects.is_empty() {
+            return objects
+        }
+
+        let object_ids: Vec<&ObjectId> = objects.iter().map(|object| &object.id).collect();
+        let rows = sqlx::query_as::<_, ObjectMetadataModel>(
+            "SELECT * FROM object_metadata WHERE object_id IN (SELECT value FROM json_each(?))"
+        )
+            .bind(sqlx::types::Json(object_ids))
+            .fetch_all(&self.db)
+            .await
+            .unwrap();
+
+        let mut metadata: HashMap<String, HashMap<String, String>> = HashMap::new();
+        for row in rows {
+            metadata.entry(row.object_id).or_default().insert(row.key, row.value);
+        }
+        for object in objects.iter_mut() {
+            object.metadata = metadata.remove(&object.id).unwrap_or_default();
+        }
+        objects
+    }
+}
+
+#[async_trait]
+impl ObjectReader for ObjectGateway {
+    async fn get_object(&self, object_id: &ObjectId) -> Option<ObjectDomain> {
+        let object = sqlx::query_as::<_, ObjectModel>("SELECT * FROM objects WHERE id = ?")
+            .bind(object_id)
+            .fetch_optional(&self.db)
+            .await
+            .unwrap()?;
+        self.with_metadata(vec![object]).await.pop()
+    }
+
+    async fn get_objects(&self) -> Vec<ObjectDomain> {
+        let objects = sqlx::query_as::<_, ObjectModel>("SELECT * FROM objects ORDER BY id")
+            .fetch_all(&self.db)
+            .await
+            .unwrap();
+        self.with_metadata(objects).await
+    }
+
+    async fn get_objects_range(&self, limit: &u64, offset: &u64) -> Vec<ObjectDomain> {
+        let objects = sqlx::query_as::<_, ObjectModel>("SELECT * FROM objects ORDER BY id LIMIT ? OFFSET ?")
+            .bind(*limit as i64)
+            .bind(*offset as i64)
+            .fetch_all(&self.db)
+            .await
+            .unwrap();
+        self.with_metadata(objects).await
+    }
+
+    async fn count_objects_by_hash(&self, hash: &str) -> u64 {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM objects WHERE hash = ?")
+            .bind(hash)
+            .fetch_one(&self.db)
+            .await
+            .unwrap() as u64
+    }
+
+    async fn get_objects_by_name(
+        &self,
+        box_id: &BoxId,
+        path: Option<&str>,
+        name: &str
+    ) -> Vec<ObjectDomain> {
+        let objects = sqlx::query_as::<_, ObjectModel>(
+            "SELECT * FROM objects WHERE box_id = ? AND path IS ? AND name = ? ORDER BY id"
+        )
+            .bind(box_id)
+            .bind(path)
+            .bind(name)
+            .fetch_all(&self.db)
+            .await
+            .unwrap();
+        self.with_metadata(objects).await
+    }
+
+    async fn list_objects(
+        &self,
+        filter: &ObjectFilter,
+        after: Option<&ObjectCursor>,
+        limit: u64
+    ) -> Vec<ObjectDomain> {
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT * FROM objects WHERE box_id = ");
+        query.push_bind(&filter.box_id);
+
+        if let Some(prefix) = &filter.key_prefix {
+            query.push(" AND key >= ").push_bind(prefix);
+            query.push(" AND substr(key, 1, ").push_bind(prefix.chars().count() as i64);
+            query.push(") = ").push_bind(prefix);
+        }
+        for (key, value) in &filter.metadata {
+            query.push(
+                " AND EXISTS (SELECT 1 FROM object_metadata \
+                WHERE object_metadata.object_id = objects.id AND object_metadata.key = "
+            );
+            query.push_bind(key).push(" AND object_metadata.value = ").push_bind(value).push(")");
+        }
+        if let Some(content_type) = &filter.content_type {
+            query.push(" AND content_type = ").push_bind(content_type);
+        }
+        if let Some(created_from) = &filter.created_from {
+            query.push(" AND created_at >= ").push_bind(created_from);
+        }
+        if let Some(created_to) = &filter.created_to {
+            query.push(" AND created_at <= ").push_bind(created_to);
+        }
+        // Objects never updated are out of an update range
+        if let Some(updated_from) = &filter.updated_from {
+            query.push(" AND updated_at >= ").push_bind(updated_from);
+        }
+        if let Some(updated_to) = &filter.updated_to {
+            query.push(" AND updated_at <= ").push_bind(updated_to);
+        }
+
+        match after {
+            Some(ObjectCursor { key, id: Some(id) }) => {
+                query.push(" AND (key > ").push_bind(key);
+                query.push(" OR (key = ").push_bind(key).push(" AND id > ").push_bind(id).push("))");
+            },
+            Some(ObjectCursor { key, id: None }) => {
+                query.push(" AND key > ").push_bind(key);
+            },
+            None => ()
+        }
+        query.push(" ORDER BY key, id LIMIT ").push_bind(limit as i64);
+
+        let objects = query.build_query_as::<ObjectModel>()
+            .fetch_all(&self.db)
+            .await
+            .unwrap();
+        self.with_metadata(objects).await
+    }
+}
+
+#[async_trait]
+impl ObjectWriter for ObjectGateway {
+    async fn save_object(&self, data: &ObjectDomain) {
+        let mut transaction = self.db.begin().await.unwrap();
+        sqlx::query(
+            "INSERT INTO objects (id, box_id, name, path, key, hash, size, content_type, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (id) DO UPDATE SET
+                box_id = excluded.box_id,
+                name = excluded.name,
+                path = excluded.path,
+                key = excluded.key,
+                hash = excluded.hash,
+                size = excluded.size,
+                content_type = excluded.content_type,
+                updated_at = excluded.updated_at"
+        )
+            .bind(&data.id)
+            .bind(&data.box_id)
+            .bind(&data.name)
+            .bind(&data.path)
+            .bind(data.key())
+            .bind(&data.hash)
+            .bind(data.size as i64)
+            .bind(&data.content_type)
+            .bind(data.created_at)
+            .bind(data.updated_at)
+            .execute(&mut *transaction)
+            .await
+            .unwrap();
+
+        sqlx::query("DELETE FROM object_metadata WHERE object_id = ?")
+            .bind(&data.id)
+            .execute(&mut *transaction)
+            .await
+            .unwrap();
+        for (key, value) in &data.metadata {
+            sqlx::query("INSERT INTO object_metadata (object_id, key, value) VALUES (?, ?, ?)")
+                .bind(&data.id)
+                .bind(key)
+                .bind(value)
+                .execute(&mut *transaction)
+                .await
+                .unwrap();
+        }
+        transaction.commit().await.unwrap();
+    }
+}
+
+#[async_trait]
+impl ObjectRemover for ObjectGateway {
+    async fn remove_object(&self, object_id: &ObjectId) {
+        sqlx::query("DELETE FROM objects WHERE id = ?")
+            .bind(object_id)
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+}
+
+fn map_object_model_to_domain(object: ObjectModel) -> ObjectDomain {
+    ObjectDomain {
+        id: object.id,
+        name: object.name,
+        path: object.path,
+        hash: object.hash,
+        size: object.size as u64,
+        content_type: object.content_type,
+        metadata: HashMap::new(),
+        box_id: object.box_id,
+        created_at: object.created_at,
+        updated_at: object.updated_at
+    }
+}
+
+impl ObjectGatewayTrait for ObjectGateway {}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use crate::adapters::database::sqlite::connect_in_memory;
+    use crate::domain::models::object::split_key;
+
+    use super::*;
+
+    fn object(id: &str, key: &str, metadata: &[(&str, &str)]) -> ObjectDomain {
+        let (path, name) = split_key(key);
+        ObjectDomain {
+            id: id.to_string(),
+            name: Some(name.to_string()),
+            path: path.map(str::to_string),
+            hash: format!("hash-{}", id),
+            size: 10,
+            content_type: "text/plain".to_string(),
+            metadata: metadata.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect(),
+            box_id: "box".to_string(),
+            created_at: Utc::now(),
+            updated_at: None
+        }
+    }
+
+    fn ids(objects: &[ObjectDomain]) -> Vec<&str> {
+        objects.iter().map(|object| object.id.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_objects() {
+        let gateway = ObjectGateway::new(connect_in_memory().await);
+        gateway.save_object(&object("1", "docs/a.txt", &[("owner", "alice"), ("team", "x")])).await;
+        gateway.save_object(&object("2", "docs/b.txt", &[])).await;
+
+        let saved = gateway.get_object(&"1".to_string()).await.unwrap();
+        assert_eq!(saved.metadata.get("owner").unwrap(), "alice");
+        assert_eq!(saved.size, 10);
+        assert_eq!(gateway.get_objects_by_name(&"box".to_string(), Some("docs"), "b.txt").await.len(), 1);
+        assert!(gateway.get_objects_by_name(&"box".to_string(), None, "b.txt").await.is_empty());
+
+        // Metadata is replaced along with the object
+        let mut updated = object("1", "docs/a.txt", &[("owner", "bob")]);
+        updated.updated_at = Some(Utc::now());
+        gateway.save_object(&updated).await;
+        let saved = gateway.get_object(&"1".to_string()).await.unwrap();
+        assert_eq!(saved.metadata.len(), 1);
+        assert!(saved.updated_at.is_some());
+        assert_eq!(gateway.count_objects_by_hash("hash-1").await, 1);
+
+        gateway.remove_object(&"1".to_string()).await;
+        assert!(gateway.get_object(&"1".to_string()).await.is_none());
+        assert_eq!(gateway.get_objects().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_list_objects() {
+        let gateway = ObjectGateway::new(connect_in_memory().await);
+        gateway.save_object(&object("1", "a/x", &[("tier", "hot")])).await;
+        gateway.save_object(&object("3", "a/y", &[("tier", "cold")])).await;
+        gateway.save_object(&object("2", "a/y", &[("tier", "hot")])).await;
+        gateway.save_object(&object("4", "ab", &[])).await;
+        gateway.save_object(&object("5", "b", &[("tier", "hot")])).await;
+        let mut other = object("6", "a/z", &[]);
+        other.box_id = "other".to_string();
+        gateway.save_object(&other).await;
+
+        let filter = ObjectFilter { box_id: "box".to_string(), ..Default::default() };
+        assert_eq!(ids(&gateway.list_objects(&filter, None, 100).await), vec!["1", "2", "3", "4", "5"]);
+
+        let prefixed = ObjectFilter { key_prefix: Some("a/".to_string()), ..filter.clone() };
+        assert_eq!(ids(&gateway.list_objects(&prefixed, None, 100).await), vec!["1", "2", "3"]);
+
+        let hot = ObjectFilter {
+            metadata: HashMap::from([("tier".to_string(), "hot".to_string())]),
+            ..filter.clone()
+        };
+        assert_eq!(ids(&gateway.list_objects(&hot, None, 100).await), vec!["1", "2", "5"]);
+
+        // Objects with the key of the cursor are listed after its id, or skipped
+        let cursor = ObjectCursor { key: "a/y".to_string(), id: Some("2".to_string()) };
+        assert_eq!(ids(&gateway.list_objects(&filter, Some(&cursor), 2).await), vec!["3", "4"]);
+        let cursor = ObjectCursor { key: "a/y".to_string(), id: None };
+        assert_eq!(ids(&gateway.list_objects(&filter, Some(&cursor), 100).await), vec!["4", "5"]);
+
+        let recent = ObjectFilter { created_from: Some(Utc::now() - TimeDelta::minutes(1)), ..filter.clone() };
+        assert_eq!(gateway.list_objects(&recent, None, 100).await.len(), 5);
+        let updated = ObjectFilter { updated_from: Some(Utc::now() - TimeDelta::minutes(1)), ..filter };
+        assert!(gateway.list_objects(&updated, None, 100).await.is_empty());
+    }
+}
@@ -1,11 +1,7 @@
-use core::option::Option;
-
 use async_trait::async_trait;
-use sea_orm::{Condition, DbBackend, DbConn, EntityTrait, JoinType, QueryFilter, QuerySelect, RelationTrait, Statement};
-use sea_orm::ActiveValue::Set;
-use sea_orm::sea_query::{Expr, IntoCondition};
+use sqlx::{FromRow, SqlitePool};
+use sqlx::types::Json;
 
-use crate::adapters::database::models::{permissions, role_permissions};
 use crate::application::common::permission_gateway::{
     PermissionGateway as PermissionGatewayTrait,
     PermissionLinker,
@@ -13,274 +9,256 @@ use crate::application::common::permission_gateway::{
     PermissionRemover,
     PermissionWriter
 };
-use crate::domain::models::permission::{Permission as PermissionDomain, PermissionId, PermissionTextId};
-use crate::domain::models::permission::Permission;
+use crate::domain::models::permission::{Permission as PermissionDomain, PermissionId, PermissionTag};
 use crate::domain::models::role::RoleId;
-use crate::domain::models::service::ServiceId;
 use crate::domain::models::user::UserId;
 
-pub struct PermissionGateway{
-    pub db: Box<DbConn>,
+#[derive(FromRow)]
+struct PermissionModel {
+    id: String,
+    tag: Json<PermissionTag>
+}
+
+pub struct PermissionGateway {
+    db: SqlitePool,
 }
 
 impl PermissionGateway {
-    pub fn new(db: Box<DbConn>) -> Self {
-        PermissionGateway {
-            db,
-        }
+    pub fn new(db: SqlitePool) -> Self {
+        PermissionGateway { db }
     }
 }
 
 #[async_trait]
 impl PermissionReader for PermissionGateway {
-    async fn get_permission_by_id(&self, permission_id: &PermissionId) -> Option<Permission> {
-        match permissions::Entity::find_by_id(*permission_id)
-            .one(&*self.db)
-            .await.unwrap() {
-            Some(permission) => Some(map_permission_model_to_domain(permission)),
-            None => return None
-        }
+    async fn get_permission(&self, permission_id: &PermissionId) -> Option<PermissionDomain> {
+        sqlx::query_as::<_, PermissionModel>("SELECT * FROM permissions WHERE id = ?")
+            .bind(permission_id)
+            .fetch_optional(&self.db)
+            .await
+            .unwrap()
+            .map(map_permission_model_to_domain)
     }
 
-    async fn get_permissions_by_service_id(&self, service_id: &ServiceId) -> Vec<Permission> {
-        let permissions = permissions::Entity::find()
-            .filter(Expr::col(permissions::Column::ServiceId).eq(*service_id))
-            .all(&*self.db)
-            .await.unwrap();
-        permissions.into_iter().map(map_permission_model_to_domain).collect()
-    }
+    async fn get_permissions(&self, permission_ids: &Vec<PermissionId>) -> Option<Vec<PermissionDomain>> {
+        let permissions = sqlx::query_as::<_, PermissionModel>(
+            "SELECT * FROM permissions WHERE id IN (SELECT value FROM json_each(?))"
+        )
+            .bind(Json(permission_ids))
+            .fetch_all(&self.db)
+            .await
+            .unwrap();
 
-    async fn get_permissions_by_ids(&self, permission_ids: &Vec<PermissionId>) -> Option<Vec<Permission>> {
-        let permissions = permissions::Entity::find()
-            .filter(
-                {
-                    let mut condition = Condition::any();
-                    for id in permission_ids {
-                        condition = condition.add(Expr::col(permissions::Column::Id).eq(*id));
-                    }
-                    condition
-                }
-            )
-            .all(&*self.db)
-            .await.unwrap();
-        
         if permissions.len() != permission_ids.len() {
             return None
         }
-        
         Some(permissions.into_iter().map(map_permission_model_to_domain).collect())
     }
 
-    async fn get_permissions_by_text_ids(&self, permission_text_ids: &Vec<PermissionTextId>) -> Option<Vec<PermissionDomain>> {
-        let permissions = permissions::Entity::find()
-            .filter(
-                {
-                    let mut condition = Condition::any();
-                    for text_id in permission_text_ids {
-                        condition = condition.add(Expr::col(permissions::Column::TextId).eq(text_id));
-                    }
-                    condition
-                }
-            )
-            .all(&*self.db)
+    async fn get_permissions_by_tags(
+        &self,
+        permission_tags: &Vec<PermissionTag>
+    ) -> Option<Vec<PermissionDomain>> {
+        // Tags are compared by their JSON text, the way they are stored
+        let tags: Vec<String> = permission_tags.iter()
+            .map(|tag| serde_json::to_string(tag).unwrap())
+            .collect();
+        let permissions = sqlx::query_as::<_, PermissionModel>(
+            "SELECT * FROM permissions WHERE tag IN (SELECT value FROM json_each(?))"
+        )
+            .bind(Json(tags))
+            .fetch_all(&self.db)
             .await
             .unwrap();
-        
-        if permissions.len() != permission_text_ids.len() {
+
+        if permissions.len() != permission_tags.len() {
             return None
         }
-        
         Some(permissions.into_iter().map(map_permission_model_to_domain).collect())
     }
 
-    async fn get_permissions_list(&self, limit: &u64, offset: &u64) -> Vec<Permission> {
-        let permissions = permissions::Entity::find()
-            .limit(*limit)
-            .offset(*offset)
-            .all(&*self.db)
-            .await.unwrap();
-        permissions.into_iter().map(map_permission_model_to_domain).collect()
+    async fn get_permissions_range(&self, limit: &u64, offset: &u64) -> Vec<PermissionDomain> {
+        sqlx::query_as::<_, PermissionModel>("SELECT * FROM permissions ORDER BY id LIMIT ? OFFSET ?")
+            .bind(*limit as i64)
+            .bind(*offset as i64)
+            .fetch_all(&self.db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(map_permission_model_to_domain)
+            .collect()
     }
 
-    async fn get_role_permissions(&self, role_id: &RoleId) -> Vec<Permission> {
-        let role_id = role_id.clone();
-        let permissions = permissions::Entity::find()
-            .join(
-                JoinType::InnerJoin,
-                permissions::Relation::RolePermissions
-                    .def()
-                    .rev()
-                    .on_condition(move |_left, right| {
-                        Expr::col((right, role_permissions::Column::RoleId)).eq(role_id)
-                            .into_condition()
-                    })
-            )
-            .all(&*self.db)
-            .await.unwrap();
-        permissions.into_iter().map(map_permission_model_to_domain).collect()
+    async fn get_role_permissions(&self, role_id: &RoleId) -> Vec<PermissionDomain> {
+        sqlx::query_as::<_, PermissionModel>(
+            "SELECT permissions.* FROM permissions
+            JOIN role_permissions ON role_permissions.permission_id = permissions.id
+            WHERE role_permissions.role_id = ?"
+        )
+            .bind(role_id)
+            .fetch_all(&self.db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(map_permission_model_to_domain)
+            .collect()
     }
 
     async fn get_user_permissions(&self, user_id: &UserId) -> Vec<PermissionDomain> {
-        let raw_sql = r#"
-            SELECT
-                permission.*
-            FROM
-                permission
-            JOIN
-                role_permissions ON permission.permission_id = role_permissions.permission_id
-            JOIN
-                role_user ON role_permissions.role_id = role_user.role_id
-            WHERE
-                role_user.user_id = $1;
-        "#;
-
-        let permissions: Vec<permissions::Model> = permissions::Entity::find().from_raw_sql(
-            Statement::from_sql_and_values(
-                DbBackend::Postgres,
-                raw_sql,
-                vec![user_id.clone().into()]
-            )
+        sqlx::query_as::<_, PermissionModel>(
+            "SELECT DISTINCT permissions.* FROM permissions
+            JOIN role_permissions ON role_permissions.permission_id = permissions.id
+            JOIN role_users ON role_users.role_id = role_permissions.role_id
+            WHERE role_users.user_id = ?"
         )
-            .all(&*self.db)
-            .await.unwrap();
-
-        permissions.into_iter().map(map_permission_model_to_domain).collect()
+            .bind(user_id)
+            .fetch_all(&self.db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(map_permission_model_to_domain)
+            .collect()
     }
 }
 
 #[async_trait]
 impl PermissionWriter for PermissionGateway {
-    async fn save_permission(&self, data: &Permission) {
-        let model = permissions::ActiveModel {
-            id: Set(data.id),
-            text_id: Set(data.text_id.clone()),
-            service_id: Set(data.service_id),
-            title: Set(data.title.clone()),
-            description: Set(data.description.clone()),
-            created_at: Set(data.created_at),
-            updated_at: Set(data.updated_at),
-        };
-        
-        match permissions::Entity::find_by_id(data.id).one(&*self.db).await.unwrap() {
-            Some(_) => {
-                permissions::Entity::update(model).exec(&*self.db).await.unwrap();
-            }
-            None => {
-                permissions::Entity::insert(model).exec(&*self.db).await.unwrap();
-            }
-        }
+    async fn save_permission(&self, data: &PermissionDomain) {
+        self.save_permissions(&vec![data.clone()]).await
     }
 
-    async fn save_permissions(&self, data: &Vec<Permission>) {
-        let found_perms = permissions::Entity::find().filter(
-            {
-                let mut condition = Condition::any();
-                for permission in data {
-                    condition = condition.add(Expr::col(permissions::Column::Id).eq(permission.id));
-                }
-                condition
-            }
-        ).all(&*self.db).await.unwrap();
-        
-        let for_insert = data.iter().filter(|model| {
-            !found_perms.iter().any(|perm| perm.id == model.id)
-        }).collect::<Vec<_>>();
-        
-        if for_insert.len() != 0 {
-            permissions::Entity::insert_many(
-                for_insert.iter().map(|permission| {
-                    map_permission_domain_to_model((**permission).clone())
-                }).collect::<Vec<_>>()
-            ).exec(&*self.db).await.unwrap();
-        }
-        
-        let for_update = data.iter().filter(|model| {
-            found_perms.iter().any(|perm| perm.id == model.id)
-        }).collect::<Vec<_>>();
-        
-        // O(n) gather all
-        for permission in for_update.iter() {
-            permissions::Entity::update(map_permission_domain_to_model((**permission).clone()))
-                .exec(&*self.db)
-                .await.unwrap();
+    async fn save_permissions(&self, data: &Vec<PermissionDomain>) {
+        let mut transaction = self.db.begin().await.unwrap();
+        for permission in data {
+            sqlx::query(
+                "INSERT INTO permissions (id, tag) VALUES (?, ?)
+                ON CONFLICT (id) DO UPDATE SET tag = excluded.tag"
+            )
+                .bind(&permission.id)
+                .bind(Json(&permission.tag))
+                .execute(&mut *transaction)
+                .await
+                .unwrap();
         }
+        transaction.commit().await.unwrap();
     }
 }
 
 #[async_trait]
 impl PermissionRemover for PermissionGateway {
     async fn remove_permission(&self, permission_id: PermissionId) {
-        permissions::Entity::delete_by_id(permission_id).exec(&*self.db).await.unwrap();
+        sqlx::query("DELETE FROM permissions WHERE id = ?")
+            .bind(permission_id)
+            .execute(&self.db)
+            .await
+            .unwrap();
     }
 }
 
 #[async_trait]
 impl PermissionLinker for PermissionGateway {
-    async fn link_permission_to_role(&self, role_id: &RoleId, permission_id: &PermissionId) {
-        let model = role_permissions::ActiveModel {
-            permission_id: Set(*permission_id),
-            role_id: Set(*role_id),
-        };
-        role_permissions::Entity::insert(model).exec(&*self.db).await.unwrap();
+    async fn is_permission_linked_to_role(&self, role_id: &RoleId, permission_id: &PermissionId) -> bool {
+        sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM role_permissions WHERE role_id = ? AND permission_id = ?)"
+        )
+            .bind(role_id)
+            .bind(permission_id)
+            .fetch_one(&self.db)
+            .await
+            .unwrap()
     }
 
-    async fn link_permissions_to_role(&self, role_id: &RoleId, permission_ids: &Vec<PermissionId>) {
-        let models = permission_ids.iter().map(|permission_id| {
-            role_permissions::ActiveModel {
-                permission_id: Set(*permission_id),
-                role_id: Set(*role_id),
-            }
-        }).collect::<Vec<_>>();
-        role_permissions::Entity::insert_many(models).exec(&*self.db).await.unwrap();
+    async fn link_permission_to_role(&self, role_id: &RoleId, permission_id: &PermissionId) {
+        self.link_permissions_to_role(role_id, &vec![permission_id.clone()]).await
     }
 
-    async fn unlink_permission_from_role(&self, role_id: &RoleId, permission_id: &PermissionId) {
-        role_permissions::Entity::delete_many()
-            .filter(
-                Expr::col(role_permissions::Column::RoleId).eq(*role_id)
-                    .and(Expr::col(role_permissions::Column::PermissionId).eq(*permission_id))
-            )
-            .exec(&*self.db)
+    async fn link_permissions_to_role(&self, role_id: &RoleId, permission_ids: &Vec<PermissionId>) {
+        sqlx::query(
+            "INSERT OR IGNORE INTO role_permissions (role_id, permission_id)
+            SELECT ?, value FROM json_each(?)"
+        )
+            .bind(role_id)
+            .bind(Json(permission_ids))
+            .execute(&self.db)
             .await
             .unwrap();
     }
 
-    async fn is_permission_linked_to_role(&self, role_id: &RoleId, permission_id: &PermissionId) -> bool {
-        role_permissions::Entity::find()
-            .filter(
-                Expr::col(role_permissions::Column::RoleId).eq(*role_id)
-                    .and(Expr::col(role_permissions::Column::PermissionId).eq(*permission_id))
-            )
-            .one(&*self.db)
+    async fn unlink_permission_from_role(&self, role_id: &RoleId, permission_id: &PermissionId) {
+        sqlx::query("DELETE FROM role_permissions WHERE role_id = ? AND permission_id = ?")
+            .bind(role_id)
+            .bind(permission_id)
+            .execute(&self.db)
             .await
-            .unwrap()
-            .is_some()
+            .unwrap();
     }
 }
 
-fn map_permission_model_to_domain(permission: permissions::Model) -> PermissionDomain {
+fn map_permission_model_to_domain(permission: PermissionModel) -> PermissionDomain {
     PermissionDomain {
         id: permission.id,
-        text_id: permission.text_id,
-        service_id: permission.service_id,
-        title: permission.title,
-        description: permission.description,
-        created_at: permission.created_at,
-        updated_at: permission.updated_at,
+        tag: permission.tag.0
     }
 }
 
-fn map_permission_domain_to_model(permission: PermissionDomain) -> permissions::ActiveModel {
-    permissions::ActiveModel {
-        id: Set(permission.id),
-        text_id: Set(permission.text_id),
-        service_id: Set(permission.service_id),
-        title: Set(permission.title),
-        description: Set(permission.description),
-        created_at: Set(permission.created_at),
-        updated_at: Set(permission.updated_at),
-    }
-}
+impl PermissionGatewayTrait for PermissionGateway {}
 
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
 
-impl PermissionGatewayTrait for PermissionGateway {}
+    use crate::adapters::database::role_db::RoleGateway;
+    use crate::adapters::database::sqlite::connect_in_memory;
+    use crate::adapters::database::user_db::UserGateway;
+    use crate::application::common::role_gateway::{RoleLinker, RoleReader, RoleRemover, RoleWriter};
+    use crate::application::common::user_gateway::UserWriter;
+    use crate::domain::models::role::Role;
+    use crate::domain::models::user::User;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_user_permissions() {
+        let db = connect_in_memory().await;
+        let users = UserGateway::new(db.clone());
+        let roles = RoleGateway::new(db.clone());
+        let permissions = PermissionGateway::new(db);
+        let user_id = "user".to_string();
+        let role_id = "role".to_string();
+        users.save_user(&User {
+            id: user_id.clone(),
+            username: "user".to_string(),
+            hashed_password: "hash".to_string(),
+            created_at: Utc::now()
+        }).await;
+        roles.save_role(&Role {
+            id: role_id.clone(),
+            title: "Admin".to_string(),
+            description: None,
+            created_at: Utc::now(),
+            updated_at: None
+        }).await;
+        roles.set_default_role(&role_id).await;
+        roles.link_role_to_user(&role_id, &user_id).await;
+        roles.link_role_to_user(&role_id, &user_id).await;
+        permissions.save_permissions(&vec![
+            PermissionDomain { id: "a".to_string(), tag: PermissionTag::GetBox },
+            PermissionDomain { id: "b".to_string(), tag: PermissionTag::GetSpecificObject("box".to_string()) },
+            PermissionDomain { id: "c".to_string(), tag: PermissionTag::GetNode },
+        ]).await;
+        permissions.link_permissions_to_role(&role_id, &vec!["a".to_string(), "b".to_string()]).await;
+
+        let tags = vec![PermissionTag::GetSpecificObject("box".to_string())];
+        assert_eq!(permissions.get_permissions_by_tags(&tags).await.unwrap()[0].id, "b");
+        assert_eq!(permissions.get_user_permissions(&user_id).await.len(), 2);
+        assert!(permissions.is_permission_linked_to_role(&role_id, &"a".to_string()).await);
+        assert_eq!(roles.get_default_role().await.unwrap().id, role_id);
+        assert_eq!(roles.get_role_by_title_not_sensitive(&"admin".to_string()).await.unwrap().id, role_id);
+
+        // Links go away with the role
+        roles.remove_role(&role_id).await;
+        assert!(permissions.get_user_permissions(&user_id).await.is_empty());
+        assert!(roles.get_user_roles(&user_id).await.is_empty());
+        assert!(roles.get_default_role().await.is_none());
+    }
+}
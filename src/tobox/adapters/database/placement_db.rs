@@ -0,0 +1,108 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+use sqlx::types::Json;
+
+use crate::application::common::placement_gateway::{
+    PlacementGateway as PlacementGatewayTrait,
+    PlacementReader,
+    PlacementRemover,
+    PlacementWriter
+};
+use crate::domain::models::node::NodeId;
+use crate::domain::models::placement::{PlacementMove, PlacementMoveId, RingMember};
+
+#[derive(FromRow)]
+struct PlacementMoveModel {
+    id: String,
+    object_id: String,
+    targets: Json<Vec<NodeId>>,
+    release: bool,
+    created_at: DateTime<Utc>
+}
+
+pub struct PlacementGateway {
+    db: SqlitePool,
+}
+
+impl PlacementGateway {
+    pub fn new(db: SqlitePool) -> Self {
+        PlacementGateway { db }
+    }
+}
+
+#[async_trait]
+impl PlacementReader for PlacementGateway {
+    async fn get_ring_members(&self) -> Option<Vec<RingMember>> {
+        sqlx::query_scalar::<_, Json<Vec<RingMember>>>("SELECT members FROM placement_ring")
+            .fetch_optional(&self.db)
+            .await
+            .unwrap()
+            .map(|members| members.0)
+    }
+
+    async fn get_moves(&self, limit: u64) -> Vec<PlacementMove> {
+        sqlx::query_as::<_, PlacementMoveModel>("SELECT * FROM placement_moves ORDER BY created_at LIMIT ?")
+            .bind(limit as i64)
+            .fetch_all(&self.db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(map_placement_move_model_to_domain)
+            .collect()
+    }
+}
+
+#[async_trait]
+impl PlacementWriter for PlacementGateway {
+    async fn save_ring_members(&self, members: &[RingMember]) {
+        sqlx::query(
+            "INSERT INTO placement_ring (id, members) VALUES (0, ?)
+            ON CONFLICT (id) DO UPDATE SET members = excluded.members"
+        )
+            .bind(Json(members))
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+
+    async fn save_move(&self, data: &PlacementMove) {
+        sqlx::query(
+            "INSERT INTO placement_moves (id, object_id, targets, release, created_at) VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT (id) DO UPDATE SET
+                targets = excluded.targets,
+                release = excluded.release"
+        )
+            .bind(&data.id)
+            .bind(&data.object_id)
+            .bind(Json(&data.targets))
+            .bind(data.release)
+            .bind(data.created_at)
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+}
+
+#[async_trait]
+impl PlacementRemover for PlacementGateway {
+    async fn remove_move(&self, move_id: &PlacementMoveId) {
+        sqlx::query("DELETE FROM placement_moves WHERE id = ?")
+            .bind(move_id)
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+}
+
+fn map_placement_move_model_to_domain(entry: PlacementMoveModel) -> PlacementMove {
+    PlacementMove {
+        id: entry.id,
+        object_id: entry.object_id,
+        targets: entry.targets.0,
+        release: entry.release,
+        created_at: entry.created_at
+    }
+}
+
+impl PlacementGatewayTrait for PlacementGateway {}
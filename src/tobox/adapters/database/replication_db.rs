@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+
+use crate::application::common::replication_gateway::{
+    ReplicationGateway as ReplicationGatewayTrait,
+    ReplicationReader,
+    ReplicationRemover,
+    ReplicationWriter
+};
+use crate::domain::models::replication::{ReplicationTask, ReplicationTaskId};
+
+#[derive(FromRow)]
+struct ReplicationTaskModel {
+    id: String,
+    object_id: String,
+    node_id: Option<String>,
+    attempts: u32,
+    next_attempt_at: DateTime<Utc>,
+    created_at: DateTime<Utc>
+}
+
+pub struct ReplicationGateway {
+    db: SqlitePool,
+}
+
+impl ReplicationGateway {
+    pub fn new(db: SqlitePool) -> Self {
+        ReplicationGateway { db }
+    }
+}
+
+#[async_trait]
+impl ReplicationReader for ReplicationGateway {
+    async fn get_due_tasks(&self, now: &DateTime<Utc>, limit: u64) -> Vec<ReplicationTask> {
+        sqlx::query_as::<_, ReplicationTaskModel>(
+            "SELECT * FROM replication_tasks WHERE next_attempt_at <= ? ORDER BY next_attempt_at LIMIT ?"
+        )
+            .bind(now)
+            .bind(limit as i64)
+            .fetch_all(&self.db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(map_replication_task_model_to_domain)
+            .collect()
+    }
+}
+
+#[async_trait]
+impl ReplicationWriter for ReplicationGateway {
+    async fn save_task(&self, data: &ReplicationTask) {
+        sqlx::query(
+            "INSERT INTO replication_tasks (id, object_id, node_id, attempts, next_attempt_at, created_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT (id) DO UPDATE SET
+                node_id = excluded.node_id,
+                attempts = excluded.attempts,
+                next_attempt_at = excluded.next_attempt_at"
+        )
+            .bind(&data.id)
+            .bind(&data.object_id)
+            .bind(&data.node_id)
+            .bind(data.attempts)
+            .bind(data.next_attempt_at)
+            .bind(data.created_at)
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+}
+
+#[async_trait]
+impl ReplicationRemover for ReplicationGateway {
+    async fn remove_task(&self, task_id: &ReplicationTaskId) {
+        sqlx::query("DELETE FROM replication_tasks WHERE id = ?")
+            .bind(task_id)
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+}
+
+fn map_replication_task_model_to_domain(task: ReplicationTaskModel) -> ReplicationTask {
+    ReplicationTask {
+        id: task.id,
+        object_id: task.object_id,
+        node_id: task.node_id,
+        attempts: task.attempts,
+        next_attempt_at: task.next_attempt_at,
+        created_at: task.created_at
+    }
+}
+
+impl ReplicationGatewayTrait for ReplicationGateway {}
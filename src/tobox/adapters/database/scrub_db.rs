@@ -0,0 +1,47 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use sqlx::types::Json;
+
+use crate::application::common::scrub_gateway::{
+    ScrubGateway as ScrubGatewayTrait,
+    ScrubReader,
+    ScrubWriter
+};
+use crate::domain::models::scrub::ScrubReport;
+
+pub struct ScrubGateway {
+    db: SqlitePool,
+}
+
+impl ScrubGateway {
+    pub fn new(db: SqlitePool) -> Self {
+        ScrubGateway { db }
+    }
+}
+
+#[async_trait]
+impl ScrubReader for ScrubGateway {
+    async fn get_report(&self) -> Option<ScrubReport> {
+        sqlx::query_scalar::<_, Json<ScrubReport>>("SELECT report FROM scrub_report")
+            .fetch_optional(&self.db)
+            .await
+            .unwrap()
+            .map(|report| report.0)
+    }
+}
+
+#[async_trait]
+impl ScrubWriter for ScrubGateway {
+    async fn save_report(&self, data: &ScrubReport) {
+        sqlx::query(
+            "INSERT INTO scrub_report (id, report) VALUES (0, ?)
+            ON CONFLICT (id) DO UPDATE SET report = excluded.report"
+        )
+            .bind(Json(data))
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+}
+
+impl ScrubGatewayTrait for ScrubGateway {}
@@ -0,0 +1,49 @@
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions};
+
+use crate::adapters::database::migration::{migrate, MigrationError};
+
+/// Database file of the node when `node.database` is not set
+pub const DEFAULT_DATABASE: &str = "tobox.db";
+
+/// Open the database of the node, created on the first start, without touching the schema
+pub async fn open(path: &str) -> Result<SqlitePool, sqlx::Error> {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .foreign_keys(true);
+    SqlitePoolOptions::new().connect_with(options).await
+}
+
+/// Open the database of the node and apply pending migrations
+///
+/// A database migrated by a newer release is refused.
+pub async fn connect(path: &str) -> Result<SqlitePool, MigrationError> {
+    let pool = open(path).await?;
+    migrate(&pool, None).await?;
+    Ok(pool)
+}
+
+/// Empty database living as long as the pool
+#[cfg(test)]
+pub async fn open_in_memory() -> SqlitePool {
+    let options = SqliteConnectOptions::new()
+        .in_memory(true)
+        .foreign_keys(true);
+    // Every connection to memory opens a database of its own
+    SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect_with(options)
+        .await
+        .unwrap()
+}
+
+/// Migrated database living as long as the pool
+#[cfg(test)]
+pub async fn connect_in_memory() -> SqlitePool {
+    let pool = open_in_memory().await;
+    migrate(&pool, None).await.unwrap();
+    pool
+}
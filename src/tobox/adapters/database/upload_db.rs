@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+use sqlx::types::Json;
+
+use crate::application::common::upload_gateway::{
+    UploadGateway as UploadGatewayTrait,
+    UploadReader,
+    UploadRemover,
+    UploadWriter
+};
+use crate::domain::models::upload::{Upload, UploadId, UploadPart};
+
+#[derive(FromRow)]
+struct UploadModel {
+    id: String,
+    box_id: String,
+    name: Option<String>,
+    path: Option<String>,
+    metadata: Json<HashMap<String, String>>,
+    user_id: Option<String>,
+    object_id: Option<String>,
+    created_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>
+}
+
+#[derive(FromRow)]
+struct UploadPartModel {
+    upload_id: String,
+    number: u32,
+    hash: String,
+    size: i64,
+    created_at: DateTime<Utc>
+}
+
+pub struct UploadGateway {
+    db: SqlitePool,
+}
+
+impl UploadGateway {
+    pub fn new(db: SqlitePool) -> Self {
+        UploadGateway { db }
+    }
+}
+
+#[async_trait]
+impl UploadReader for UploadGateway {
+    async fn get_upload(&self, upload_id: &UploadId) -> Option<Upload> {
+        sqlx::query_as::<_, UploadModel>("SELECT * FROM uploads WHERE id = ?")
+            .bind(upload_id)
+            .fetch_optional(&self.db)
+            .await
+            .unwrap()
+            .map(map_upload_model_to_domain)
+    }
+
+    async fn get_expired_uploads(&self, now: &DateTime<Utc>) -> Vec<Upload> {
+        sqlx::query_as::<_, UploadModel>("SELECT * FROM uploads WHERE expires_at <= ? ORDER BY expires_at")
+            .bind(now)
+            .fetch_all(&self.db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(map_upload_model_to_domain)
+            .collect()
+    }
+
+    async fn get_upload_parts(&self, upload_id: &UploadId) -> Vec<UploadPart> {
+        sqlx::query_as::<_, UploadPartModel>("SELECT * FROM upload_parts WHERE upload_id = ? ORDER BY number")
+            .bind(upload_id)
+            .fetch_all(&self.db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(map_upload_part_model_to_domain)
+            .collect()
+    }
+}
+
+#[async_trait]
+impl UploadWriter for UploadGateway {
+    async fn save_upload(&self, data: &Upload) {
+        sqlx::query(
+            "INSERT INTO uploads (id, box_id, name, path, metadata, user_id, object_id, created_at, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (id) DO UPDATE SET
+                name = excluded.name,
+                path = excluded.path,
+                metadata = excluded.metadata,
+                expires_at = excluded.expires_at"
+        )
+            .bind(&data.id)
+            .bind(&data.box_id)
+            .bind(&data.name)
+            .bind(&data.path)
+            .bind(Json(&data.metadata))
+            .bind(&data.user_id)
+            .bind(&data.object_id)
+            .bind(data.created_at)
+            .bind(data.expires_at)
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+
+    async fn save_upload_part(&self, data: &UploadPart) {
+        sqlx::query(
+            "INSERT INTO upload_parts (upload_id, number, hash, size, created_at) VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT (upload_id, number) DO UPDATE SET
+                hash = excluded.hash,
+                size = excluded.size,
+                created_at = excluded.created_at"
+        )
+            .bind(&data.upload_id)
+            .bind(data.number)
+            .bind(&data.hash)
+            .bind(data.size as i64)
+            .bind(data.created_at)
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+}
+
+#[async_trait]
+impl UploadRemover for UploadGateway {
+    async fn remove_upload(&self, upload_id: &UploadId) {
+        sqlx::query("DELETE FROM uploads WHERE id = ?")
+            .bind(upload_id)
+            .execute(&self.db)
+            .await
+            .unwrap();
+    }
+}
+
+fn map_upload_model_to_domain(upload: UploadModel) -> Upload {
+    Upload {
+        id: upload.id,
+        box_id: upload.box_id,
+        name: upload.name,
+        path: upload.path,
+        metadata: upload.metadata.0,
+        user_id: upload.user_id,
+        object_id: upload.object_id,
+        created_at: upload.created_at,
+        expires_at: upload.expires_at
+    }
+}
+
+fn map_upload_part_model_to_domain(part: UploadPartModel) -> UploadPart {
+    UploadPart {
+        upload_id: part.upload_id,
+        number: part.number,
+        hash: part.hash,
+        size: part.size as u64,
+        created_at: part.created_at
+    }
+}
+
+impl UploadGatewayTrait for UploadGateway {}
@@ -1,173 +1,154 @@
-use core::option::Option;
-use std::sync::{Arc, Mutex};
-
 use async_trait::async_trait;
-use cached::{Cached, TimedCache};
-use sea_orm::{DbConn, EntityTrait, QueryFilter, QuerySelect};
-use sea_orm::ActiveValue::Set;
-use sea_orm::sea_query::{Condition, Expr};
-use sea_orm::sea_query::extension::postgres::PgExpr;
-
-use crate::adapters::database::models::sea_orm_active_enums::UserState;
-use crate::adapters::database::models::users;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+use sqlx::types::Json;
+
 use crate::application::common::user_gateway::{
     UserGateway as UserGatewayTrait,
     UserReader,
+    UserRemover,
     UserWriter
 };
 use crate::domain::models::user::{User as UserDomain, UserId};
-use crate::domain::models::user::UserState as UserStateDomain;
 
-pub struct UserGateway{
-    pub db: Box<DbConn>,
-    cache_user_by_id: Arc<Mutex<TimedCache<UserId, users::Model>>>,
+#[derive(FromRow)]
+struct UserModel {
+    id: String,
+    username: String,
+    hashed_password: String,
+    created_at: DateTime<Utc>
 }
 
-impl UserGateway {
-    pub fn new(db: Box<DbConn>) -> Self {
-        UserGateway {
-            db,
-            cache_user_by_id: Arc::new(Mutex::new(TimedCache::with_lifespan(3))),
+pub struct UserGateway {
+    db: SqlitePool,
+}
 
-        }
+impl UserGateway {
+    pub fn new(db: SqlitePool) -> Self {
+        UserGateway { db }
     }
 }
 
 #[async_trait]
 impl UserReader for UserGateway {
-    async fn get_user_by_id(&self, user_id: &UserId) -> Option<UserDomain> {
-
-        let cached_value = self.cache_user_by_id.lock().unwrap().cache_get(user_id).cloned();
-        if cached_value.is_some() {
-            return match cached_value {
-                Some(value) => Some(map_user_model_to_domain(value.clone())),
-                None => None
-            }
-        }
-
-        match users::Entity::find_by_id(user_id.clone()).one(&*self.db).await.unwrap() {
-            Some(user) => {
-                self.cache_user_by_id.lock().unwrap().cache_set(user_id.clone(), user.clone());
-                Option::from(map_user_model_to_domain(user))
-            }
-            None => None
-        }
+    async fn get_user(&self, user_id: &UserId) -> Option<UserDomain> {
+        sqlx::query_as::<_, UserModel>("SELECT * FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await
+            .unwrap()
+            .map(map_user_model_to_domain)
     }
 
-    async fn get_users_by_ids(&self, user_ids: &Vec<UserId>) -> Option<Vec<UserDomain>> {
-        let users: Vec<users::Model> = users::Entity::find().filter(
-            {
-                let mut condition = Condition::any();
-                for id in user_ids {
-                    condition = condition.add(Expr::col(users::Column::Id).eq(*id));
-                }
-                condition
-            }
-            )
-            .all(&*self.db)
+    async fn get_users(&self, user_ids: &Vec<UserId>) -> Option<Vec<UserDomain>> {
+        let users = sqlx::query_as::<_, UserModel>(
+            "SELECT * FROM users WHERE id IN (SELECT value FROM json_each(?))"
+        )
+            .bind(Json(user_ids))
+            .fetch_all(&self.db)
             .await
             .unwrap();
 
         if users.len() != user_ids.len() {
             return None
         }
-
-        Option::from(
-            users.iter()
-                .map(|user| map_user_model_to_domain(user.clone()))
-                .collect::<Vec<_>>()
-        )
+        Some(users.into_iter().map(map_user_model_to_domain).collect())
     }
 
-    async fn get_users_list(&self, limit: &u64, offset: &u64) -> Vec<UserDomain> {
-        let users: Vec<users::Model> = users::Entity::find()
-            .limit(limit.clone())
-            .offset(offset.clone())
-            .all(&*self.db)
+    async fn get_users_range(&self, limit: &u64, offset: &u64) -> Vec<UserDomain> {
+        sqlx::query_as::<_, UserModel>("SELECT * FROM users ORDER BY created_at, id LIMIT ? OFFSET ?")
+            .bind(*limit as i64)
+            .bind(*offset as i64)
+            .fetch_all(&self.db)
             .await
-            .unwrap();
-        users.iter().map(|user| map_user_model_to_domain(user.clone())).collect()
+            .unwrap()
+            .into_iter()
+            .map(map_user_model_to_domain)
+            .collect()
     }
 
     async fn get_user_by_username_not_sensitive(&self, username: &String) -> Option<UserDomain> {
-        let user: Option<users::Model> = users::Entity::find().filter(
-                Expr::col(users::Column::Username).ilike(username)
-            )
-            .one(&*self.db)
+        sqlx::query_as::<_, UserModel>("SELECT * FROM users WHERE username = ? COLLATE NOCASE")
+            .bind(username)
+            .fetch_optional(&self.db)
             .await
-            .unwrap();
-
-        match user {
-            Some(user) => Some(map_user_model_to_domain(user)),
-            None => None
-        }
+            .unwrap()
+            .map(map_user_model_to_domain)
     }
+}
 
-    async fn get_user_by_email_not_sensitive(&self, email: &String) -> Option<UserDomain> {
-        let user: Option<users::Model> = users::Entity::find().filter(
-                Expr::col(users::Column::Email).ilike(email)
-            )
-            .one(&*self.db)
+#[async_trait]
+impl UserWriter for UserGateway {
+    async fn save_user(&self, data: &UserDomain) {
+        sqlx::query(
+            "INSERT INTO users (id, username, hashed_password, created_at) VALUES (?, ?, ?, ?)
+            ON CONFLICT (id) DO UPDATE SET
+                username = excluded.username,
+                hashed_password = excluded.hashed_password"
+        )
+            .bind(&data.id)
+            .bind(&data.username)
+            .bind(&data.hashed_password)
+            .bind(data.created_at)
+            .execute(&self.db)
             .await
             .unwrap();
-
-        match user {
-            Some(user) => Some(map_user_model_to_domain(user)),
-            None => None
-        }
     }
 }
 
 #[async_trait]
-impl UserWriter for UserGateway {
-    async fn save_user(&self, data: &UserDomain) {
-        let user_model = users::ActiveModel {
-            id: Set(data.id),
-            username: Set(data.username.clone()),
-            email: Set(data.email.clone()),
-            first_name: Set(data.first_name.clone()),
-            last_name: Set(data.last_name.clone()),
-            state: Set(match data.state {
-                UserStateDomain::Active => UserState::Active,
-                UserStateDomain::Inactive => UserState::Inactive,
-                UserStateDomain::Banned => UserState::Banned,
-                UserStateDomain::Deleted => UserState::Deleted
-            }),
-            hashed_password: Set(data.hashed_password.clone()),
-            created_at: Set(data.created_at),
-            updated_at: Set(data.updated_at.clone())
-        };
-
-        match users::Entity::find_by_id(data.id).one(&*self.db).await.unwrap() {
-            Some(_) => {
-                users::Entity::update(user_model).exec(&*self.db).await.unwrap();
-            }
-            None => {
-                users::Entity::insert(user_model).exec(&*self.db).await.unwrap();
-            }
-        }
+impl UserRemover for UserGateway {
+    async fn remove_user(&self, user_id: &UserId) {
+        sqlx::query("DELETE FROM users WHERE id = ?")
+            .bind(user_id)
+            .execute(&self.db)
+            .await
+            .unwrap();
     }
 }
 
-
-
-fn map_user_model_to_domain(user: users::Model) -> UserDomain {
+fn map_user_model_to_domain(user: UserModel) -> UserDomain {
     UserDomain {
         id: user.id,
         username: user.username,
-        email: user.email,
-        first_name: user.first_name,
-        last_name: user.last_name,
-        state: match user.state {
-            UserState::Active => UserStateDomain::Active,
-            UserState::Inactive => UserStateDomain::Inactive,
-            UserState::Banned => UserStateDomain::Banned,
-            UserState::Deleted => UserStateDomain::Deleted
-        },
         hashed_password: user.hashed_password,
-        created_at: user.created_at,
-        updated_at: user.updated_at
+        created_at: user.created_at
     }
 }
 
-impl UserGatewayTrait for UserGateway {}
\ No newline at end of file
+impl UserGatewayTrait for UserGateway {}
+
+#[cfg(test)]
+mod tests {
+    use crate::adapters::database::sqlite::connect_in_memory;
+
+    use super::*;
+
+    fn user(id: &str, username: &str) -> UserDomain {
+        UserDomain {
+            id: id.to_string(),
+            username: username.to_string(),
+            hashed_password: "hash".to_string(),
+            created_at: Utc::now()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_users() {
+        let gateway = UserGateway::new(connect_in_memory().await);
+        gateway.save_user(&user("a", "Alice")).await;
+        gateway.save_user(&user("b", "Bob")).await;
+
+        let found = gateway.get_user_by_username_not_sensitive(&"alice".to_string()).await.unwrap();
+        assert_eq!(found.id, "a");
+        assert!(gateway.get_users(&vec!["a".to_string(), "b".to_string()]).await.is_some());
+        assert!(gateway.get_users(&vec!["a".to_string(), "c".to_string()]).await.is_none());
+
+        gateway.save_user(&user("a", "Carol")).await;
+        assert_eq!(gateway.get_user(&"a".to_string()).await.unwrap().username, "Carol");
+        assert_eq!(gateway.get_users_range(&10, &1).await.len(), 1);
+
+        gateway.remove_user(&"a".to_string()).await;
+        assert!(gateway.get_user(&"a".to_string()).await.is_none());
+    }
+}
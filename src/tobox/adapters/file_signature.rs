@@ -0,0 +1,91 @@
+/// Number of leading bytes that is enough to detect any of the known signatures
+pub const SIGNATURE_WINDOW: usize = 512;
+
+pub const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+struct Signature {
+    offset: usize,
+    magic: &'static [u8],
+    content_type: &'static str,
+}
+
+const SIGNATURES: &[Signature] = &[
+    Signature { offset: 0, magic: b"\x89PNG\r\n\x1a\n", content_type: "image/png" },
+    Signature { offset: 0, magic: b"\xff\xd8\xff", content_type: "image/jpeg" },
+    Signature { offset: 0, magic: b"GIF87a", content_type: "image/gif" },
+    Signature { offset: 0, magic: b"GIF89a", content_type: "image/gif" },
+    Signature { offset: 8, magic: b"WEBP", content_type: "image/webp" },
+    Signature { offset: 0, magic: b"BM", content_type: "image/bmp" },
+    Signature { offset: 0, magic: b"\x00\x00\x01\x00", content_type: "image/x-icon" },
+    Signature { offset: 0, magic: b"II*\x00", content_type: "image/tiff" },
+    Signature { offset: 0, magic: b"MM\x00*", content_type: "image/tiff" },
+    Signature { offset: 0, magic: b"%PDF-", content_type: "application/pdf" },
+    Signature { offset: 0, magic: b"PK\x03\x04", content_type: "application/zip" },
+    Signature { offset: 0, magic: b"\x1f\x8b", content_type: "application/gzip" },
+    Signature { offset: 0, magic: b"BZh", content_type: "application/x-bzip2" },
+    Signature { offset: 0, magic: b"\xfd7zXZ\x00", content_type: "application/x-xz" },
+    Signature { offset: 0, magic: b"\x28\xb5\x2f\xfd", content_type: "application/zstd" },
+    Signature { offset: 0, magic: b"7z\xbc\xaf\x27\x1c", content_type: "application/x-7z-compressed" },
+    Signature { offset: 0, magic: b"Rar!\x1a\x07", content_type: "application/vnd.rar" },
+    Signature { offset: 0, magic: b"\x00asm", content_type: "application/wasm" },
+    Signature { offset: 0, magic: b"\x7fELF", content_type: "application/x-elf" },
+    Signature { offset: 0, magic: b"MZ", content_type: "application/vnd.microsoft.portable-executable" },
+    Signature { offset: 0, magic: b"SQLite format 3\x00", content_type: "application/vnd.sqlite3" },
+    Signature { offset: 0, magic: b"ID3", content_type: "audio/mpeg" },
+    Signature { offset: 0, magic: b"fLaC", content_type: "audio/flac" },
+    Signature { offset: 0, magic: b"OggS", content_type: "audio/ogg" },
+    Signature { offset: 8, magic: b"WAVE", content_type: "audio/wav" },
+    Signature { offset: 8, magic: b"AVI ", content_type: "video/x-msvideo" },
+    Signature { offset: 4, magic: b"ftypqt", content_type: "video/quicktime" },
+    Signature { offset: 4, magic: b"ftyp", content_type: "video/mp4" },
+    Signature { offset: 0, magic: b"\x1a\x45\xdf\xa3", content_type: "video/webm" },
+];
+
+
+/// Detect content type by the first bytes of a file
+///
+/// Binary signatures are checked first, then the content is considered
+/// as text if it is valid utf-8 without control characters.
+pub fn detect_content_type(head: &[u8]) -> &'static str {
+    for signature in SIGNATURES {
+        let end = signature.offset + signature.magic.len();
+        if head.len() >= end && &head[signature.offset..end] == signature.magic {
+            return signature.content_type
+        }
+    }
+
+    if !head.is_empty() && is_text(head) {
+        return "text/plain"
+    }
+
+    DEFAULT_CONTENT_TYPE
+}
+
+fn is_text(head: &[u8]) -> bool {
+    let text = match std::str::from_utf8(head) {
+        Ok(text) => text,
+        // The window may cut a multibyte character in the middle
+        Err(error) if error.error_len().is_none() => {
+            std::str::from_utf8(&head[..error.valid_up_to()]).unwrap()
+        },
+        Err(_) => return false
+    };
+    !text.chars().any(|c| c.is_control() && !matches!(c, '\t' | '\n' | '\r' | '\x0c'))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_content_type() {
+        assert_eq!(detect_content_type(b"\x89PNG\r\n\x1a\n\x00\x00"), "image/png");
+        assert_eq!(detect_content_type(b"RIFF\x00\x00\x00\x00WEBPVP8 "), "image/webp");
+        assert_eq!(detect_content_type(b"\x00\x00\x00\x18ftypmp42"), "video/mp4");
+        assert_eq!(detect_content_type("hello, мир".as_bytes()), "text/plain");
+        assert_eq!(detect_content_type(&"мир".as_bytes()[..5]), "text/plain");
+        assert_eq!(detect_content_type(b"\x00\x01\x02\x03"), DEFAULT_CONTENT_TYPE);
+        assert_eq!(detect_content_type(b""), DEFAULT_CONTENT_TYPE);
+    }
+}
@@ -0,0 +1,77 @@
+use hmac::{Hmac, Mac};
+use rand::random;
+use sha2::Sha256;
+
+use crate::application::common::signer::Signer;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// HMAC-SHA256 signatures in hex
+pub struct HmacSigner {
+    key: Vec<u8>
+}
+
+impl HmacSigner {
+
+    /// The key is derived from the secret for the purpose, so signatures made for one purpose
+    /// are not valid for another and do not disclose anything about the secret itself
+    pub fn new(secret: &str, purpose: &str) -> Self {
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(purpose.as_bytes());
+        Self { key: mac.finalize().into_bytes().to_vec() }
+    }
+
+    /// Signatures are valid until the node is restarted
+    pub fn random() -> Self {
+        Self { key: (0..32).map(|_| random::<u8>()).collect() }
+    }
+
+    fn mac(&self, data: &str) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key).unwrap();
+        mac.update(data.as_bytes());
+        mac
+    }
+}
+
+impl Signer for HmacSigner {
+    fn sign(&self, data: &str) -> String {
+        self.mac(data).finalize().into_bytes().iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    fn verify(&self, data: &str, signature: &str) -> bool {
+        if !signature.len().is_multiple_of(2) || !signature.is_ascii() {
+            return false
+        }
+        let signature: Option<Vec<u8>> = (0..signature.len())
+            .step_by(2)
+            .map(|index| u8::from_str_radix(&signature[index..index + 2], 16).ok())
+            .collect();
+        match signature {
+            // Constant time comparison
+            Some(signature) => self.mac(data).verify_slice(&signature).is_ok(),
+            None => false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign() {
+        let signer = HmacSigner::new("secret", "presign");
+        let signature = signer.sign("data");
+        assert_eq!(signature.len(), 64);
+        assert!(signer.verify("data", &signature));
+        assert!(signer.verify("data", &signature.to_uppercase()));
+        assert!(!signer.verify("other", &signature));
+        assert!(!signer.verify("data", &signature[..62]));
+        assert!(!signer.verify("data", "zz"));
+
+        // The same secret gives other signatures for another purpose
+        assert!(!HmacSigner::new("secret", "other").verify("data", &signature));
+        assert!(HmacSigner::new("secret", "presign").verify("data", &signature));
+        assert!(!HmacSigner::random().verify("data", &signature));
+    }
+}
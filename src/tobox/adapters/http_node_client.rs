@@ -0,0 +1,375 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use reqwest::{Body, Method, RequestBuilder, StatusCode};
+use tokio_stream::{Stream, StreamExt};
+
+use crate::application::common::node_client::{
+    ForwardBody,
+    ForwardRequest,
+    ForwardResponse,
+    NodeClient
+};
+use crate::domain::models::file_stream::FileStream;
+use crate::domain::models::metadata::{
+    AppendRequest,
+    AppendResponse,
+    MetadataCommand,
+    Proposal,
+    VoteRequest,
+    VoteResponse
+};
+use crate::domain::models::node::{NodeId, NodeInfo};
+use crate::domain::models::replication::Replica;
+use crate::domain::models::scrub::BoxDigest;
+
+/// Requests between nodes carry the cluster secret in this header
+pub const CLUSTER_SECRET_HEADER: &str = "X-Cluster-Secret";
+
+/// Peers answering slower are treated as missing the heartbeat,
+/// blob uploads are limited only by the connection time
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Metadata consensus requests are answered without delay, a slow peer must not
+/// hold back heartbeats of the leader to the others
+const CONSENSUS_TIMEOUT: Duration = Duration::from_millis(500);
+
+pub struct HttpNodeClient {
+    client: reqwest::Client,
+    secret: Option<String>
+}
+
+impl HttpNodeClient {
+    pub fn new(secret: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .connect_timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap(),
+            secret
+        }
+    }
+    
+    fn with_secret(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.secret {
+            Some(secret) => request.header(CLUSTER_SECRET_HEADER, secret),
+            None => request
+        }
+    }
+}
+
+/// Body of a response, read as the client consumes it
+struct ResponseStream(Pin<Box<dyn Stream<Item = io::Result<Bytes>> + Send>>);
+
+impl Stream for ResponseStream {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.as_mut().poll_next(cx)
+    }
+}
+
+impl FileStream for ResponseStream {}
+
+#[async_trait]
+impl NodeClient for HttpNodeClient {
+    async fn get_info(&self, address: &str) -> Result<NodeInfo, String> {
+        self.client.get(format!("{}/node/cluster/info", address))
+            .timeout(REQUEST_TIMEOUT)
+            .send().await
+            .and_then(|response| response.error_for_status())
+            .map_err(|error| error.to_string())?
+            .json::<NodeInfo>().await
+            .map_err(|error| error.to_string())
+    }
+
+    async fn has_blob(&self, address: &str, hash: &str) -> Result<bool, String> {
+        let response = self.with_secret(
+            self.client.head(format!("{}/node/cluster/blobs/{}", address, hash))
+        )
+            .timeout(REQUEST_TIMEOUT)
+            .send().await
+            .map_err(|error| error.to_string())?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(false),
+            _ => response.error_for_status().map(|_| true).map_err(|error| error.to_string())
+        }
+    }
+
+    async fn push_blob(
+        &self,
+        address: &str,
+        hash: &str,
+        content: Box<dyn FileStream>
+    ) -> Result<(), String> {
+        self.with_secret(
+            self.client.put(format!("{}/node/cluster/blobs/{}", address, hash))
+        )
+            .body(Body::wrap_stream(content))
+            .send().await
+            .and_then(|response| response.error_for_status())
+            .map(|_| ())
+            .map_err(|error| error.to_string())
+    }
+
+    async fn push_object(&self, address: &str, replica: &Replica) -> Result<(), String> {
+        self.with_secret(
+            self.client.put(format!("{}/node/cluster/objects", address))
+        )
+            .timeout(REQUEST_TIMEOUT)
+            .json(replica)
+            .send().await
+            .and_then(|response| response.error_for_status())
+            .map(|_| ())
+            .map_err(|error| error.to_string())
+    }
+
+    async fn fetch_blob(&self, address: &str, hash: &str) -> Result<Box<dyn FileStream>, String> {
+        let response = self.with_secret(
+            self.client.get(format!("{}/node/cluster/blobs/{}", address, hash))
+        )
+            .send().await
+            .and_then(|response| response.error_for_status())
+            .map_err(|error| error.to_string())?;
+        Ok(Box::new(ResponseStream(Box::pin(
+            response.bytes_stream().map(|chunk| chunk.map_err(io::Error::other))
+        ))))
+    }
+
+    async fn get_digests(&self, address: &str, node_id: &NodeId) -> Result<Vec<BoxDigest>, String> {
+        self.with_secret(
+            self.client.get(format!("{}/node/cluster/digests/{}", address, node_id))
+        )
+            .timeout(REQUEST_TIMEOUT)
+            .send().await
+            .and_then(|response| response.error_for_status())
+            .map_err(|error| error.to_string())?
+            .json::<Vec<BoxDigest>>().await
+            .map_err(|error| error.to_string())
+    }
+
+    async fn request_vote(&self, address: &str, request: &VoteRequest) -> Result<VoteResponse, String> {
+        self.with_secret(
+            self.client.post(format!("{}/node/cluster/metadata/vote", address))
+        )
+            .timeout(CONSENSUS_TIMEOUT)
+            .json(request)
+            .send().await
+            .and_then(|response| response.error_for_status())
+            .map_err(|error| error.to_string())?
+            .json::<VoteResponse>().await
+            .map_err(|error| error.to_string())
+    }
+
+    async fn append_entries(&self, address: &str, request: &AppendRequest) -> Result<AppendResponse, String> {
+        self.with_secret(
+            self.client.post(format!("{}/node/cluster/metadata/append", address))
+        )
+            .timeout(CONSENSUS_TIMEOUT)
+            .json(request)
+            .send().await
+            .and_then(|response| response.error_for_status())
+            .map_err(|error| error.to_string())?
+            .json::<AppendResponse>().await
+            .map_err(|error| error.to_string())
+    }
+
+    async fn propose(&self, address: &str, command: &MetadataCommand) -> Result<Proposal, String> {
+        self.with_secret(
+            self.client.post(format!("{}/node/cluster/metadata/proposals", address))
+        )
+            .timeout(REQUEST_TIMEOUT)
+            .json(command)
+            .send().await
+            .and_then(|response| response.error_for_status())
+            .map_err(|error| error.to_string())?
+            .json::<Proposal>().await
+            .map_err(|error| error.to_string())
+    }
+
+    async fn forward(
+        &self,
+        address: &str,
+        request: ForwardRequest
+    ) -> Result<ForwardResponse, String> {
+        let method = Method::from_bytes(request.method.as_bytes())
+            .map_err(|error| error.to_string())?;
+        let mut builder = self.with_secret(
+            self.client.request(method, format!("{}{}", address, request.path))
+        );
+        for (name, value) in request.headers {
+            builder = builder.header(name, value);
+        }
+        builder = match request.body {
+            ForwardBody::Buffered(bytes) => builder.body(bytes),
+            ForwardBody::Stream(stream) => builder.body(Body::wrap_stream(stream))
+        };
+        
+        let response = builder.send().await.map_err(|error| error.to_string())?;
+        Ok(ForwardResponse {
+            status: response.status().as_u16(),
+            headers: response.headers().iter()
+                .filter_map(|(name, value)| Some((
+                    name.to_string(),
+                    value.to_str().ok()?.to_string()
+                )))
+                .collect(),
+            body: Box::new(ResponseStream(Box::pin(
+                response.bytes_stream().map(|chunk| chunk.map_err(io::Error::other))
+            )))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use actix_web::{App, HttpRequest, HttpResponse, HttpServer, web};
+    use chrono::Utc;
+
+    use crate::domain::models::object::Object;
+    use crate::domain::models::r#box::{Box as BoxDomain, BoxAccessPolicy, DuplicateNamePolicy};
+
+    use super::*;
+
+    /// Start a server answering with the node info, returns its address
+    fn serve(info: NodeInfo) -> String {
+        let server = HttpServer::new(move || {
+            let info = info.clone();
+            App::new().route("/node/cluster/info", web::get().to(
+                move || { let info = info.clone(); async move { HttpResponse::Ok().json(info) } }
+            ))
+        }).workers(1).bind(("127.0.0.1", 0)).unwrap();
+        let address = format!("http://{}", server.addrs()[0]);
+        actix_web::rt::spawn(server.run());
+        address
+    }
+
+    #[actix_web::test]
+    async fn test_get_info() {
+        let client = HttpNodeClient::new(None);
+        let addresses: Vec<String> = ["a", "b", "c"].iter().map(|id| serve(NodeInfo {
+            id: id.to_string(),
+            version: "0.1.0".to_string(),
+            capacity: 1024
+        })).collect();
+        
+        for (address, id) in addresses.iter().zip(["a", "b", "c"]) {
+            let info = client.get_info(address).await.unwrap();
+            assert_eq!(info.id, id);
+            assert_eq!(info.capacity, 1024);
+        }
+        
+        // Nothing listens on the port of a dropped listener
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        assert!(client.get_info(&address).await.is_err());
+    }
+
+    
+    struct OneChunk(Option<Bytes>);
+    
+    impl Stream for OneChunk {
+        type Item = io::Result<Bytes>;
+        
+        fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            Poll::Ready(self.0.take().map(Ok))
+        }
+    }
+    
+    impl FileStream for OneChunk {}
+
+    #[actix_web::test]
+    async fn test_push() {
+        let blobs: Arc<Mutex<HashMap<String, Bytes>>> = Arc::default();
+        let objects: Arc<Mutex<Vec<String>>> = Arc::default();
+        let is_peer = |req: &HttpRequest| req.headers().get(CLUSTER_SECRET_HEADER)
+            .is_some_and(|value| value == "secret");
+        
+        let (server_blobs, server_objects) = (blobs.clone(), objects.clone());
+        let server = HttpServer::new(move || {
+            let (head_blobs, put_blobs, get_blobs, objects) = (
+                server_blobs.clone(), server_blobs.clone(), server_blobs.clone(), server_objects.clone()
+            );
+            App::new()
+                .route("/node/cluster/blobs/{hash}", web::head().to(
+                    move |req: HttpRequest, hash: web::Path<String>| {
+                        let found = head_blobs.lock().unwrap().contains_key(hash.as_str());
+                        async move { match (is_peer(&req), found) {
+                            (false, _) => HttpResponse::Unauthorized().finish(),
+                            (true, true) => HttpResponse::Ok().finish(),
+                            (true, false) => HttpResponse::NotFound().finish()
+                        } }
+                    }
+                ))
+                .route("/node/cluster/blobs/{hash}", web::put().to(
+                    move |hash: web::Path<String>, body: Bytes| {
+                        put_blobs.lock().unwrap().insert(hash.into_inner(), body);
+                        async { HttpResponse::NoContent().finish() }
+                    }
+                ))
+                .route("/node/cluster/blobs/{hash}", web::get().to(
+                    move |hash: web::Path<String>| {
+                        let blob = get_blobs.lock().unwrap().get(hash.as_str()).cloned();
+                        async move { match blob {
+                            Some(blob) => HttpResponse::Ok().body(blob),
+                            None => HttpResponse::NotFound().finish()
+                        } }
+                    }
+                ))
+                .route("/node/cluster/objects", web::put().to(
+                    move |replica: web::Json<Replica>| {
+                        objects.lock().unwrap().push(replica.object.id.clone());
+                        async { HttpResponse::NoContent().finish() }
+                    }
+                ))
+        }).workers(1).bind(("127.0.0.1", 0)).unwrap();
+        let address = format!("http://{}", server.addrs()[0]);
+        actix_web::rt::spawn(server.run());
+        
+        let client = HttpNodeClient::new(Some("secret".to_string()));
+        assert!(!client.has_blob(&address, "hash").await.unwrap());
+        client.push_blob(
+            &address,
+            "hash",
+            Box::new(OneChunk(Some(Bytes::from_static(b"content"))))
+        ).await.unwrap();
+        assert!(client.has_blob(&address, "hash").await.unwrap());
+        assert_eq!(blobs.lock().unwrap()["hash"], "content");
+        
+        let mut content = client.fetch_blob(&address, "hash").await.unwrap();
+        assert_eq!(content.next().await.unwrap().unwrap(), "content");
+        assert!(client.fetch_blob(&address, "missing").await.is_err());
+        assert!(HttpNodeClient::new(None).has_blob(&address, "hash").await.is_err());
+        
+        let replica = Replica {
+            object: Object {
+                id: "object".to_string(),
+                name: None,
+                path: None,
+                hash: "hash".to_string(),
+                size: 7,
+                content_type: "text/plain".to_string(),
+                metadata: HashMap::new(),
+                box_id: "box".to_string(),
+                created_at: Utc::now(),
+                updated_at: None
+            },
+            r#box: BoxDomain {
+                id: "box".to_string(),
+                duplicate_names: DuplicateNamePolicy::Allow,
+                access: BoxAccessPolicy::Private,
+                created_at: Utc::now()
+            }
+        };
+        client.push_object(&address, &replica).await.unwrap();
+        assert_eq!(*objects.lock().unwrap(), ["object"]);
+    }
+}
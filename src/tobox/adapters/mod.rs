@@ -1,6 +1,10 @@
 pub mod database;
 pub mod argon2_password_hasher;
 pub mod sha256_session_hasher;
+pub mod file_signature;
 pub mod auth;
 pub mod redis_confirm_code;
 pub mod rmq_email_sender;
+pub mod http_node_client;
+pub mod replicated_metadata;
+pub mod hmac_signer;
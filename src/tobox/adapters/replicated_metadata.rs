@@ -0,0 +1,290 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::application::common::box_gateway::{BoxGateway, BoxReader, BoxRemover, BoxWriter};
+use crate::application::common::permission_gateway::{
+    PermissionGateway,
+    PermissionLinker,
+    PermissionReader,
+    PermissionWriter
+};
+use crate::application::common::role_gateway::{
+    RoleGateway,
+    RoleLinker,
+    RoleReader,
+    RoleRemover,
+    RoleWriter
+};
+use crate::application::common::user_gateway::{UserGateway, UserReader, UserRemover, UserWriter};
+use crate::application::metadata::proposer::MetadataProposer;
+use crate::domain::models::metadata::MetadataCommand;
+use crate::domain::models::permission::{Permission as PermissionDomain, PermissionId, PermissionTag};
+use crate::domain::models::r#box::{Box as BoxDomain, BoxId};
+use crate::domain::models::role::{Role as RoleDomain, RoleId};
+use crate::domain::models::user::{User as UserDomain, UserId};
+
+/// The writer traits have no way to report a failure, a change that is not applied
+/// in time is logged and the caller reads the previous state
+async fn propose(proposer: &MetadataProposer, command: MetadataCommand) {
+    if let Err(error) = proposer.propose(command).await {
+        log::error!("Metadata change is not replicated: {}", error);
+    }
+}
+
+/// Users of the cluster: read from the local database,
+/// changed through the replicated metadata log
+pub struct ReplicatedUserGateway {
+    local: Arc<dyn UserGateway + Send + Sync>,
+    proposer: MetadataProposer
+}
+
+impl ReplicatedUserGateway {
+    pub fn new(local: Arc<dyn UserGateway + Send + Sync>, proposer: MetadataProposer) -> Self {
+        Self { local, proposer }
+    }
+}
+
+#[async_trait]
+impl UserReader for ReplicatedUserGateway {
+    async fn get_user(&self, user_id: &UserId) -> Option<UserDomain> {
+        self.local.get_user(user_id).await
+    }
+
+    async fn get_users(&self, user_ids: &Vec<UserId>) -> Option<Vec<UserDomain>> {
+        self.local.get_users(user_ids).await
+    }
+
+    async fn get_users_range(&self, limit: &u64, offset: &u64) -> Vec<UserDomain> {
+        self.local.get_users_range(limit, offset).await
+    }
+
+    async fn get_user_by_username_not_sensitive(&self, username: &String) -> Option<UserDomain> {
+        self.local.get_user_by_username_not_sensitive(username).await
+    }
+}
+
+#[async_trait]
+impl UserWriter for ReplicatedUserGateway {
+    async fn save_user(&self, data: &UserDomain) {
+        propose(&self.proposer, MetadataCommand::SaveUser { user: data.clone() }).await
+    }
+}
+
+#[async_trait]
+impl UserRemover for ReplicatedUserGateway {
+    async fn remove_user(&self, user_id: &UserId) {
+        propose(&self.proposer, MetadataCommand::RemoveUser { user_id: user_id.clone() }).await
+    }
+}
+
+impl UserGateway for ReplicatedUserGateway {}
+
+/// Roles of the cluster: read from the local database,
+/// changed through the replicated metadata log
+pub struct ReplicatedRoleGateway {
+    local: Arc<dyn RoleGateway + Send + Sync>,
+    proposer: MetadataProposer
+}
+
+impl ReplicatedRoleGateway {
+    pub fn new(local: Arc<dyn RoleGateway + Send + Sync>, proposer: MetadataProposer) -> Self {
+        Self { local, proposer }
+    }
+}
+
+#[async_trait]
+impl RoleReader for ReplicatedRoleGateway {
+    async fn get_role(&self, role_id: &RoleId) -> Option<RoleDomain> {
+        self.local.get_role(role_id).await
+    }
+
+    async fn get_roles(&self, role_ids: &Vec<RoleId>) -> Option<Vec<RoleDomain>> {
+        self.local.get_roles(role_ids).await
+    }
+
+    async fn get_roles_range(&self, limit: &u64, offset: &u64) -> Vec<RoleDomain> {
+        self.local.get_roles_range(limit, offset).await
+    }
+
+    async fn get_user_roles(&self, user_id: &UserId) -> Vec<RoleDomain> {
+        self.local.get_user_roles(user_id).await
+    }
+
+    async fn get_role_by_title_not_sensitive(&self, title: &String) -> Option<RoleDomain> {
+        self.local.get_role_by_title_not_sensitive(title).await
+    }
+
+    async fn get_default_role(&self) -> Option<RoleDomain> {
+        self.local.get_default_role().await
+    }
+}
+
+#[async_trait]
+impl RoleWriter for ReplicatedRoleGateway {
+    async fn save_role(&self, data: &RoleDomain) {
+        propose(&self.proposer, MetadataCommand::SaveRole { role: data.clone() }).await
+    }
+
+    async fn set_default_role(&self, role_id: &RoleId) {
+        propose(&self.proposer, MetadataCommand::SetDefaultRole { role_id: role_id.clone() }).await
+    }
+}
+
+#[async_trait]
+impl RoleLinker for ReplicatedRoleGateway {
+    async fn link_role_to_user(&self, role_id: &RoleId, user_id: &UserId) {
+        propose(&self.proposer, MetadataCommand::LinkRoleUser {
+            role_id: role_id.clone(),
+            user_id: user_id.clone()
+        }).await
+    }
+
+    async fn unlink_role_from_user(&self, role_id: &RoleId, user_id: &UserId) {
+        propose(&self.proposer, MetadataCommand::UnlinkRoleUser {
+            role_id: role_id.clone(),
+            user_id: user_id.clone()
+        }).await
+    }
+
+    async fn is_role_linked_to_user(&self, role_id: &RoleId, user_id: &UserId) -> bool {
+        self.local.is_role_linked_to_user(role_id, user_id).await
+    }
+}
+
+#[async_trait]
+impl RoleRemover for ReplicatedRoleGateway {
+    async fn remove_role(&self, role_id: &RoleId) {
+        propose(&self.proposer, MetadataCommand::RemoveRole { role_id: role_id.clone() }).await
+    }
+}
+
+impl RoleGateway for ReplicatedRoleGateway {}
+
+/// Permissions of the cluster and their links to roles: read from the local database,
+/// changed through the replicated metadata log
+pub struct ReplicatedPermissionGateway {
+    local: Arc<dyn PermissionGateway>,
+    proposer: MetadataProposer
+}
+
+impl ReplicatedPermissionGateway {
+    pub fn new(local: Arc<dyn PermissionGateway>, proposer: MetadataProposer) -> Self {
+        Self { local, proposer }
+    }
+}
+
+#[async_trait]
+impl PermissionReader for ReplicatedPermissionGateway {
+    async fn get_permission(&self, permission_id: &PermissionId) -> Option<PermissionDomain> {
+        self.local.get_permission(permission_id).await
+    }
+
+    async fn get_permissions(&self, permission_ids: &Vec<PermissionId>) -> Option<Vec<PermissionDomain>> {
+        self.local.get_permissions(permission_ids).await
+    }
+
+    async fn get_permissions_by_tags(
+        &self,
+        permission_tags: &Vec<PermissionTag>
+    ) -> Option<Vec<PermissionDomain>> {
+        self.local.get_permissions_by_tags(permission_tags).await
+    }
+
+    async fn get_permissions_range(&self, limit: &u64, offset: &u64) -> Vec<PermissionDomain> {
+        self.local.get_permissions_range(limit, offset).await
+    }
+
+    async fn get_role_permissions(&self, role_id: &RoleId) -> Vec<PermissionDomain> {
+        self.local.get_role_permissions(role_id).await
+    }
+
+    async fn get_user_permissions(&self, user_id: &UserId) -> Vec<PermissionDomain> {
+        self.local.get_user_permissions(user_id).await
+    }
+}
+
+#[async_trait]
+impl PermissionWriter for ReplicatedPermissionGateway {
+    async fn save_permission(&self, data: &PermissionDomain) {
+        propose(&self.proposer, MetadataCommand::SavePermissions { permissions: vec![data.clone()] }).await
+    }
+
+    async fn save_permissions(&self, data: &Vec<PermissionDomain>) {
+        propose(&self.proposer, MetadataCommand::SavePermissions { permissions: data.clone() }).await
+    }
+}
+
+#[async_trait]
+impl PermissionLinker for ReplicatedPermissionGateway {
+    async fn is_permission_linked_to_role(&self, role_id: &RoleId, permission_id: &PermissionId) -> bool {
+        self.local.is_permission_linked_to_role(role_id, permission_id).await
+    }
+
+    async fn link_permission_to_role(&self, role_id: &RoleId, permission_id: &PermissionId) {
+        propose(&self.proposer, MetadataCommand::LinkRolePermissions {
+            role_id: role_id.clone(),
+            permission_ids: vec![permission_id.clone()]
+        }).await
+    }
+
+    async fn link_permissions_to_role(&self, role_id: &RoleId, permission_ids: &Vec<PermissionId>) {
+        propose(&self.proposer, MetadataCommand::LinkRolePermissions {
+            role_id: role_id.clone(),
+            permission_ids: permission_ids.clone()
+        }).await
+    }
+
+    async fn unlink_permission_from_role(&self, role_id: &RoleId, permission_id: &PermissionId) {
+        propose(&self.proposer, MetadataCommand::UnlinkRolePermission {
+            role_id: role_id.clone(),
+            permission_id: permission_id.clone()
+        }).await
+    }
+}
+
+impl PermissionGateway for ReplicatedPermissionGateway {}
+
+/// Boxes of the cluster: read from the local database,
+/// changed through the replicated metadata log
+pub struct ReplicatedBoxGateway {
+    local: Arc<dyn BoxGateway + Send + Sync>,
+    proposer: MetadataProposer
+}
+
+impl ReplicatedBoxGateway {
+    pub fn new(local: Arc<dyn BoxGateway + Send + Sync>, proposer: MetadataProposer) -> Self {
+        Self { local, proposer }
+    }
+}
+
+#[async_trait]
+impl BoxReader for ReplicatedBoxGateway {
+    async fn get_box(&self, box_id: &BoxId) -> Option<BoxDomain> {
+        self.local.get_box(box_id).await
+    }
+
+    async fn get_boxes(&self) -> Vec<BoxDomain> {
+        self.local.get_boxes().await
+    }
+
+    async fn get_boxes_range(&self, limit: &u64, offset: &u64) -> Vec<BoxDomain> {
+        self.local.get_boxes_range(limit, offset).await
+    }
+}
+
+#[async_trait]
+impl BoxWriter for ReplicatedBoxGateway {
+    async fn save_box(&self, data: &BoxDomain) {
+        propose(&self.proposer, MetadataCommand::SaveBox { r#box: data.clone() }).await
+    }
+}
+
+#[async_trait]
+impl BoxRemover for ReplicatedBoxGateway {
+    async fn remove_box(&self, box_id: &BoxId) {
+        propose(&self.proposer, MetadataCommand::RemoveBox { box_id: box_id.clone() }).await
+    }
+}
+
+impl BoxGateway for ReplicatedBoxGateway {}
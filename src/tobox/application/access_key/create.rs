@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::application::common::access_key_gateway::AccessKeyWriter;
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::access_key::AccessKeyId;
+use crate::domain::services::access::AccessService;
+use crate::domain::services::access_key::AccessKeyService;
+
+#[derive(Debug, Serialize)]
+pub struct CreateAccessKeyResultDTO {
+    pub id: AccessKeyId,
+    pub secret: String,
+    pub created_at: DateTime<Utc>
+}
+
+pub struct CreateAccessKey<'a> {
+    pub access_key_writer: &'a dyn AccessKeyWriter,
+    pub access_key_service: &'a AccessKeyService,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<(), CreateAccessKeyResultDTO> for CreateAccessKey<'_> {
+    async fn execute(&self, _data: ()) -> Result<CreateAccessKeyResultDTO, ApplicationError> {
+
+        match self.access_service.ensure_can_manage_access_keys(
+            self.id_provider.is_auth()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        let access_key = self.access_key_service.create_access_key(
+            self.id_provider.user_id().unwrap().clone()
+        );
+
+        self.access_key_writer.save_access_key(&access_key).await;
+
+        // The secret is shown only once
+        Ok(CreateAccessKeyResultDTO {
+            id: access_key.id,
+            secret: access_key.secret,
+            created_at: access_key.created_at
+        })
+    }
+}
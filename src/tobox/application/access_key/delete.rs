@@ -0,0 +1,50 @@
+use serde::Deserialize;
+
+use crate::application::common::access_key_gateway::AccessKeyGateway;
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::access_key::AccessKeyId;
+use crate::domain::services::access::AccessService;
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteAccessKeyDTO {
+    pub id: AccessKeyId
+}
+
+pub struct DeleteAccessKey<'a> {
+    pub access_key_gateway: &'a dyn AccessKeyGateway,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<DeleteAccessKeyDTO, ()> for DeleteAccessKey<'_> {
+    async fn execute(&self, data: DeleteAccessKeyDTO) -> Result<(), ApplicationError> {
+
+        match self.access_service.ensure_can_manage_access_keys(
+            self.id_provider.is_auth()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        match self.access_key_gateway.get_access_key(&data.id).await {
+            Some(access_key) if Some(&access_key.user_id) == self.id_provider.user_id() => (),
+            _ => return Err(
+                ApplicationError::NotFound(ErrorContent::from("Access key not found"))
+            )
+        };
+
+        self.access_key_gateway.remove_access_key(&data.id).await;
+
+        Ok(())
+    }
+}
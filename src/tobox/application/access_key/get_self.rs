@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::application::common::access_key_gateway::AccessKeyReader;
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::access_key::AccessKeyId;
+use crate::domain::services::access::AccessService;
+
+#[derive(Debug, Serialize)]
+pub struct AccessKeyItem {
+    pub id: AccessKeyId,
+    pub created_at: DateTime<Utc>
+}
+
+pub type GetSelfAccessKeysResultDTO = Vec<AccessKeyItem>;
+
+pub struct GetSelfAccessKeys<'a> {
+    pub access_key_reader: &'a dyn AccessKeyReader,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<(), GetSelfAccessKeysResultDTO> for GetSelfAccessKeys<'_> {
+    async fn execute(&self, _data: ()) -> Result<GetSelfAccessKeysResultDTO, ApplicationError> {
+
+        match self.access_service.ensure_can_manage_access_keys(
+            self.id_provider.is_auth()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        let access_keys = self.access_key_reader.get_user_access_keys(
+            self.id_provider.user_id().unwrap()
+        ).await;
+
+        Ok(access_keys.into_iter().map(|access_key| AccessKeyItem {
+            id: access_key.id,
+            created_at: access_key.created_at
+        }).collect())
+    }
+}
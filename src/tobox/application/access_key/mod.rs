@@ -0,0 +1,3 @@
+pub mod create;
+pub mod get_self;
+pub mod delete;
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::application::common::api_key_gateway::ApiKeyWriter;
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::hasher::Hasher;
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::permission_gateway::PermissionReader;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::api_key::ApiKeyId;
+use crate::domain::models::permission::PermissionId;
+use crate::domain::services::access::AccessService;
+use crate::domain::services::api_key::ApiKeyService;
+use crate::domain::services::validator::ValidatorService;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyDTO {
+    pub name: String,
+    /// Permissions of the caller when not set
+    pub permission_ids: Option<Vec<PermissionId>>,
+    pub expires_at: Option<DateTime<Utc>>
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateApiKeyResultDTO {
+    pub id: ApiKeyId,
+    pub name: String,
+    pub key: String,
+    pub permissions: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>
+}
+
+pub struct CreateApiKey<'a> {
+    pub api_key_writer: &'a dyn ApiKeyWriter,
+    pub permission_reader: &'a dyn PermissionReader,
+    pub api_key_hasher: &'a dyn Hasher,
+    pub api_key_service: &'a ApiKeyService,
+    pub validator: &'a ValidatorService,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<CreateApiKeyDTO, CreateApiKeyResultDTO> for CreateApiKey<'_> {
+    async fn execute(&self, data: CreateApiKeyDTO) -> Result<CreateApiKeyResultDTO, ApplicationError> {
+
+        match self.access_service.ensure_can_manage_api_keys(
+            self.id_provider.is_auth()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        let mut validator_err_map: HashMap<String, String> = HashMap::new();
+        self.validator.validate_api_key_name(&data.name).unwrap_or_else(|e| {
+            validator_err_map.insert("name".to_string(), e);
+        });
+        if data.expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+            validator_err_map.insert("expires_at".to_string(), "Expiry time should be in the future".to_string());
+        }
+        if !validator_err_map.is_empty() {
+            return Err(
+                ApplicationError::InvalidData(ErrorContent::from(validator_err_map))
+            )
+        }
+
+        // A key gets no more rights than the caller has, which is narrower than
+        // the rights of the owner when the caller is an api key itself
+        let granted = self.id_provider.permissions();
+        let user_id = self.id_provider.user_id().unwrap();
+        let permissions = match data.permission_ids {
+            Some(mut permission_ids) => {
+                permission_ids.sort();
+                permission_ids.dedup();
+                let permissions = match self.permission_reader.get_permissions(&permission_ids).await {
+                    Some(permissions) => permissions,
+                    None => return Err(
+                        ApplicationError::NotFound(ErrorContent::from("Permission not found"))
+                    )
+                };
+                if let Some(permission) = permissions.iter().find(
+                    |permission| !granted.contains(&permission.tag.to_string())
+                ) {
+                    return Err(ApplicationError::Forbidden(ErrorContent::from(format!(
+                        "The permission {} is not granted to the user", permission.tag
+                    ))))
+                }
+                permissions
+            },
+            None => self.permission_reader.get_user_permissions(user_id).await
+                .into_iter()
+                .filter(|permission| granted.contains(&permission.tag.to_string()))
+                .collect()
+        };
+
+        let key = self.api_key_service.generate_key();
+        let api_key = self.api_key_service.create_api_key(
+            data.name,
+            self.api_key_hasher.hash(&key).await,
+            user_id.clone(),
+            permissions.into_iter().map(|permission| permission.tag).collect(),
+            data.expires_at
+        );
+
+        self.api_key_writer.save_api_key(&api_key).await;
+
+        // The key is shown only once
+        Ok(CreateApiKeyResultDTO {
+            id: api_key.id,
+            name: api_key.name,
+            key,
+            permissions: api_key.permissions.iter().map(|tag| tag.to_string()).collect(),
+            created_at: api_key.created_at,
+            expires_at: api_key.expires_at
+        })
+    }
+}
@@ -0,0 +1,50 @@
+use serde::Deserialize;
+
+use crate::application::common::api_key_gateway::ApiKeyGateway;
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::api_key::ApiKeyId;
+use crate::domain::services::access::AccessService;
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteApiKeyDTO {
+    pub id: ApiKeyId
+}
+
+pub struct DeleteApiKey<'a> {
+    pub api_key_gateway: &'a dyn ApiKeyGateway,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<DeleteApiKeyDTO, ()> for DeleteApiKey<'_> {
+    async fn execute(&self, data: DeleteApiKeyDTO) -> Result<(), ApplicationError> {
+
+        match self.access_service.ensure_can_manage_api_keys(
+            self.id_provider.is_auth()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        match self.api_key_gateway.get_api_key(&data.id).await {
+            Some(api_key) if Some(&api_key.user_id) == self.id_provider.user_id() => (),
+            _ => return Err(
+                ApplicationError::NotFound(ErrorContent::from("Api key not found"))
+            )
+        };
+
+        self.api_key_gateway.remove_api_key(&data.id).await;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::application::common::api_key_gateway::ApiKeyReader;
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::api_key::ApiKeyId;
+use crate::domain::services::access::AccessService;
+
+#[derive(Debug, Serialize)]
+pub struct ApiKeyItem {
+    pub id: ApiKeyId,
+    pub name: String,
+    pub permissions: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>
+}
+
+pub type GetSelfApiKeysResultDTO = Vec<ApiKeyItem>;
+
+pub struct GetSelfApiKeys<'a> {
+    pub api_key_reader: &'a dyn ApiKeyReader,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<(), GetSelfApiKeysResultDTO> for GetSelfApiKeys<'_> {
+    async fn execute(&self, _data: ()) -> Result<GetSelfApiKeysResultDTO, ApplicationError> {
+
+        match self.access_service.ensure_can_manage_api_keys(
+            self.id_provider.is_auth()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        let api_keys = self.api_key_reader.get_user_api_keys(
+            self.id_provider.user_id().unwrap()
+        ).await;
+
+        Ok(api_keys.into_iter().map(|api_key| ApiKeyItem {
+            id: api_key.id,
+            name: api_key.name,
+            permissions: api_key.permissions.iter().map(|tag| tag.to_string()).collect(),
+            created_at: api_key.created_at,
+            expires_at: api_key.expires_at
+        }).collect())
+    }
+}
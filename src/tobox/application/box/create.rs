@@ -1,20 +1,36 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::application::common::box_gateway::BoxGateway;
 use crate::application::common::exceptions::{ApplicationError, ErrorContent};
 use crate::application::common::id_provider::IdProvider;
 use crate::application::common::interactor::Interactor;
 use crate::domain::exceptions::DomainError;
-use crate::domain::models::r#box::BoxId;
+use crate::domain::models::r#box::{BoxAccessPolicy, BoxId, DuplicateNamePolicy};
 use crate::domain::services::access::AccessService;
 use crate::domain::services::r#box::BoxService;
 use crate::domain::services::validator::ValidatorService;
 
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateBoxDTO {
+    /// Generated when not set
+    pub id: Option<BoxId>,
+    /// Handling of objects stored with a path and name already taken in the box
+    #[serde(default)]
+    pub duplicate_names: DuplicateNamePolicy,
+    /// Private when not set
+    #[serde(default)]
+    pub access: BoxAccessPolicy
+}
+
 #[derive(Debug, Serialize)]
 pub struct CreateBoxResultDTO{
-    id: BoxId,
-    created_at: DateTime<Utc>
+    pub id: BoxId,
+    pub duplicate_names: DuplicateNamePolicy,
+    pub access: BoxAccessPolicy,
+    pub created_at: DateTime<Utc>
 }
 
 pub struct CreateBox<'a> {
@@ -25,8 +41,8 @@ pub struct CreateBox<'a> {
     pub id_provider: Box<dyn IdProvider>,
 }
 
-impl Interactor<(), CreateBoxResultDTO> for CreateBox<'_> {
-    async fn execute(&self, _data: ()) -> Result<CreateBoxResultDTO, ApplicationError> {
+impl Interactor<CreateBoxDTO, CreateBoxResultDTO> for CreateBox<'_> {
+    async fn execute(&self, data: CreateBoxDTO) -> Result<CreateBoxResultDTO, ApplicationError> {
         
         match self.access_service.ensure_can_create_box(
             self.id_provider.is_auth(),
@@ -43,12 +59,32 @@ impl Interactor<(), CreateBoxResultDTO> for CreateBox<'_> {
             }
         };
         
-        let r#box = self.box_service.create_box();
+        if let Some(id) = &data.id {
+            let mut validator_err_map: HashMap<String, String> = HashMap::new();
+            self.validator.validate_box_id(id).unwrap_or_else(|e| {
+                validator_err_map.insert("id".to_string(), e.to_string());
+            });
+            if !validator_err_map.is_empty() {
+                return Err(
+                    ApplicationError::InvalidData(ErrorContent::from(validator_err_map))
+                )
+            }
+            
+            if self.box_gateway.get_box(id).await.is_some() {
+                return Err(
+                    ApplicationError::Conflict(ErrorContent::from("Box already exists"))
+                )
+            }
+        }
+        
+        let r#box = self.box_service.create_box(data.id, data.duplicate_names, data.access);
         
         self.box_gateway.save_box(&r#box).await;
 
         Ok(CreateBoxResultDTO {
             id: r#box.id,
+            duplicate_names: r#box.duplicate_names,
+            access: r#box.access,
             created_at: r#box.created_at
         })
     }
@@ -4,6 +4,7 @@ use crate::application::common::box_gateway::BoxGateway;
 use crate::application::common::exceptions::{ApplicationError, ErrorContent};
 use crate::application::common::id_provider::IdProvider;
 use crate::application::common::interactor::Interactor;
+use crate::application::common::object_gateway::{ObjectFilter, ObjectReader};
 use crate::domain::exceptions::DomainError;
 use crate::domain::models::r#box::BoxId;
 use crate::domain::services::access::AccessService;
@@ -17,6 +18,7 @@ pub struct DeleteBoxDTO {
 
 pub struct DeleteBox<'a> {
     pub box_gateway: &'a dyn BoxGateway,
+    pub object_reader: &'a dyn ObjectReader,
     pub box_service: &'a BoxService,
     pub validator: &'a ValidatorService,
     pub access_service: &'a AccessService,
@@ -45,7 +47,14 @@ impl Interactor<DeleteBoxDTO, ()> for DeleteBox<'_> {
         match self.box_gateway.get_box(&data.id).await {
             Some(_) => (),
             None => return Err(
-                ApplicationError::InvalidData(ErrorContent::from("Box not found"))
+                ApplicationError::NotFound(ErrorContent::from("Box not found"))
+            )
+        }
+        
+        let filter = ObjectFilter { box_id: data.id.clone(), ..Default::default() };
+        if !self.object_reader.list_objects(&filter, None, 1).await.is_empty() {
+            return Err(
+                ApplicationError::Conflict(ErrorContent::from("Box is not empty"))
             )
         }
         
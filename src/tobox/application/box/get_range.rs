@@ -8,7 +8,7 @@ use crate::application::common::exceptions::{ApplicationError, ErrorContent};
 use crate::application::common::id_provider::IdProvider;
 use crate::application::common::interactor::Interactor;
 use crate::domain::exceptions::DomainError;
-use crate::domain::models::r#box::BoxId;
+use crate::domain::models::r#box::{BoxAccessPolicy, BoxId, DuplicateNamePolicy};
 use crate::domain::services::access::AccessService;
 use crate::domain::services::validator::ValidatorService;
 
@@ -21,6 +21,8 @@ pub struct GetBoxRangeDTO {
 #[derive(Debug, Serialize)]
 pub struct BoxItem {
     pub id: BoxId,
+    pub duplicate_names: DuplicateNamePolicy,
+    pub access: BoxAccessPolicy,
     pub created_at: DateTime<Utc>,
 }
 
@@ -82,6 +84,8 @@ impl Interactor<GetBoxRangeDTO, GetBoxRangeResultDTO> for GetBoxRange<'_> {
         
         Ok(boxes.into_iter().map(|b| BoxItem {
             id: b.id,
+            duplicate_names: b.duplicate_names,
+            access: b.access,
             created_at: b.created_at,
         }).collect())
     }
@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::application::common::box_gateway::BoxGateway;
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::r#box::{BoxAccessPolicy, BoxId, DuplicateNamePolicy};
+use crate::domain::services::access::AccessService;
+
+/// Fields not set are left as they are
+#[derive(Debug)]
+pub struct UpdateBoxDTO {
+    pub id: BoxId,
+    pub duplicate_names: Option<DuplicateNamePolicy>,
+    pub access: Option<BoxAccessPolicy>
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpdateBoxResultDTO {
+    pub id: BoxId,
+    pub duplicate_names: DuplicateNamePolicy,
+    pub access: BoxAccessPolicy,
+    pub created_at: DateTime<Utc>
+}
+
+pub struct UpdateBox<'a> {
+    pub box_gateway: &'a dyn BoxGateway,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<UpdateBoxDTO, UpdateBoxResultDTO> for UpdateBox<'_> {
+    async fn execute(&self, data: UpdateBoxDTO) -> Result<UpdateBoxResultDTO, ApplicationError> {
+
+        match self.access_service.ensure_can_update_box(
+            self.id_provider.is_auth(),
+            &data.id,
+            self.id_provider.permissions()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        let mut r#box = self.box_gateway.get_box(&data.id).await.ok_or(
+            ApplicationError::NotFound(ErrorContent::from("Box not found"))
+        )?;
+
+        if let Some(duplicate_names) = data.duplicate_names {
+            r#box.duplicate_names = duplicate_names;
+        }
+        if let Some(access) = data.access {
+            r#box.access = access;
+        }
+        self.box_gateway.save_box(&r#box).await;
+
+        Ok(UpdateBoxResultDTO {
+            id: r#box.id,
+            duplicate_names: r#box.duplicate_names,
+            access: r#box.access,
+            created_at: r#box.created_at
+        })
+    }
+}
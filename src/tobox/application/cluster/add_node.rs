@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::application::cluster::get_nodes::NodeItem;
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::node_client::NodeClient;
+use crate::application::common::node_gateway::NodeGateway;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::node::NodeId;
+use crate::domain::services::access::AccessService;
+use crate::domain::services::node::NodeService;
+use crate::domain::services::validator::ValidatorService;
+
+#[derive(Debug, Deserialize)]
+pub struct AddNodeDTO {
+    /// Base url of the node, e.g. `http://10.0.0.2:3030`
+    pub address: String
+}
+
+pub type AddNodeResultDTO = NodeItem;
+
+/// Register a peer node, the node must be reachable to report its identity
+pub struct AddNode<'a> {
+    pub node_gateway: &'a dyn NodeGateway,
+    pub node_client: &'a dyn NodeClient,
+    pub node_service: &'a NodeService,
+    pub validator: &'a ValidatorService,
+    pub access_service: &'a AccessService,
+    /// Id of this node
+    pub node_id: &'a NodeId,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<AddNodeDTO, AddNodeResultDTO> for AddNode<'_> {
+    async fn execute(&self, data: AddNodeDTO) -> Result<AddNodeResultDTO, ApplicationError> {
+        
+        match self.access_service.ensure_can_add_node(
+            self.id_provider.is_auth(),
+            self.id_provider.permissions()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+        
+        let mut validator_err_map: HashMap<String, String> = HashMap::new();
+        self.validator.validate_node_address(&data.address).unwrap_or_else(|e| {
+            validator_err_map.insert("address".to_string(), e.to_string());
+        });
+        if !validator_err_map.is_empty() {
+            return Err(
+                ApplicationError::InvalidData(ErrorContent::from(validator_err_map))
+            )
+        }
+        
+        if self.node_gateway.get_node_by_address(&data.address).await.is_some() {
+            return Err(
+                ApplicationError::Conflict(ErrorContent::from("Node already exists"))
+            )
+        }
+        
+        let info = match self.node_client.get_info(&data.address).await {
+            Ok(info) => info,
+            Err(error) => {
+                validator_err_map.insert(
+                    "address".to_string(),
+                    format!("Node is not reachable: {}", error)
+                );
+                return Err(
+                    ApplicationError::InvalidData(ErrorContent::from(validator_err_map))
+                )
+            }
+        };
+        
+        if info.id == *self.node_id {
+            validator_err_map.insert(
+                "address".to_string(),
+                "Node can not be added to itself".to_string()
+            );
+            return Err(
+                ApplicationError::InvalidData(ErrorContent::from(validator_err_map))
+            )
+        }
+        
+        if self.node_gateway.get_node(&info.id).await.is_some() {
+            return Err(
+                ApplicationError::Conflict(ErrorContent::from(
+                    "Node is already registered with another address"
+                ))
+            )
+        }
+        
+        let node = self.node_service.create_node(data.address, info);
+        
+        self.node_gateway.save_node(&node).await;
+        
+        Ok(NodeItem::from(node))
+    }
+}
@@ -0,0 +1,24 @@
+use crate::application::common::exceptions::ApplicationError;
+use crate::application::common::file_storage_manager::FileStorageReader;
+use crate::application::common::interactor::Interactor;
+use crate::domain::models::node::{NodeId, NodeInfo};
+
+/// Identity of this node, requested by peers on every heartbeat
+///
+/// Does not require authorization: nodes learn each other's identity before
+/// they share any credentials.
+pub struct GetNodeInfo<'a> {
+    pub file_storage_reader: &'a dyn FileStorageReader,
+    pub node_id: &'a NodeId,
+    pub version: &'a str,
+}
+
+impl Interactor<(), NodeInfo> for GetNodeInfo<'_> {
+    async fn execute(&self, _data: ()) -> Result<NodeInfo, ApplicationError> {
+        Ok(NodeInfo {
+            id: self.node_id.clone(),
+            version: self.version.to_string(),
+            capacity: self.file_storage_reader.available_space().await,
+        })
+    }
+}
@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::node_gateway::NodeReader;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::node::{Node, NodeId, NodeStatus};
+use crate::domain::services::access::AccessService;
+
+#[derive(Debug, Deserialize)]
+pub struct GetNodesDTO {
+    pub status: Option<NodeStatus>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NodeItem {
+    pub id: NodeId,
+    pub address: String,
+    pub version: String,
+    pub capacity: u64,
+    pub status: NodeStatus,
+    pub last_heartbeat: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Node> for NodeItem {
+    fn from(node: Node) -> Self {
+        Self {
+            id: node.id,
+            address: node.address,
+            version: node.version,
+            capacity: node.capacity,
+            status: node.status,
+            last_heartbeat: node.last_heartbeat,
+            created_at: node.created_at,
+        }
+    }
+}
+
+pub type GetNodesResultDTO = Vec<NodeItem>;
+
+pub struct GetNodes<'a> {
+    pub node_reader: &'a dyn NodeReader,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<GetNodesDTO, GetNodesResultDTO> for GetNodes<'_> {
+    async fn execute(&self, data: GetNodesDTO) -> Result<GetNodesResultDTO, ApplicationError> {
+
+        match self.access_service.ensure_can_get_node(
+            self.id_provider.is_auth(),
+            self.id_provider.permissions()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        Ok(self.node_reader.get_nodes().await.into_iter()
+            .filter(|node| data.status.as_ref().is_none_or(|status| node.status == *status))
+            .map(NodeItem::from)
+            .collect())
+    }
+}
@@ -0,0 +1,38 @@
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::scrub_gateway::ScrubReader;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::scrub::ScrubReport;
+use crate::domain::services::access::AccessService;
+
+pub struct GetScrubStatus<'a> {
+    pub scrub_reader: &'a dyn ScrubReader,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<(), ScrubReport> for GetScrubStatus<'_> {
+    async fn execute(&self, _data: ()) -> Result<ScrubReport, ApplicationError> {
+
+        match self.access_service.ensure_can_get_node(
+            self.id_provider.is_auth(),
+            self.id_provider.permissions()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        match self.scrub_reader.get_report().await {
+            Some(report) => Ok(report),
+            None => Err(ApplicationError::NotFound(ErrorContent::from("The node has not been scrubbed yet")))
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use chrono::Utc;
+
+use crate::application::common::node_client::NodeClient;
+use crate::application::common::node_gateway::NodeGateway;
+use crate::domain::models::node::{NodeId, NodeStatus};
+use crate::domain::services::node::NodeService;
+
+/// Request every registered node and update its status
+///
+/// Seed addresses from the config that are not registered yet are added
+/// as soon as they answer, so a cluster can be assembled without api calls.
+pub async fn heartbeat_nodes(
+    node_gateway: &dyn NodeGateway,
+    node_client: &dyn NodeClient,
+    node_service: &NodeService,
+    node_id: &NodeId,
+    seeds: &[String]
+) {
+    for address in seeds {
+        if node_gateway.get_node_by_address(address).await.is_some() {
+            continue
+        }
+        match node_client.get_info(address).await {
+            Ok(info) if info.id == *node_id => (),
+            Ok(info) if node_gateway.get_node(&info.id).await.is_some() => log::warn!(
+                "Seed node {} is already registered with another address", address
+            ),
+            Ok(info) => {
+                let node = node_service.create_node(address.clone(), info);
+                node_gateway.save_node(&node).await;
+                log::info!("Node {} joined at {}", node.id, node.address);
+            }
+            Err(error) => log::debug!("Seed node {} is not reachable: {}", address, error)
+        }
+    }
+    
+    for node in node_gateway.get_nodes().await {
+        let status = node.status.clone();
+        let node = match node_client.get_info(&node.address).await {
+            Ok(info) if info.id == node.id => node_service.heartbeat_received(node, info),
+            Ok(info) => {
+                log::warn!(
+                    "Node {} at {} reports another id {}", node.id, node.address, info.id
+                );
+                node_service.heartbeat_missed(node, &Utc::now())
+            }
+            Err(error) => {
+                log::debug!("Node {} missed a heartbeat: {}", node.id, error);
+                node_service.heartbeat_missed(node, &Utc::now())
+            }
+        };
+        if node.status != status {
+            match node.status {
+                NodeStatus::Up => log::info!("Node {} is up", node.id),
+                NodeStatus::Suspect => log::warn!("Node {} is suspect", node.id),
+                NodeStatus::Down => log::error!("Node {} is down", node.id),
+            }
+        }
+        node_gateway.save_node(&node).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use async_trait::async_trait;
+    use chrono::TimeDelta;
+
+    use crate::adapters::database::node_db::NodeGateway as NodeDb;
+    use crate::adapters::database::sqlite::connect_in_memory;
+    use crate::application::common::node_gateway::{NodeReader, NodeWriter};
+    use crate::application::common::test_doubles::TestNodeClient;
+    use crate::domain::models::node::NodeInfo;
+
+    use super::*;
+
+    /// Nodes answering by address, others are unreachable
+    struct FakeClient(HashMap<String, NodeId>);
+
+    #[async_trait]
+    impl TestNodeClient for FakeClient {
+        async fn get_info(&self, address: &str) -> Result<NodeInfo, String> {
+            match self.0.get(address) {
+                Some(id) => Ok(NodeInfo { id: id.clone(), version: "0.1.0".to_string(), capacity: 1 }),
+                None => Err("connection refused".to_string())
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_nodes() {
+        let gateway = NodeDb::new(connect_in_memory().await);
+        let service = NodeService { };
+        let client = FakeClient(HashMap::from([
+            ("http://a".to_string(), "a".to_string()),
+            ("http://b".to_string(), "b".to_string()),
+            ("http://self".to_string(), "self".to_string()),
+        ]));
+        let seeds = ["http://a", "http://b", "http://c", "http://self"].map(String::from);
+        
+        heartbeat_nodes(&gateway, &client, &service, &"self".to_string(), &seeds).await;
+        let mut ids: Vec<NodeId> = gateway.get_nodes().await.into_iter().map(|node| node.id).collect();
+        ids.sort();
+        assert_eq!(ids, ["a", "b"]);
+        
+        // b stops answering long enough to be considered down
+        let mut b = gateway.get_node(&"b".to_string()).await.unwrap();
+        b.last_heartbeat = Some(Utc::now() - TimeDelta::minutes(5));
+        gateway.save_node(&b).await;
+        let client = FakeClient(HashMap::from([("http://a".to_string(), "a".to_string())]));
+        
+        heartbeat_nodes(&gateway, &client, &service, &"self".to_string(), &[]).await;
+        assert_eq!(gateway.get_node(&"a".to_string()).await.unwrap().status, NodeStatus::Up);
+        assert_eq!(gateway.get_node(&"b".to_string()).await.unwrap().status, NodeStatus::Down);
+    }
+}
@@ -1,3 +1,6 @@
-
 pub mod add_node;
 pub mod get_nodes;
+pub mod remove_node;
+pub mod get_info;
+pub mod heartbeat;
+pub mod get_scrub_status;
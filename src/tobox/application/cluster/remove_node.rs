@@ -0,0 +1,51 @@
+use serde::Deserialize;
+
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::node_gateway::NodeGateway;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::node::NodeId;
+use crate::domain::services::access::AccessService;
+
+#[derive(Debug, Deserialize)]
+pub struct RemoveNodeDTO {
+    pub id: NodeId
+}
+
+pub struct RemoveNode<'a> {
+    pub node_gateway: &'a dyn NodeGateway,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<RemoveNodeDTO, ()> for RemoveNode<'_> {
+    async fn execute(&self, data: RemoveNodeDTO) -> Result<(), ApplicationError> {
+        
+        match self.access_service.ensure_can_remove_node(
+            self.id_provider.is_auth(),
+            self.id_provider.permissions()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+        
+        match self.node_gateway.get_node(&data.id).await {
+            Some(_) => (),
+            None => return Err(
+                ApplicationError::NotFound(ErrorContent::from("Node not found"))
+            )
+        }
+        
+        self.node_gateway.remove_node(&data.id).await;
+
+        Ok(())
+    }
+}
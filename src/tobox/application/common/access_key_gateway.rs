@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+
+use crate::domain::models::access_key::{AccessKey, AccessKeyId};
+use crate::domain::models::user::UserId;
+
+#[async_trait]
+pub trait AccessKeyReader {
+    async fn get_access_key(&self, access_key_id: &AccessKeyId) -> Option<AccessKey>;
+    async fn get_user_access_keys(&self, user_id: &UserId) -> Vec<AccessKey>;
+}
+
+#[async_trait]
+pub trait AccessKeyWriter {
+    async fn save_access_key(&self, data: &AccessKey);
+}
+
+#[async_trait]
+pub trait AccessKeyRemover {
+    async fn remove_access_key(&self, access_key_id: &AccessKeyId);
+}
+
+pub trait AccessKeyGateway: AccessKeyReader + AccessKeyWriter + AccessKeyRemover {}
@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+
+use crate::domain::models::api_key::{ApiKey, ApiKeyId};
+use crate::domain::models::user::UserId;
+
+#[async_trait]
+pub trait ApiKeyReader {
+    async fn get_api_key(&self, api_key_id: &ApiKeyId) -> Option<ApiKey>;
+    async fn get_api_key_by_hash(&self, key_hash: &str) -> Option<ApiKey>;
+    async fn get_user_api_keys(&self, user_id: &UserId) -> Vec<ApiKey>;
+}
+
+#[async_trait]
+pub trait ApiKeyWriter {
+    async fn save_api_key(&self, data: &ApiKey);
+}
+
+#[async_trait]
+pub trait ApiKeyRemover {
+    async fn remove_api_key(&self, api_key_id: &ApiKeyId);
+}
+
+pub trait ApiKeyGateway: ApiKeyReader + ApiKeyWriter + ApiKeyRemover {}
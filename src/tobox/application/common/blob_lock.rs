@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+/// Locks of blobs by their hash, shared by everything that changes blob references
+///
+/// A blob is committed and its object is saved under the lock, references are counted
+/// and the blob is removed under the same lock. Otherwise an upload deduplicated onto
+/// an existing blob could lose it to a release that does not see the new object yet.
+#[derive(Default)]
+pub struct BlobLocks {
+    locks: Mutex<HashMap<String, Arc<AsyncMutex<()>>>>
+}
+
+impl BlobLocks {
+    pub async fn lock(&self, hash: &str) -> BlobGuard<'_> {
+        let lock = self.locks.lock().unwrap()
+            .entry(hash.to_string())
+            .or_default()
+            .clone();
+        BlobGuard {
+            locks: self,
+            hash: hash.to_string(),
+            guard: Some(lock.lock_owned().await)
+        }
+    }
+}
+
+/// The lock is held until the guard is dropped
+pub struct BlobGuard<'a> {
+    locks: &'a BlobLocks,
+    hash: String,
+    guard: Option<OwnedMutexGuard<()>>
+}
+
+impl Drop for BlobGuard<'_> {
+    fn drop(&mut self) {
+        self.guard.take();
+        let mut locks = self.locks.locks.lock().unwrap();
+        // Only the map refers to the lock: nobody holds or waits for it
+        if locks.get(&self.hash).is_some_and(|lock| Arc::strong_count(lock) == 1) {
+            locks.remove(&self.hash);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_blob_locks() {
+        let locks = BlobLocks::default();
+        let guard = locks.lock("hash").await;
+        // Other blobs are not blocked
+        drop(locks.lock("other").await);
+
+        let waiter = locks.lock("hash");
+        tokio::pin!(waiter);
+        assert!(tokio::time::timeout(Duration::from_millis(20), &mut waiter).await.is_err());
+        drop(guard);
+        drop(waiter.await);
+
+        assert!(locks.locks.lock().unwrap().is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use serde::Serialize;
+use crate::application::common::file_storage_manager::FileStorageError;
 use crate::domain::exceptions::DomainError;
 
 #[derive(Debug, Serialize, Clone)]
@@ -40,4 +41,25 @@ pub enum ApplicationError {
     Conflict(ErrorContent),
     Unauthorized(ErrorContent),
     Forbidden(ErrorContent),
+    /// No node of the cluster can serve the request or the storage of the node failed
+    Unavailable(ErrorContent),
+}
+
+impl From<FileStorageError> for ApplicationError {
+    fn from(error: FileStorageError) -> Self {
+        match error {
+            FileStorageError::InvalidContentType(text) => {
+                ApplicationError::InvalidData(ErrorContent::from(text))
+            },
+            FileStorageError::InvalidSize(text) => {
+                ApplicationError::InvalidData(ErrorContent::from(text))
+            },
+            FileStorageError::NotFound(text) => {
+                ApplicationError::NotFound(ErrorContent::from(text))
+            },
+            FileStorageError::Interrupted(text) => {
+                ApplicationError::Unavailable(ErrorContent::from(text))
+            }
+        }
+    }
 }
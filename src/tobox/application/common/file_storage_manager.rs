@@ -3,30 +3,60 @@ use crate::domain::models::file_info::FileInfo;
 use crate::domain::models::file_stream::FileStream;
 
 
+#[derive(Debug)]
 pub enum FileStorageError {
     InvalidContentType(String),
-    InvalidSize(String)
+    InvalidSize(String),
+    /// The blob is not stored or no valid copy of it is left
+    NotFound(String),
+    /// Reading or writing the blob failed, e.g. the disk is full or no volume is available
+    Interrupted(String)
 }
 
+/// State of a stored blob after it was hashed again
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlobHealth {
+    Healthy,
+    /// Damaged or lost copies were restored from the other copies or parity on this node
+    Repaired,
+    /// No copy on this node matches the hash
+    Corrupted,
+    Missing
+}
+
+/// Uploaded files are kept in a content-addressed blob store: a blob is identified
+/// by the sha256 of its content and may be shared by several objects.
 #[async_trait]
 pub trait FileStorageReader {
-    async fn read_file(&self, filename: &str) -> Box<dyn FileStream>;
+    async fn read_file(&self, hash: &str) -> Result<Box<dyn FileStream>, FileStorageError>;
+    async fn read_file_range(
+        &self,
+        hash: &str,
+        offset: u64,
+        length: u64
+    ) -> Result<Box<dyn FileStream>, FileStorageError>;
+    async fn is_file_exists(&self, hash: &str) -> bool;
+    async fn get_files(&self) -> Vec<String>;
+    
+    ///  Free space for new blobs in bytes
+    async fn available_space(&self) -> u64;
 }
 
 #[async_trait]
 pub trait FileStorageWriter {
     
-    ///  Save a file to the storage
+    ///  Save a file to the staging area of the storage
     ///  * filename: the name of the file
     ///  * content_type: the content type of the file
     ///  * size_range: the range of the file size
-    ///  * bytes: the file content
+    ///  * bytes: the file content, consumed chunk by chunk
     ///  * return: the file hash sha256
     /// 
     /// Content-type and size-range, if set, will be used to check for consistency 
     /// with the downloaded stream and will throw an exception if it does not match.
     /// 
-    /// The file will be saved in the storage and the hash sha256 will be returned.
+    /// The file will be saved and synced to disk, but it is not visible to readers
+    /// until `commit_file` is called.
     /// 
     /// Content-type, filesize and hash are calculated during file upload, it justifies the 
     /// existence of this function
@@ -35,15 +65,55 @@ pub trait FileStorageWriter {
         filename: &str,
         content_type: Option<&str>,
         size_range: Option<(u64, u64)>,
-        bytes: &dyn FileStream
+        bytes: &mut dyn FileStream
+    ) -> Result<FileInfo, FileStorageError>;
+    
+    ///  Atomically move a saved file into the blob store under its hash
+    ///
+    ///  If a blob with the same hash already exists, the saved file is discarded
+    ///  and the existing blob is reused.
+    async fn commit_file(&self, filename: &str, hash: &str) -> Result<(), FileStorageError>;
+    
+    ///  Remove a saved file that will not be committed
+    async fn discard_file(&self, filename: &str);
+    
+    ///  Save a part of a multipart upload, a part with the same number is replaced
+    ///  * return: the part hash sha256 and size
+    async fn save_part(
+        &self,
+        upload_id: &str,
+        number: u32,
+        bytes: &mut dyn FileStream
     ) -> Result<FileInfo, FileStorageError>;
     
-    async fn rename_file(&self, filename: &str, new_filename: &str);
+    ///  Concatenate upload parts in the given order into a file in the staging area
+    ///
+    ///  The result is the same as of `save_file` with the whole content,
+    ///  the file is made visible by `commit_file`.
+    async fn assemble_parts(
+        &self,
+        upload_id: &str,
+        numbers: &[u32],
+        filename: &str,
+        content_type: Option<&str>,
+        size_range: Option<(u64, u64)>
+    ) -> Result<FileInfo, FileStorageError>;
+    
+    ///  Hash every copy of the blob again and restore the damaged ones from the healthy ones
+    ///
+    ///  Unlike reads, which skip a damaged copy, the content is always read from disk.
+    async fn repair_file(&self, hash: &str) -> BlobHealth;
 }
 
 #[async_trait]
 pub trait FileStorageRemover {
-    async fn remove_file<T: Into<String>>(&self, filename: &T);
+    async fn remove_file(&self, hash: &str);
+    
+    ///  Remove files left in the staging area by interrupted uploads
+    async fn remove_staged_files(&self);
+    
+    ///  Remove all parts of a multipart upload
+    async fn remove_parts(&self, upload_id: &str);
 }
 
 pub trait FileStorageManager: FileStorageReader + FileStorageWriter + FileStorageRemover {}
@@ -1,3 +1,5 @@
+use std::rc::Rc;
+
 use crate::domain::models::user::UserId;
 
 pub trait IdProvider {
@@ -6,3 +8,19 @@ pub trait IdProvider {
     fn permissions(&self) -> &Vec<String>;
     fn is_auth(&self) -> &bool;
 }
+
+/// One identity shared by the interactors of a request, e.g. a lookup followed by a read
+impl<T: IdProvider + ?Sized> IdProvider for Rc<T> {
+    fn token(&self) -> Option<&String> {
+        (**self).token()
+    }
+    fn user_id(&self) -> Option<&UserId> {
+        (**self).user_id()
+    }
+    fn permissions(&self) -> &Vec<String> {
+        (**self).permissions()
+    }
+    fn is_auth(&self) -> &bool {
+        (**self).is_auth()
+    }
+}
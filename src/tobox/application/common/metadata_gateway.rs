@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+
+use crate::domain::models::metadata::{HardState, LogEntry, LogIndex};
+
+/// Replicated metadata log of this node
+#[async_trait]
+pub trait MetadataLogReader {
+    async fn get_hard_state(&self) -> HardState;
+    async fn get_entry(&self, index: LogIndex) -> Option<LogEntry>;
+    
+    /// Entries starting at the index, in order
+    async fn get_entries(&self, from: LogIndex, limit: u64) -> Vec<LogEntry>;
+    async fn get_last_entry(&self) -> Option<LogEntry>;
+}
+
+#[async_trait]
+pub trait MetadataLogWriter {
+    async fn save_hard_state(&self, data: &HardState);
+    
+    /// Entries follow the last one without gaps
+    async fn append_entries(&self, entries: &[LogEntry]);
+}
+
+#[async_trait]
+pub trait MetadataLogRemover {
+    /// Remove the entry at the index and all entries after it
+    async fn truncate_entries(&self, from: LogIndex);
+}
+
+pub trait MetadataLogGateway: MetadataLogReader + MetadataLogWriter + MetadataLogRemover {}
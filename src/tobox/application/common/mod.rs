@@ -12,4 +12,17 @@ pub mod access_log_gateway;
 pub mod server;
 pub mod box_gateway;
 pub mod object_gateway;
-pub mod file_storage_manager;
\ No newline at end of file
+pub mod file_storage_manager;
+pub mod access_key_gateway;
+pub mod api_key_gateway;
+pub mod upload_gateway;
+pub mod node_gateway;
+pub mod node_client;
+pub mod replication_gateway;
+pub mod placement_gateway;
+pub mod scrub_gateway;
+pub mod metadata_gateway;
+pub mod signer;
+pub mod blob_lock;
+#[cfg(test)]
+pub mod test_doubles;
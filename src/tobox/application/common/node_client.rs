@@ -0,0 +1,78 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use crate::domain::models::file_stream::FileStream;
+use crate::domain::models::metadata::{
+    AppendRequest,
+    AppendResponse,
+    MetadataCommand,
+    Proposal,
+    VoteRequest,
+    VoteResponse
+};
+use crate::domain::models::node::{NodeId, NodeInfo};
+use crate::domain::models::replication::Replica;
+use crate::domain::models::scrub::BoxDigest;
+
+pub enum ForwardBody {
+    /// Small bodies are kept in memory, so the request can be sent to several nodes
+    Buffered(Bytes),
+    Stream(Box<dyn FileStream>)
+}
+
+/// Client request passed by an intermediate node to a storage node
+pub struct ForwardRequest {
+    pub method: String,
+    /// Path with the query string
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: ForwardBody
+}
+
+pub struct ForwardResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Box<dyn FileStream>
+}
+
+/// Requests to peer nodes of the cluster
+#[async_trait]
+pub trait NodeClient {
+    /// Ask the node at the address about itself, also serves as a heartbeat
+    async fn get_info(&self, address: &str) -> Result<NodeInfo, String>;
+    
+    async fn has_blob(&self, address: &str, hash: &str) -> Result<bool, String>;
+    
+    /// Upload a blob, the node checks the content against the hash
+    async fn push_blob(
+        &self,
+        address: &str,
+        hash: &str,
+        content: Box<dyn FileStream>
+    ) -> Result<(), String>;
+    
+    /// Register an object on the node, its blob must be pushed first
+    async fn push_object(&self, address: &str, replica: &Replica) -> Result<(), String>;
+    
+    /// Content of a blob stored on the node
+    async fn fetch_blob(&self, address: &str, hash: &str) -> Result<Box<dyn FileStream>, String>;
+    
+    /// Digests of the boxes of the node, over the objects owned by both nodes
+    async fn get_digests(&self, address: &str, node_id: &NodeId) -> Result<Vec<BoxDigest>, String>;
+    
+    /// Ask the node for its vote in a metadata leader election
+    async fn request_vote(&self, address: &str, request: &VoteRequest) -> Result<VoteResponse, String>;
+    
+    /// Send metadata log entries to a follower, without entries it is a heartbeat of the leader
+    async fn append_entries(&self, address: &str, request: &AppendRequest) -> Result<AppendResponse, String>;
+    
+    /// Ask the metadata leader to append a change to its log
+    async fn propose(&self, address: &str, command: &MetadataCommand) -> Result<Proposal, String>;
+    
+    /// Send a client request to the node, fails only when the node does not answer
+    async fn forward(
+        &self,
+        address: &str,
+        request: ForwardRequest
+    ) -> Result<ForwardResponse, String>;
+}
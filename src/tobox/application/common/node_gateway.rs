@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+
+use crate::domain::models::node::{Node, NodeId};
+
+#[async_trait]
+pub trait NodeReader {
+    async fn get_node(&self, node_id: &NodeId) -> Option<Node>;
+    async fn get_node_by_address(&self, address: &str) -> Option<Node>;
+    async fn get_nodes(&self) -> Vec<Node>;
+}
+
+#[async_trait]
+pub trait NodeWriter {
+    async fn save_node(&self, data: &Node);
+}
+
+#[async_trait]
+pub trait NodeRemover {
+    async fn remove_node(&self, node_id: &NodeId);
+}
+
+pub trait NodeGateway: NodeReader + NodeWriter + NodeRemover {}
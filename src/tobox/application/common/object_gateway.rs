@@ -1,12 +1,54 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
 use crate::domain::models::object::{Object as ObjectDomain, ObjectId};
+use crate::domain::models::r#box::BoxId;
+
+/// Conditions of an object listing, all set conditions must match
+#[derive(Debug, Clone, Default)]
+pub struct ObjectFilter {
+    pub box_id: BoxId,
+    /// Prefix of the key: path joined with the name, or id for objects without a name
+    pub key_prefix: Option<String>,
+    /// Every pair must be present in the object metadata
+    pub metadata: HashMap<String, String>,
+    pub content_type: Option<String>,
+    /// Bounds are inclusive
+    pub created_from: Option<DateTime<Utc>>,
+    pub created_to: Option<DateTime<Utc>>,
+    pub updated_from: Option<DateTime<Utc>>,
+    pub updated_to: Option<DateTime<Utc>>,
+}
+
+/// Listing position, objects are ordered by key and then by id
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectCursor {
+    pub key: String,
+    /// Objects with the same key are skipped entirely when not set
+    pub id: Option<ObjectId>,
+}
 
 #[async_trait]
 pub trait ObjectReader {
     async fn get_object(&self, object_id: &ObjectId) -> Option<ObjectDomain>;
     async fn get_objects(&self) -> Vec<ObjectDomain>;
     async fn get_objects_range(&self, limit: &u64, offset: &u64) -> Vec<ObjectDomain>;
+    async fn count_objects_by_hash(&self, hash: &str) -> u64;
+    async fn get_objects_by_name(
+        &self,
+        box_id: &BoxId,
+        path: Option<&str>,
+        name: &str
+    ) -> Vec<ObjectDomain>;
+    /// Objects matching the filter placed after the cursor, in the cursor order
+    async fn list_objects(
+        &self,
+        filter: &ObjectFilter,
+        after: Option<&ObjectCursor>,
+        limit: u64
+    ) -> Vec<ObjectDomain>;
 }
 
 #[async_trait]
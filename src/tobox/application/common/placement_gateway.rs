@@ -0,0 +1,25 @@
+use async_trait::async_trait;
+
+use crate::domain::models::placement::{PlacementMove, PlacementMoveId, RingMember};
+
+/// Ring the objects of this node are placed by and the pending moves, must survive restarts
+#[async_trait]
+pub trait PlacementReader {
+    /// Members of the ring the last rebalancing plan was made for
+    async fn get_ring_members(&self) -> Option<Vec<RingMember>>;
+    /// Pending moves, the oldest first
+    async fn get_moves(&self, limit: u64) -> Vec<PlacementMove>;
+}
+
+#[async_trait]
+pub trait PlacementWriter {
+    async fn save_ring_members(&self, members: &[RingMember]);
+    async fn save_move(&self, data: &PlacementMove);
+}
+
+#[async_trait]
+pub trait PlacementRemover {
+    async fn remove_move(&self, move_id: &PlacementMoveId);
+}
+
+pub trait PlacementGateway: PlacementReader + PlacementWriter + PlacementRemover {}
@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::domain::models::replication::{ReplicationTask, ReplicationTaskId};
+
+/// Outbound replication queue, must survive restarts
+#[async_trait]
+pub trait ReplicationReader {
+    /// Tasks with the next attempt not later than now, the earliest first
+    async fn get_due_tasks(&self, now: &DateTime<Utc>, limit: u64) -> Vec<ReplicationTask>;
+}
+
+#[async_trait]
+pub trait ReplicationWriter {
+    async fn save_task(&self, data: &ReplicationTask);
+}
+
+#[async_trait]
+pub trait ReplicationRemover {
+    async fn remove_task(&self, task_id: &ReplicationTaskId);
+}
+
+pub trait ReplicationGateway: ReplicationReader + ReplicationWriter + ReplicationRemover {}
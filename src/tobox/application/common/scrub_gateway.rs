@@ -0,0 +1,17 @@
+use async_trait::async_trait;
+
+use crate::domain::models::scrub::ScrubReport;
+
+#[async_trait]
+pub trait ScrubReader {
+    /// Report of the last finished scrub
+    async fn get_report(&self) -> Option<ScrubReport>;
+}
+
+#[async_trait]
+pub trait ScrubWriter {
+    /// Replaces the previous report
+    async fn save_report(&self, data: &ScrubReport);
+}
+
+pub trait ScrubGateway: ScrubReader + ScrubWriter {}
@@ -1,11 +1,15 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
-use crate::domain::models::session::Session;
-
+use crate::domain::models::session::{Session, SessionId};
+use crate::domain::models::user::UserId;
 
 #[async_trait]
 pub trait SessionReader {
-    async fn get_session(&self, token: &String) -> Option<Session>;
+    async fn get_session(&self, token_hash: &str) -> Option<Session>;
+    async fn get_session_by_id(&self, id: &SessionId) -> Option<Session>;
+    /// Expired sessions not swept yet are included
+    async fn get_user_sessions(&self, user_id: &UserId) -> Vec<Session>;
 }
 
 #[async_trait]
@@ -13,5 +17,13 @@ pub trait SessionWriter {
     async fn save_session(&self, data: &Session);
 }
 
+#[async_trait]
+pub trait SessionRemover {
+    async fn remove_session(&self, id: &SessionId);
+    /// * return: number of removed sessions
+    async fn remove_user_sessions(&self, user_id: &UserId) -> u64;
+    /// * return: number of removed sessions
+    async fn remove_expired_sessions(&self, now: &DateTime<Utc>) -> u64;
+}
 
-pub trait SessionGateway: SessionReader + SessionWriter {}
+pub trait SessionGateway: SessionReader + SessionWriter + SessionRemover {}
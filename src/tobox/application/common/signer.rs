@@ -0,0 +1,5 @@
+/// Signs data with a key known to the nodes only
+pub trait Signer {
+    fn sign(&self, data: &str) -> String;
+    fn verify(&self, data: &str, signature: &str) -> bool;
+}
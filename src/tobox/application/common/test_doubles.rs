@@ -0,0 +1,93 @@
+use async_trait::async_trait;
+
+use crate::application::common::node_client::{ForwardRequest, ForwardResponse, NodeClient};
+use crate::domain::models::file_stream::FileStream;
+use crate::domain::models::metadata::{
+    AppendRequest,
+    AppendResponse,
+    MetadataCommand,
+    Proposal,
+    VoteRequest,
+    VoteResponse
+};
+use crate::domain::models::node::{NodeId, NodeInfo};
+use crate::domain::models::replication::Replica;
+use crate::domain::models::scrub::BoxDigest;
+
+const NOT_USED: &str = "not used by the test";
+
+/// Requests to peer nodes made by a test, a test overrides only the requests it makes
+///
+/// Gateways are not faked, tests use the sqlite ones on a database in memory.
+#[async_trait]
+pub trait TestNodeClient {
+    async fn get_info(&self, _: &str) -> Result<NodeInfo, String> {
+        Err(NOT_USED.to_string())
+    }
+    async fn has_blob(&self, _: &str, _: &str) -> Result<bool, String> {
+        Err(NOT_USED.to_string())
+    }
+    async fn push_blob(&self, _: &str, _: &str, _: Box<dyn FileStream>) -> Result<(), String> {
+        Err(NOT_USED.to_string())
+    }
+    async fn push_object(&self, _: &str, _: &Replica) -> Result<(), String> {
+        Err(NOT_USED.to_string())
+    }
+    async fn fetch_blob(&self, _: &str, _: &str) -> Result<Box<dyn FileStream>, String> {
+        Err(NOT_USED.to_string())
+    }
+    async fn get_digests(&self, _: &str, _: &NodeId) -> Result<Vec<BoxDigest>, String> {
+        Err(NOT_USED.to_string())
+    }
+    async fn request_vote(&self, _: &str, _: &VoteRequest) -> Result<VoteResponse, String> {
+        Err(NOT_USED.to_string())
+    }
+    async fn append_entries(&self, _: &str, _: &AppendRequest) -> Result<AppendResponse, String> {
+        Err(NOT_USED.to_string())
+    }
+    async fn propose(&self, _: &str, _: &MetadataCommand) -> Result<Proposal, String> {
+        Err(NOT_USED.to_string())
+    }
+    async fn forward(&self, _: &str, _: ForwardRequest) -> Result<ForwardResponse, String> {
+        Err(NOT_USED.to_string())
+    }
+}
+
+#[async_trait]
+impl<T: TestNodeClient + Sync> NodeClient for T {
+    async fn get_info(&self, address: &str) -> Result<NodeInfo, String> {
+        TestNodeClient::get_info(self, address).await
+    }
+    async fn has_blob(&self, address: &str, hash: &str) -> Result<bool, String> {
+        TestNodeClient::has_blob(self, address, hash).await
+    }
+    async fn push_blob(
+        &self,
+        address: &str,
+        hash: &str,
+        content: Box<dyn FileStream>
+    ) -> Result<(), String> {
+        TestNodeClient::push_blob(self, address, hash, content).await
+    }
+    async fn push_object(&self, address: &str, replica: &Replica) -> Result<(), String> {
+        TestNodeClient::push_object(self, address, replica).await
+    }
+    async fn fetch_blob(&self, address: &str, hash: &str) -> Result<Box<dyn FileStream>, String> {
+        TestNodeClient::fetch_blob(self, address, hash).await
+    }
+    async fn get_digests(&self, address: &str, node_id: &NodeId) -> Result<Vec<BoxDigest>, String> {
+        TestNodeClient::get_digests(self, address, node_id).await
+    }
+    async fn request_vote(&self, address: &str, request: &VoteRequest) -> Result<VoteResponse, String> {
+        TestNodeClient::request_vote(self, address, request).await
+    }
+    async fn append_entries(&self, address: &str, request: &AppendRequest) -> Result<AppendResponse, String> {
+        TestNodeClient::append_entries(self, address, request).await
+    }
+    async fn propose(&self, address: &str, command: &MetadataCommand) -> Result<Proposal, String> {
+        TestNodeClient::propose(self, address, command).await
+    }
+    async fn forward(&self, address: &str, request: ForwardRequest) -> Result<ForwardResponse, String> {
+        TestNodeClient::forward(self, address, request).await
+    }
+}
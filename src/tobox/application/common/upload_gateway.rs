@@ -0,0 +1,27 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::domain::models::upload::{Upload, UploadId, UploadPart};
+
+#[async_trait]
+pub trait UploadReader {
+    async fn get_upload(&self, upload_id: &UploadId) -> Option<Upload>;
+    async fn get_expired_uploads(&self, now: &DateTime<Utc>) -> Vec<Upload>;
+    /// Parts ordered by number
+    async fn get_upload_parts(&self, upload_id: &UploadId) -> Vec<UploadPart>;
+}
+
+#[async_trait]
+pub trait UploadWriter {
+    async fn save_upload(&self, data: &Upload);
+    /// Replaces the part with the same number
+    async fn save_upload_part(&self, data: &UploadPart);
+}
+
+#[async_trait]
+pub trait UploadRemover {
+    /// Removes the upload with its parts
+    async fn remove_upload(&self, upload_id: &UploadId);
+}
+
+pub trait UploadGateway: UploadReader + UploadWriter + UploadRemover {}
@@ -1,11 +1,17 @@
 use chrono::Utc;
 use strum::IntoEnumIterator;
 
+use crate::application::common::blob_lock::BlobLocks;
+use crate::application::common::file_storage_manager::FileStorageManager;
 use crate::application::common::hasher::Hasher;
 use crate::application::common::init_state_gateway::InitStateGateway;
+use crate::application::common::object_gateway::ObjectReader;
 use crate::application::common::permission_gateway::PermissionGateway;
 use crate::application::common::role_gateway::RoleGateway;
+use crate::application::common::upload_gateway::UploadGateway;
 use crate::application::common::user_gateway::UserGateway;
+use crate::application::object::gc::collect_garbage;
+use crate::application::upload::expire::remove_expired_uploads;
 use crate::config::CredentialsConfig;
 use crate::domain::models::permission::{NodePermission, Permission, PermissionTextId};
 use crate::domain::services::permission::PermissionService;
@@ -212,3 +218,19 @@ pub async fn control_account(
 
     log::info!("Control role created!");
 }
+
+/// Recover the file storage after an unclean shutdown
+///
+/// Removes uploads that were interrupted before commit and blobs
+/// that were committed, but never registered by an object.
+pub async fn file_storage(
+    object_reader: &dyn ObjectReader,
+    upload_gateway: &dyn UploadGateway,
+    file_storage: &dyn FileStorageManager,
+    blob_locks: &BlobLocks,
+) {
+    file_storage.remove_staged_files().await;
+    remove_expired_uploads(upload_gateway, file_storage).await;
+    collect_garbage(object_reader, file_storage, file_storage, blob_locks).await;
+    log::info!("File storage recovered!");
+}
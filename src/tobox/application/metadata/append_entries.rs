@@ -0,0 +1,97 @@
+use chrono::Utc;
+use tokio::sync::Mutex;
+
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::interactor::Interactor;
+use crate::application::common::metadata_gateway::MetadataLogGateway;
+use crate::application::metadata::apply::{apply_committed, MetadataStore};
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::metadata::{AppendRequest, AppendResponse, LogEntry, RaftState};
+use crate::domain::services::access::AccessService;
+use crate::domain::services::raft::RaftService;
+
+pub struct AppendEntriesDTO {
+    pub request: AppendRequest,
+    /// Cluster secret presented by the peer
+    pub secret: Option<String>
+}
+
+/// Requested by the metadata leader with new entries or as a heartbeat
+///
+/// Entries already stored are skipped, a stored entry of another term is removed
+/// together with the entries after it. Committed entries are applied before the response.
+pub struct AppendEntries<'a> {
+    pub raft_state: &'a Mutex<RaftState>,
+    pub log_gateway: &'a dyn MetadataLogGateway,
+    pub raft_service: &'a RaftService,
+    pub store: MetadataStore<'a>,
+    pub access_service: &'a AccessService,
+    pub cluster_secret: Option<&'a str>,
+}
+
+impl Interactor<AppendEntriesDTO, AppendResponse> for AppendEntries<'_> {
+    async fn execute(&self, data: AppendEntriesDTO) -> Result<AppendResponse, ApplicationError> {
+        
+        match self.access_service.ensure_is_peer(data.secret.as_deref(), self.cluster_secret) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+        
+        let request = data.request;
+        let mut state = self.raft_state.lock().await;
+        let hard_state = state.hard_state.clone();
+        let is_accepted = self.raft_service.accept_leader(
+            &mut state,
+            &request.leader_id,
+            request.term,
+            &Utc::now()
+        );
+        if state.hard_state != hard_state {
+            self.log_gateway.save_hard_state(&state.hard_state).await;
+        }
+        
+        let last_index = self.log_gateway.get_last_entry().await.map_or(0, |entry| entry.index);
+        let is_matching = request.prev_log_index == 0 || self.log_gateway
+            .get_entry(request.prev_log_index).await
+            .is_some_and(|entry| entry.term == request.prev_log_term);
+        if !is_accepted || !is_matching {
+            return Ok(AppendResponse {
+                term: state.hard_state.term,
+                success: false,
+                match_index: last_index.min(request.prev_log_index.saturating_sub(1)),
+            })
+        }
+        
+        let mut new_entries: &[LogEntry] = &[];
+        for (position, entry) in request.entries.iter().enumerate() {
+            match self.log_gateway.get_entry(entry.index).await {
+                Some(stored) if stored.term == entry.term => continue,
+                Some(_) => {
+                    self.log_gateway.truncate_entries(entry.index).await;
+                    new_entries = &request.entries[position..];
+                },
+                None => new_entries = &request.entries[position..]
+            }
+            break
+        }
+        if !new_entries.is_empty() {
+            self.log_gateway.append_entries(new_entries).await;
+        }
+        
+        let last_new = request.prev_log_index + request.entries.len() as u64;
+        self.raft_service.follow_commit(&mut state, request.leader_commit, last_new);
+        apply_committed(&mut state, self.log_gateway, &self.store).await;
+        Ok(AppendResponse {
+            term: state.hard_state.term,
+            success: true,
+            match_index: last_new,
+        })
+    }
+}
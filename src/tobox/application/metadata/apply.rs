@@ -0,0 +1,80 @@
+use crate::application::common::box_gateway::BoxGateway;
+use crate::application::common::metadata_gateway::MetadataLogGateway;
+use crate::application::common::permission_gateway::PermissionGateway;
+use crate::application::common::role_gateway::RoleGateway;
+use crate::application::common::user_gateway::UserGateway;
+use crate::domain::models::metadata::{MetadataCommand, RaftState};
+
+/// Entries applied in one pass
+const APPLY_BATCH_SIZE: u64 = 256;
+
+/// Local gateways of this node the metadata log is applied to
+pub struct MetadataStore<'a> {
+    pub user_gateway: &'a dyn UserGateway,
+    pub role_gateway: &'a dyn RoleGateway,
+    pub permission_gateway: &'a dyn PermissionGateway,
+    pub box_gateway: &'a dyn BoxGateway,
+}
+
+impl MetadataStore<'_> {
+    
+    pub async fn apply(&self, command: &MetadataCommand) {
+        match command {
+            MetadataCommand::SaveUser { user } => self.user_gateway.save_user(user).await,
+            MetadataCommand::RemoveUser { user_id } => self.user_gateway.remove_user(user_id).await,
+            MetadataCommand::SaveRole { role } => self.role_gateway.save_role(role).await,
+            MetadataCommand::RemoveRole { role_id } => self.role_gateway.remove_role(role_id).await,
+            MetadataCommand::SetDefaultRole { role_id } => {
+                self.role_gateway.set_default_role(role_id).await
+            },
+            MetadataCommand::LinkRoleUser { role_id, user_id } => {
+                // Links are not unique, an entry applied again after a restart must not add one
+                if !self.role_gateway.is_role_linked_to_user(role_id, user_id).await {
+                    self.role_gateway.link_role_to_user(role_id, user_id).await
+                }
+            },
+            MetadataCommand::UnlinkRoleUser { role_id, user_id } => {
+                self.role_gateway.unlink_role_from_user(role_id, user_id).await
+            },
+            MetadataCommand::SavePermissions { permissions } => {
+                self.permission_gateway.save_permissions(permissions).await
+            },
+            MetadataCommand::LinkRolePermissions { role_id, permission_ids } => {
+                for permission_id in permission_ids {
+                    if !self.permission_gateway.is_permission_linked_to_role(role_id, permission_id).await {
+                        self.permission_gateway.link_permission_to_role(role_id, permission_id).await
+                    }
+                }
+            },
+            MetadataCommand::UnlinkRolePermission { role_id, permission_id } => {
+                self.permission_gateway.unlink_permission_from_role(role_id, permission_id).await
+            },
+            MetadataCommand::SaveBox { r#box } => self.box_gateway.save_box(r#box).await,
+            MetadataCommand::RemoveBox { box_id } => self.box_gateway.remove_box(box_id).await,
+        }
+    }
+}
+
+/// Apply the committed entries in order
+///
+/// The applied index is saved after every entry, an interrupted pass continues
+/// from the entry it stopped at.
+pub async fn apply_committed(
+    state: &mut RaftState,
+    log_gateway: &dyn MetadataLogGateway,
+    store: &MetadataStore<'_>
+) {
+    while state.hard_state.applied < state.commit_index {
+        let limit = APPLY_BATCH_SIZE.min(state.commit_index - state.hard_state.applied);
+        let entries = log_gateway.get_entries(state.hard_state.applied + 1, limit).await;
+        if entries.is_empty() {
+            log::error!("Committed metadata entry {} is missing", state.hard_state.applied + 1);
+            return
+        }
+        for entry in entries {
+            store.apply(&entry.command).await;
+            state.hard_state.applied = entry.index;
+            log_gateway.save_hard_state(&state.hard_state).await;
+        }
+    }
+}
@@ -0,0 +1,540 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::sync::{mpsc, Mutex};
+
+use crate::application::common::metadata_gateway::MetadataLogGateway;
+use crate::application::common::node_client::NodeClient;
+use crate::application::common::node_gateway::NodeReader;
+use crate::application::metadata::apply::{apply_committed, MetadataStore};
+use crate::application::metadata::proposer::{PendingProposals, ProposalRequest};
+use crate::domain::models::metadata::{
+    AppendRequest,
+    LogEntry,
+    LogIndex,
+    MetadataCommand,
+    Proposal,
+    RaftRole,
+    RaftState,
+    Term,
+    VoteRequest
+};
+use crate::domain::models::node::{Node, NodeId};
+use crate::domain::services::raft::RaftService;
+
+/// Entries sent to a follower in one request
+const APPEND_BATCH_SIZE: u64 = 64;
+
+/// Replicated log of the metadata shared by the cluster: users, roles, permissions and boxes
+///
+/// Every node takes part, the registered nodes are the voters. A leader elected by a majority
+/// appends changes to its log and sends them to the followers, a change is committed once
+/// a majority has stored it and then applied by every node in the same order.
+pub struct MetadataConsensus<'a> {
+    pub raft_state: &'a Mutex<RaftState>,
+    pub log_gateway: &'a dyn MetadataLogGateway,
+    pub node_reader: &'a dyn NodeReader,
+    pub node_client: &'a dyn NodeClient,
+    pub raft_service: &'a RaftService,
+    pub store: MetadataStore<'a>,
+}
+
+impl MetadataConsensus<'_> {
+
+    /// Tick periodically and append the changes of this node until the loop is dropped
+    pub async fn run(&self, mut requests: mpsc::Receiver<ProposalRequest>, tick_interval: Duration) {
+        let mut interval = tokio::time::interval(tick_interval);
+        let mut pending = PendingProposals::default();
+        loop {
+            tokio::select! {
+                _ = interval.tick() => self.tick().await,
+                Some(request) = requests.recv() => match self.propose(&request.command).await {
+                    Ok(proposal) => pending.push(proposal, request.reply),
+                    Err(error) => {
+                        let _ = request.reply.send(Err(error));
+                    }
+                }
+            }
+            let applied = self.raft_state.lock().await.hard_state.applied;
+            pending.resolve(applied, self.log_gateway).await;
+        }
+    }
+
+    /// Start an election when the leader is not heard of, send entries when leading
+    pub async fn tick(&self) {
+        let node_id = self.raft_state.lock().await.node_id.clone();
+        let peers: Vec<Node> = self.node_reader.get_nodes().await.into_iter()
+            .filter(|node| node.id != node_id)
+            .collect();
+        let peer_ids: Vec<NodeId> = peers.iter().map(|node| node.id.clone()).collect();
+
+        let election = {
+            let mut state = self.raft_state.lock().await;
+            let now = Utc::now();
+            if self.raft_service.is_election_due(&state, &now) {
+                let last_log = self.last_log().await;
+                let request = self.raft_service.start_election(&mut state, last_log, &now);
+                self.log_gateway.save_hard_state(&state.hard_state).await;
+                log::debug!("Node {} starts the metadata election of term {}", state.node_id, request.term);
+                if self.raft_service.try_lead(&mut state, &peer_ids, last_log.0) {
+                    log::info!("Node {} is the metadata leader of term {}", state.node_id, request.term);
+                }
+                Some(request)
+            } else {
+                None
+            }
+        };
+        if let Some(request) = election {
+            self.elect(&peers, &peer_ids, &request).await;
+        }
+
+        if self.raft_state.lock().await.role == RaftRole::Leader {
+            self.replicate(&peers, &peer_ids).await;
+        }
+    }
+
+    /// Append a change to the log of the leader, a follower passes it to the leader
+    pub async fn propose(&self, command: &MetadataCommand) -> Result<Proposal, String> {
+        let leader_id = {
+            let state = self.raft_state.lock().await;
+            if state.role == RaftRole::Leader {
+                return append_command(&state, self.log_gateway, command).await
+            }
+            state.leader_id.clone().ok_or("No metadata leader is elected")?
+        };
+        let leader = self.node_reader.get_node(&leader_id).await
+            .ok_or(format!("Metadata leader {} is not registered", leader_id))?;
+        self.node_client.propose(&leader.address, command).await
+    }
+
+    async fn last_log(&self) -> (LogIndex, Term) {
+        self.log_gateway.get_last_entry().await.map_or((0, 0), |entry| (entry.index, entry.term))
+    }
+
+    async fn elect(&self, peers: &[Node], peer_ids: &[NodeId], request: &VoteRequest) {
+        for peer in peers {
+            let response = match self.node_client.request_vote(&peer.address, request).await {
+                Ok(response) => response,
+                Err(error) => {
+                    log::debug!("Node {} did not vote: {}", peer.id, error);
+                    continue
+                }
+            };
+            let mut state = self.raft_state.lock().await;
+            if state.role != RaftRole::Candidate || state.hard_state.term != request.term {
+                return
+            }
+            let hard_state = state.hard_state.clone();
+            self.raft_service.count_vote(&mut state, &peer.id, &response, &Utc::now());
+            if state.hard_state != hard_state {
+                self.log_gateway.save_hard_state(&state.hard_state).await;
+            }
+            if self.raft_service.try_lead(&mut state, peer_ids, request.last_log_index) {
+                log::info!("Node {} is the metadata leader of term {}", state.node_id, request.term);
+                return
+            }
+        }
+    }
+
+    async fn replicate(&self, peers: &[Node], peer_ids: &[NodeId]) {
+        for peer in peers {
+            let request = {
+                let state = self.raft_state.lock().await;
+                if state.role != RaftRole::Leader {
+                    return
+                }
+                // Peers registered after the election start from the end of the log
+                let next = match state.next_index.get(&peer.id) {
+                    Some(next) => *next,
+                    None => self.last_log().await.0 + 1
+                };
+                let prev_log_term = match next - 1 {
+                    0 => 0,
+                    prev => self.log_gateway.get_entry(prev).await.map_or(0, |entry| entry.term)
+                };
+                AppendRequest {
+                    term: state.hard_state.term,
+                    leader_id: state.node_id.clone(),
+                    prev_log_index: next - 1,
+                    prev_log_term,
+                    entries: self.log_gateway.get_entries(next, APPEND_BATCH_SIZE).await,
+                    leader_commit: state.commit_index,
+                }
+            };
+            match self.node_client.append_entries(&peer.address, &request).await {
+                Ok(response) => {
+                    let mut state = self.raft_state.lock().await;
+                    let hard_state = state.hard_state.clone();
+                    self.raft_service.record_append(&mut state, &peer.id, &response, &Utc::now());
+                    if state.hard_state != hard_state {
+                        log::info!("Node {} is no longer the metadata leader", state.node_id);
+                        self.log_gateway.save_hard_state(&state.hard_state).await;
+                        return
+                    }
+                }
+                Err(error) => log::debug!("Metadata entries are not sent to node {}: {}", peer.id, error)
+            }
+        }
+
+        let mut state = self.raft_state.lock().await;
+        let uncommitted: Vec<LogEntry> = self.log_gateway.get_entries(
+            state.commit_index + 1,
+            APPEND_BATCH_SIZE
+        ).await;
+        self.raft_service.advance_commit(&mut state, peer_ids, &uncommitted);
+        apply_committed(&mut state, self.log_gateway, &self.store).await;
+    }
+}
+
+/// Append a change to the log, only the leader accepts changes
+pub async fn append_command(
+    state: &RaftState,
+    log_gateway: &dyn MetadataLogGateway,
+    command: &MetadataCommand
+) -> Result<Proposal, String> {
+    if state.role != RaftRole::Leader {
+        return Err("This node is not the metadata leader".to_string())
+    }
+    let index = log_gateway.get_last_entry().await.map_or(0, |entry| entry.index) + 1;
+    log_gateway.append_entries(&[LogEntry {
+        index,
+        term: state.hard_state.term,
+        command: command.clone(),
+    }]).await;
+    Ok(Proposal { index, term: state.hard_state.term })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::future::Future;
+    use std::sync::{Arc, Mutex as SyncMutex};
+
+    use async_trait::async_trait;
+    use chrono::TimeDelta;
+    use tokio::sync::oneshot;
+
+    use crate::adapters::database::box_db::BoxGateway as BoxDb;
+    use crate::adapters::database::metadata_db::MetadataLogGateway as MetadataLogDb;
+    use crate::adapters::database::node_db::NodeGateway as NodeDb;
+    use crate::adapters::database::permission_db::PermissionGateway as PermissionDb;
+    use crate::adapters::database::role_db::RoleGateway as RoleDb;
+    use crate::adapters::database::sqlite::connect_in_memory;
+    use crate::adapters::database::user_db::UserGateway as UserDb;
+    use crate::application::common::box_gateway::BoxReader;
+    use crate::application::common::interactor::Interactor;
+    use crate::application::common::metadata_gateway::MetadataLogReader;
+    use crate::application::common::node_gateway::NodeWriter;
+    use crate::application::common::test_doubles::TestNodeClient;
+    use crate::application::common::user_gateway::UserReader;
+    use crate::application::metadata::append_entries::{AppendEntries, AppendEntriesDTO};
+    use crate::application::metadata::receive_proposal::{ReceiveProposal, ReceiveProposalDTO};
+    use crate::application::metadata::request_vote::{RequestVote, RequestVoteDTO};
+    use crate::domain::models::metadata::{AppendResponse, HardState, VoteResponse};
+    use crate::domain::models::node::NodeStatus;
+    use crate::domain::models::r#box::{Box as BoxDomain, BoxAccessPolicy, DuplicateNamePolicy};
+    use crate::domain::models::user::User;
+    use crate::domain::services::access::AccessService;
+
+    use super::*;
+
+    const SECRET: &str = "secret";
+
+    enum Rpc {
+        Vote(VoteRequest),
+        Append(AppendRequest),
+        Propose(MetadataCommand)
+    }
+
+    enum RpcResult {
+        Vote(VoteResponse),
+        Append(AppendResponse),
+        Propose(Proposal)
+    }
+
+    struct Message {
+        to: String,
+        rpc: Rpc,
+        reply: oneshot::Sender<Result<RpcResult, String>>
+    }
+
+    /// Passes requests of a node to the cluster, requests from or to an isolated node fail
+    struct TestClient {
+        node_id: NodeId,
+        sender: mpsc::UnboundedSender<Message>,
+        isolated: Arc<SyncMutex<HashSet<NodeId>>>
+    }
+
+    impl TestClient {
+        async fn call(&self, address: &str, rpc: Rpc) -> Result<RpcResult, String> {
+            {
+                let isolated = self.isolated.lock().unwrap();
+                if isolated.contains(&self.node_id) || isolated.contains(address) {
+                    return Err("unreachable".to_string())
+                }
+            }
+            let (reply, receiver) = oneshot::channel();
+            self.sender.send(Message { to: address.to_string(), rpc, reply })
+                .map_err(|error| error.to_string())?;
+            receiver.await.map_err(|error| error.to_string())?
+        }
+    }
+
+    #[async_trait]
+    impl TestNodeClient for TestClient {
+        async fn request_vote(&self, address: &str, request: &VoteRequest) -> Result<VoteResponse, String> {
+            match self.call(address, Rpc::Vote(request.clone())).await? {
+                RpcResult::Vote(response) => Ok(response),
+                _ => unreachable!()
+            }
+        }
+
+        async fn append_entries(&self, address: &str, request: &AppendRequest) -> Result<AppendResponse, String> {
+            match self.call(address, Rpc::Append(request.clone())).await? {
+                RpcResult::Append(response) => Ok(response),
+                _ => unreachable!()
+            }
+        }
+
+        async fn propose(&self, address: &str, command: &MetadataCommand) -> Result<Proposal, String> {
+            match self.call(address, Rpc::Propose(command.clone())).await? {
+                RpcResult::Propose(proposal) => Ok(proposal),
+                _ => unreachable!()
+            }
+        }
+    }
+
+    /// Gateways of a node over its own database
+    struct TestNode {
+        state: Mutex<RaftState>,
+        log: MetadataLogDb,
+        users: UserDb,
+        roles: RoleDb,
+        permissions: PermissionDb,
+        boxes: BoxDb,
+        nodes: NodeDb,
+        client: TestClient
+    }
+
+    impl TestNode {
+        fn store(&self) -> MetadataStore<'_> {
+            MetadataStore {
+                user_gateway: &self.users,
+                role_gateway: &self.roles,
+                permission_gateway: &self.permissions,
+                box_gateway: &self.boxes,
+            }
+        }
+
+        fn consensus<'a>(&'a self, raft_service: &'a RaftService) -> MetadataConsensus<'a> {
+            MetadataConsensus {
+                raft_state: &self.state,
+                log_gateway: &self.log,
+                node_reader: &self.nodes,
+                node_client: &self.client,
+                raft_service,
+                store: self.store(),
+            }
+        }
+    }
+
+    /// Nodes exchanging requests in process, the requests are served while nodes tick
+    struct Cluster {
+        nodes: Vec<TestNode>,
+        inbox: Mutex<mpsc::UnboundedReceiver<Message>>,
+        isolated: Arc<SyncMutex<HashSet<NodeId>>>,
+        raft_service: RaftService,
+        access_service: AccessService
+    }
+
+    impl Cluster {
+        async fn new(ids: &[&str]) -> Self {
+            let (sender, inbox) = mpsc::unbounded_channel();
+            let isolated = Arc::new(SyncMutex::new(HashSet::new()));
+            let raft_service = RaftService { };
+            let mut nodes = vec![];
+            for id in ids {
+                let db = connect_in_memory().await;
+                let node = TestNode {
+                    state: Mutex::new(raft_service.new_state(id.to_string(), HardState::default(), &Utc::now())),
+                    log: MetadataLogDb::new(db.clone()),
+                    users: UserDb::new(db.clone()),
+                    roles: RoleDb::new(db.clone()),
+                    permissions: PermissionDb::new(db.clone()),
+                    boxes: BoxDb::new(db.clone()),
+                    nodes: NodeDb::new(db),
+                    client: TestClient {
+                        node_id: id.to_string(),
+                        sender: sender.clone(),
+                        isolated: isolated.clone()
+                    },
+                };
+                // The address of a node is its id
+                for peer in ids {
+                    node.nodes.save_node(&Node {
+                        id: peer.to_string(),
+                        address: peer.to_string(),
+                        version: "0.1.0".to_string(),
+                        capacity: 1,
+                        status: NodeStatus::Up,
+                        last_heartbeat: None,
+                        created_at: Utc::now(),
+                    }).await;
+                }
+                nodes.push(node);
+            }
+            Self {
+                nodes,
+                inbox: Mutex::new(inbox),
+                isolated,
+                raft_service,
+                access_service: AccessService { },
+            }
+        }
+
+        fn node(&self, id: &str) -> &TestNode {
+            self.nodes.iter().find(|node| node.client.node_id == id).unwrap()
+        }
+
+        async fn handle(&self, message: Message) {
+            let node = self.node(&message.to);
+            let secret = Some(SECRET.to_string());
+            let result = match message.rpc {
+                Rpc::Vote(request) => RequestVote {
+                    raft_state: &node.state,
+                    log_gateway: &node.log,
+                    raft_service: &self.raft_service,
+                    access_service: &self.access_service,
+                    cluster_secret: Some(SECRET),
+                }.execute(RequestVoteDTO { request, secret }).await.map(RpcResult::Vote),
+                Rpc::Append(request) => AppendEntries {
+                    raft_state: &node.state,
+                    log_gateway: &node.log,
+                    raft_service: &self.raft_service,
+                    store: node.store(),
+                    access_service: &self.access_service,
+                    cluster_secret: Some(SECRET),
+                }.execute(AppendEntriesDTO { request, secret }).await.map(RpcResult::Append),
+                Rpc::Propose(command) => ReceiveProposal {
+                    raft_state: &node.state,
+                    log_gateway: &node.log,
+                    access_service: &self.access_service,
+                    cluster_secret: Some(SECRET),
+                }.execute(ReceiveProposalDTO { command, secret }).await.map(RpcResult::Propose),
+            };
+            let _ = message.reply.send(result.map_err(|error| format!("{:?}", error)));
+        }
+
+        /// Run the future while serving the requests it makes
+        async fn run<T>(&self, future: impl Future<Output = T>) -> T {
+            let serve = async {
+                let mut inbox = self.inbox.lock().await;
+                while let Some(message) = inbox.recv().await {
+                    self.handle(message).await;
+                }
+            };
+            tokio::select! {
+                output = future => output,
+                _ = serve => unreachable!()
+            }
+        }
+
+        async fn step(&self) {
+            self.run(async {
+                for node in &self.nodes {
+                    node.consensus(&self.raft_service).tick().await;
+                }
+            }).await
+        }
+
+        async fn propose(&self, id: &str, command: MetadataCommand) -> Result<Proposal, String> {
+            self.run(self.node(id).consensus(&self.raft_service).propose(&command)).await
+        }
+
+        async fn expire(&self, id: &str) {
+            self.node(id).state.lock().await.election_deadline = Utc::now() - TimeDelta::seconds(1);
+        }
+
+        fn isolate(&self, id: &str, is_isolated: bool) {
+            let mut isolated = self.isolated.lock().unwrap();
+            match is_isolated {
+                true => isolated.insert(id.to_string()),
+                false => isolated.remove(id)
+            };
+        }
+
+        async fn role(&self, id: &str) -> RaftRole {
+            self.node(id).state.lock().await.role.clone()
+        }
+    }
+
+    fn save_user(id: &str) -> MetadataCommand {
+        MetadataCommand::SaveUser { user: User {
+            id: id.to_string(),
+            username: id.to_string(),
+            hashed_password: "hash".to_string(),
+            created_at: Utc::now(),
+        } }
+    }
+
+    fn save_box(id: &str) -> MetadataCommand {
+        MetadataCommand::SaveBox { r#box: BoxDomain {
+            id: id.to_string(),
+            duplicate_names: DuplicateNamePolicy::Allow,
+            access: BoxAccessPolicy::Private,
+            created_at: Utc::now(),
+        } }
+    }
+
+    #[tokio::test]
+    async fn test_replicated_log() {
+        let cluster = Cluster::new(&["a", "b", "c"]).await;
+        cluster.expire("a").await;
+        cluster.step().await;
+        assert_eq!(cluster.role("a").await, RaftRole::Leader);
+        assert_eq!(cluster.node("c").state.lock().await.leader_id.as_deref(), Some("a"));
+
+        // A change made on a follower is passed to the leader and applied by every node
+        let proposal = cluster.propose("b", save_user("alice")).await.unwrap();
+        assert_eq!(proposal, Proposal { index: 1, term: 1 });
+        cluster.step().await;
+        cluster.step().await;
+        for node in &cluster.nodes {
+            assert!(node.users.get_user(&"alice".to_string()).await.is_some());
+            assert_eq!(node.log.get_hard_state().await.applied, 1);
+        }
+
+        // The leader is cut off, its change is never committed
+        cluster.isolate("a", true);
+        let lost = cluster.propose("a", save_box("lost")).await.unwrap();
+        cluster.step().await;
+        cluster.expire("b").await;
+        cluster.step().await;
+        assert_eq!(cluster.role("b").await, RaftRole::Leader);
+        assert_eq!(cluster.role("a").await, RaftRole::Leader);
+        cluster.propose("c", save_box("photos")).await.unwrap();
+        cluster.step().await;
+        cluster.step().await;
+
+        // Back in the cluster, the old leader follows the new one and its entry is replaced
+        cluster.isolate("a", false);
+        cluster.step().await;
+        cluster.step().await;
+        assert_eq!(cluster.role("a").await, RaftRole::Follower);
+        for node in &cluster.nodes {
+            let entries: Vec<(LogIndex, Term)> = node.log.get_entries(1, 10).await.iter()
+                .map(|entry| (entry.index, entry.term))
+                .collect();
+            assert_eq!(entries, [(1, 1), (2, 2)]);
+            assert_eq!(node.boxes.get_boxes().await.len(), 1);
+            assert!(node.boxes.get_box(&"photos".to_string()).await.is_some());
+        }
+
+        let node = cluster.node("a");
+        let mut pending = PendingProposals::default();
+        let (reply, receiver) = oneshot::channel();
+        pending.push(lost, reply);
+        pending.resolve(node.log.get_hard_state().await.applied, &node.log).await;
+        assert!(receiver.await.unwrap().is_err());
+    }
+}
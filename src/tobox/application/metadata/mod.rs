@@ -0,0 +1,6 @@
+pub mod apply;
+pub mod consensus;
+pub mod proposer;
+pub mod request_vote;
+pub mod append_entries;
+pub mod receive_proposal;
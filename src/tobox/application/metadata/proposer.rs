@@ -0,0 +1,80 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{mpsc, oneshot};
+
+use crate::application::common::metadata_gateway::MetadataLogReader;
+use crate::domain::models::metadata::{LogIndex, MetadataCommand, Proposal};
+
+/// Changes waiting to be passed to the consensus loop
+const QUEUE_CAPACITY: usize = 64;
+
+/// Time a change waits for the cluster to apply it
+const PROPOSAL_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct ProposalRequest {
+    pub command: MetadataCommand,
+    pub reply: oneshot::Sender<Result<(), String>>
+}
+
+/// Passes metadata changes of the replicated gateways to the consensus loop
+#[derive(Clone)]
+pub struct MetadataProposer {
+    sender: mpsc::Sender<ProposalRequest>,
+    receiver: Arc<Mutex<Option<mpsc::Receiver<ProposalRequest>>>>
+}
+
+impl Default for MetadataProposer {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel(QUEUE_CAPACITY);
+        Self { sender, receiver: Arc::new(Mutex::new(Some(receiver))) }
+    }
+}
+
+impl MetadataProposer {
+    
+    /// Requests are taken once, by the consensus loop
+    pub fn take_requests(&self) -> Option<mpsc::Receiver<ProposalRequest>> {
+        self.receiver.lock().unwrap().take()
+    }
+    
+    /// Returns once the change is applied on this node
+    pub async fn propose(&self, command: MetadataCommand) -> Result<(), String> {
+        let (reply, receiver) = oneshot::channel();
+        self.sender.send(ProposalRequest { command, reply }).await
+            .map_err(|_| "Metadata consensus is not running".to_string())?;
+        match tokio::time::timeout(PROPOSAL_TIMEOUT, receiver).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err("Metadata consensus has stopped".to_string()),
+            Err(_) => Err("The change was not applied in time".to_string())
+        }
+    }
+}
+
+/// Appended changes waiting to be applied on this node
+#[derive(Default)]
+pub struct PendingProposals(Vec<(Proposal, oneshot::Sender<Result<(), String>>)>);
+
+impl PendingProposals {
+    
+    pub fn push(&mut self, proposal: Proposal, reply: oneshot::Sender<Result<(), String>>) {
+        self.0.push((proposal, reply));
+    }
+    
+    /// Answer the changes applied up to the index
+    ///
+    /// A change fails when another leader has put a different entry at its index.
+    pub async fn resolve(&mut self, applied: LogIndex, log_reader: &dyn MetadataLogReader) {
+        let (done, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.0).into_iter()
+            .filter(|(_, reply)| !reply.is_closed())
+            .partition(|(proposal, _)| proposal.index <= applied);
+        self.0 = waiting;
+        for (proposal, reply) in done {
+            let result = match log_reader.get_entry(proposal.index).await {
+                Some(entry) if entry.term == proposal.term => Ok(()),
+                _ => Err("The change was overwritten by another leader".to_string())
+            };
+            let _ = reply.send(result);
+        }
+    }
+}
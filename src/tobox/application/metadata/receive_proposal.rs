@@ -0,0 +1,44 @@
+use tokio::sync::Mutex;
+
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::interactor::Interactor;
+use crate::application::common::metadata_gateway::MetadataLogGateway;
+use crate::application::metadata::consensus::append_command;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::metadata::{MetadataCommand, Proposal, RaftState};
+use crate::domain::services::access::AccessService;
+
+pub struct ReceiveProposalDTO {
+    pub command: MetadataCommand,
+    /// Cluster secret presented by the peer
+    pub secret: Option<String>
+}
+
+/// Change of metadata passed by a follower to the leader
+pub struct ReceiveProposal<'a> {
+    pub raft_state: &'a Mutex<RaftState>,
+    pub log_gateway: &'a dyn MetadataLogGateway,
+    pub access_service: &'a AccessService,
+    pub cluster_secret: Option<&'a str>,
+}
+
+impl Interactor<ReceiveProposalDTO, Proposal> for ReceiveProposal<'_> {
+    async fn execute(&self, data: ReceiveProposalDTO) -> Result<Proposal, ApplicationError> {
+        
+        match self.access_service.ensure_is_peer(data.secret.as_deref(), self.cluster_secret) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+        
+        let state = self.raft_state.lock().await;
+        append_command(&state, self.log_gateway, &data.command).await
+            .map_err(|error| ApplicationError::Unavailable(ErrorContent::from(error)))
+    }
+}
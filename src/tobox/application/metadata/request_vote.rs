@@ -0,0 +1,53 @@
+use chrono::Utc;
+use tokio::sync::Mutex;
+
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::interactor::Interactor;
+use crate::application::common::metadata_gateway::MetadataLogGateway;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::metadata::{RaftState, VoteRequest, VoteResponse};
+use crate::domain::services::access::AccessService;
+use crate::domain::services::raft::RaftService;
+
+pub struct RequestVoteDTO {
+    pub request: VoteRequest,
+    /// Cluster secret presented by the peer
+    pub secret: Option<String>
+}
+
+/// Requested by a candidate of a metadata leader election
+pub struct RequestVote<'a> {
+    pub raft_state: &'a Mutex<RaftState>,
+    pub log_gateway: &'a dyn MetadataLogGateway,
+    pub raft_service: &'a RaftService,
+    pub access_service: &'a AccessService,
+    pub cluster_secret: Option<&'a str>,
+}
+
+impl Interactor<RequestVoteDTO, VoteResponse> for RequestVote<'_> {
+    async fn execute(&self, data: RequestVoteDTO) -> Result<VoteResponse, ApplicationError> {
+        
+        match self.access_service.ensure_is_peer(data.secret.as_deref(), self.cluster_secret) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+        
+        let mut state = self.raft_state.lock().await;
+        let last_log = self.log_gateway.get_last_entry().await
+            .map_or((0, 0), |entry| (entry.index, entry.term));
+        let hard_state = state.hard_state.clone();
+        let response = self.raft_service.vote(&mut state, &data.request, last_log, &Utc::now());
+        // The vote is kept before it is sent
+        if state.hard_state != hard_state {
+            self.log_gateway.save_hard_state(&state.hard_state).await;
+        }
+        Ok(response)
+    }
+}
@@ -7,4 +7,8 @@ pub mod permission;
 pub mod r#box;
 pub mod object;
 pub mod sync;
-pub mod session;
\ No newline at end of file
+pub mod metadata;
+pub mod session;
+pub mod access_key;
+pub mod api_key;
+pub mod upload;
\ No newline at end of file
@@ -1,50 +1,71 @@
 use std::collections::HashMap;
 
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 
+use crate::application::common::blob_lock::BlobLocks;
 use crate::application::common::box_gateway::BoxReader;
 use crate::application::common::exceptions::{ApplicationError, ErrorContent};
-use crate::application::common::file_storage_manager::{FileStorageError, FileStorageWriter};
+use crate::application::common::file_storage_manager::{
+    FileStorageRemover,
+    FileStorageWriter
+};
 use crate::application::common::id_provider::IdProvider;
 use crate::application::common::interactor::Interactor;
 use crate::application::common::object_gateway::ObjectGateway;
+use crate::application::common::replication_gateway::ReplicationWriter;
+use crate::application::object::duplicate::check_duplicate_name;
+use crate::application::object::gc::release_blob;
 use crate::domain::exceptions::DomainError;
 use crate::domain::models::file_stream::FileStream;
 use crate::domain::models::object::ObjectId;
 use crate::domain::models::r#box::BoxId;
 use crate::domain::services::access::AccessService;
 use crate::domain::services::object::ObjectService;
+use crate::domain::services::replication::ReplicationService;
 use crate::domain::services::validator::ValidatorService;
 
-#[derive(Deserialize)]
 pub struct CreateObjectDTO {
+    /// Chosen by an intermediate node, generated otherwise
+    pub id: Option<ObjectId>,
     pub box_id: BoxId,
     pub name: Option<String>,
+    pub path: Option<String>,
     pub file: Box<dyn FileStream>,
-    pub metadata: HashMap<String, String>
+    pub metadata: HashMap<String, String>,
+    /// Sha256 of the content declared by the client, checked after upload
+    pub expected_hash: Option<String>,
+    /// Remove other objects with the same path and name in the box
+    pub replace: bool
 }
 
 #[derive(Debug, Serialize)] 
 pub struct CreateObjectResultDTO {
     pub id: ObjectId,
     pub name: String,
+    pub path: Option<String>,
     pub hash: String,
     pub size: u64,
     pub content_type: String,
     pub metadata: HashMap<String, String>,
     pub box_id: BoxId,
     pub created_at: DateTime<Utc>,
-    pub updated_at: Option<DateTime<Utc>>
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>
 }
 
 pub struct CreateObject<'a> {
     pub box_reader: &'a dyn BoxReader,
     pub file_storage_writer: &'a dyn FileStorageWriter,
+    pub file_storage_remover: &'a dyn FileStorageRemover,
     pub object_gateway: &'a dyn ObjectGateway,
+    pub replication_writer: &'a dyn ReplicationWriter,
     pub object_service: &'a ObjectService,
+    pub replication_service: &'a ReplicationService,
     pub validator: &'a ValidatorService,
     pub access_service: &'a AccessService,
+    pub blob_locks: &'a BlobLocks,
     pub id_provider: Box<dyn IdProvider>,
 }
 
@@ -59,6 +80,12 @@ impl Interactor<CreateObjectDTO, CreateObjectResultDTO> for CreateObject<'_> {
             });
         }
         
+        if let Some(path) = &data.path {
+            self.validator.validate_object_path(path).unwrap_or_else(|e| {
+                validator_err_map.insert("path".to_string(), e.to_string());
+            });
+        }
+        
         self.validator.validate_object_metadata(&data.metadata).unwrap_or_else(|e| {
             validator_err_map.insert("metadata".to_string(), e.to_string());
         });
@@ -96,28 +123,75 @@ impl Interactor<CreateObjectDTO, CreateObjectResultDTO> for CreateObject<'_> {
             }
         };
         
-        let object_id = self.object_service.generate_object_id();
+        // Replaced objects are removed only after the new one is stored
+        let replaced_objects = match (&data.name, data.replace) {
+            (Some(name), true) => {
+                match self.access_service.ensure_can_delete_object(
+                    self.id_provider.is_auth(),
+                    &r#box.id,
+                    self.id_provider.permissions()
+                ) {
+                    Ok(_) => (),
+                    Err(error) => return match error {
+                        DomainError::AccessDenied => Err(
+                            ApplicationError::Forbidden(ErrorContent::from(error))
+                        ),
+                        DomainError::AuthorizationRequired => Err(
+                            ApplicationError::Unauthorized(ErrorContent::from(error))
+                        )
+                    }
+                };
+                self.object_gateway.get_objects_by_name(&r#box.id, data.path.as_deref(), name).await
+            },
+            _ => vec![]
+        };
         
-        let file_info = match self.file_storage_writer.save_file(
+        // Replaced objects are not duplicates
+        let warnings = if data.replace {
+            vec![]
+        } else {
+            check_duplicate_name(
+                self.object_gateway,
+                &r#box,
+                data.path.as_deref(),
+                data.name.as_deref(),
+                None
+            ).await?
+        };
+        
+        let object_id = match data.id {
+            Some(id) if self.object_gateway.get_object(&id).await.is_some() => return Err(
+                ApplicationError::Conflict(ErrorContent::from("Object already exists"))
+            ),
+            Some(id) => id,
+            None => self.object_service.generate_object_id()
+        };
+        
+        let mut file = data.file;
+        let file_info = self.file_storage_writer.save_file(
             &object_id,
             None,
             None,
-            data.file.as_ref()
-        ).await {
-            Ok(file_info) => file_info,
-            Err(error) => return match error {
-                FileStorageError::InvalidContentType(text) => Err(
-                    ApplicationError::InvalidData(ErrorContent::from(text))
-                ),
-                FileStorageError::InvalidSize(text) => Err(
-                    ApplicationError::InvalidData(ErrorContent::from(text))
+            file.as_mut()
+        ).await?;
+        
+        if let Some(expected_hash) = &data.expected_hash {
+            if !expected_hash.eq_ignore_ascii_case(&file_info.hash) {
+                self.file_storage_writer.discard_file(&object_id).await;
+                validator_err_map.insert(
+                    "file".to_string(),
+                    "Content hash does not match the expected one".to_string()
+                );
+                return Err(
+                    ApplicationError::InvalidData(ErrorContent::from(validator_err_map))
                 )
             }
-        };
+        }
         
         let object = self.object_service.create_object(
             object_id,
             data.name,
+            data.path,
             file_info.hash,
             file_info.size,
             file_info.content_type,
@@ -125,18 +199,38 @@ impl Interactor<CreateObjectDTO, CreateObjectResultDTO> for CreateObject<'_> {
             r#box.id
         );
         
-        self.file_storage_writer.rename_file(
-            &object.id,
-            &object.hash
-        ).await;
+        // The blob is committed before the object is registered: a crash in between
+        // leaves only an unreferenced blob, which is collected on startup.
+        // The lock is released before the replaced objects, they may share the blob
+        {
+            let _guard = self.blob_locks.lock(&object.hash).await;
+            self.file_storage_writer.commit_file(
+                &object.id,
+                &object.hash
+            ).await?;
 
-        self.object_gateway.save_object(&object).await;
+            self.object_gateway.save_object(&object).await;
+        }
         
-        // Todo: sync with other nodes in background
+        // Pushed to other nodes in background
+        self.replication_writer.save_task(
+            &self.replication_service.create_task(object.id.clone())
+        ).await;
+        
+        for replaced_object in replaced_objects {
+            self.object_gateway.remove_object(&replaced_object.id).await;
+            release_blob(
+                self.object_gateway,
+                self.file_storage_remover,
+                self.blob_locks,
+                &replaced_object.hash
+            ).await;
+        }
 
         Ok(CreateObjectResultDTO {
             id: object.id.clone(),
             name: object.name.unwrap_or(object.id),
+            path: object.path,
             hash: object.hash,
             size: object.size,
             content_type: object.content_type,
@@ -144,6 +238,7 @@ impl Interactor<CreateObjectDTO, CreateObjectResultDTO> for CreateObject<'_> {
             box_id: object.box_id,
             created_at: object.created_at,
             updated_at: None,
+            warnings
         })
     }
 }
@@ -0,0 +1,62 @@
+use serde::Deserialize;
+
+use crate::application::common::blob_lock::BlobLocks;
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::file_storage_manager::FileStorageRemover;
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::object_gateway::ObjectGateway;
+use crate::application::object::gc::release_blob;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::object::ObjectId;
+use crate::domain::services::access::AccessService;
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteObjectDTO {
+    pub id: ObjectId
+}
+
+pub struct DeleteObject<'a> {
+    pub object_gateway: &'a dyn ObjectGateway,
+    pub file_storage_remover: &'a dyn FileStorageRemover,
+    pub access_service: &'a AccessService,
+    pub blob_locks: &'a BlobLocks,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<DeleteObjectDTO, ()> for DeleteObject<'_> {
+    async fn execute(&self, data: DeleteObjectDTO) -> Result<(), ApplicationError> {
+
+        let object = self.object_gateway.get_object(&data.id).await.ok_or(
+            ApplicationError::NotFound(ErrorContent::from("Object not found"))
+        )?;
+
+        match self.access_service.ensure_can_delete_object(
+            self.id_provider.is_auth(),
+            &object.box_id,
+            self.id_provider.permissions()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        self.object_gateway.remove_object(&object.id).await;
+
+        // The blob may still be shared with other objects
+        release_blob(
+            self.object_gateway,
+            self.file_storage_remover,
+            self.blob_locks,
+            &object.hash
+        ).await;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::object_gateway::ObjectReader;
+use crate::domain::models::object::ObjectId;
+use crate::domain::models::r#box::{Box as BoxDomain, DuplicateNamePolicy};
+
+/// Apply the duplicate name policy of the box to an object being stored
+///
+/// * except: the object being renamed, which is not a duplicate of itself
+/// * return: warnings to be passed to the client
+pub async fn check_duplicate_name(
+    object_reader: &dyn ObjectReader,
+    r#box: &BoxDomain,
+    path: Option<&str>,
+    name: Option<&str>,
+    except: Option<&ObjectId>
+) -> Result<Vec<String>, ApplicationError> {
+    let name = match name {
+        Some(name) if r#box.duplicate_names != DuplicateNamePolicy::Allow => name,
+        _ => return Ok(vec![])
+    };
+
+    let is_duplicate = object_reader.get_objects_by_name(&r#box.id, path, name).await
+        .iter()
+        .any(|object| Some(&object.id) != except);
+    if !is_duplicate {
+        return Ok(vec![])
+    }
+
+    let message = "Object with the same path and name already exists in the box";
+    match r#box.duplicate_names {
+        DuplicateNamePolicy::Reject => Err(ApplicationError::Conflict(ErrorContent::from(message))),
+        _ => Ok(vec![message.to_string()])
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::application::common::box_gateway::BoxReader;
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::object_gateway::ObjectReader;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::object::{split_key, ObjectId};
+use crate::domain::models::r#box::BoxId;
+use crate::domain::services::access::AccessService;
+
+#[derive(Debug, Deserialize)]
+pub struct FindObjectDTO {
+    pub box_id: BoxId,
+    /// Path joined with the name, or id for objects without a name
+    pub key: String
+}
+
+#[derive(Debug, Serialize)]
+pub struct FindObjectResultDTO {
+    pub id: ObjectId,
+    pub name: String,
+    pub path: Option<String>,
+    pub hash: String,
+    pub size: u64,
+    pub content_type: String,
+    pub metadata: HashMap<String, String>,
+    pub box_id: BoxId,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>
+}
+
+pub struct FindObject<'a> {
+    pub box_reader: &'a dyn BoxReader,
+    pub object_reader: &'a dyn ObjectReader,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>
+}
+
+impl Interactor<FindObjectDTO, FindObjectResultDTO> for FindObject<'_> {
+    async fn execute(&self, data: FindObjectDTO) -> Result<FindObjectResultDTO, ApplicationError> {
+
+        let access = self.box_reader.get_box(&data.box_id).await
+            .map(|r#box| r#box.access)
+            .unwrap_or_default();
+
+        match self.access_service.ensure_can_get_object(
+            self.id_provider.is_auth(),
+            &data.box_id,
+            &access,
+            self.id_provider.permissions()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        // The latest object wins if several share the name
+        let (path, name) = split_key(&data.key);
+        let by_name = self.object_reader.get_objects_by_name(&data.box_id, path, name).await
+            .into_iter()
+            .max_by_key(|object| object.updated_at.unwrap_or(object.created_at));
+
+        let object = match by_name {
+            Some(object) => object,
+            None => self.object_reader.get_object(&data.key).await
+                .filter(|object| object.box_id == data.box_id && object.name.is_none())
+                .ok_or(ApplicationError::NotFound(ErrorContent::from("Object not found")))?
+        };
+
+        Ok(FindObjectResultDTO {
+            id: object.id.clone(),
+            name: object.name.unwrap_or(object.id),
+            path: object.path,
+            hash: object.hash,
+            size: object.size,
+            content_type: object.content_type,
+            metadata: object.metadata,
+            box_id: object.box_id,
+            created_at: object.created_at,
+            updated_at: object.updated_at
+        })
+    }
+}
@@ -0,0 +1,121 @@
+use crate::application::common::blob_lock::BlobLocks;
+use crate::application::common::file_storage_manager::{FileStorageReader, FileStorageRemover};
+use crate::application::common::object_gateway::ObjectReader;
+
+/// Remove the blob if no object references it anymore
+///
+/// The reference count of a blob is the number of objects with its hash.
+/// It is counted under the lock of the blob, so an object saved with the hash
+/// in the meantime keeps the blob.
+pub async fn release_blob(
+    object_reader: &dyn ObjectReader,
+    file_storage_remover: &dyn FileStorageRemover,
+    blob_locks: &BlobLocks,
+    hash: &str
+) -> bool {
+    let _guard = blob_locks.lock(hash).await;
+    if object_reader.count_objects_by_hash(hash).await > 0 {
+        return false
+    }
+    file_storage_remover.remove_file(hash).await;
+    log::debug!("Blob {} removed", hash);
+    true
+}
+
+/// Sweep the blob store and remove every blob that is not referenced by any object
+pub async fn collect_garbage(
+    object_reader: &dyn ObjectReader,
+    file_storage_reader: &dyn FileStorageReader,
+    file_storage_remover: &dyn FileStorageRemover,
+    blob_locks: &BlobLocks
+) {
+    let mut removed = 0;
+    for hash in file_storage_reader.get_files().await {
+        if release_blob(object_reader, file_storage_remover, blob_locks, &hash).await {
+            removed += 1;
+        }
+    }
+    log::info!("Blob garbage collection finished, {} blobs removed", removed);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bytes::Bytes;
+    use chrono::Utc;
+    use tokio::io;
+
+    use crate::adapters::database::file_storage::FileStorage;
+    use crate::adapters::database::object_db::ObjectGateway;
+    use crate::adapters::database::sqlite::connect_in_memory;
+    use crate::application::common::file_storage_manager::{FileStorageReader, FileStorageWriter};
+    use crate::application::common::object_gateway::{ObjectRemover, ObjectWriter};
+    use crate::domain::models::file_stream::FileStream;
+    use crate::domain::models::object::Object;
+
+    use super::*;
+
+    type OnceStream = tokio_stream::Iter<std::vec::IntoIter<io::Result<Bytes>>>;
+
+    impl FileStream for OnceStream {}
+
+    async fn stage(storage: &FileStorage, filename: &str) -> String {
+        let mut stream: OnceStream = tokio_stream::iter(vec![Ok(Bytes::from_static(b"shared content"))]);
+        match storage.save_file(filename, None, None, &mut stream).await {
+            Ok(file_info) => file_info.hash,
+            Err(_) => panic!("save_file failed")
+        }
+    }
+
+    fn object(id: &str, hash: &str) -> Object {
+        Object {
+            id: id.to_string(),
+            name: None,
+            path: None,
+            hash: hash.to_string(),
+            size: 14,
+            content_type: "text/plain".to_string(),
+            metadata: Default::default(),
+            box_id: "box".to_string(),
+            created_at: Utc::now(),
+            updated_at: None
+        }
+    }
+
+    #[tokio::test]
+    async fn test_release_during_deduplicated_commit() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FileStorage::new(dir.path());
+        let objects = ObjectGateway::new(connect_in_memory().await);
+        let locks = BlobLocks::default();
+
+        let hash = stage(&storage, "a").await;
+        storage.commit_file("a", &hash).await.unwrap();
+        objects.save_object(&object("a", &hash)).await;
+        assert_eq!(stage(&storage, "b").await, hash);
+
+        // The only object of the blob is removed while another one is uploaded
+        objects.remove_object(&"a".to_string()).await;
+        let commit = async {
+            let _guard = locks.lock(&hash).await;
+            storage.commit_file("b", &hash).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            objects.save_object(&object("b", &hash)).await;
+        };
+        let release = async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            release_blob(&objects, &storage, &locks, &hash).await
+        };
+        let ((), released) = tokio::join!(commit, release);
+
+        assert!(!released);
+        assert!(storage.is_file_exists(&hash).await);
+        assert_eq!(objects.count_objects_by_hash(&hash).await, 1);
+
+        // Released once the last object is removed
+        objects.remove_object(&"b".to_string()).await;
+        assert!(release_blob(&objects, &storage, &locks, &hash).await);
+        assert!(!storage.is_file_exists(&hash).await);
+    }
+}
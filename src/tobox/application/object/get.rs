@@ -1,5 +1,6 @@
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
 
+use crate::application::common::box_gateway::BoxReader;
 use crate::application::common::exceptions::{ApplicationError, ErrorContent};
 use crate::application::common::file_storage_manager::FileStorageReader;
 use crate::application::common::id_provider::IdProvider;
@@ -10,28 +11,81 @@ use crate::domain::models::file_stream::FileStream;
 use crate::domain::models::object::ObjectId;
 use crate::domain::services::access::AccessService;
 
-#[derive(Debug, Deserialize)]
+pub enum ObjectRange {
+    /// First and last byte positions, inclusive
+    FromTo(u64, u64),
+    From(u64),
+    /// Last n bytes
+    Suffix(u64)
+}
+
+pub enum RangeCondition {
+    Hash(String),
+    Date(DateTime<Utc>)
+}
+
 pub struct GetObjectDTO {
-    pub id: ObjectId
+    pub id: ObjectId,
+    pub range: Option<ObjectRange>,
+    /// The range is served only while the object is still the same
+    pub if_range: Option<RangeCondition>,
+    /// Hashes known by the client, `*` matches any
+    pub if_none_match: Option<Vec<String>>,
+    pub if_modified_since: Option<DateTime<Utc>>
+}
+
+impl GetObjectDTO {
+    pub fn new(id: ObjectId) -> Self {
+        Self {
+            id,
+            range: None,
+            if_range: None,
+            if_none_match: None,
+            if_modified_since: None
+        }
+    }
+}
+
+pub enum ObjectContent {
+    NotModified,
+    Full(Box<dyn FileStream>),
+    /// First and last byte positions, inclusive
+    Partial(u64, u64, Box<dyn FileStream>),
+    RangeNotSatisfiable
+}
+
+pub struct GetObjectResultDTO {
+    pub name: String,
+    pub hash: String,
+    pub size: u64,
+    pub content_type: String,
+    pub last_modified: DateTime<Utc>,
+    pub content: ObjectContent
 }
 
 pub struct GetObject<'a> {
+    pub box_reader: &'a dyn BoxReader,
     pub file_storage_reader: &'a dyn FileStorageReader,
     pub object_reader: &'a dyn ObjectReader,
     pub access_service: &'a AccessService,
     pub id_provider: Box<dyn IdProvider>
 }
 
-impl Interactor<GetObjectDTO, Box<dyn FileStream>> for GetObject<'_> {
-    async fn execute(&self, data: GetObjectDTO) -> Result<Box<dyn FileStream>, ApplicationError> {
-        
+impl Interactor<GetObjectDTO, GetObjectResultDTO> for GetObject<'_> {
+    async fn execute(&self, data: GetObjectDTO) -> Result<GetObjectResultDTO, ApplicationError> {
+
         let object = self.object_reader.get_object(&data.id).await.ok_or(
             ApplicationError::NotFound(ErrorContent::from("Object not found"))
         )?;
+        // An object may arrive before its box, it is private until then
+        let access = self.box_reader.get_box(&object.box_id).await
+            .map(|r#box| r#box.access)
+            .unwrap_or_default();
 
         match self.access_service.ensure_can_get_object(
             self.id_provider.is_auth(),
             &object.box_id,
+            &access,
             self.id_provider.permissions()
         ) {
             Ok(_) => (),
@@ -45,6 +99,81 @@ impl Interactor<GetObjectDTO, Box<dyn FileStream>> for GetObject<'_> {
             }
         };
 
-        Ok(self.file_storage_reader.read_file(&object.hash).await)
+        let last_modified = object.updated_at.unwrap_or(object.created_at);
+
+        let is_modified = match (&data.if_none_match, &data.if_modified_since) {
+            // If-None-Match takes precedence over If-Modified-Since
+            (Some(hashes), _) => !hashes.iter().any(|hash| hash == "*" || *hash == object.hash),
+            (None, Some(since)) => last_modified.timestamp() > since.timestamp(),
+            (None, None) => true
+        };
+
+        let range = match (data.range, data.if_range) {
+            (Some(range), Some(RangeCondition::Hash(hash))) if hash == object.hash => Some(range),
+            (Some(range), Some(RangeCondition::Date(date)))
+                if date.timestamp() == last_modified.timestamp() => Some(range),
+            (Some(range), None) => Some(range),
+            _ => None
+        };
+
+        let content = if !is_modified {
+            ObjectContent::NotModified
+        } else if let Some(range) = range {
+            match resolve_range(&range, object.size) {
+                Some((first, last)) => ObjectContent::Partial(
+                    first,
+                    last,
+                    self.file_storage_reader.read_file_range(
+                        &object.hash,
+                        first,
+                        last - first + 1
+                    ).await?
+                ),
+                None => ObjectContent::RangeNotSatisfiable
+            }
+        } else {
+            ObjectContent::Full(self.file_storage_reader.read_file(&object.hash).await?)
+        };
+
+        Ok(GetObjectResultDTO {
+            name: object.name.unwrap_or(object.id),
+            hash: object.hash,
+            size: object.size,
+            content_type: object.content_type,
+            last_modified,
+            content
+        })
+    }
+}
+
+/// Resolve the range into first and last byte positions within the object
+fn resolve_range(range: &ObjectRange, size: u64) -> Option<(u64, u64)> {
+    match *range {
+        ObjectRange::FromTo(first, last) if first <= last && first < size => {
+            Some((first, last.min(size - 1)))
+        },
+        ObjectRange::From(first) if first < size => Some((first, size - 1)),
+        ObjectRange::Suffix(length) if length > 0 && size > 0 => {
+            Some((size - length.min(size), size - 1))
+        },
+        _ => None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_range() {
+        assert_eq!(resolve_range(&ObjectRange::FromTo(0, 9), 100), Some((0, 9)));
+        assert_eq!(resolve_range(&ObjectRange::FromTo(90, 200), 100), Some((90, 99)));
+        assert_eq!(resolve_range(&ObjectRange::FromTo(100, 200), 100), None);
+        assert_eq!(resolve_range(&ObjectRange::FromTo(9, 0), 100), None);
+        assert_eq!(resolve_range(&ObjectRange::From(50), 100), Some((50, 99)));
+        assert_eq!(resolve_range(&ObjectRange::Suffix(10), 100), Some((90, 99)));
+        assert_eq!(resolve_range(&ObjectRange::Suffix(500), 100), Some((0, 99)));
+        assert_eq!(resolve_range(&ObjectRange::Suffix(10), 0), None);
     }
 }
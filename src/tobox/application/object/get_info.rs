@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
+use crate::application::common::box_gateway::BoxReader;
 use crate::application::common::exceptions::{ApplicationError, ErrorContent};
 use crate::application::common::file_storage_manager::FileStorageReader;
 use crate::application::common::id_provider::IdProvider;
@@ -17,10 +18,11 @@ pub struct GetObjectInfoDTO {
     pub id: ObjectId
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize)]
 pub struct GetObjectInfoResultDTO {
     pub id: ObjectId,
     pub name: String,
+    pub path: Option<String>,
     pub hash: String,
     pub size: u64,
     pub content_type: String,
@@ -31,6 +33,7 @@ pub struct GetObjectInfoResultDTO {
 }
 
 pub struct GetObjectInfo<'a> {  
+    pub box_reader: &'a dyn BoxReader,
     pub object_reader: &'a dyn ObjectReader,
     pub file_storage_reader: &'a dyn FileStorageReader,
     pub access_service: &'a AccessService,
@@ -43,10 +46,15 @@ impl Interactor<GetObjectInfoDTO, GetObjectInfoResultDTO> for GetObjectInfo<'_>
         let object = self.object_reader.get_object(&data.id).await.ok_or(
             ApplicationError::NotFound(ErrorContent::from("Object not found"))
         )?;
+        // An object may arrive before its box, it is private until then
+        let access = self.box_reader.get_box(&object.box_id).await
+            .map(|r#box| r#box.access)
+            .unwrap_or_default();
 
         match self.access_service.ensure_can_get_object(
             self.id_provider.is_auth(),
             &object.box_id,
+            &access,
             self.id_provider.permissions()
         ) {
             Ok(_) => (),
@@ -63,6 +71,7 @@ impl Interactor<GetObjectInfoDTO, GetObjectInfoResultDTO> for GetObjectInfo<'_>
         Ok(GetObjectInfoResultDTO {
             id: object.id.clone(),
             name: object.name.unwrap_or(object.id.clone()),
+            path: object.path,
             hash: object.hash,
             size: object.size,
             content_type: object.content_type,
@@ -0,0 +1,524 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::application::common::box_gateway::BoxReader;
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::object_gateway::{ObjectCursor, ObjectFilter, ObjectReader};
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::object::{Object, ObjectId};
+use crate::domain::models::r#box::BoxId;
+use crate::domain::services::access::AccessService;
+use crate::domain::services::validator::ValidatorService;
+
+const DEFAULT_LIMIT: u64 = 100;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ListObjectsDTO {
+    pub box_id: BoxId,
+    /// Prefix of the key, or of the id for objects without a name
+    pub prefix: Option<String>,
+    /// Keys containing the delimiter after the prefix are rolled up into common prefixes,
+    /// `/` lists a directory
+    pub delimiter: Option<String>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    pub content_type: Option<String>,
+    pub created_from: Option<DateTime<Utc>>,
+    pub created_to: Option<DateTime<Utc>>,
+    pub updated_from: Option<DateTime<Utc>>,
+    pub updated_to: Option<DateTime<Utc>>,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    /// List only keys greater than this one, ignored when the cursor is set
+    pub start_after: Option<String>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ObjectItem {
+    pub id: ObjectId,
+    /// Path joined with the name, or id for objects without a name
+    pub key: String,
+    pub hash: String,
+    pub size: u64,
+    pub content_type: String,
+    pub metadata: HashMap<String, String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListObjectsResultDTO {
+    /// Ordered by key, objects with the same name by id
+    pub objects: Vec<ObjectItem>,
+    /// Rolled up keys, each one ends with the delimiter
+    pub common_prefixes: Vec<String>,
+    /// Set when there are more objects
+    pub next_cursor: Option<String>
+}
+
+pub struct ListObjects<'a> {
+    pub box_reader: &'a dyn BoxReader,
+    pub object_reader: &'a dyn ObjectReader,
+    pub validator: &'a ValidatorService,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>
+}
+
+impl Interactor<ListObjectsDTO, ListObjectsResultDTO> for ListObjects<'_> {
+    async fn execute(&self, data: ListObjectsDTO) -> Result<ListObjectsResultDTO, ApplicationError> {
+
+        let r#box = self.box_reader.get_box(&data.box_id).await;
+        let access = r#box.as_ref().map(|r#box| r#box.access.clone()).unwrap_or_default();
+
+        match self.access_service.ensure_can_list_objects(
+            self.id_provider.is_auth(),
+            &data.box_id,
+            &access,
+            self.id_provider.permissions()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        let mut validator_err_map: HashMap<String, String> = HashMap::new();
+
+        let limit = data.limit.unwrap_or(DEFAULT_LIMIT);
+        self.validator.validate_list_limit(&limit).unwrap_or_else(|e| {
+            validator_err_map.insert("limit".to_string(), e.to_string());
+        });
+
+        self.validator.validate_object_metadata(&data.metadata).unwrap_or_else(|e| {
+            validator_err_map.insert("metadata".to_string(), e.to_string());
+        });
+
+        for (field, from, to) in [
+            ("created", &data.created_from, &data.created_to),
+            ("updated", &data.updated_from, &data.updated_to)
+        ] {
+            if let (Some(from), Some(to)) = (from, to) {
+                if from > to {
+                    validator_err_map.insert(
+                        format!("{}_from", field),
+                        format!("should not be later than {}_to", field)
+                    );
+                }
+            }
+        }
+
+        if data.delimiter.as_deref() == Some("") {
+            validator_err_map.insert("delimiter".to_string(), "should not be empty".to_string());
+        }
+
+        let after = match (&data.cursor, &data.start_after) {
+            (Some(cursor), _) => match decode_cursor(cursor) {
+                Some(cursor) => Some(cursor),
+                None => {
+                    validator_err_map.insert("cursor".to_string(), "Invalid cursor".to_string());
+                    None
+                }
+            },
+            (None, Some(start_after)) => Some(ObjectCursor { key: start_after.clone(), id: None }),
+            (None, None) => None
+        };
+
+        if !validator_err_map.is_empty() {
+            return Err(
+                ApplicationError::InvalidData(ErrorContent::from(validator_err_map))
+            )
+        }
+
+        if r#box.is_none() {
+            return Err(ApplicationError::NotFound(ErrorContent::from("Box not found")))
+        }
+
+        let prefix = data.prefix.clone().unwrap_or_default();
+        let filter = ObjectFilter {
+            box_id: data.box_id,
+            key_prefix: data.prefix,
+            metadata: data.metadata,
+            content_type: data.content_type,
+            created_from: data.created_from,
+            created_to: data.created_to,
+            updated_from: data.updated_from,
+            updated_to: data.updated_to,
+        };
+
+        let start_after = if data.cursor.is_none() { data.start_after.as_deref() } else { None };
+        let mut page = ListPage::new(&prefix, data.delimiter.as_deref(), start_after, limit as usize);
+        let mut after = after;
+
+        // One more entry tells whether there is a next page
+        let batch_size = limit + 1;
+        let next_cursor = 'page: loop {
+            let objects = self.object_reader.list_objects(&filter, after.as_ref(), batch_size).await;
+            let is_last_batch = (objects.len() as u64) < batch_size;
+            for object in objects {
+                match page.push(object) {
+                    Entry::Object(cursor) => after = Some(cursor),
+                    // Objects under the common prefix are skipped by the next request
+                    Entry::CommonPrefix(cursor) => {
+                        after = Some(cursor);
+                        continue 'page;
+                    },
+                    Entry::Full => break 'page page.last_cursor.take()
+                }
+            }
+            if is_last_batch {
+                break None;
+            }
+        };
+
+        Ok(ListObjectsResultDTO {
+            objects: page.objects.into_iter().map(|object| ObjectItem {
+                key: object.key(),
+                id: object.id,
+                hash: object.hash,
+                size: object.size,
+                content_type: object.content_type,
+                metadata: object.metadata,
+                created_at: object.created_at,
+                updated_at: object.updated_at
+            }).collect(),
+            common_prefixes: page.common_prefixes,
+            next_cursor: next_cursor.map(|cursor| encode_cursor(&cursor))
+        })
+    }
+}
+
+enum Entry {
+    /// The object is listed, the cursor points to it
+    Object(ObjectCursor),
+    /// The object is rolled up, the cursor points past every key with the common prefix
+    CommonPrefix(ObjectCursor),
+    /// The object belongs to the next page
+    Full,
+}
+
+/// Page of a listing, filled with objects in key order
+///
+/// A common prefix counts as a single entry.
+struct ListPage<'a> {
+    prefix: &'a str,
+    delimiter: Option<&'a str>,
+    start_after: Option<&'a str>,
+    limit: usize,
+    objects: Vec<Object>,
+    common_prefixes: Vec<String>,
+    last_cursor: Option<ObjectCursor>,
+}
+
+impl<'a> ListPage<'a> {
+    fn new(
+        prefix: &'a str,
+        delimiter: Option<&'a str>,
+        start_after: Option<&'a str>,
+        limit: usize
+    ) -> Self {
+        Self {
+            prefix,
+            delimiter,
+            start_after,
+            limit,
+            objects: vec![],
+            common_prefixes: vec![],
+            last_cursor: None,
+        }
+    }
+
+    fn push(&mut self, object: Object) -> Entry {
+        let key = object.key();
+        let common_prefix = self.delimiter.and_then(
+            |delimiter| common_prefix(&key, self.prefix, delimiter)
+        );
+        if let Some(common_prefix) = &common_prefix {
+            // Listing may start in the middle of the common prefix
+            if self.start_after.is_some_and(|start_after| common_prefix.as_str() <= start_after) {
+                return Entry::CommonPrefix(skip_prefix(common_prefix))
+            }
+        }
+
+        if self.objects.len() + self.common_prefixes.len() == self.limit {
+            return Entry::Full
+        }
+
+        match common_prefix {
+            Some(common_prefix) => {
+                let cursor = skip_prefix(&common_prefix);
+                self.last_cursor = Some(cursor.clone());
+                self.common_prefixes.push(common_prefix);
+                Entry::CommonPrefix(cursor)
+            },
+            None => {
+                let cursor = ObjectCursor { key, id: Some(object.id.clone()) };
+                self.last_cursor = Some(cursor.clone());
+                self.objects.push(object);
+                Entry::Object(cursor)
+            }
+        }
+    }
+}
+
+/// Merge pages of the same listing made by several nodes, each holding a part of the box
+///
+/// Every node lists `limit` entries after the same cursor, so the first `limit` entries
+/// of the merge are the page of the whole box. Objects are ordered by `(key, id)` as they
+/// are listed, an object held by several nodes and a common prefix met on several nodes
+/// are listed once. The next cursor points to the last entry, as on a single node.
+pub fn merge_pages(pages: Vec<ListObjectsResultDTO>, limit: Option<u64>) -> ListObjectsResultDTO {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
+    let mut is_truncated = pages.iter().any(|page| page.next_cursor.is_some());
+
+    let mut pages: Vec<_> = pages.into_iter()
+        .map(|page| {
+            let mut entries: Vec<PageEntry> = page.objects.into_iter()
+                .map(PageEntry::Object)
+                .chain(page.common_prefixes.into_iter().map(PageEntry::CommonPrefix))
+                .collect();
+            entries.sort_by_cached_key(PageEntry::position);
+            entries.into_iter().peekable()
+        })
+        .collect();
+    let mut heads = BinaryHeap::new();
+    for (index, page) in pages.iter_mut().enumerate() {
+        if let Some(entry) = page.peek() {
+            heads.push(Reverse((entry.position(), index)));
+        }
+    }
+
+    let mut result = ListObjectsResultDTO { objects: vec![], common_prefixes: vec![], next_cursor: None };
+    let mut last: Option<(String, Option<ObjectId>)> = None;
+    while let Some(Reverse((position, index))) = heads.pop() {
+        let entry = pages[index].next().unwrap();
+        if let Some(next) = pages[index].peek() {
+            heads.push(Reverse((next.position(), index)));
+        }
+        if last.as_ref() == Some(&position) {
+            continue
+        }
+        if result.objects.len() + result.common_prefixes.len() == limit {
+            is_truncated = true;
+            break
+        }
+        match entry {
+            PageEntry::Object(object) => result.objects.push(object),
+            PageEntry::CommonPrefix(prefix) => result.common_prefixes.push(prefix)
+        }
+        last = Some(position);
+    }
+
+    if is_truncated {
+        result.next_cursor = last.map(|(key, id)| encode_cursor(&ObjectCursor { key, id }));
+    }
+    result
+}
+
+enum PageEntry {
+    Object(ObjectItem),
+    CommonPrefix(String),
+}
+
+impl PageEntry {
+    /// Position in the listing, the same as the cursor pointing to the entry
+    fn position(&self) -> (String, Option<ObjectId>) {
+        match self {
+            PageEntry::Object(object) => (object.key.clone(), Some(object.id.clone())),
+            PageEntry::CommonPrefix(prefix) => {
+                let cursor = skip_prefix(prefix);
+                (cursor.key, cursor.id)
+            }
+        }
+    }
+}
+
+/// Part of the key up to and including the first delimiter after the prefix
+fn common_prefix(key: &str, prefix: &str, delimiter: &str) -> Option<String> {
+    let rest = key.strip_prefix(prefix)?;
+    rest.find(delimiter).map(
+        |position| key[..prefix.len() + position + delimiter.len()].to_string()
+    )
+}
+
+/// Cursor placed after every key starting with the prefix
+fn skip_prefix(prefix: &str) -> ObjectCursor {
+    ObjectCursor { key: format!("{}{}", prefix, char::MAX), id: None }
+}
+
+
+/// Cursors are opaque to clients: hex of the json encoded position
+fn encode_cursor(cursor: &ObjectCursor) -> String {
+    serde_json::to_vec(&(&cursor.key, &cursor.id)).unwrap().iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn decode_cursor(cursor: &str) -> Option<ObjectCursor> {
+    if !cursor.len().is_multiple_of(2) || !cursor.is_ascii() {
+        return None
+    }
+    let bytes = (0..cursor.len()).step_by(2)
+        .map(|i| u8::from_str_radix(&cursor[i..i + 2], 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    let (key, id) = serde_json::from_slice::<(String, Option<ObjectId>)>(&bytes).ok()?;
+    Some(ObjectCursor { key, id })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::object::split_key;
+
+    use super::*;
+
+    #[test]
+    fn test_cursor() {
+        let cursor = ObjectCursor { key: "photo \"1\".png".to_string(), id: Some("abc".to_string()) };
+        assert_eq!(decode_cursor(&encode_cursor(&cursor)), Some(cursor));
+        assert_eq!(decode_cursor("zz"), None);
+        assert_eq!(decode_cursor("7b7"), None);
+        assert_eq!(decode_cursor("ÿÿ"), None);
+    }
+
+    #[test]
+    fn test_common_prefix() {
+        assert_eq!(common_prefix("a/b/c", "", "/"), Some("a/".to_string()));
+        assert_eq!(common_prefix("a/b/c", "a/", "/"), Some("a/b/".to_string()));
+        assert_eq!(common_prefix("a/b", "a/", "/"), None);
+        assert_eq!(common_prefix("a--b", "", "--"), Some("a--".to_string()));
+        assert!(skip_prefix("a/").key.as_str() > "a/\u{FFFF}z");
+    }
+
+    fn object(key: &str) -> Object {
+        let (path, name) = split_key(key);
+        Object {
+            id: key.to_string(),
+            name: Some(name.to_string()),
+            path: path.map(str::to_string),
+            hash: String::new(),
+            size: 0,
+            content_type: String::new(),
+            metadata: HashMap::new(),
+            box_id: String::new(),
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    /// Fill a page from sorted keys the way the gateway lists them after the cursor
+    fn list_page<'a>(
+        keys: &[&str],
+        prefix: &'a str,
+        delimiter: Option<&'a str>,
+        start_after: Option<&'a str>,
+        limit: usize
+    ) -> (ListPage<'a>, Option<ObjectCursor>) {
+        let mut page = ListPage::new(prefix, delimiter, start_after, limit);
+        let mut after = start_after.map(|key| ObjectCursor { key: key.to_string(), id: None });
+        for key in keys.iter().filter(|key| key.starts_with(prefix)) {
+            if after.as_ref().is_some_and(|after| **key <= *after.key) {
+                continue;
+            }
+            match page.push(object(key)) {
+                Entry::Object(cursor) | Entry::CommonPrefix(cursor) => after = Some(cursor),
+                Entry::Full => return (page, after)
+            }
+        }
+        (page, None)
+    }
+
+    fn keys(page: &ListPage) -> Vec<String> {
+        page.objects.iter().map(|object| object.key()).collect()
+    }
+
+    #[test]
+    fn test_list_page() {
+        let objects = ["a", "b/1", "b/2", "c", "d/1"];
+
+        let (page, next) = list_page(&objects, "", None, None, 1000);
+        assert_eq!(keys(&page), vec!["a", "b/1", "b/2", "c", "d/1"]);
+        assert!(next.is_none());
+
+        let (page, _) = list_page(&objects, "", Some("/"), None, 1000);
+        assert_eq!(keys(&page), vec!["a", "c"]);
+        assert_eq!(page.common_prefixes, vec!["b/", "d/"]);
+
+        let (page, _) = list_page(&objects, "b/", Some("/"), None, 1000);
+        assert_eq!(keys(&page), vec!["b/1", "b/2"]);
+        assert!(page.common_prefixes.is_empty());
+
+        // Pages continue after the common prefix
+        let (page, next) = list_page(&objects, "", Some("/"), None, 2);
+        assert_eq!(keys(&page), vec!["a"]);
+        assert_eq!(next, Some(skip_prefix("b/")));
+
+        // Common prefixes before the start key are not listed
+        let (page, next) = list_page(&objects, "", Some("/"), Some("b/1"), 2);
+        assert_eq!(keys(&page), vec!["c"]);
+        assert_eq!(page.common_prefixes, vec!["d/"]);
+        assert!(next.is_none());
+    }
+
+    fn result(keys: &[(&str, &str)], common_prefixes: &[&str], is_truncated: bool) -> ListObjectsResultDTO {
+        ListObjectsResultDTO {
+            objects: keys.iter().map(|(key, id)| ObjectItem {
+                id: id.to_string(),
+                key: key.to_string(),
+                hash: String::new(),
+                size: 0,
+                content_type: String::new(),
+                metadata: HashMap::new(),
+                created_at: Utc::now(),
+                updated_at: None
+            }).collect(),
+            common_prefixes: common_prefixes.iter().map(|prefix| prefix.to_string()).collect(),
+            next_cursor: is_truncated.then(|| "cursor".to_string())
+        }
+    }
+
+    fn merged_keys(page: &ListObjectsResultDTO) -> Vec<(&str, &str)> {
+        page.objects.iter().map(|object| (object.key.as_str(), object.id.as_str())).collect()
+    }
+
+    #[test]
+    fn test_merge_pages() {
+        // Replicas of "b" are held by both nodes, objects with the same key are ordered by id
+        let first = || result(&[("a", "1"), ("b", "2"), ("c", "4")], &["d/"], false);
+        let second = || result(&[("b", "2"), ("b", "3"), ("e", "5")], &["d/"], false);
+
+        let page = merge_pages(vec![first(), second()], None);
+        assert_eq!(merged_keys(&page), vec![("a", "1"), ("b", "2"), ("b", "3"), ("c", "4"), ("e", "5")]);
+        assert_eq!(page.common_prefixes, vec!["d/"]);
+        assert!(page.next_cursor.is_none());
+
+        // The cursor points to the last entry, a common prefix counts as one
+        let page = merge_pages(vec![first(), second()], Some(3));
+        assert_eq!(merged_keys(&page), vec![("a", "1"), ("b", "2"), ("b", "3")]);
+        assert_eq!(
+            page.next_cursor.as_deref().and_then(decode_cursor),
+            Some(ObjectCursor { key: "b".to_string(), id: Some("3".to_string()) })
+        );
+        let page = merge_pages(vec![first(), second()], Some(5));
+        assert_eq!(page.common_prefixes, vec!["d/"]);
+        assert_eq!(page.next_cursor.as_deref().and_then(decode_cursor), Some(skip_prefix("d/")));
+
+        // A node with more entries than merged keeps the listing going
+        let page = merge_pages(vec![result(&[("a", "1")], &[], true), result(&[], &[], false)], Some(1));
+        assert_eq!(merged_keys(&page), vec![("a", "1")]);
+        assert!(page.next_cursor.is_some());
+
+        let page = merge_pages(vec![], None);
+        assert!(page.objects.is_empty() && page.next_cursor.is_none());
+    }
+}
@@ -1,3 +1,10 @@
 pub mod create;
 pub mod get;
 pub mod get_info;
+pub mod find;
+pub mod list;
+pub mod update;
+pub mod delete;
+pub mod gc;
+pub mod duplicate;
+pub mod presign;
@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::application::common::box_gateway::BoxReader;
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::object_gateway::ObjectReader;
+use crate::application::common::signer::Signer;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::object::ObjectId;
+use crate::domain::models::presign::{PresignedAction, PresignedGrant};
+use crate::domain::models::r#box::BoxId;
+use crate::domain::services::access::AccessService;
+use crate::domain::services::presign::PresignService;
+
+/// Exactly one of `object_id` for a download and `box_id` for an upload is set
+#[derive(Debug, Deserialize)]
+pub struct PresignObjectDTO {
+    pub object_id: Option<ObjectId>,
+    pub box_id: Option<BoxId>,
+    /// Seconds, an hour when not set
+    pub expires_in: Option<u32>,
+    /// Limit of the upload request body in bytes
+    pub max_length: Option<u64>
+}
+
+#[derive(Debug, Serialize)]
+pub struct PresignObjectResultDTO {
+    pub method: String,
+    pub object_id: Option<ObjectId>,
+    pub box_id: BoxId,
+    pub expires_at: DateTime<Utc>,
+    pub max_length: Option<u64>,
+    #[serde(skip)]
+    pub grant: PresignedGrant,
+    #[serde(skip)]
+    pub signature: String
+}
+
+pub struct PresignObject<'a> {
+    pub object_reader: &'a dyn ObjectReader,
+    pub box_reader: &'a dyn BoxReader,
+    pub url_signer: &'a dyn Signer,
+    pub presign_service: &'a PresignService,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>
+}
+
+impl Interactor<PresignObjectDTO, PresignObjectResultDTO> for PresignObject<'_> {
+    async fn execute(&self, data: PresignObjectDTO) -> Result<PresignObjectResultDTO, ApplicationError> {
+
+        let (action, box_id) = match (data.object_id, data.box_id) {
+            (Some(object_id), None) => {
+                let object = self.object_reader.get_object(&object_id).await.ok_or(
+                    ApplicationError::NotFound(ErrorContent::from("Object not found"))
+                )?;
+                (PresignedAction::GetObject(object.id), object.box_id)
+            },
+            (None, Some(box_id)) => {
+                let r#box = self.box_reader.get_box(&box_id).await.ok_or(
+                    ApplicationError::NotFound(ErrorContent::from("Box not found"))
+                )?;
+                (PresignedAction::CreateObject, r#box.id)
+            },
+            _ => return Err(ApplicationError::InvalidData(
+                ErrorContent::from("Either object_id or box_id must be set")
+            ))
+        };
+
+        // The url is never given more rights than the issuer has
+        let access = match action {
+            PresignedAction::GetObject(_) => self.access_service.ensure_can_get_object(
+                self.id_provider.is_auth(),
+                &box_id,
+                &self.box_reader.get_box(&box_id).await
+                    .map(|r#box| r#box.access)
+                    .unwrap_or_default(),
+                self.id_provider.permissions()
+            ),
+            PresignedAction::CreateObject => self.access_service.ensure_can_create_object(
+                self.id_provider.is_auth(),
+                &box_id,
+                self.id_provider.permissions()
+            )
+        };
+        match access {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        let user_id = self.id_provider.user_id().ok_or(
+            ApplicationError::Unauthorized(ErrorContent::from(DomainError::AuthorizationRequired))
+        )?;
+        let grant = self.presign_service.create_grant(
+            action,
+            box_id,
+            user_id.clone(),
+            data.expires_in,
+            data.max_length,
+            &Utc::now()
+        ).map_err(|error| ApplicationError::InvalidData(ErrorContent::from(error)))?;
+        let signature = self.url_signer.sign(&self.presign_service.string_to_sign(&grant));
+
+        Ok(PresignObjectResultDTO {
+            method: self.presign_service.method(&grant.action).to_string(),
+            object_id: match &grant.action {
+                PresignedAction::GetObject(object_id) => Some(object_id.clone()),
+                PresignedAction::CreateObject => None
+            },
+            box_id: grant.box_id.clone(),
+            expires_at: grant.expires_at,
+            max_length: grant.max_length,
+            grant,
+            signature
+        })
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::box_gateway::BoxReader;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::object_gateway::ObjectGateway;
+use crate::application::object::duplicate::check_duplicate_name;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::object::ObjectId;
+use crate::domain::models::r#box::BoxId;
+use crate::domain::services::access::AccessService;
+use crate::domain::services::object::ObjectService;
+use crate::domain::services::validator::ValidatorService;
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateObjectDTO {
+    pub id: ObjectId,
+    pub name: Option<String>,
+    pub path: Option<String>,
+    pub metadata: HashMap<String, String>
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpdateObjectResultDTO {
+    pub id: ObjectId,
+    pub name: String,
+    pub path: Option<String>,
+    pub hash: String,
+    pub size: u64,
+    pub content_type: String,
+    pub metadata: HashMap<String, String>,
+    pub box_id: BoxId,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>
+}
+
+pub struct UpdateObject<'a> {
+    pub box_reader: &'a dyn BoxReader,
+    pub object_gateway: &'a dyn ObjectGateway,
+    pub object_service: &'a ObjectService,
+    pub validator: &'a ValidatorService,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<UpdateObjectDTO, UpdateObjectResultDTO> for UpdateObject<'_> {
+    async fn execute(&self, data: UpdateObjectDTO) -> Result<UpdateObjectResultDTO, ApplicationError> {
+
+        let mut validator_err_map: HashMap<String, String> = HashMap::new();
+
+        if let Some(name) = &data.name {
+            self.validator.validate_object_name(name).unwrap_or_else(|e| {
+                validator_err_map.insert("name".to_string(), e.to_string());
+            });
+        }
+
+        if let Some(path) = &data.path {
+            self.validator.validate_object_path(path).unwrap_or_else(|e| {
+                validator_err_map.insert("path".to_string(), e.to_string());
+            });
+        }
+
+        self.validator.validate_object_metadata(&data.metadata).unwrap_or_else(|e| {
+            validator_err_map.insert("metadata".to_string(), e.to_string());
+        });
+
+        if !validator_err_map.is_empty() {
+            return Err(
+                ApplicationError::InvalidData(ErrorContent::from(validator_err_map))
+            )
+        }
+
+        let object = self.object_gateway.get_object(&data.id).await.ok_or(
+            ApplicationError::NotFound(ErrorContent::from("Object not found"))
+        )?;
+
+        match self.access_service.ensure_can_update_object(
+            self.id_provider.is_auth(),
+            &object.box_id,
+            self.id_provider.permissions()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        let r#box = self.box_reader.get_box(&object.box_id).await.ok_or(
+            ApplicationError::NotFound(ErrorContent::from("Box not found"))
+        )?;
+
+        let warnings = check_duplicate_name(
+            self.object_gateway,
+            &r#box,
+            data.path.as_deref(),
+            data.name.as_deref(),
+            Some(&object.id)
+        ).await?;
+
+        let object = self.object_service.update_object(
+            object,
+            data.name,
+            data.path,
+            data.metadata
+        );
+
+        self.object_gateway.save_object(&object).await;
+
+        Ok(UpdateObjectResultDTO {
+            id: object.id.clone(),
+            name: object.name.unwrap_or(object.id),
+            path: object.path,
+            hash: object.hash,
+            size: object.size,
+            content_type: object.content_type,
+            metadata: object.metadata,
+            box_id: object.box_id,
+            created_at: object.created_at,
+            updated_at: object.updated_at,
+            warnings
+        })
+    }
+}
@@ -10,6 +10,7 @@ use crate::application::common::interactor::Interactor;
 use crate::application::common::permission_gateway::PermissionReader;
 use crate::application::common::session_gateway::SessionWriter;
 use crate::application::common::user_gateway::UserReader;
+use crate::domain::models::session::SessionId;
 use crate::domain::models::user::UserId;
 use crate::domain::services::access::AccessService;
 use crate::domain::services::session::SessionService;
@@ -19,11 +20,17 @@ use crate::domain::services::validator::ValidatorService;
 pub struct CreateSessionDTO {
     pub username: String,
     pub password: String,
+    /// Filled from the request, not from the body
+    #[serde(skip)]
+    pub ip: Option<String>,
+    #[serde(skip)]
+    pub user_agent: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct CreateSessionResultDTO{
-    token: String,
+    pub token: String,
+    id: SessionId,
     user_id: UserId,
     permissions: Vec<String>,
     expires_at: DateTime<Utc>
@@ -35,6 +42,7 @@ pub struct CreateSession<'a> {
     pub user_reader: &'a dyn UserReader,
     pub permission_reader: &'a dyn PermissionReader,
     pub password_hasher: &'a dyn Hasher,
+    pub session_hasher: &'a dyn Hasher,
     pub validator: &'a ValidatorService,
     pub access_service: &'a AccessService,
     pub id_provider: Box<dyn IdProvider>,
@@ -95,15 +103,21 @@ impl Interactor<CreateSessionDTO, CreateSessionResultDTO> for CreateSession<'_>
             &user.id
         ).await;
 
+        // Only the hash is stored, the token is returned to the client once
+        let token = self.session_service.generate_token();
         let session = self.session_service.create_session(
+            self.session_hasher.hash(&token).await,
             user.id.clone(),
-            permissions.iter().map(|p| p.tag.clone()).collect()
+            permissions.iter().map(|p| p.tag.clone()).collect(),
+            self.session_service.parse_origin(data.ip, data.user_agent.as_deref()),
+            &Utc::now()
         );
 
         self.session_writer.save_session(&session).await;
 
         Ok(CreateSessionResultDTO {
-            token: session.token,
+            token,
+            id: session.id,
             user_id: user.id,
             permissions: permissions.iter().map(|p| p.tag.to_string()).collect(),
             expires_at: session.expires_at
@@ -0,0 +1,65 @@
+use serde::Deserialize;
+
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::session_gateway::SessionGateway;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::session::SessionId;
+use crate::domain::services::access::AccessService;
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteSessionDTO {
+    pub id: SessionId
+}
+
+/// Revoke a session, the token is refused from the next request on
+///
+/// Own sessions are revoked by any user, sessions of others require `DeleteSession`.
+pub struct DeleteSession<'a> {
+    pub session_gateway: &'a dyn SessionGateway,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<DeleteSessionDTO, ()> for DeleteSession<'_> {
+    async fn execute(&self, data: DeleteSessionDTO) -> Result<(), ApplicationError> {
+
+        match self.access_service.ensure_can_manage_self_sessions(
+            self.id_provider.is_auth()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        let session = match self.session_gateway.get_session_by_id(&data.id).await {
+            Some(session) => session,
+            None => return Err(
+                ApplicationError::NotFound(ErrorContent::from("Session not found"))
+            )
+        };
+
+        if Some(&session.user_id) != self.id_provider.user_id() {
+            // Sessions of others are not disclosed to users who can not revoke them
+            if self.access_service.ensure_can_delete_session(
+                self.id_provider.is_auth(),
+                self.id_provider.permissions()
+            ).is_err() {
+                return Err(
+                    ApplicationError::NotFound(ErrorContent::from("Session not found"))
+                )
+            }
+        }
+
+        self.session_gateway.remove_session(&session.id).await;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use serde::Serialize;
+
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::session_gateway::SessionRemover;
+use crate::domain::exceptions::DomainError;
+use crate::domain::services::access::AccessService;
+
+#[derive(Debug, Serialize)]
+pub struct DeleteAllSelfSessionsResultDTO {
+    pub removed: u64
+}
+
+/// Log out everywhere: revoke every session of the user, including the one of the request
+pub struct DeleteAllSelfSessions<'a> {
+    pub session_remover: &'a dyn SessionRemover,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<(), DeleteAllSelfSessionsResultDTO> for DeleteAllSelfSessions<'_> {
+    async fn execute(&self, _data: ()) -> Result<DeleteAllSelfSessionsResultDTO, ApplicationError> {
+
+        match self.access_service.ensure_can_manage_self_sessions(
+            self.id_provider.is_auth()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        let removed = self.session_remover.remove_user_sessions(
+            self.id_provider.user_id().unwrap()
+        ).await;
+
+        Ok(DeleteAllSelfSessionsResultDTO { removed })
+    }
+}
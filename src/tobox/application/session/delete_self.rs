@@ -0,0 +1,48 @@
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::hasher::Hasher;
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::session_gateway::SessionGateway;
+use crate::domain::exceptions::DomainError;
+use crate::domain::services::access::AccessService;
+
+/// Log out: revoke the session of the request
+pub struct DeleteSelfSession<'a> {
+    pub session_gateway: &'a dyn SessionGateway,
+    pub session_hasher: &'a dyn Hasher,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<(), ()> for DeleteSelfSession<'_> {
+    async fn execute(&self, _data: ()) -> Result<(), ApplicationError> {
+
+        match self.access_service.ensure_can_manage_self_sessions(
+            self.id_provider.is_auth()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        let token = match self.id_provider.token() {
+            Some(token) => token,
+            None => return Err(
+                ApplicationError::InvalidData(ErrorContent::from("The request is not made with a session"))
+            )
+        };
+
+        let token_hash = self.session_hasher.hash(token).await;
+        if let Some(session) = self.session_gateway.get_session(&token_hash).await {
+            self.session_gateway.remove_session(&session.id).await;
+        }
+
+        Ok(())
+    }
+}
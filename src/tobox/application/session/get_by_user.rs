@@ -0,0 +1,60 @@
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::session_gateway::SessionReader;
+use crate::application::common::user_gateway::UserReader;
+use crate::application::session::get_self::SessionItem;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::user::UserId;
+use crate::domain::services::access::AccessService;
+use crate::domain::services::session::SessionService;
+
+#[derive(Debug, Deserialize)]
+pub struct GetUserSessionsDTO {
+    pub user_id: UserId
+}
+
+pub type GetUserSessionsResultDTO = Vec<SessionItem>;
+
+pub struct GetUserSessions<'a> {
+    pub session_reader: &'a dyn SessionReader,
+    pub user_reader: &'a dyn UserReader,
+    pub session_service: &'a SessionService,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<GetUserSessionsDTO, GetUserSessionsResultDTO> for GetUserSessions<'_> {
+    async fn execute(&self, data: GetUserSessionsDTO) -> Result<GetUserSessionsResultDTO, ApplicationError> {
+
+        match self.access_service.ensure_can_get_sessions(
+            self.id_provider.is_auth(),
+            self.id_provider.permissions()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        if self.user_reader.get_user(&data.user_id).await.is_none() {
+            return Err(ApplicationError::NotFound(ErrorContent::from("User not found")))
+        }
+
+        let now = Utc::now();
+        let sessions = self.session_reader.get_user_sessions(&data.user_id).await;
+
+        Ok(sessions.into_iter()
+            .filter(|session| !self.session_service.is_expired(session, &now))
+            .map(|session| SessionItem::new(session, None))
+            .collect())
+    }
+}
@@ -0,0 +1,80 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::hasher::Hasher;
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::session_gateway::SessionReader;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::session::{Session, SessionId, SessionOrigin};
+use crate::domain::services::access::AccessService;
+use crate::domain::services::session::SessionService;
+
+#[derive(Debug, Serialize)]
+pub struct SessionItem {
+    pub id: SessionId,
+    #[serde(flatten)]
+    pub origin: SessionOrigin,
+    /// The session of the request
+    pub current: bool,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>
+}
+
+impl SessionItem {
+    /// * param current_token_hash: hash of the token of the request, if any
+    pub fn new(session: Session, current_token_hash: Option<&String>) -> Self {
+        Self {
+            current: current_token_hash == Some(&session.token_hash),
+            id: session.id,
+            origin: session.origin,
+            created_at: session.created_at,
+            expires_at: session.expires_at
+        }
+    }
+}
+
+pub type GetSelfSessionsResultDTO = Vec<SessionItem>;
+
+pub struct GetSelfSessions<'a> {
+    pub session_reader: &'a dyn SessionReader,
+    pub session_service: &'a SessionService,
+    pub session_hasher: &'a dyn Hasher,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<(), GetSelfSessionsResultDTO> for GetSelfSessions<'_> {
+    async fn execute(&self, _data: ()) -> Result<GetSelfSessionsResultDTO, ApplicationError> {
+
+        match self.access_service.ensure_can_manage_self_sessions(
+            self.id_provider.is_auth()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        // Requests signed by access keys have no token
+        let current_token_hash = match self.id_provider.token() {
+            Some(token) => Some(self.session_hasher.hash(token).await),
+            None => None
+        };
+        let now = Utc::now();
+        let sessions = self.session_reader.get_user_sessions(
+            self.id_provider.user_id().unwrap()
+        ).await;
+
+        Ok(sessions.into_iter()
+            .filter(|session| !self.session_service.is_expired(session, &now))
+            .map(|session| SessionItem::new(session, current_token_hash.as_ref()))
+            .collect())
+    }
+}
@@ -1 +1,7 @@
-pub mod create;
\ No newline at end of file
+pub mod create;
+pub mod sweep;
+pub mod get_self;
+pub mod get_by_user;
+pub mod delete;
+pub mod delete_self;
+pub mod delete_all_self;
@@ -0,0 +1,18 @@
+use chrono::Utc;
+
+use crate::application::common::exceptions::ApplicationError;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::session_gateway::SessionRemover;
+
+/// Remove expired sessions, run periodically in background
+///
+/// Expired sessions are refused anyway, the sweep only keeps the table from growing.
+pub struct SweepSessions<'a> {
+    pub session_remover: &'a dyn SessionRemover,
+}
+
+impl Interactor<(), u64> for SweepSessions<'_> {
+    async fn execute(&self, _data: ()) -> Result<u64, ApplicationError> {
+        Ok(self.session_remover.remove_expired_sessions(&Utc::now()).await)
+    }
+}
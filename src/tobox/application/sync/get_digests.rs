@@ -0,0 +1,63 @@
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::file_storage_manager::FileStorageReader;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::node_gateway::NodeReader;
+use crate::application::common::object_gateway::ObjectReader;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::node::NodeId;
+use crate::domain::models::scrub::BoxDigest;
+use crate::domain::services::access::AccessService;
+use crate::domain::services::placement::PlacementService;
+use crate::domain::services::scrub::ScrubService;
+
+pub struct GetDigestsDTO {
+    /// Node comparing its objects with this one
+    pub node_id: NodeId,
+    /// Cluster secret presented by the peer
+    pub secret: Option<String>
+}
+
+/// Box digests over the objects this node and the peer both own by the hash ring
+pub struct GetDigests<'a> {
+    pub object_reader: &'a dyn ObjectReader,
+    pub node_reader: &'a dyn NodeReader,
+    pub file_storage_reader: &'a dyn FileStorageReader,
+    pub placement_service: &'a PlacementService,
+    pub scrub_service: &'a ScrubService,
+    pub access_service: &'a AccessService,
+    pub cluster_secret: Option<&'a str>,
+    pub node_id: &'a NodeId,
+    /// Nodes holding a copy of every object, including this one
+    pub replication_factor: usize,
+}
+
+impl Interactor<GetDigestsDTO, Vec<BoxDigest>> for GetDigests<'_> {
+    async fn execute(&self, data: GetDigestsDTO) -> Result<Vec<BoxDigest>, ApplicationError> {
+        
+        match self.access_service.ensure_is_peer(data.secret.as_deref(), self.cluster_secret) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+        
+        let ring = self.placement_service.build_ring(self.placement_service.ring_members(
+            self.node_id,
+            self.file_storage_reader.available_space().await,
+            self.node_reader.get_nodes().await
+        ));
+        let objects = self.placement_service.shared_objects(
+            &ring,
+            self.object_reader.get_objects().await,
+            self.node_id,
+            &data.node_id,
+            self.replication_factor
+        );
+        Ok(self.scrub_service.box_digests(&objects))
+    }
+}
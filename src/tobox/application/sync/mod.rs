@@ -0,0 +1,8 @@
+pub mod replicate;
+pub mod check_blob;
+pub mod receive_blob;
+pub mod receive_object;
+pub mod rebalance;
+pub mod scrub;
+pub mod send_blob;
+pub mod get_digests;
@@ -0,0 +1,176 @@
+use crate::application::common::blob_lock::BlobLocks;
+use crate::application::common::box_gateway::BoxReader;
+use crate::application::common::exceptions::ApplicationError;
+use crate::application::common::file_storage_manager::{FileStorageReader, FileStorageRemover};
+use crate::application::common::interactor::Interactor;
+use crate::application::common::node_client::NodeClient;
+use crate::application::common::node_gateway::NodeReader;
+use crate::application::common::object_gateway::ObjectGateway;
+use crate::application::common::placement_gateway::PlacementGateway;
+use crate::application::object::gc::release_blob;
+use crate::domain::models::node::{Node, NodeId, NodeStatus};
+use crate::domain::models::placement::{PlacementMove, Ring};
+use crate::domain::models::replication::Replica;
+use crate::domain::services::placement::PlacementService;
+
+/// Moves processed by a single run
+const BATCH_SIZE: u64 = 100;
+
+/// Keep the objects of this node on their owners by the hash ring, run periodically in background
+///
+/// When the cluster membership or the weight of a node changes, the objects whose owners
+/// changed are queued and sent to the new owners. A move is removed only after every
+/// target has accepted the object, an interrupted run repeats it.
+pub struct RebalanceObjects<'a> {
+    pub placement_gateway: &'a dyn PlacementGateway,
+    pub object_gateway: &'a dyn ObjectGateway,
+    pub box_reader: &'a dyn BoxReader,
+    pub node_reader: &'a dyn NodeReader,
+    pub node_client: &'a dyn NodeClient,
+    pub file_storage_reader: &'a dyn FileStorageReader,
+    pub file_storage_remover: &'a dyn FileStorageRemover,
+    pub placement_service: &'a PlacementService,
+    pub blob_locks: &'a BlobLocks,
+    pub node_id: &'a NodeId,
+    /// Nodes holding a copy of every object, including this one
+    pub replication_factor: usize,
+    /// Blob bytes sent by a single run, no new transfer is started once it is spent
+    pub byte_budget: u64,
+}
+
+impl Interactor<(), usize> for RebalanceObjects<'_> {
+    async fn execute(&self, _data: ()) -> Result<usize, ApplicationError> {
+        let ring = self.update_ring().await;
+
+        let mut budget = self.byte_budget;
+        let mut done = 0;
+        for entry in self.placement_gateway.get_moves(BATCH_SIZE).await {
+            if budget == 0 {
+                log::debug!("Rebalancing budget is spent, the remaining moves wait for the next run");
+                break
+            }
+            if self.apply(entry, &ring, &mut budget).await {
+                done += 1;
+            }
+        }
+        Ok(done)
+    }
+}
+
+impl RebalanceObjects<'_> {
+
+    /// Ring of the current membership, a plan is made when it differs from the last one
+    async fn update_ring(&self) -> Ring {
+        let members = self.placement_service.ring_members(
+            self.node_id,
+            self.file_storage_reader.available_space().await,
+            self.node_reader.get_nodes().await
+        );
+        let previous = self.placement_gateway.get_ring_members().await;
+        let ring = self.placement_service.build_ring(members.clone());
+        if previous.as_ref() == Some(&members) {
+            return ring
+        }
+
+        // The first ring of the node has nothing to be compared with
+        if let Some(previous) = previous {
+            let objects: Vec<_> = self.object_gateway.get_objects().await
+                .into_iter()
+                .map(|object| object.id)
+                .collect();
+            let plan = self.placement_service.plan_rebalance(
+                &self.placement_service.build_ring(previous),
+                &ring,
+                self.node_id,
+                &objects,
+                self.replication_factor
+            );
+            log::info!("Cluster ring changed, {} of {} objects to move", plan.len(), objects.len());
+            // The plan is saved first: an interrupted run makes it again
+            // and the duplicates are accepted by peers without changes
+            for entry in &plan {
+                self.placement_gateway.save_move(entry).await;
+            }
+        }
+        self.placement_gateway.save_ring_members(&members).await;
+        ring
+    }
+
+    /// Send the object to the targets of the move, returns whether the move is finished
+    async fn apply(&self, entry: PlacementMove, ring: &Ring, budget: &mut u64) -> bool {
+        let object = match self.object_gateway.get_object(&entry.object_id).await {
+            Some(object) => object,
+            // Removed before it was moved
+            None => {
+                self.placement_gateway.remove_move(&entry.id).await;
+                return true
+            }
+        };
+        let r#box = match self.box_reader.get_box(&object.box_id).await {
+            Some(r#box) => r#box,
+            None => {
+                self.placement_gateway.remove_move(&entry.id).await;
+                return true
+            }
+        };
+
+        let mut pending = vec![];
+        let mut delivered = true;
+        for node_id in &entry.targets {
+            let node = match self.node_reader.get_node(node_id).await {
+                Some(node) if node.status == NodeStatus::Up && *budget > 0 => node,
+                Some(_) => {
+                    pending.push(node_id.clone());
+                    continue
+                }
+                // The node left the cluster, the next plan finds another owner
+                None => {
+                    delivered = false;
+                    continue
+                }
+            };
+            let replica = Replica { object: object.clone(), r#box: r#box.clone() };
+            match self.push(&node, replica).await {
+                Ok(sent) => *budget = budget.saturating_sub(sent),
+                Err(error) => {
+                    log::warn!("Object {} move to node {} failed: {}", object.id, node.id, error);
+                    pending.push(node_id.clone());
+                }
+            }
+        }
+
+        if !pending.is_empty() {
+            self.placement_gateway.save_move(&PlacementMove { targets: pending, ..entry }).await;
+            return false
+        }
+        self.placement_gateway.remove_move(&entry.id).await;
+
+        // The ring may have changed again since the plan was made
+        let owners = self.placement_service.owners(ring, &object.id, self.replication_factor);
+        if entry.release && delivered && !owners.contains(self.node_id) {
+            self.object_gateway.remove_object(&object.id).await;
+            release_blob(
+                self.object_gateway,
+                self.file_storage_remover,
+                self.blob_locks,
+                &object.hash
+            ).await;
+            log::debug!("Object {} moved out of this node", object.id);
+        }
+        true
+    }
+
+    /// Returns the blob bytes sent, nothing when the node already has the blob
+    async fn push(&self, node: &Node, replica: Replica) -> Result<u64, String> {
+        let mut sent = 0;
+        if !self.node_client.has_blob(&node.address, &replica.object.hash).await? {
+            let content = self.file_storage_reader.read_file(&replica.object.hash).await.map_err(
+                |_| "Blob is not readable".to_string()
+            )?;
+            self.node_client.push_blob(&node.address, &replica.object.hash, content).await?;
+            sent = replica.object.size;
+        }
+        self.node_client.push_object(&node.address, &replica).await?;
+        Ok(sent)
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::file_storage_manager::FileStorageManager;
+use crate::application::common::interactor::Interactor;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::file_stream::FileStream;
+use crate::domain::services::access::AccessService;
+use crate::domain::services::object::ObjectService;
+use crate::domain::services::validator::ValidatorService;
+
+pub struct ReceiveBlobDTO {
+    pub hash: String,
+    /// Cluster secret presented by the peer
+    pub secret: Option<String>,
+    pub file: Box<dyn FileStream>
+}
+
+/// Store a blob pushed by a peer
+///
+/// Blobs are addressed by content, so a blob already stored is accepted
+/// without reading the content again.
+pub struct ReceiveBlob<'a> {
+    pub file_storage: &'a dyn FileStorageManager,
+    pub object_service: &'a ObjectService,
+    pub validator: &'a ValidatorService,
+    pub access_service: &'a AccessService,
+    pub cluster_secret: Option<&'a str>,
+}
+
+impl Interactor<ReceiveBlobDTO, ()> for ReceiveBlob<'_> {
+    async fn execute(&self, data: ReceiveBlobDTO) -> Result<(), ApplicationError> {
+        
+        match self.access_service.ensure_is_peer(data.secret.as_deref(), self.cluster_secret) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+        
+        let mut validator_err_map: HashMap<String, String> = HashMap::new();
+        self.validator.validate_blob_hash(&data.hash).unwrap_or_else(|e| {
+            validator_err_map.insert("hash".to_string(), e.to_string());
+        });
+        if !validator_err_map.is_empty() {
+            return Err(
+                ApplicationError::InvalidData(ErrorContent::from(validator_err_map))
+            )
+        }
+        
+        if self.file_storage.is_file_exists(&data.hash).await {
+            return Ok(())
+        }
+        
+        let filename = self.object_service.generate_object_id();
+        let mut file = data.file;
+        let file_info = self.file_storage.save_file(
+            &filename,
+            None,
+            None,
+            file.as_mut()
+        ).await?;
+        
+        if file_info.hash != data.hash {
+            self.file_storage.discard_file(&filename).await;
+            validator_err_map.insert(
+                "file".to_string(),
+                "Content hash does not match the expected one".to_string()
+            );
+            return Err(
+                ApplicationError::InvalidData(ErrorContent::from(validator_err_map))
+            )
+        }
+        
+        self.file_storage.commit_file(&filename, &file_info.hash).await?;
+        
+        Ok(())
+    }
+}
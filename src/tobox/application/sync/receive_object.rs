@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use crate::application::common::blob_lock::BlobLocks;
+use crate::application::common::box_gateway::BoxGateway;
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::file_storage_manager::FileStorageReader;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::object_gateway::ObjectGateway;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::replication::Replica;
+use crate::domain::services::access::AccessService;
+use crate::domain::services::validator::ValidatorService;
+
+pub struct ReceiveObjectDTO {
+    pub replica: Replica,
+    /// Cluster secret presented by the peer
+    pub secret: Option<String>
+}
+
+/// Register an object pushed by a peer, its blob is pushed before
+///
+/// Receiving the same object again changes nothing, a newer version
+/// of the object replaces the stored one.
+pub struct ReceiveObject<'a> {
+    pub object_gateway: &'a dyn ObjectGateway,
+    pub box_gateway: &'a dyn BoxGateway,
+    pub file_storage_reader: &'a dyn FileStorageReader,
+    pub validator: &'a ValidatorService,
+    pub access_service: &'a AccessService,
+    pub blob_locks: &'a BlobLocks,
+    pub cluster_secret: Option<&'a str>,
+}
+
+impl Interactor<ReceiveObjectDTO, ()> for ReceiveObject<'_> {
+    async fn execute(&self, data: ReceiveObjectDTO) -> Result<(), ApplicationError> {
+        
+        match self.access_service.ensure_is_peer(data.secret.as_deref(), self.cluster_secret) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+        
+        let Replica { object, r#box } = data.replica;
+        
+        let mut validator_err_map: HashMap<String, String> = HashMap::new();
+        self.validator.validate_blob_hash(&object.hash).unwrap_or_else(|e| {
+            validator_err_map.insert("hash".to_string(), e.to_string());
+        });
+        self.validator.validate_box_id(&r#box.id).unwrap_or_else(|e| {
+            validator_err_map.insert("box_id".to_string(), e.to_string());
+        });
+        if object.box_id != r#box.id {
+            validator_err_map.insert("box_id".to_string(), "Box does not match".to_string());
+        }
+        if !validator_err_map.is_empty() {
+            return Err(
+                ApplicationError::InvalidData(ErrorContent::from(validator_err_map))
+            )
+        }
+        
+        // The blob must not be released between the check and the save
+        let _guard = self.blob_locks.lock(&object.hash).await;
+        if !self.file_storage_reader.is_file_exists(&object.hash).await {
+            return Err(
+                ApplicationError::Conflict(ErrorContent::from("Blob of the object is missing"))
+            )
+        }
+        
+        if let Some(stored) = self.object_gateway.get_object(&object.id).await {
+            if stored.updated_at >= object.updated_at {
+                return Ok(())
+            }
+        }
+        
+        if self.box_gateway.get_box(&r#box.id).await.is_none() {
+            self.box_gateway.save_box(&r#box).await;
+        }
+        
+        self.object_gateway.save_object(&object).await;
+        
+        Ok(())
+    }
+}
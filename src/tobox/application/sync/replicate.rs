@@ -0,0 +1,145 @@
+use chrono::Utc;
+
+use crate::application::common::box_gateway::BoxReader;
+use crate::application::common::exceptions::ApplicationError;
+use crate::application::common::file_storage_manager::FileStorageReader;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::node_client::NodeClient;
+use crate::application::common::node_gateway::NodeReader;
+use crate::application::common::object_gateway::ObjectReader;
+use crate::application::common::replication_gateway::ReplicationGateway;
+use crate::domain::models::node::{Node, NodeId, NodeStatus};
+use crate::domain::models::replication::{Replica, ReplicationTask};
+use crate::domain::services::placement::PlacementService;
+use crate::domain::services::replication::ReplicationService;
+
+/// Tasks processed by a single run
+const BATCH_SIZE: u64 = 100;
+
+/// Push newly created objects to peer nodes, run periodically in background
+///
+/// A task is removed only after the peer has accepted the object, so an interrupted
+/// run is repeated. The peer accepts the same object again without changes.
+///
+/// Copies are sent to the owners of the object by the hash ring. The node the object
+/// was uploaded to keeps its copy even when it is not an owner.
+pub struct ReplicateObjects<'a> {
+    pub replication_gateway: &'a dyn ReplicationGateway,
+    pub object_reader: &'a dyn ObjectReader,
+    pub box_reader: &'a dyn BoxReader,
+    pub node_reader: &'a dyn NodeReader,
+    pub node_client: &'a dyn NodeClient,
+    pub file_storage_reader: &'a dyn FileStorageReader,
+    pub placement_service: &'a PlacementService,
+    pub replication_service: &'a ReplicationService,
+    pub node_id: &'a NodeId,
+    /// Nodes holding a copy of every object, including this one
+    pub replication_factor: usize,
+}
+
+impl Interactor<(), usize> for ReplicateObjects<'_> {
+    async fn execute(&self, _data: ()) -> Result<usize, ApplicationError> {
+        let tasks = self.replication_gateway.get_due_tasks(&Utc::now(), BATCH_SIZE).await;
+        let count = tasks.len();
+        for task in tasks {
+            match &task.node_id {
+                None => self.assign_targets(task).await,
+                Some(node_id) => match self.node_reader.get_node(node_id).await {
+                    Some(node) => self.push(task, &node).await,
+                    // The node left the cluster
+                    None => self.replication_gateway.remove_task(&task.id).await
+                }
+            }
+        }
+        Ok(count)
+    }
+}
+
+impl ReplicateObjects<'_> {
+    
+    async fn assign_targets(&self, task: ReplicationTask) {
+        let copies = self.replication_factor.saturating_sub(1);
+        let nodes = self.node_reader.get_nodes().await;
+        let ring = self.placement_service.build_ring(self.placement_service.ring_members(
+            self.node_id,
+            self.file_storage_reader.available_space().await,
+            nodes.clone()
+        ));
+        let targets: Vec<Node> = self.placement_service
+            .owners(&ring, &task.object_id, self.replication_factor)
+            .into_iter()
+            .filter_map(|owner| nodes.iter().find(|node| node.id == owner).cloned())
+            .collect();
+        if targets.is_empty() && copies > 0 {
+            log::debug!("No nodes to replicate object {} to", task.object_id);
+            self.replication_gateway.save_task(
+                &self.replication_service.retry_later(task, &Utc::now())
+            ).await;
+            return
+        }
+        if targets.len() < copies {
+            log::warn!(
+                "Object {} is replicated to {} of {} nodes",
+                task.object_id,
+                targets.len(),
+                copies
+            );
+        }
+        // Split tasks are saved first: an interrupted run assigns targets again
+        // and the duplicates are accepted by peers without changes
+        for node in targets {
+            self.replication_gateway.save_task(
+                &self.replication_service.assign_task(&task, node.id)
+            ).await;
+        }
+        self.replication_gateway.remove_task(&task.id).await;
+    }
+    
+    async fn push(&self, task: ReplicationTask, node: &Node) {
+        if node.status == NodeStatus::Down {
+            self.replication_gateway.save_task(
+                &self.replication_service.retry_later(task, &Utc::now())
+            ).await;
+            return
+        }
+        
+        let object = match self.object_reader.get_object(&task.object_id).await {
+            Some(object) => object,
+            // Removed before it was replicated
+            None => return self.replication_gateway.remove_task(&task.id).await
+        };
+        let r#box = match self.box_reader.get_box(&object.box_id).await {
+            Some(r#box) => r#box,
+            None => return self.replication_gateway.remove_task(&task.id).await
+        };
+        
+        let result = async {
+            if !self.node_client.has_blob(&node.address, &object.hash).await? {
+                let content = self.file_storage_reader.read_file(&object.hash).await.map_err(
+                    |_| "Blob is not readable".to_string()
+                )?;
+                self.node_client.push_blob(&node.address, &object.hash, content).await?;
+            }
+            self.node_client.push_object(&node.address, &Replica { object, r#box }).await
+        }.await;
+        
+        match result {
+            Ok(()) => {
+                log::debug!("Object {} replicated to node {}", task.object_id, node.id);
+                self.replication_gateway.remove_task(&task.id).await;
+            },
+            Err(error) => {
+                log::warn!(
+                    "Object {} replication to node {} failed (attempt {}): {}",
+                    task.object_id,
+                    node.id,
+                    task.attempts + 1,
+                    error
+                );
+                self.replication_gateway.save_task(
+                    &self.replication_service.retry_later(task, &Utc::now())
+                ).await;
+            }
+        }
+    }
+}
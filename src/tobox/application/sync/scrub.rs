@@ -0,0 +1,148 @@
+use std::collections::BTreeSet;
+
+use chrono::Utc;
+
+use crate::application::common::blob_lock::BlobLocks;
+use crate::application::common::exceptions::ApplicationError;
+use crate::application::common::file_storage_manager::{BlobHealth, FileStorageManager};
+use crate::application::common::interactor::Interactor;
+use crate::application::common::node_client::NodeClient;
+use crate::application::common::node_gateway::NodeReader;
+use crate::application::common::object_gateway::ObjectReader;
+use crate::application::common::scrub_gateway::ScrubGateway;
+use crate::domain::models::node::{Node, NodeId, NodeStatus};
+use crate::domain::models::scrub::ScrubReport;
+use crate::domain::services::object::ObjectService;
+use crate::domain::services::placement::PlacementService;
+use crate::domain::services::scrub::ScrubService;
+
+/// Check the blobs of this node and compare its objects with peers, run periodically in background
+///
+/// Every blob referenced by an object is hashed again. A damaged blob is restored from
+/// the other copies or parity on this node, then from a peer holding the same content.
+/// Objects are compared with every peer by box digests over the objects both nodes own,
+/// divergences are reported only: a missing object may as well be a deleted one.
+pub struct ScrubBlobs<'a> {
+    pub object_reader: &'a dyn ObjectReader,
+    pub node_reader: &'a dyn NodeReader,
+    pub node_client: &'a dyn NodeClient,
+    pub file_storage: &'a dyn FileStorageManager,
+    pub scrub_gateway: &'a dyn ScrubGateway,
+    pub object_service: &'a ObjectService,
+    pub placement_service: &'a PlacementService,
+    pub scrub_service: &'a ScrubService,
+    pub blob_locks: &'a BlobLocks,
+    pub node_id: &'a NodeId,
+    /// Nodes holding a copy of every object, including this one
+    pub replication_factor: usize,
+}
+
+impl Interactor<(), ScrubReport> for ScrubBlobs<'_> {
+    async fn execute(&self, _data: ()) -> Result<ScrubReport, ApplicationError> {
+        let started_at = Utc::now();
+        let objects = self.object_reader.get_objects().await;
+        let peers: Vec<Node> = self.node_reader.get_nodes().await.into_iter()
+            .filter(|node| node.status == NodeStatus::Up && node.id != *self.node_id)
+            .collect();
+
+        let hashes: BTreeSet<&String> = objects.iter().map(|object| &object.hash).collect();
+        let mut report = ScrubReport {
+            blobs_checked: hashes.len() as u64,
+            blobs_healthy: 0,
+            blobs_repaired: 0,
+            blobs_restored: 0,
+            blobs_damaged: vec![],
+            divergences: vec![],
+            started_at,
+            finished_at: started_at,
+        };
+        for hash in hashes {
+            match self.file_storage.repair_file(hash).await {
+                BlobHealth::Healthy => report.blobs_healthy += 1,
+                BlobHealth::Repaired => {
+                    log::info!("Blob {} is repaired", hash);
+                    report.blobs_repaired += 1
+                },
+                BlobHealth::Corrupted | BlobHealth::Missing if self.restore(hash, &peers).await => {
+                    log::info!("Blob {} is restored from a peer", hash);
+                    report.blobs_restored += 1
+                },
+                BlobHealth::Corrupted | BlobHealth::Missing => {
+                    log::error!("Blob {} is damaged and no copy is found", hash);
+                    report.blobs_damaged.push(hash.clone())
+                }
+            }
+        }
+
+        let ring = self.placement_service.build_ring(self.placement_service.ring_members(
+            self.node_id,
+            self.file_storage.available_space().await,
+            self.node_reader.get_nodes().await
+        ));
+        for peer in &peers {
+            let remote = match self.node_client.get_digests(&peer.address, self.node_id).await {
+                Ok(remote) => remote,
+                Err(error) => {
+                    log::warn!("Digests of node {} are not received: {}", peer.id, error);
+                    continue
+                }
+            };
+            let local = self.scrub_service.box_digests(&self.placement_service.shared_objects(
+                &ring,
+                objects.clone(),
+                self.node_id,
+                &peer.id,
+                self.replication_factor
+            ));
+            let divergences = self.scrub_service.compare(&peer.id, &local, &remote);
+            if !divergences.is_empty() {
+                log::warn!("Objects of {} boxes differ on node {}", divergences.len(), peer.id);
+            }
+            report.divergences.extend(divergences);
+        }
+
+        report.finished_at = Utc::now();
+        self.scrub_gateway.save_report(&report).await;
+        Ok(report)
+    }
+}
+
+impl ScrubBlobs<'_> {
+
+    /// Fetch the blob from the first peer having a copy that matches the hash
+    async fn restore(&self, hash: &str, peers: &[Node]) -> bool {
+        for peer in peers {
+            let result: Result<bool, String> = async {
+                if !self.node_client.has_blob(&peer.address, hash).await? {
+                    return Ok(false)
+                }
+                let mut content = self.node_client.fetch_blob(&peer.address, hash).await?;
+                let filename = self.object_service.generate_object_id();
+                let file_info = self.file_storage.save_file(
+                    &filename,
+                    None,
+                    None,
+                    content.as_mut()
+                ).await.map_err(|_| "Blob is not received".to_string())?;
+                if file_info.hash != hash {
+                    self.file_storage.discard_file(&filename).await;
+                    return Err("Content hash does not match".to_string())
+                }
+                // The damaged blob would be taken for the same content
+                let _guard = self.blob_locks.lock(hash).await;
+                self.file_storage.remove_file(hash).await;
+                self.file_storage.commit_file(&filename, hash).await.map_err(
+                    |_| "Blob is not committed".to_string()
+                )?;
+                Ok(true)
+            }.await;
+
+            match result {
+                Ok(true) => return true,
+                Ok(false) => (),
+                Err(error) => log::warn!("Blob {} is not restored from node {}: {}", hash, peer.id, error)
+            }
+        }
+        false
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::file_storage_manager::FileStorageReader;
+use crate::application::common::interactor::Interactor;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::file_stream::FileStream;
+use crate::domain::services::access::AccessService;
+use crate::domain::services::validator::ValidatorService;
+
+pub struct SendBlobDTO {
+    pub hash: String,
+    /// Cluster secret presented by the peer
+    pub secret: Option<String>
+}
+
+/// Requested by a peer to restore a blob it has lost
+pub struct SendBlob<'a> {
+    pub file_storage_reader: &'a dyn FileStorageReader,
+    pub validator: &'a ValidatorService,
+    pub access_service: &'a AccessService,
+    pub cluster_secret: Option<&'a str>,
+}
+
+impl Interactor<SendBlobDTO, Box<dyn FileStream>> for SendBlob<'_> {
+    async fn execute(&self, data: SendBlobDTO) -> Result<Box<dyn FileStream>, ApplicationError> {
+        
+        match self.access_service.ensure_is_peer(data.secret.as_deref(), self.cluster_secret) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+        
+        let mut validator_err_map: HashMap<String, String> = HashMap::new();
+        self.validator.validate_blob_hash(&data.hash).unwrap_or_else(|e| {
+            validator_err_map.insert("hash".to_string(), e.to_string());
+        });
+        if !validator_err_map.is_empty() {
+            return Err(
+                ApplicationError::InvalidData(ErrorContent::from(validator_err_map))
+            )
+        }
+        
+        if !self.file_storage_reader.is_file_exists(&data.hash).await {
+            return Err(ApplicationError::NotFound(ErrorContent::from("Blob not found")))
+        }
+        
+        // A damaged copy fails the hash check of the receiver
+        Ok(self.file_storage_reader.read_file(&data.hash).await?)
+    }
+}
@@ -0,0 +1,42 @@
+use serde::Deserialize;
+
+use crate::application::common::exceptions::ApplicationError;
+use crate::application::common::file_storage_manager::FileStorageRemover;
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::upload_gateway::UploadGateway;
+use crate::application::upload::session::get_own_upload;
+use crate::domain::models::upload::UploadId;
+use crate::domain::services::access::AccessService;
+use crate::domain::services::upload::UploadService;
+
+#[derive(Debug, Deserialize)]
+pub struct AbortUploadDTO {
+    pub id: UploadId
+}
+
+pub struct AbortUpload<'a> {
+    pub upload_gateway: &'a dyn UploadGateway,
+    pub file_storage_remover: &'a dyn FileStorageRemover,
+    pub upload_service: &'a UploadService,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<AbortUploadDTO, ()> for AbortUpload<'_> {
+    async fn execute(&self, data: AbortUploadDTO) -> Result<(), ApplicationError> {
+
+        let upload = get_own_upload(
+            self.upload_gateway,
+            self.upload_service,
+            self.access_service,
+            self.id_provider.as_ref(),
+            &data.id
+        ).await?;
+
+        self.file_storage_remover.remove_parts(&upload.id).await;
+        self.upload_gateway.remove_upload(&upload.id).await;
+
+        Ok(())
+    }
+}
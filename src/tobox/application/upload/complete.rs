@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::application::common::blob_lock::BlobLocks;
+use crate::application::common::box_gateway::BoxReader;
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::file_storage_manager::{
+    FileStorageRemover,
+    FileStorageWriter
+};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::object_gateway::ObjectGateway;
+use crate::application::common::replication_gateway::ReplicationWriter;
+use crate::application::common::upload_gateway::UploadGateway;
+use crate::application::object::create::CreateObjectResultDTO;
+use crate::application::object::duplicate::check_duplicate_name;
+use crate::application::upload::session::get_own_upload;
+use crate::domain::models::upload::{UploadId, UploadPart};
+use crate::domain::services::access::AccessService;
+use crate::domain::services::object::ObjectService;
+use crate::domain::services::replication::ReplicationService;
+use crate::domain::services::upload::UploadService;
+
+#[derive(Debug, Deserialize)]
+pub struct CompletePart {
+    pub number: u32,
+    pub hash: String
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompleteUploadDTO {
+    pub id: UploadId,
+    /// Parts to assemble in ascending order, all uploaded parts when not set
+    pub parts: Option<Vec<CompletePart>>
+}
+
+pub struct CompleteUpload<'a> {
+    pub box_reader: &'a dyn BoxReader,
+    pub upload_gateway: &'a dyn UploadGateway,
+    pub object_gateway: &'a dyn ObjectGateway,
+    pub replication_writer: &'a dyn ReplicationWriter,
+    pub file_storage_writer: &'a dyn FileStorageWriter,
+    pub file_storage_remover: &'a dyn FileStorageRemover,
+    pub upload_service: &'a UploadService,
+    pub object_service: &'a ObjectService,
+    pub replication_service: &'a ReplicationService,
+    pub access_service: &'a AccessService,
+    pub blob_locks: &'a BlobLocks,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<CompleteUploadDTO, CreateObjectResultDTO> for CompleteUpload<'_> {
+    async fn execute(&self, data: CompleteUploadDTO) -> Result<CreateObjectResultDTO, ApplicationError> {
+
+        let upload = get_own_upload(
+            self.upload_gateway,
+            self.upload_service,
+            self.access_service,
+            self.id_provider.as_ref(),
+            &data.id
+        ).await?;
+
+        let mut validator_err_map: HashMap<String, String> = HashMap::new();
+
+        let r#box = match self.box_reader.get_box(&upload.box_id).await {
+            Some(r#box) => r#box,
+            None => {
+                validator_err_map.insert("box_id".to_string(), "Box not found".to_string());
+                return Err(
+                    ApplicationError::InvalidData(ErrorContent::from(validator_err_map))
+                )
+            }
+        };
+
+        // Checked on completion, the name may have been taken since the upload started
+        let warnings = check_duplicate_name(
+            self.object_gateway,
+            &r#box,
+            upload.path.as_deref(),
+            upload.name.as_deref(),
+            None
+        ).await?;
+
+        let uploaded_parts = self.upload_gateway.get_upload_parts(&upload.id).await;
+        let numbers = match select_parts(&uploaded_parts, data.parts.as_deref()) {
+            Ok(numbers) => numbers,
+            Err(error) => {
+                validator_err_map.insert("parts".to_string(), error);
+                return Err(
+                    ApplicationError::InvalidData(ErrorContent::from(validator_err_map))
+                )
+            }
+        };
+
+        let object_id = match upload.object_id.clone() {
+            Some(id) if self.object_gateway.get_object(&id).await.is_some() => return Err(
+                ApplicationError::Conflict(ErrorContent::from("Object already exists"))
+            ),
+            Some(id) => id,
+            None => self.object_service.generate_object_id()
+        };
+
+        let file_info = self.file_storage_writer.assemble_parts(
+            &upload.id,
+            &numbers,
+            &object_id,
+            None,
+            None
+        ).await?;
+
+        let object = self.object_service.create_object(
+            object_id,
+            upload.name,
+            upload.path,
+            file_info.hash,
+            file_info.size,
+            file_info.content_type,
+            upload.metadata,
+            upload.box_id
+        );
+
+        // Same order as for a single stream upload: a crash before the object
+        // is saved leaves an unreferenced blob and an upload that expires
+        {
+            let _guard = self.blob_locks.lock(&object.hash).await;
+            self.file_storage_writer.commit_file(
+                &object.id,
+                &object.hash
+            ).await?;
+
+            self.object_gateway.save_object(&object).await;
+        }
+        
+        self.replication_writer.save_task(
+            &self.replication_service.create_task(object.id.clone())
+        ).await;
+
+        self.file_storage_remover.remove_parts(&upload.id).await;
+        self.upload_gateway.remove_upload(&upload.id).await;
+
+        Ok(CreateObjectResultDTO {
+            id: object.id.clone(),
+            name: object.name.unwrap_or(object.id),
+            path: object.path,
+            hash: object.hash,
+            size: object.size,
+            content_type: object.content_type,
+            metadata: object.metadata,
+            box_id: object.box_id,
+            created_at: object.created_at,
+            updated_at: None,
+            warnings
+        })
+    }
+}
+
+/// Resolve the part numbers to assemble, checking the requested parts against the uploaded ones
+fn select_parts(
+    uploaded: &[UploadPart],
+    requested: Option<&[CompletePart]>
+) -> Result<Vec<u32>, String> {
+    if uploaded.is_empty() {
+        return Err("No parts uploaded".to_string())
+    }
+
+    let requested = match requested {
+        Some(requested) if !requested.is_empty() => requested,
+        Some(_) => return Err("At least one part is required".to_string()),
+        None => return Ok(uploaded.iter().map(|part| part.number).collect())
+    };
+
+    let mut numbers = Vec::with_capacity(requested.len());
+    for part in requested {
+        if numbers.last().is_some_and(|last| *last >= part.number) {
+            return Err("Parts should be in ascending order".to_string())
+        }
+        match uploaded.iter().find(|uploaded| uploaded.number == part.number) {
+            Some(uploaded) if uploaded.hash.eq_ignore_ascii_case(part.hash.trim_matches('"')) => (),
+            Some(_) => return Err(format!("Part {} hash does not match", part.number)),
+            None => return Err(format!("Part {} is not uploaded", part.number))
+        }
+        numbers.push(part.number);
+    }
+    Ok(numbers)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn part(number: u32, hash: &str) -> UploadPart {
+        UploadPart {
+            upload_id: "upload".to_string(),
+            number,
+            hash: hash.to_string(),
+            size: 1,
+            created_at: Utc::now()
+        }
+    }
+
+    fn requested(number: u32, hash: &str) -> CompletePart {
+        CompletePart { number, hash: hash.to_string() }
+    }
+
+    #[test]
+    fn test_select_parts() {
+        let uploaded = vec![part(1, "aa"), part(2, "bb"), part(5, "cc")];
+
+        assert_eq!(select_parts(&uploaded, None), Ok(vec![1, 2, 5]));
+        assert_eq!(
+            select_parts(&uploaded, Some(&[requested(1, "AA"), requested(5, "\"cc\"")])),
+            Ok(vec![1, 5])
+        );
+        assert!(select_parts(&uploaded, Some(&[requested(2, "bb"), requested(1, "aa")])).is_err());
+        assert!(select_parts(&uploaded, Some(&[requested(1, "bb")])).is_err());
+        assert!(select_parts(&uploaded, Some(&[requested(3, "aa")])).is_err());
+        assert!(select_parts(&uploaded, Some(&[])).is_err());
+        assert!(select_parts(&[], None).is_err());
+    }
+}
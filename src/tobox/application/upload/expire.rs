@@ -0,0 +1,21 @@
+use chrono::Utc;
+
+use crate::application::common::file_storage_manager::FileStorageRemover;
+use crate::application::common::upload_gateway::UploadGateway;
+
+/// Remove uploads that were not completed in time, along with their parts
+///
+/// Parts are removed first: if interrupted, the upload is still found
+/// and removed next time.
+pub async fn remove_expired_uploads(
+    upload_gateway: &dyn UploadGateway,
+    file_storage_remover: &dyn FileStorageRemover
+) -> usize {
+    let uploads = upload_gateway.get_expired_uploads(&Utc::now()).await;
+    for upload in &uploads {
+        file_storage_remover.remove_parts(&upload.id).await;
+        upload_gateway.remove_upload(&upload.id).await;
+        log::debug!("Expired upload {} removed", upload.id);
+    }
+    uploads.len()
+}
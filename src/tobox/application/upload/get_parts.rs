@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::application::common::exceptions::ApplicationError;
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::upload_gateway::UploadReader;
+use crate::application::upload::session::get_own_upload;
+use crate::domain::models::upload::UploadId;
+use crate::domain::services::access::AccessService;
+use crate::domain::services::upload::UploadService;
+
+#[derive(Debug, Deserialize)]
+pub struct GetUploadPartsDTO {
+    pub id: UploadId
+}
+
+#[derive(Debug, Serialize)]
+pub struct UploadPartItem {
+    pub number: u32,
+    pub hash: String,
+    pub size: u64,
+    pub created_at: DateTime<Utc>
+}
+
+/// Parts ordered by number
+pub type GetUploadPartsResultDTO = Vec<UploadPartItem>;
+
+pub struct GetUploadParts<'a> {
+    pub upload_reader: &'a dyn UploadReader,
+    pub upload_service: &'a UploadService,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<GetUploadPartsDTO, GetUploadPartsResultDTO> for GetUploadParts<'_> {
+    async fn execute(&self, data: GetUploadPartsDTO) -> Result<GetUploadPartsResultDTO, ApplicationError> {
+
+        let upload = get_own_upload(
+            self.upload_reader,
+            self.upload_service,
+            self.access_service,
+            self.id_provider.as_ref(),
+            &data.id
+        ).await?;
+
+        Ok(self.upload_reader.get_upload_parts(&upload.id).await.into_iter().map(|part| UploadPartItem {
+            number: part.number,
+            hash: part.hash,
+            size: part.size,
+            created_at: part.created_at
+        }).collect())
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::application::common::box_gateway::BoxReader;
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::file_storage_manager::FileStorageRemover;
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::upload_gateway::UploadGateway;
+use crate::application::upload::expire::remove_expired_uploads;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::object::ObjectId;
+use crate::domain::models::r#box::BoxId;
+use crate::domain::models::upload::UploadId;
+use crate::domain::services::access::AccessService;
+use crate::domain::services::upload::UploadService;
+use crate::domain::services::validator::ValidatorService;
+
+#[derive(Debug, Deserialize)]
+pub struct InitiateUploadDTO {
+    pub box_id: BoxId,
+    pub name: Option<String>,
+    pub path: Option<String>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    /// Chosen by an intermediate node, never by the client
+    #[serde(skip)]
+    pub object_id: Option<ObjectId>
+}
+
+#[derive(Debug, Serialize)]
+pub struct InitiateUploadResultDTO {
+    pub id: UploadId,
+    pub box_id: BoxId,
+    pub name: Option<String>,
+    pub path: Option<String>,
+    pub expires_at: DateTime<Utc>
+}
+
+pub struct InitiateUpload<'a> {
+    pub box_reader: &'a dyn BoxReader,
+    pub upload_gateway: &'a dyn UploadGateway,
+    pub file_storage_remover: &'a dyn FileStorageRemover,
+    pub upload_service: &'a UploadService,
+    pub validator: &'a ValidatorService,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<InitiateUploadDTO, InitiateUploadResultDTO> for InitiateUpload<'_> {
+    async fn execute(&self, data: InitiateUploadDTO) -> Result<InitiateUploadResultDTO, ApplicationError> {
+
+        let mut validator_err_map: HashMap<String, String> = HashMap::new();
+
+        if let Some(name) = &data.name {
+            self.validator.validate_object_name(name).unwrap_or_else(|e| {
+                validator_err_map.insert("name".to_string(), e.to_string());
+            });
+        }
+
+        if let Some(path) = &data.path {
+            self.validator.validate_object_path(path).unwrap_or_else(|e| {
+                validator_err_map.insert("path".to_string(), e.to_string());
+            });
+        }
+
+        self.validator.validate_object_metadata(&data.metadata).unwrap_or_else(|e| {
+            validator_err_map.insert("metadata".to_string(), e.to_string());
+        });
+
+        if !validator_err_map.is_empty() {
+            return Err(
+                ApplicationError::InvalidData(ErrorContent::from(validator_err_map))
+            )
+        }
+
+        let r#box = match self.box_reader.get_box(&data.box_id).await {
+            Some(r#box) => r#box,
+            None => {
+                validator_err_map.insert("box_id".to_string(), "Box not found".to_string());
+                return Err(
+                    ApplicationError::InvalidData(ErrorContent::from(validator_err_map))
+                )
+            }
+        };
+
+        match self.access_service.ensure_can_create_object(
+            self.id_provider.is_auth(),
+            &r#box.id,
+            self.id_provider.permissions()
+        ) {
+            Ok(_) => (),
+            Err(error) => return match error {
+                DomainError::AccessDenied => Err(
+                    ApplicationError::Forbidden(ErrorContent::from(error))
+                ),
+                DomainError::AuthorizationRequired => Err(
+                    ApplicationError::Unauthorized(ErrorContent::from(error))
+                )
+            }
+        };
+
+        // Abandoned uploads are cleaned up as new ones are started
+        remove_expired_uploads(self.upload_gateway, self.file_storage_remover).await;
+
+        let upload = self.upload_service.create_upload(
+            r#box.id,
+            data.name,
+            data.path,
+            data.metadata,
+            self.id_provider.user_id().cloned(),
+            data.object_id
+        );
+
+        self.upload_gateway.save_upload(&upload).await;
+
+        Ok(InitiateUploadResultDTO {
+            id: upload.id,
+            box_id: upload.box_id,
+            name: upload.name,
+            path: upload.path,
+            expires_at: upload.expires_at
+        })
+    }
+}
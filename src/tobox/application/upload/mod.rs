@@ -0,0 +1,7 @@
+pub mod initiate;
+pub mod put_part;
+pub mod get_parts;
+pub mod complete;
+pub mod abort;
+pub mod expire;
+mod session;
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::file_storage_manager::FileStorageWriter;
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::upload_gateway::UploadGateway;
+use crate::application::upload::session::get_own_upload;
+use crate::domain::models::file_stream::FileStream;
+use crate::domain::models::upload::UploadId;
+use crate::domain::services::access::AccessService;
+use crate::domain::services::upload::UploadService;
+use crate::domain::services::validator::ValidatorService;
+
+pub struct PutUploadPartDTO {
+    pub upload_id: UploadId,
+    pub number: u32,
+    pub file: Box<dyn FileStream>
+}
+
+#[derive(Debug, Serialize)]
+pub struct PutUploadPartResultDTO {
+    pub number: u32,
+    pub hash: String,
+    pub size: u64
+}
+
+pub struct PutUploadPart<'a> {
+    pub upload_gateway: &'a dyn UploadGateway,
+    pub file_storage_writer: &'a dyn FileStorageWriter,
+    pub upload_service: &'a UploadService,
+    pub validator: &'a ValidatorService,
+    pub access_service: &'a AccessService,
+    pub id_provider: Box<dyn IdProvider>,
+}
+
+impl Interactor<PutUploadPartDTO, PutUploadPartResultDTO> for PutUploadPart<'_> {
+    async fn execute(&self, data: PutUploadPartDTO) -> Result<PutUploadPartResultDTO, ApplicationError> {
+
+        let mut validator_err_map: HashMap<String, String> = HashMap::new();
+
+        self.validator.validate_upload_part_number(&data.number).unwrap_or_else(|e| {
+            validator_err_map.insert("number".to_string(), e.to_string());
+        });
+
+        if !validator_err_map.is_empty() {
+            return Err(
+                ApplicationError::InvalidData(ErrorContent::from(validator_err_map))
+            )
+        }
+
+        let upload = get_own_upload(
+            self.upload_gateway,
+            self.upload_service,
+            self.access_service,
+            self.id_provider.as_ref(),
+            &data.upload_id
+        ).await?;
+
+        let mut file = data.file;
+        let file_info = self.file_storage_writer.save_part(
+            &upload.id,
+            data.number,
+            file.as_mut()
+        ).await?;
+
+        let part = self.upload_service.create_part(
+            upload.id,
+            data.number,
+            file_info.hash,
+            file_info.size
+        );
+
+        self.upload_gateway.save_upload_part(&part).await;
+
+        Ok(PutUploadPartResultDTO {
+            number: part.number,
+            hash: part.hash,
+            size: part.size
+        })
+    }
+}
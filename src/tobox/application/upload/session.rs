@@ -0,0 +1,46 @@
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::upload_gateway::UploadReader;
+use crate::domain::exceptions::DomainError;
+use crate::domain::models::upload::{Upload, UploadId};
+use crate::domain::services::access::AccessService;
+use crate::domain::services::upload::UploadService;
+
+/// Get an upload that is not expired and can be continued by the requester
+///
+/// Only the one who initiated the upload can continue it, and only while
+/// objects can still be created in the box.
+pub async fn get_own_upload(
+    upload_reader: &dyn UploadReader,
+    upload_service: &UploadService,
+    access_service: &AccessService,
+    id_provider: &dyn IdProvider,
+    upload_id: &UploadId
+) -> Result<Upload, ApplicationError> {
+    let upload = match upload_reader.get_upload(upload_id).await {
+        Some(upload) if !upload_service.is_expired(&upload) => upload,
+        _ => return Err(ApplicationError::NotFound(ErrorContent::from("Upload not found")))
+    };
+
+    if upload.user_id.as_ref() != id_provider.user_id() {
+        return Err(ApplicationError::Forbidden(ErrorContent::from(DomainError::AccessDenied)))
+    }
+
+    match access_service.ensure_can_create_object(
+        id_provider.is_auth(),
+        &upload.box_id,
+        id_provider.permissions()
+    ) {
+        Ok(_) => (),
+        Err(error) => return match error {
+            DomainError::AccessDenied => Err(
+                ApplicationError::Forbidden(ErrorContent::from(error))
+            ),
+            DomainError::AuthorizationRequired => Err(
+                ApplicationError::Unauthorized(ErrorContent::from(error))
+            )
+        }
+    };
+
+    Ok(upload)
+}
@@ -9,6 +9,8 @@ use serde::{Deserialize, Serialize};
 #[serde(rename_all = "lowercase")]
 pub enum ClusterMode {
     Combined,
+    /// Reed-Solomon erasure coding with the given number of parity shards,
+    /// data survives the loss of that many volumes (nodes)
     Ec(u8)
 }
 
@@ -16,13 +18,28 @@ pub enum ClusterMode {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ClusterConfig {
     pub mode: ClusterMode,
-    pub nodes: Vec<String>
+    /// Addresses of seed nodes, registered once they answer a heartbeat
+    pub nodes: Vec<String>,
+    /// Nodes holding a copy of every object, including the node it was uploaded to,
+    /// 2 when not set
+    #[serde(default)]
+    pub replication_factor: Option<u8>,
+    /// Bytes per second sent to other nodes when objects are moved after a membership change,
+    /// 16 MiB when not set
+    #[serde(default)]
+    pub rebalance_rate: Option<u64>,
+    /// Shared by all nodes of the cluster to authenticate requests between them
+    #[serde(default)]
+    pub secret: Option<String>
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct DiskConfig {
     pub mode: ClusterMode,
     pub volumes: Vec<String>,
+    /// Copies of every blob in the combined mode, a copy on each volume when not set
+    #[serde(default)]
+    pub replicas: Option<u8>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -39,8 +56,17 @@ pub struct CredentialsConfig {
     pub hashed_password: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct S3Config {
+    /// Region expected in the credential scope of signed requests
+    pub region: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct NodeConfig {
+    /// Identity of the node in the cluster, generated on the first start
+    #[serde(default)]
+    pub id: Option<String>,
     pub host: String,
     pub port: u16,
     pub workers: Option<usize>,
@@ -48,7 +74,12 @@ pub struct NodeConfig {
     pub tls: Option<Tls>,
     pub disk: DiskConfig,
     pub cluster: Option<ClusterConfig>,
-    pub credentials: Option<CredentialsConfig>
+    pub credentials: Option<CredentialsConfig>,
+    /// S3-compatible api is served under `/s3` when set
+    pub s3: Option<S3Config>,
+    /// Path of the SQLite database file, `tobox.db` when not set
+    #[serde(default)]
+    pub database: Option<String>
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -74,6 +105,7 @@ impl Default for Config {
                 tls: None
             }),
             node: Some(NodeConfig {
+                id: None,
                 host: "127.0.0.1".to_string(),
                 port: 3030,
                 tls: None,
@@ -81,10 +113,13 @@ impl Default for Config {
                 is_intermediate: false,
                 disk: DiskConfig {
                     mode: ClusterMode::Combined,
-                    volumes: vec![]
+                    volumes: vec!["storage".to_string()],
+                    replicas: None
                 },
                 cluster: None,
-                credentials: None
+                credentials: None,
+                s3: None,
+                database: None
             })
         }
     }
@@ -0,0 +1,17 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::models::user::UserId;
+
+pub type AccessKeyId = String;
+
+/// Credentials of s3 clients
+///
+/// The secret is kept as is: request signatures are verified by recomputing them.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AccessKey {
+    pub id: AccessKeyId,
+    pub secret: String,
+    pub user_id: UserId,
+    pub created_at: DateTime<Utc>
+}
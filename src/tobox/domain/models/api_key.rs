@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::models::permission::PermissionTag;
+use crate::domain::models::user::UserId;
+
+pub type ApiKeyId = String;
+
+/// Long-lived credentials for scripts and services, sent as `Authorization: Bearer <key>`
+///
+/// Only the hash of the key is stored. A key acts with its permissions that the owner still has.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: ApiKeyId,
+    pub name: String,
+    pub key_hash: String,
+    pub user_id: UserId,
+    pub permissions: Vec<PermissionTag>,
+    pub created_at: DateTime<Utc>,
+    /// Never expires when not set
+    pub expires_at: Option<DateTime<Utc>>
+}
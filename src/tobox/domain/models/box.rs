@@ -3,8 +3,36 @@ use serde::{Deserialize, Serialize};
 
 pub type BoxId = String;
 
+/// What happens when an object gets the same path and name as another object in the box
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateNamePolicy {
+    #[default]
+    Allow,
+    /// The object is stored and the response carries a warning
+    Warn,
+    Reject
+}
+
+/// Who can read the objects of the box without permissions
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BoxAccessPolicy {
+    /// Objects are available by permissions only
+    #[default]
+    Private,
+    /// Anyone can get an object by its id
+    PublicRead,
+    /// Anyone can also list the objects of the box
+    PublicList
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Box {
     pub id: BoxId,
+    #[serde(default)]
+    pub duplicate_names: DuplicateNamePolicy,
+    #[serde(default)]
+    pub access: BoxAccessPolicy,
     pub created_at: DateTime<Utc>
 }
@@ -1,4 +1,8 @@
+use bytes::Bytes;
 use tokio::io;
 use tokio_stream::Stream;
 
-pub trait FileStream: Stream<Item = io::Result<u8>> + Unpin + Send {}
+/// Stream of file content split into chunks.
+///
+/// Chunk size is up to the implementation, an empty chunk is not an end of the stream.
+pub trait FileStream: Stream<Item = io::Result<Bytes>> + Unpin + Send {}
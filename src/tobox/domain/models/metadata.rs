@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::models::node::NodeId;
+use crate::domain::models::permission::{Permission, PermissionId};
+use crate::domain::models::r#box::{Box as BoxDomain, BoxId};
+use crate::domain::models::role::{Role, RoleId};
+use crate::domain::models::user::{User, UserId};
+
+/// Election round, a node follows the leader of the highest term it has seen
+pub type Term = u64;
+
+/// Position in the metadata log, starting at 1
+pub type LogIndex = u64;
+
+/// Change of the metadata shared by the cluster, applied by every node in log order
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MetadataCommand {
+    SaveUser { user: User },
+    RemoveUser { user_id: UserId },
+    SaveRole { role: Role },
+    RemoveRole { role_id: RoleId },
+    SetDefaultRole { role_id: RoleId },
+    LinkRoleUser { role_id: RoleId, user_id: UserId },
+    UnlinkRoleUser { role_id: RoleId, user_id: UserId },
+    SavePermissions { permissions: Vec<Permission> },
+    LinkRolePermissions { role_id: RoleId, permission_ids: Vec<PermissionId> },
+    UnlinkRolePermission { role_id: RoleId, permission_id: PermissionId },
+    SaveBox { r#box: BoxDomain },
+    RemoveBox { box_id: BoxId }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub index: LogIndex,
+    /// Term of the leader that appended the entry
+    pub term: Term,
+    pub command: MetadataCommand
+}
+
+/// Consensus state kept on disk, so a restarted node neither votes twice in a term
+/// nor applies entries again
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct HardState {
+    pub term: Term,
+    pub voted_for: Option<NodeId>,
+    /// Last entry applied to the local gateways
+    pub applied: LogIndex
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RaftRole {
+    Follower,
+    Candidate,
+    Leader
+}
+
+/// Consensus state of this node, rebuilt from the hard state on start
+#[derive(Clone, Debug)]
+pub struct RaftState {
+    pub node_id: NodeId,
+    pub hard_state: HardState,
+    pub role: RaftRole,
+    pub leader_id: Option<NodeId>,
+    /// Last entry stored by a majority of nodes
+    pub commit_index: LogIndex,
+    /// Nodes that voted for this node in the current term
+    pub votes: Vec<NodeId>,
+    /// Next entry to send to each peer, on the leader
+    pub next_index: HashMap<NodeId, LogIndex>,
+    /// Last entry known to be stored by each peer, on the leader
+    pub match_index: HashMap<NodeId, LogIndex>,
+    /// A follower starts an election when no leader is heard of until then
+    pub election_deadline: DateTime<Utc>
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VoteRequest {
+    pub term: Term,
+    pub candidate_id: NodeId,
+    pub last_log_index: LogIndex,
+    pub last_log_term: Term
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VoteResponse {
+    pub term: Term,
+    pub granted: bool
+}
+
+/// Sent by the leader with new entries, or without them as a heartbeat
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AppendRequest {
+    pub term: Term,
+    pub leader_id: NodeId,
+    /// Entry preceding the new ones, the follower must have it
+    pub prev_log_index: LogIndex,
+    pub prev_log_term: Term,
+    pub entries: Vec<LogEntry>,
+    pub leader_commit: LogIndex
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AppendResponse {
+    pub term: Term,
+    pub success: bool,
+    /// Last entry matching the leader log on success,
+    /// otherwise the last entry of the follower, a hint where to continue from
+    pub match_index: LogIndex
+}
+
+/// Position of an entry appended by the leader,
+/// the change is made once an entry with the same term is applied at the index
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Proposal {
+    pub index: LogIndex,
+    pub term: Term
+}
@@ -6,4 +6,13 @@ pub mod role;
 pub mod session;
 pub mod file_stream;
 pub mod file_info;
-mod id;
\ No newline at end of file
+pub mod access_key;
+pub mod api_key;
+pub mod upload;
+pub mod node;
+pub mod replication;
+pub mod placement;
+pub mod scrub;
+pub mod metadata;
+pub mod presign;
+mod id;
@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub type NodeId = String;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeStatus {
+    Up,
+    /// Missed recent heartbeats, but may still be reachable
+    Suspect,
+    Down
+}
+
+/// Peer node registered in the cluster
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Node {
+    pub id: NodeId,
+    /// Base url of the node, e.g. `http://10.0.0.2:3030`
+    pub address: String,
+    pub version: String,
+    /// Free space of the node file storage in bytes
+    pub capacity: u64,
+    pub status: NodeStatus,
+    pub last_heartbeat: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>
+}
+
+/// What a node reports about itself
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NodeInfo {
+    pub id: NodeId,
+    pub version: String,
+    pub capacity: u64
+}
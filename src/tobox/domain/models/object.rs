@@ -5,10 +5,15 @@ use crate::domain::models::r#box::BoxId;
 
 pub type ObjectId = String;
 
+/// Separator of path segments and of the path and the name in a key
+pub const PATH_DELIMITER: char = '/';
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Object {
     pub id: ObjectId,
     pub name: Option<String>,
+    /// Virtual directory of the object, segments are separated by `/`
+    pub path: Option<String>,
     pub hash: String,
     pub size: u64,
     pub content_type: String,
@@ -17,3 +22,22 @@ pub struct Object {
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>
 }
+
+impl Object {
+    /// Path joined with the name, objects without a name are addressed by id
+    pub fn key(&self) -> String {
+        match (&self.path, &self.name) {
+            (Some(path), Some(name)) => format!("{}{}{}", path, PATH_DELIMITER, name),
+            (None, Some(name)) => name.clone(),
+            (_, None) => self.id.clone()
+        }
+    }
+}
+
+/// Split a key into the path and the name
+pub fn split_key(key: &str) -> (Option<&str>, &str) {
+    match key.rsplit_once(PATH_DELIMITER) {
+        Some((path, name)) => (Some(path), name),
+        None => (None, key)
+    }
+}
@@ -1,19 +1,22 @@
+use serde::{Deserialize, Serialize};
 use strum_macros::Display;
 use crate::domain::models::r#box::BoxId;
 
 pub type PermissionId = String;
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Permission {
     pub id: PermissionId,
     pub tag: PermissionTag,
 }
 
-#[derive(Display, Debug, Clone)]
+#[derive(Display, Debug, Clone, Serialize, Deserialize)]
 pub enum PermissionTag {
     GetUser,
     CreateUser,
     DeleteUser,
     
+    GetSession,
     CreateSession,
     DeleteSession,
     
@@ -30,8 +33,11 @@ pub enum PermissionTag {
     
     GetBox,
     CreateBox,
+    UpdateBox,
     DeleteBox,
     
+    #[strum(serialize = "UpdateSpecificBox({0})")]
+    UpdateSpecificBox(BoxId),
     #[strum(serialize = "DeleteSpecificBox({0})")]
     DeleteSpecificBox(BoxId),
     
@@ -47,5 +53,16 @@ pub enum PermissionTag {
     #[strum(serialize = "UpdateSpecificObject({0})")]
     UpdateSpecificObject(BoxId),
     #[strum(serialize = "DeleteSpecificObject({0})")]
-    DeleteSpecificObject(BoxId)
+    DeleteSpecificObject(BoxId),
+    
+    GetNode,
+    AddNode,
+    RemoveNode
+}
+
+impl PermissionTag {
+    /// Permissions of requests without a session
+    pub fn guest_tags() -> Vec<PermissionTag> {
+        vec![PermissionTag::CreateSession]
+    }
 }
@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::models::node::NodeId;
+use crate::domain::models::object::ObjectId;
+
+pub type PlacementMoveId = String;
+
+/// Node placed on the hash ring
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RingMember {
+    pub node_id: NodeId,
+    /// Virtual nodes of the member, derived from its free space
+    pub weight: u32
+}
+
+/// Consistent-hash ring: a key is owned by the first members met clockwise from its hash
+#[derive(Clone, Debug, Default)]
+pub struct Ring {
+    pub members: Vec<RingMember>,
+    /// Positions of the virtual nodes, in ascending order
+    pub points: Vec<(u64, NodeId)>
+}
+
+/// Entry of a rebalancing plan: an object of this node to be copied to its new owners
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlacementMove {
+    pub id: PlacementMoveId,
+    pub object_id: ObjectId,
+    /// Owners that have not received the object yet
+    pub targets: Vec<NodeId>,
+    /// This node no longer owns the object, the local copy is removed once all targets have it
+    pub release: bool,
+    pub created_at: DateTime<Utc>
+}
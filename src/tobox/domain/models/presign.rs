@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+
+use crate::domain::models::object::ObjectId;
+use crate::domain::models::r#box::BoxId;
+use crate::domain::models::user::UserId;
+
+/// The only request a presigned url can be used for
+#[derive(Clone, Debug, PartialEq)]
+pub enum PresignedAction {
+    /// Download of the object
+    GetObject(ObjectId),
+    /// Upload of a new object to the box
+    CreateObject
+}
+
+/// Scope of a presigned url, whoever has the url acts on behalf of the issuer within it
+#[derive(Clone, Debug, PartialEq)]
+pub struct PresignedGrant {
+    pub action: PresignedAction,
+    pub box_id: BoxId,
+    /// Issuer of the url
+    pub user_id: UserId,
+    /// Whole seconds, as the expiry is a part of the url
+    pub expires_at: DateTime<Utc>,
+    /// Limit of the request body in bytes, uploads only
+    pub max_length: Option<u64>
+}
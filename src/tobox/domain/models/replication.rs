@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::models::node::NodeId;
+use crate::domain::models::object::{Object, ObjectId};
+use crate::domain::models::r#box::Box as BoxDomain;
+
+pub type ReplicationTaskId = String;
+
+/// Entry of the outbound queue: an object to be pushed to a peer node
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReplicationTask {
+    pub id: ReplicationTaskId,
+    pub object_id: ObjectId,
+    /// Target nodes are chosen when the task is first processed,
+    /// the task is then split into a task per target
+    pub node_id: Option<NodeId>,
+    pub attempts: u32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>
+}
+
+/// Object metadata sent to a peer once the blob is there
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Replica {
+    pub object: Object,
+    /// Created on the peer when missing
+    pub r#box: BoxDomain
+}
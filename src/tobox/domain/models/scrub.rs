@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::models::node::NodeId;
+use crate::domain::models::r#box::BoxId;
+
+/// Merkle tree of the objects of a box: objects are split into buckets by their id,
+/// a bucket digest covers its objects and the root covers the buckets
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BoxDigest {
+    pub box_id: BoxId,
+    pub root: String,
+    pub buckets: Vec<String>
+}
+
+/// Objects of a box that differ between this node and a peer
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Divergence {
+    pub node_id: NodeId,
+    pub box_id: BoxId,
+    /// Buckets of the box digest that differ, all of them when the box is missing on one side
+    pub buckets: Vec<usize>
+}
+
+/// Result of the last scrub of this node
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScrubReport {
+    pub blobs_checked: u64,
+    pub blobs_healthy: u64,
+    /// Restored from other copies or parity on this node
+    pub blobs_repaired: u64,
+    /// Fetched again from a peer
+    pub blobs_restored: u64,
+    /// Hashes of blobs that could not be recovered
+    pub blobs_damaged: Vec<String>,
+    pub divergences: Vec<Divergence>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>
+}
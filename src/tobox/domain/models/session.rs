@@ -3,10 +3,31 @@ use serde::{Deserialize, Serialize};
 use crate::domain::models::permission::PermissionTag;
 use crate::domain::models::user::UserId;
 
+pub type SessionId = String;
+
+/// Session of a logged in user
+///
+/// Only the hash of the token is stored, the token itself is known to the client alone.
+/// Sessions are referred to by their id, which gives no access.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Session {
-    pub token: String,
+    pub id: SessionId,
+    pub token_hash: String,
     pub user_id: UserId,
     pub permissions: Vec<PermissionTag>,
+    pub origin: SessionOrigin,
+    pub created_at: DateTime<Utc>,
+    /// Moved forward while the session is in use
     pub expires_at: DateTime<Utc>
 }
+
+/// Where the session was created, shown to the user to recognize it
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionOrigin {
+    pub ip: Option<String>,
+    /// Browser or program, e.g. `Firefox` or `curl`
+    pub client: Option<String>,
+    pub os: Option<String>,
+    /// `Desktop`, `Mobile` or `Tablet`, known for browsers only
+    pub device: Option<String>
+}
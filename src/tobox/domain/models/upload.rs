@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::models::object::ObjectId;
+use crate::domain::models::r#box::BoxId;
+use crate::domain::models::user::UserId;
+
+pub type UploadId = String;
+
+/// Session of an object uploaded in parts
+///
+/// The object is created only when the session is completed.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Upload {
+    pub id: UploadId,
+    pub box_id: BoxId,
+    pub name: Option<String>,
+    pub path: Option<String>,
+    pub metadata: HashMap<String, String>,
+    pub user_id: Option<UserId>,
+    /// Id of the completed object when chosen by an intermediate node
+    pub object_id: Option<ObjectId>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UploadPart {
+    pub upload_id: UploadId,
+    pub number: u32,
+    pub hash: String,
+    pub size: u64,
+    pub created_at: DateTime<Utc>
+}
@@ -1,7 +1,7 @@
 
 use crate::domain::exceptions::DomainError;
 use crate::domain::models::permission::PermissionTag;
-use crate::domain::models::r#box::BoxId;
+use crate::domain::models::r#box::{BoxAccessPolicy, BoxId};
 
 pub struct AccessService {}
 
@@ -41,6 +41,28 @@ impl AccessService {
         Err(DomainError::AccessDenied)
     }
     
+    pub fn ensure_can_update_box(
+        &self,
+        is_auth: &bool,
+        box_id: &BoxId,
+        permissions: &Vec<String>
+    ) -> Result<(), DomainError> {
+        
+        if !is_auth {
+            return Err(DomainError::AuthorizationRequired)
+        }
+        
+        if permissions.contains(&PermissionTag::UpdateSpecificBox(box_id.clone()).to_string()) {
+            return Ok(())
+        }
+        
+        if permissions.contains(&PermissionTag::UpdateBox.to_string()) {
+            return Ok(())
+        }
+        
+        Err(DomainError::AccessDenied)
+    }
+    
     pub fn ensure_can_delete_box(
         &self,
         is_auth: &bool,
@@ -85,7 +107,39 @@ impl AccessService {
         Err(DomainError::AccessDenied)
     }
     
+    /// Objects of public boxes are available to anyone, guests included
     pub fn ensure_can_get_object(
+        &self,
+        is_auth: &bool,
+        box_id: &BoxId,
+        access: &BoxAccessPolicy,
+        permissions: &Vec<String>
+    ) -> Result<(), DomainError> {
+        
+        if *access != BoxAccessPolicy::Private {
+            return Ok(())
+        }
+        
+        self.ensure_can_get_private_object(is_auth, box_id, permissions)
+    }
+    
+    /// Only boxes with the `public_list` policy can be listed by anyone
+    pub fn ensure_can_list_objects(
+        &self,
+        is_auth: &bool,
+        box_id: &BoxId,
+        access: &BoxAccessPolicy,
+        permissions: &Vec<String>
+    ) -> Result<(), DomainError> {
+        
+        if *access == BoxAccessPolicy::PublicList {
+            return Ok(())
+        }
+        
+        self.ensure_can_get_private_object(is_auth, box_id, permissions)
+    }
+    
+    fn ensure_can_get_private_object(
         &self,
         is_auth: &bool,
         box_id: &BoxId,
@@ -206,6 +260,24 @@ impl AccessService {
         Err(DomainError::AccessDenied)
     }
 
+    pub fn ensure_can_get_sessions(
+        &self,
+        is_auth: &bool,
+        permissions: &Vec<String>
+    ) -> Result<(), DomainError> {
+        
+        if !is_auth {
+            return Err(DomainError::AuthorizationRequired)
+        }
+        
+        if permissions.contains(&PermissionTag::GetSession.to_string()) {
+            return Ok(())
+        }
+        
+        Err(DomainError::AccessDenied)
+    }
+    
+    /// Sessions of other users require `DeleteSession`
     pub fn ensure_can_delete_session(
         &self,
         is_auth: &bool,
@@ -236,6 +308,46 @@ impl AccessService {
         
         Err(DomainError::AccessDenied)
     }
+    
+    /// Own sessions are listed and revoked by the user, so only authorization is required
+    pub fn ensure_can_manage_self_sessions(
+        &self,
+        is_auth: &bool
+    ) -> Result<(), DomainError> {
+        
+        if !is_auth {
+            return Err(DomainError::AuthorizationRequired)
+        }
+        
+        Ok(())
+    }
+    
+    /// Access keys are managed by their owner, so only authorization is required
+    pub fn ensure_can_manage_access_keys(
+        &self,
+        is_auth: &bool
+    ) -> Result<(), DomainError> {
+        
+        if !is_auth {
+            return Err(DomainError::AuthorizationRequired)
+        }
+        
+        Ok(())
+    }
+    
+    /// Api keys are managed by their owner, so only authorization is required
+    pub fn ensure_can_manage_api_keys(
+        &self,
+        is_auth: &bool
+    ) -> Result<(), DomainError> {
+        
+        if !is_auth {
+            return Err(DomainError::AuthorizationRequired)
+        }
+        
+        Ok(())
+    }
+    
     pub fn ensure_can_create_role(
         &self,
         is_auth: &bool,
@@ -388,4 +500,136 @@ impl AccessService {
 
         Err(DomainError::AccessDenied)
     }
+
+    pub fn ensure_can_get_node(
+        &self,
+        is_auth: &bool,
+        permissions: &Vec<String>
+    ) -> Result<(), DomainError> {
+
+        if !is_auth {
+            return Err(DomainError::AuthorizationRequired)
+        }
+
+        if permissions.contains(&PermissionTag::GetNode.to_string()) {
+            return Ok(())
+        }
+
+        Err(DomainError::AccessDenied)
+    }
+
+    pub fn ensure_can_add_node(
+        &self,
+        is_auth: &bool,
+        permissions: &Vec<String>
+    ) -> Result<(), DomainError> {
+
+        if !is_auth {
+            return Err(DomainError::AuthorizationRequired)
+        }
+
+        if permissions.contains(&PermissionTag::AddNode.to_string()) {
+            return Ok(())
+        }
+
+        Err(DomainError::AccessDenied)
+    }
+
+    pub fn ensure_can_remove_node(
+        &self,
+        is_auth: &bool,
+        permissions: &Vec<String>
+    ) -> Result<(), DomainError> {
+
+        if !is_auth {
+            return Err(DomainError::AuthorizationRequired)
+        }
+
+        if permissions.contains(&PermissionTag::RemoveNode.to_string()) {
+            return Ok(())
+        }
+
+        Err(DomainError::AccessDenied)
+    }
+
+    /// Requests between cluster nodes carry the secret shared through the cluster config,
+    /// they are refused when no secret is configured
+    pub fn ensure_is_peer(
+        &self,
+        secret: Option<&str>,
+        cluster_secret: Option<&str>
+    ) -> Result<(), DomainError> {
+        
+        let cluster_secret = match cluster_secret {
+            Some(cluster_secret) => cluster_secret,
+            None => return Err(DomainError::AccessDenied)
+        };
+        
+        match secret {
+            None => Err(DomainError::AuthorizationRequired),
+            Some(secret) if constant_time_eq(secret.as_bytes(), cluster_secret.as_bytes()) => Ok(()),
+            Some(_) => Err(DomainError::AccessDenied)
+        }
+    }
+}
+
+/// Comparison time does not depend on the position of the first difference
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_box_access_policy() {
+        let service = AccessService {};
+        let box_id = "box".to_string();
+        let guest = PermissionTag::guest_tags().iter().map(|tag| tag.to_string()).collect();
+        
+        assert!(matches!(
+            service.ensure_can_get_object(&false, &box_id, &BoxAccessPolicy::Private, &guest),
+            Err(DomainError::AuthorizationRequired)
+        ));
+        assert!(service.ensure_can_get_object(&false, &box_id, &BoxAccessPolicy::PublicRead, &guest).is_ok());
+        assert!(service.ensure_can_get_object(&false, &box_id, &BoxAccessPolicy::PublicList, &guest).is_ok());
+        // A public box can be read by id, but not listed
+        assert!(matches!(
+            service.ensure_can_list_objects(&false, &box_id, &BoxAccessPolicy::PublicRead, &guest),
+            Err(DomainError::AuthorizationRequired)
+        ));
+        assert!(service.ensure_can_list_objects(&false, &box_id, &BoxAccessPolicy::PublicList, &guest).is_ok());
+        
+        let permissions = vec![PermissionTag::GetSpecificObject(box_id.clone()).to_string()];
+        assert!(service.ensure_can_list_objects(&true, &box_id, &BoxAccessPolicy::Private, &permissions).is_ok());
+        assert!(matches!(
+            service.ensure_can_list_objects(&true, &"other".to_string(), &BoxAccessPolicy::PublicRead, &permissions),
+            Err(DomainError::AccessDenied)
+        ));
+    }
+
+    #[test]
+    fn test_node_permissions() {
+        let service = AccessService {};
+        let permissions = vec![PermissionTag::GetNode.to_string()];
+
+        assert!(matches!(
+            service.ensure_can_get_node(&false, &permissions),
+            Err(DomainError::AuthorizationRequired)
+        ));
+        assert!(service.ensure_can_get_node(&true, &permissions).is_ok());
+        assert!(matches!(
+            service.ensure_can_add_node(&true, &permissions),
+            Err(DomainError::AccessDenied)
+        ));
+        assert!(matches!(
+            service.ensure_can_remove_node(&true, &permissions),
+            Err(DomainError::AccessDenied)
+        ));
+
+        let permissions = vec![PermissionTag::AddNode.to_string(), PermissionTag::RemoveNode.to_string()];
+        assert!(service.ensure_can_add_node(&true, &permissions).is_ok());
+        assert!(service.ensure_can_remove_node(&true, &permissions).is_ok());
+    }
 }
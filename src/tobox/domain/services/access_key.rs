@@ -0,0 +1,24 @@
+use chrono::Utc;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+
+use crate::domain::models::access_key::AccessKey;
+use crate::domain::models::user::UserId;
+
+pub struct AccessKeyService { }
+
+impl AccessKeyService {
+
+    pub fn create_access_key(&self, user_id: UserId) -> AccessKey {
+        let mut rng = rand::thread_rng();
+        AccessKey {
+            id: format!(
+                "TBX{}",
+                (0..17).map(|_| rng.sample(Alphanumeric) as char).collect::<String>()
+            ).to_uppercase(),
+            secret: (0..40).map(|_| rng.sample(Alphanumeric) as char).collect(),
+            user_id,
+            created_at: Utc::now(),
+        }
+    }
+}
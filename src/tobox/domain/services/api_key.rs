@@ -0,0 +1,88 @@
+use chrono::{DateTime, Utc};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+
+use crate::domain::id_generator::generate_id;
+use crate::domain::models::api_key::ApiKey;
+use crate::domain::models::permission::PermissionTag;
+use crate::domain::models::user::UserId;
+
+/// Api keys start with the prefix, which tells them from session tokens
+pub const API_KEY_PREFIX: &str = "tbk_";
+
+pub struct ApiKeyService { }
+
+impl ApiKeyService {
+    
+    pub fn generate_key(&self) -> String {
+        let mut rng = rand::thread_rng();
+        format!(
+            "{}{}",
+            API_KEY_PREFIX,
+            (0..48).map(|_| rng.sample(Alphanumeric) as char).collect::<String>()
+        )
+    }
+
+    pub fn create_api_key(
+        &self,
+        name: String,
+        key_hash: String,
+        user_id: UserId,
+        permissions: Vec<PermissionTag>,
+        expires_at: Option<DateTime<Utc>>
+    ) -> ApiKey {
+        ApiKey {
+            id: generate_id(16),
+            name,
+            key_hash,
+            user_id,
+            permissions,
+            created_at: Utc::now(),
+            expires_at
+        }
+    }
+    
+    pub fn is_expired(&self, api_key: &ApiKey, now: &DateTime<Utc>) -> bool {
+        api_key.expires_at.is_some_and(|expires_at| expires_at <= *now)
+    }
+    
+    /// Permissions of the key that the owner still has, a key never has more rights than its owner
+    pub fn effective_permissions(&self, api_key: &ApiKey, owner_permissions: &[PermissionTag]) -> Vec<String> {
+        let owner_permissions: Vec<String> = owner_permissions.iter().map(|tag| tag.to_string()).collect();
+        api_key.permissions.iter()
+            .map(|tag| tag.to_string())
+            .filter(|tag| owner_permissions.contains(tag))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_key() {
+        let service = ApiKeyService {};
+        let key = service.generate_key();
+        assert!(key.starts_with(API_KEY_PREFIX));
+        assert_eq!(key.len(), API_KEY_PREFIX.len() + 48);
+
+        let now = Utc::now();
+        let mut api_key = service.create_api_key(
+            "backup".to_string(),
+            "hash".to_string(),
+            "user".to_string(),
+            vec![PermissionTag::GetObject, PermissionTag::GetSpecificObject("box".to_string())],
+            None
+        );
+        assert!(!service.is_expired(&api_key, &now));
+        api_key.expires_at = Some(now);
+        assert!(service.is_expired(&api_key, &now));
+
+        // The owner lost a permission after the key was created
+        assert_eq!(
+            service.effective_permissions(&api_key, &[PermissionTag::GetObject, PermissionTag::CreateObject]),
+            vec!["GetObject".to_string()]
+        );
+    }
+}
@@ -1,14 +1,25 @@
 use chrono::Utc;
 use crate::domain::id_generator::generate_id;
-use crate::domain::models::r#box::Box;
+use crate::domain::models::r#box::{Box, BoxAccessPolicy, BoxId, DuplicateNamePolicy};
 
 pub struct BoxService { }
 
 impl BoxService {
 
-    pub fn create_box(&self) -> Box {
+    pub fn generate_box_id(&self) -> BoxId {
+        generate_id(16)
+    }
+
+    pub fn create_box(
+        &self,
+        id: Option<BoxId>,
+        duplicate_names: DuplicateNamePolicy,
+        access: BoxAccessPolicy
+    ) -> Box {
         Box {
-            id: generate_id(16),
+            id: id.unwrap_or_else(|| self.generate_box_id()),
+            duplicate_names,
+            access,
             created_at: Utc::now(),
         }
     }
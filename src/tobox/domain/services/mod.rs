@@ -5,4 +5,13 @@ pub mod session;
 pub mod role;
 pub mod r#box;
 pub mod object;
-pub mod permission;
\ No newline at end of file
+pub mod permission;
+pub mod access_key;
+pub mod api_key;
+pub mod upload;
+pub mod node;
+pub mod replication;
+pub mod placement;
+pub mod scrub;
+pub mod raft;
+pub mod presign;
@@ -0,0 +1,90 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use crate::domain::id_generator::generate_id;
+use crate::domain::models::node::{Node, NodeId, NodeInfo, NodeStatus};
+
+/// A node silent for this long is suspect
+const SUSPECT_AFTER_SECONDS: i64 = 15;
+/// A node silent for this long is down
+const DOWN_AFTER_SECONDS: i64 = 60;
+
+pub struct NodeService { }
+
+impl NodeService {
+
+    pub fn generate_node_id(&self) -> NodeId {
+        generate_id(16)
+    }
+
+    pub fn create_node(&self, address: String, info: NodeInfo) -> Node {
+        let now = Utc::now();
+        Node {
+            id: info.id,
+            address,
+            version: info.version,
+            capacity: info.capacity,
+            status: NodeStatus::Up,
+            last_heartbeat: Some(now),
+            created_at: now,
+        }
+    }
+
+    pub fn heartbeat_received(&self, node: Node, info: NodeInfo) -> Node {
+        Node {
+            version: info.version,
+            capacity: info.capacity,
+            status: NodeStatus::Up,
+            last_heartbeat: Some(Utc::now()),
+            ..node
+        }
+    }
+
+    /// Nodes able to serve requests, with the most free space first
+    pub fn available_nodes(&self, mut nodes: Vec<Node>) -> Vec<Node> {
+        nodes.retain(|node| node.status == NodeStatus::Up);
+        nodes.sort_by(|a, b| b.capacity.cmp(&a.capacity).then_with(|| a.id.cmp(&b.id)));
+        nodes
+    }
+
+    /// Status of a node that did not answer, by the time since it was last seen
+    pub fn heartbeat_missed(&self, node: Node, now: &DateTime<Utc>) -> Node {
+        let silence = *now - node.last_heartbeat.unwrap_or(node.created_at);
+        let status = if silence >= TimeDelta::seconds(DOWN_AFTER_SECONDS) {
+            NodeStatus::Down
+        } else if silence >= TimeDelta::seconds(SUSPECT_AFTER_SECONDS) {
+            NodeStatus::Suspect
+        } else {
+            node.status.clone()
+        };
+        Node { status, ..node }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heartbeat_missed() {
+        let service = NodeService { };
+        let node = service.create_node(
+            "http://127.0.0.1:3030".to_string(),
+            NodeInfo { id: "a".to_string(), version: "0.1.0".to_string(), capacity: 1 }
+        );
+        let seen = node.last_heartbeat.unwrap();
+        
+        let node = service.heartbeat_missed(node, &(seen + TimeDelta::seconds(5)));
+        assert_eq!(node.status, NodeStatus::Up);
+        let node = service.heartbeat_missed(node, &(seen + TimeDelta::seconds(20)));
+        assert_eq!(node.status, NodeStatus::Suspect);
+        let node = service.heartbeat_missed(node, &(seen + TimeDelta::seconds(60)));
+        assert_eq!(node.status, NodeStatus::Down);
+        
+        let node = service.heartbeat_received(
+            node,
+            NodeInfo { id: "a".to_string(), version: "0.2.0".to_string(), capacity: 2 }
+        );
+        assert_eq!(node.status, NodeStatus::Up);
+        assert_eq!(node.version, "0.2.0");
+        assert_eq!(node.capacity, 2);
+    }
+}
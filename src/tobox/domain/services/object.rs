@@ -16,6 +16,7 @@ impl ObjectService {
         &self, 
         id: ObjectId,
         name: Option<String>,
+        path: Option<String>,
         hash: String,
         size: u64,
         content_type: String,
@@ -25,6 +26,7 @@ impl ObjectService {
         Object {
             id,
             name,
+            path,
             hash,
             size,
             content_type,
@@ -39,10 +41,12 @@ impl ObjectService {
         &self,
         object: Object,
         new_name: Option<String>,
+        new_path: Option<String>,
         new_metadata: HashMap<String, String>,
     ) -> Object {
         Object {
             name: new_name,
+            path: new_path,
             metadata: new_metadata,
             updated_at: Some(Utc::now()),
             ..object
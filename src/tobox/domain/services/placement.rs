@@ -0,0 +1,214 @@
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+
+use crate::domain::id_generator::generate_id;
+use crate::domain::models::node::{Node, NodeId, NodeStatus};
+use crate::domain::models::object::{Object, ObjectId};
+use crate::domain::models::placement::{PlacementMove, Ring, RingMember};
+
+/// Free space worth a virtual node, the weight changes only in these steps,
+/// so writing to a node does not move objects on every heartbeat
+const BYTES_PER_VIRTUAL_NODE: u64 = 4 * 1024 * 1024 * 1024;
+const MIN_VIRTUAL_NODES: u32 = 16;
+const MAX_VIRTUAL_NODES: u32 = 1024;
+
+pub struct PlacementService { }
+
+impl PlacementService {
+
+    pub fn node_weight(&self, capacity: u64) -> u32 {
+        (capacity / BYTES_PER_VIRTUAL_NODE)
+            .clamp(MIN_VIRTUAL_NODES as u64, MAX_VIRTUAL_NODES as u64) as u32
+    }
+
+    /// This node and the peers that are not down, ordered by id
+    ///
+    /// Suspect peers keep their place, a short outage should not move objects.
+    pub fn ring_members(&self, node_id: &NodeId, capacity: u64, nodes: Vec<Node>) -> Vec<RingMember> {
+        let mut members: Vec<RingMember> = nodes.into_iter()
+            .filter(|node| node.status != NodeStatus::Down && node.id != *node_id)
+            .map(|node| RingMember { weight: self.node_weight(node.capacity), node_id: node.id })
+            .collect();
+        members.push(RingMember { node_id: node_id.clone(), weight: self.node_weight(capacity) });
+        members.sort_by(|a, b| a.node_id.cmp(&b.node_id));
+        members
+    }
+
+    /// Storage nodes that are not down, ordered by id, as their ring is seen by
+    /// an intermediate node, which is not a member itself
+    pub fn storage_members(&self, nodes: Vec<Node>) -> Vec<RingMember> {
+        let mut members: Vec<RingMember> = nodes.into_iter()
+            .filter(|node| node.status != NodeStatus::Down)
+            .map(|node| RingMember { weight: self.node_weight(node.capacity), node_id: node.id })
+            .collect();
+        members.sort_by(|a, b| a.node_id.cmp(&b.node_id));
+        members
+    }
+
+    pub fn build_ring(&self, members: Vec<RingMember>) -> Ring {
+        let mut points: Vec<(u64, NodeId)> = members.iter()
+            .flat_map(|member| (0..member.weight).map(
+                |index| (position(&format!("{}#{}", member.node_id, index)), member.node_id.clone())
+            ))
+            .collect();
+        points.sort();
+        Ring { members, points }
+    }
+
+    /// Distinct members met clockwise from the key, the first one is the primary owner
+    pub fn owners(&self, ring: &Ring, key: &str, count: usize) -> Vec<NodeId> {
+        let count = count.min(ring.members.len());
+        let start = ring.points.partition_point(|(point, _)| *point < position(key));
+        let mut owners: Vec<NodeId> = Vec::with_capacity(count);
+        for (_, node_id) in ring.points[start..].iter().chain(&ring.points[..start]) {
+            if owners.len() == count {
+                break
+            }
+            if !owners.contains(node_id) {
+                owners.push(node_id.clone());
+            }
+        }
+        owners
+    }
+
+    /// Objects owned by both nodes, the only ones two nodes can be compared by
+    pub fn shared_objects(
+        &self,
+        ring: &Ring,
+        objects: Vec<Object>,
+        first: &NodeId,
+        second: &NodeId,
+        copies: usize
+    ) -> Vec<Object> {
+        objects.into_iter()
+            .filter(|object| {
+                let owners = self.owners(ring, &object.id, copies);
+                owners.contains(first) && owners.contains(second)
+            })
+            .collect()
+    }
+
+    /// Moves of the objects held by this node after the ring has changed
+    ///
+    /// Only objects whose owners changed are moved. A new owner receives the object
+    /// from the first previous owner still in the ring, so every copy is sent once.
+    ///
+    /// * copies: owners of every object
+    pub fn plan_rebalance(
+        &self,
+        previous: &Ring,
+        current: &Ring,
+        node_id: &NodeId,
+        objects: &[ObjectId],
+        copies: usize
+    ) -> Vec<PlacementMove> {
+        let mut moves = vec![];
+        for object_id in objects {
+            let old = self.owners(previous, object_id, copies);
+            let new = self.owners(current, object_id, copies);
+            if old == new {
+                continue
+            }
+
+            let source = old.iter().find(
+                |owner| current.members.iter().any(|member| member.node_id == **owner)
+            );
+            let targets: Vec<NodeId> = match source {
+                Some(source) if source == node_id => new.iter()
+                    .filter(|owner| !old.contains(owner))
+                    .cloned()
+                    .collect(),
+                // The source node sends the object
+                Some(_) => vec![],
+                // Every previous owner has left, this copy may be the last one
+                None => new.iter().filter(|owner| *owner != node_id).cloned().collect()
+            };
+            let release = !new.contains(node_id);
+            if targets.is_empty() && !release {
+                continue
+            }
+            moves.push(PlacementMove {
+                id: generate_id(32),
+                object_id: object_id.clone(),
+                targets,
+                release,
+                created_at: Utc::now(),
+            });
+        }
+        moves
+    }
+}
+
+/// Position of a key on the ring
+fn position(key: &str) -> u64 {
+    let digest = Sha256::digest(key.as_bytes());
+    u64::from_be_bytes(digest[..8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(node_id: &str, weight: u32) -> RingMember {
+        RingMember { node_id: node_id.to_string(), weight }
+    }
+
+    fn keys() -> Vec<ObjectId> {
+        (0..2000).map(|index| format!("object-{}", index)).collect()
+    }
+
+    #[test]
+    fn test_owners() {
+        let service = PlacementService { };
+        let ring = service.build_ring(vec![member("a", 16), member("b", 16), member("c", 16)]);
+
+        let owners = service.owners(&ring, "object", 2);
+        assert_eq!(owners.len(), 2);
+        assert_ne!(owners[0], owners[1]);
+        assert_eq!(service.owners(&ring, "object", 2), owners);
+        assert_eq!(service.owners(&ring, "object", 5).len(), 3);
+        assert!(service.owners(&Ring::default(), "object", 2).is_empty());
+    }
+
+    #[test]
+    fn test_weight() {
+        let service = PlacementService { };
+        let ring = service.build_ring(vec![member("a", 16), member("b", 64)]);
+        let owned_by_b = keys().iter()
+            .filter(|key| service.owners(&ring, key, 1)[0] == "b")
+            .count();
+        assert!(owned_by_b > keys().len() * 3 / 5);
+
+        assert_eq!(service.node_weight(0), MIN_VIRTUAL_NODES);
+        assert_eq!(service.node_weight(64 * BYTES_PER_VIRTUAL_NODE + 1), 64);
+        assert_eq!(service.node_weight(u64::MAX), MAX_VIRTUAL_NODES);
+    }
+
+    #[test]
+    fn test_plan_rebalance() {
+        let service = PlacementService { };
+        let members = vec![member("a", 16), member("b", 16), member("c", 16)];
+        let previous = service.build_ring(members.clone());
+        let current = service.build_ring([members, vec![member("d", 16)]].concat());
+
+        // Only objects now owned by the new node are moved, the rest stay in place
+        let mut moved = 0;
+        for node_id in ["a", "b", "c"] {
+            let plan = service.plan_rebalance(&previous, &current, &node_id.to_string(), &keys(), 2);
+            for entry in plan {
+                assert!(service.owners(&current, &entry.object_id, 2).contains(&"d".to_string()));
+                assert!(entry.targets.iter().all(|target| target == "d"));
+                moved += entry.targets.len();
+            }
+        }
+        let owned_by_d = keys().iter()
+            .filter(|key| service.owners(&current, key, 2).contains(&"d".to_string()))
+            .count();
+        assert_eq!(moved, owned_by_d);
+        assert!(owned_by_d < keys().len() * 3 / 4);
+
+        // Objects of a removed node are sent by the remaining owner
+        let plan = service.plan_rebalance(&current, &previous, &"d".to_string(), &keys(), 2);
+        assert!(plan.iter().all(|entry| entry.release && entry.targets.is_empty()));
+    }
+}
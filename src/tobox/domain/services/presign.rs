@@ -0,0 +1,192 @@
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::domain::models::permission::PermissionTag;
+use crate::domain::models::presign::{PresignedAction, PresignedGrant};
+use crate::domain::models::r#box::BoxId;
+use crate::domain::models::user::UserId;
+
+/// Seconds a presigned url is valid when not set by the issuer
+pub const DEFAULT_PRESIGN_EXPIRES_IN: u32 = 60 * 60;
+pub const MAX_PRESIGN_EXPIRES_IN: u32 = 7 * 24 * 60 * 60;
+
+pub struct PresignService { }
+
+impl PresignService {
+
+    pub fn create_grant(
+        &self,
+        action: PresignedAction,
+        box_id: BoxId,
+        user_id: UserId,
+        expires_in: Option<u32>,
+        max_length: Option<u64>,
+        now: &DateTime<Utc>
+    ) -> Result<PresignedGrant, String> {
+        let expires_in = expires_in.unwrap_or(DEFAULT_PRESIGN_EXPIRES_IN);
+        if expires_in == 0 || expires_in > MAX_PRESIGN_EXPIRES_IN {
+            return Err(format!("Expiry must be from 1 to {} seconds", MAX_PRESIGN_EXPIRES_IN))
+        }
+        if max_length.is_some() && action != PresignedAction::CreateObject {
+            return Err("Length limit is allowed for uploads only".to_string())
+        }
+
+        let expires_at = DateTime::from_timestamp(now.timestamp(), 0).unwrap()
+            + TimeDelta::seconds(expires_in as i64);
+        Ok(PresignedGrant { action, box_id, user_id, expires_at, max_length })
+    }
+
+    pub fn method(&self, action: &PresignedAction) -> &'static str {
+        match action {
+            PresignedAction::GetObject(_) => "GET",
+            PresignedAction::CreateObject => "POST"
+        }
+    }
+
+    /// Everything the url grants is signed, changing any part of it invalidates the signature
+    pub fn string_to_sign(&self, grant: &PresignedGrant) -> String {
+        let object_id = match &grant.action {
+            PresignedAction::GetObject(object_id) => object_id.as_str(),
+            PresignedAction::CreateObject => ""
+        };
+        format!(
+            "TOBOX-PRESIGN\n{}\n{}\n{}\n{}\n{}\n{}",
+            self.method(&grant.action),
+            object_id,
+            grant.box_id,
+            grant.user_id,
+            grant.expires_at.timestamp(),
+            grant.max_length.map(|length| length.to_string()).unwrap_or_default()
+        )
+    }
+
+    /// Permissions for the box of the grant, the url is checked against the request beforehand
+    pub fn grant_permissions(&self, grant: &PresignedGrant) -> Vec<String> {
+        let tag = match grant.action {
+            PresignedAction::GetObject(_) => PermissionTag::GetSpecificObject(grant.box_id.clone()),
+            PresignedAction::CreateObject => PermissionTag::CreateSpecificObject(grant.box_id.clone())
+        };
+        vec![tag.to_string()]
+    }
+
+    /// * param method: method of the request, `HEAD` is allowed for downloads
+    /// * param action: what the request does
+    /// * param content_length: `Content-Length` of the request
+    pub fn check_request(
+        &self,
+        grant: &PresignedGrant,
+        method: &str,
+        action: &PresignedAction,
+        content_length: Option<u64>,
+        now: &DateTime<Utc>
+    ) -> Result<(), String> {
+        if grant.expires_at <= *now {
+            return Err("Url expired".to_string())
+        }
+
+        let method_allowed = method == self.method(&grant.action)
+            || (method == "HEAD" && matches!(grant.action, PresignedAction::GetObject(_)));
+        if !method_allowed || *action != grant.action {
+            return Err("Url is not signed for this request".to_string())
+        }
+
+        if let Some(max_length) = grant.max_length {
+            match content_length {
+                Some(length) if length <= max_length => (),
+                Some(_) => return Err("Request body is too large".to_string()),
+                None => return Err("Content-Length is required".to_string())
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_grant() {
+        let service = PresignService {};
+        let now = Utc::now();
+        let grant = service.create_grant(
+            PresignedAction::CreateObject,
+            "box".to_string(),
+            "user".to_string(),
+            None,
+            Some(1024),
+            &now
+        ).unwrap();
+        assert_eq!(grant.expires_at.timestamp(), now.timestamp() + DEFAULT_PRESIGN_EXPIRES_IN as i64);
+        assert_eq!(grant.expires_at.timestamp_subsec_nanos(), 0);
+        assert_eq!(service.grant_permissions(&grant), vec!["CreateSpecificObject(box)".to_string()]);
+
+        for expires_in in [0, MAX_PRESIGN_EXPIRES_IN + 1] {
+            assert!(service.create_grant(
+                PresignedAction::CreateObject,
+                "box".to_string(),
+                "user".to_string(),
+                Some(expires_in),
+                None,
+                &now
+            ).is_err());
+        }
+        assert!(service.create_grant(
+            PresignedAction::GetObject("object".to_string()),
+            "box".to_string(),
+            "user".to_string(),
+            None,
+            Some(1024),
+            &now
+        ).is_err());
+    }
+
+    #[test]
+    fn test_check_request() {
+        let service = PresignService {};
+        let now = Utc::now();
+        let download = service.create_grant(
+            PresignedAction::GetObject("object".to_string()),
+            "box".to_string(),
+            "user".to_string(),
+            Some(60),
+            None,
+            &now
+        ).unwrap();
+        let action = PresignedAction::GetObject("object".to_string());
+        assert!(service.check_request(&download, "GET", &action, None, &now).is_ok());
+        assert!(service.check_request(&download, "HEAD", &action, None, &now).is_ok());
+        assert!(service.check_request(&download, "DELETE", &action, None, &now).is_err());
+        assert!(service.check_request(
+            &download,
+            "GET",
+            &PresignedAction::GetObject("other".to_string()),
+            None,
+            &now
+        ).is_err());
+        assert_eq!(
+            service.check_request(&download, "GET", &action, None, &(now + TimeDelta::seconds(60))).unwrap_err(),
+            "Url expired"
+        );
+
+        let upload = PresignedGrant {
+            action: PresignedAction::CreateObject,
+            max_length: Some(1024),
+            ..download.clone()
+        };
+        let action = PresignedAction::CreateObject;
+        assert!(service.check_request(&upload, "POST", &action, Some(1024), &now).is_ok());
+        assert!(service.check_request(&upload, "POST", &action, Some(1025), &now).is_err());
+        assert!(service.check_request(&upload, "POST", &action, None, &now).is_err());
+        assert!(service.check_request(&upload, "HEAD", &action, Some(0), &now).is_err());
+
+        // Every part of the grant is signed
+        assert_ne!(service.string_to_sign(&download), service.string_to_sign(&PresignedGrant {
+            user_id: "other".to_string(),
+            ..download.clone()
+        }));
+        assert_ne!(service.string_to_sign(&upload), service.string_to_sign(&PresignedGrant {
+            max_length: Some(1025),
+            ..upload.clone()
+        }));
+    }
+}
@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use rand::Rng;
+
+use crate::domain::models::metadata::{
+    AppendResponse,
+    HardState,
+    LogEntry,
+    LogIndex,
+    RaftRole,
+    RaftState,
+    Term,
+    VoteRequest,
+    VoteResponse
+};
+use crate::domain::models::node::NodeId;
+
+/// A follower waits for the leader this long, randomized so that
+/// nodes rarely start an election at the same time
+const ELECTION_TIMEOUT_MIN_MILLIS: i64 = 1500;
+const ELECTION_TIMEOUT_MAX_MILLIS: i64 = 3000;
+
+/// Consensus on the order of metadata changes, by the Raft algorithm
+///
+/// Only the decisions are made here, the log itself is kept by the gateway.
+pub struct RaftService { }
+
+impl RaftService {
+
+    pub fn new_state(&self, node_id: NodeId, hard_state: HardState, now: &DateTime<Utc>) -> RaftState {
+        RaftState {
+            node_id,
+            commit_index: hard_state.applied,
+            hard_state,
+            role: RaftRole::Follower,
+            leader_id: None,
+            votes: vec![],
+            next_index: HashMap::new(),
+            match_index: HashMap::new(),
+            election_deadline: self.election_deadline(now),
+        }
+    }
+
+    pub fn election_deadline(&self, now: &DateTime<Utc>) -> DateTime<Utc> {
+        *now + TimeDelta::milliseconds(
+            rand::thread_rng().gen_range(ELECTION_TIMEOUT_MIN_MILLIS..=ELECTION_TIMEOUT_MAX_MILLIS)
+        )
+    }
+
+    pub fn is_election_due(&self, state: &RaftState, now: &DateTime<Utc>) -> bool {
+        state.role != RaftRole::Leader && *now >= state.election_deadline
+    }
+
+    /// Step down to a follower of a newer term, the node has not voted in it yet
+    ///
+    /// * return: whether the hard state changed
+    pub fn observe_term(&self, state: &mut RaftState, term: Term, now: &DateTime<Utc>) -> bool {
+        if term <= state.hard_state.term {
+            return false
+        }
+        state.hard_state.term = term;
+        state.hard_state.voted_for = None;
+        if state.role != RaftRole::Follower {
+            state.role = RaftRole::Follower;
+            state.election_deadline = self.election_deadline(now);
+        }
+        state.leader_id = None;
+        state.votes.clear();
+        true
+    }
+
+    /// Become a candidate of the next term voting for itself
+    ///
+    /// * last_log: index and term of the last entry of this node
+    pub fn start_election(
+        &self,
+        state: &mut RaftState,
+        last_log: (LogIndex, Term),
+        now: &DateTime<Utc>
+    ) -> VoteRequest {
+        state.hard_state.term += 1;
+        state.hard_state.voted_for = Some(state.node_id.clone());
+        state.role = RaftRole::Candidate;
+        state.leader_id = None;
+        state.votes = vec![state.node_id.clone()];
+        state.election_deadline = self.election_deadline(now);
+        VoteRequest {
+            term: state.hard_state.term,
+            candidate_id: state.node_id.clone(),
+            last_log_index: last_log.0,
+            last_log_term: last_log.1,
+        }
+    }
+
+    /// A node votes once per term, for a candidate whose log is at least as complete as its own
+    pub fn vote(
+        &self,
+        state: &mut RaftState,
+        request: &VoteRequest,
+        last_log: (LogIndex, Term),
+        now: &DateTime<Utc>
+    ) -> VoteResponse {
+        self.observe_term(state, request.term, now);
+        let is_up_to_date = (request.last_log_term, request.last_log_index) >= (last_log.1, last_log.0);
+        let granted = request.term == state.hard_state.term
+            && is_up_to_date
+            && state.hard_state.voted_for.as_ref().is_none_or(|node_id| *node_id == request.candidate_id);
+        if granted {
+            state.hard_state.voted_for = Some(request.candidate_id.clone());
+            state.election_deadline = self.election_deadline(now);
+        }
+        VoteResponse { term: state.hard_state.term, granted }
+    }
+
+    pub fn count_vote(
+        &self,
+        state: &mut RaftState,
+        node_id: &NodeId,
+        response: &VoteResponse,
+        now: &DateTime<Utc>
+    ) {
+        self.observe_term(state, response.term, now);
+        if state.role == RaftRole::Candidate
+            && response.term == state.hard_state.term
+            && response.granted
+            && !state.votes.contains(node_id) {
+            state.votes.push(node_id.clone());
+        }
+    }
+
+    /// A candidate voted for by a majority becomes the leader
+    ///
+    /// * peers: other nodes of the cluster, down ones included
+    /// * return: whether the node became the leader
+    pub fn try_lead(&self, state: &mut RaftState, peers: &[NodeId], last_index: LogIndex) -> bool {
+        if state.role != RaftRole::Candidate || state.votes.len() * 2 <= peers.len() + 1 {
+            return false
+        }
+        state.role = RaftRole::Leader;
+        state.leader_id = Some(state.node_id.clone());
+        state.next_index = peers.iter().map(|peer| (peer.clone(), last_index + 1)).collect();
+        state.match_index = peers.iter().map(|peer| (peer.clone(), 0)).collect();
+        true
+    }
+
+    /// Follow the sender of entries unless it is the leader of an older term
+    ///
+    /// * return: whether the entries are accepted
+    pub fn accept_leader(
+        &self,
+        state: &mut RaftState,
+        leader_id: &NodeId,
+        term: Term,
+        now: &DateTime<Utc>
+    ) -> bool {
+        if term < state.hard_state.term {
+            return false
+        }
+        self.observe_term(state, term, now);
+        // A candidate of the same term has lost the election
+        state.role = RaftRole::Follower;
+        state.leader_id = Some(leader_id.clone());
+        state.votes.clear();
+        state.election_deadline = self.election_deadline(now);
+        true
+    }
+
+    pub fn record_append(
+        &self,
+        state: &mut RaftState,
+        node_id: &NodeId,
+        response: &AppendResponse,
+        now: &DateTime<Utc>
+    ) {
+        self.observe_term(state, response.term, now);
+        if state.role != RaftRole::Leader || response.term != state.hard_state.term {
+            return
+        }
+        if response.success {
+            let matched = state.match_index.entry(node_id.clone()).or_insert(0);
+            *matched = (*matched).max(response.match_index);
+            state.next_index.insert(node_id.clone(), *matched + 1);
+        } else {
+            // Back off to the end of the follower log, at least one entry at a time
+            let next = state.next_index.entry(node_id.clone()).or_insert(1);
+            *next = (*next - 1).min(response.match_index + 1).max(1);
+        }
+    }
+
+    /// Commit the last entry of the current term stored by a majority, with all entries before it
+    ///
+    /// Entries of previous terms are never committed by counting copies, a later leader could
+    /// still overwrite them.
+    ///
+    /// * uncommitted: entries after the commit index, in order
+    pub fn advance_commit(&self, state: &mut RaftState, peers: &[NodeId], uncommitted: &[LogEntry]) {
+        if state.role != RaftRole::Leader {
+            return
+        }
+        for entry in uncommitted.iter().rev() {
+            if entry.term != state.hard_state.term || entry.index <= state.commit_index {
+                continue
+            }
+            let copies = 1 + peers.iter()
+                .filter(|peer| state.match_index.get(*peer).is_some_and(|index| *index >= entry.index))
+                .count();
+            if copies * 2 > peers.len() + 1 {
+                state.commit_index = entry.index;
+                return
+            }
+        }
+    }
+
+    /// Commit index of a follower after it has stored the entries up to `last_new`
+    pub fn follow_commit(&self, state: &mut RaftState, leader_commit: LogIndex, last_new: LogIndex) {
+        state.commit_index = state.commit_index.max(leader_commit.min(last_new));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::metadata::MetadataCommand;
+
+    use super::*;
+
+    fn state(node_id: &str) -> RaftState {
+        RaftService { }.new_state(node_id.to_string(), HardState::default(), &Utc::now())
+    }
+
+    fn entry(index: LogIndex, term: Term) -> LogEntry {
+        LogEntry { index, term, command: MetadataCommand::RemoveBox { box_id: "box".to_string() } }
+    }
+
+    #[test]
+    fn test_election() {
+        let service = RaftService { };
+        let now = Utc::now();
+        let peers = ["b".to_string(), "c".to_string()];
+        let mut a = state("a");
+        let mut b = state("b");
+        let mut c = state("c");
+        assert!(!service.is_election_due(&a, &now));
+        assert!(service.is_election_due(&a, &(now + TimeDelta::seconds(5))));
+
+        let request = service.start_election(&mut a, (0, 0), &now);
+        assert_eq!(request.term, 1);
+        assert!(!service.try_lead(&mut a, &peers, 0));
+
+        let response = service.vote(&mut b, &request, (0, 0), &now);
+        assert!(response.granted);
+        // One vote per term
+        let other = VoteRequest { candidate_id: "c".to_string(), ..request.clone() };
+        assert!(!service.vote(&mut b, &other, (0, 0), &now).granted);
+
+        service.count_vote(&mut a, &"b".to_string(), &response, &now);
+        assert!(service.try_lead(&mut a, &peers, 0));
+        assert_eq!(a.role, RaftRole::Leader);
+        assert_eq!(a.next_index["c"], 1);
+
+        // A candidate with a shorter log is refused
+        let request = VoteRequest { term: 2, candidate_id: "b".to_string(), last_log_index: 1, last_log_term: 1 };
+        let response = service.vote(&mut c, &request, (2, 1), &now);
+        assert!(!response.granted);
+        assert_eq!(c.hard_state.term, 2);
+
+        // The leader of an older term steps down once it hears of a newer one
+        service.record_append(&mut a, &"c".to_string(), &AppendResponse {
+            term: 2, success: false, match_index: 2
+        }, &now);
+        assert_eq!(a.role, RaftRole::Follower);
+        assert!(!service.accept_leader(&mut c, &"a".to_string(), 1, &now));
+    }
+
+    #[test]
+    fn test_commit() {
+        let service = RaftService { };
+        let now = Utc::now();
+        let peers = ["b".to_string(), "c".to_string(), "d".to_string(), "e".to_string()];
+        let mut a = state("a");
+        a.hard_state.term = 1;
+        service.start_election(&mut a, (2, 1), &now);
+        a.votes = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        assert!(service.try_lead(&mut a, &peers, 2));
+        let uncommitted = [entry(1, 1), entry(2, 1), entry(3, 2)];
+
+        // Entries of a previous term are not committed by themselves
+        for peer in ["b", "c"] {
+            service.record_append(&mut a, &peer.to_string(), &AppendResponse {
+                term: 2, success: true, match_index: 2
+            }, &now);
+        }
+        service.advance_commit(&mut a, &peers, &uncommitted);
+        assert_eq!(a.commit_index, 0);
+
+        service.record_append(&mut a, &"b".to_string(), &AppendResponse {
+            term: 2, success: true, match_index: 3
+        }, &now);
+        service.advance_commit(&mut a, &peers, &uncommitted);
+        assert_eq!(a.commit_index, 0);
+        service.record_append(&mut a, &"c".to_string(), &AppendResponse {
+            term: 2, success: true, match_index: 3
+        }, &now);
+        service.advance_commit(&mut a, &peers, &uncommitted);
+        assert_eq!(a.commit_index, 3);
+
+        // A follower missing entries is sent earlier ones
+        service.record_append(&mut a, &"d".to_string(), &AppendResponse {
+            term: 2, success: false, match_index: 0
+        }, &now);
+        assert_eq!(a.next_index["d"], 1);
+    }
+}
@@ -0,0 +1,66 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use crate::domain::id_generator::generate_id;
+use crate::domain::models::node::NodeId;
+use crate::domain::models::object::ObjectId;
+use crate::domain::models::replication::ReplicationTask;
+
+/// Delay before the first retry, doubled by every failed attempt
+const RETRY_BASE_SECONDS: i64 = 5;
+const RETRY_MAX_SECONDS: i64 = 3600;
+
+pub struct ReplicationService { }
+
+impl ReplicationService {
+
+    pub fn create_task(&self, object_id: ObjectId) -> ReplicationTask {
+        let now = Utc::now();
+        ReplicationTask {
+            id: generate_id(32),
+            object_id,
+            node_id: None,
+            attempts: 0,
+            next_attempt_at: now,
+            created_at: now,
+        }
+    }
+
+    pub fn assign_task(&self, task: &ReplicationTask, node_id: NodeId) -> ReplicationTask {
+        ReplicationTask {
+            id: generate_id(32),
+            node_id: Some(node_id),
+            ..task.clone()
+        }
+    }
+
+    pub fn retry_later(&self, task: ReplicationTask, now: &DateTime<Utc>) -> ReplicationTask {
+        let delay = RETRY_BASE_SECONDS
+            .saturating_mul(1 << task.attempts.min(16))
+            .min(RETRY_MAX_SECONDS);
+        ReplicationTask {
+            attempts: task.attempts + 1,
+            next_attempt_at: *now + TimeDelta::seconds(delay),
+            ..task
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_later() {
+        let service = ReplicationService { };
+        let now = Utc::now();
+        let mut task = service.create_task("object".to_string());
+        
+        let mut delays = vec![];
+        for _ in 0..12 {
+            task = service.retry_later(task, &now);
+            delays.push((task.next_attempt_at - now).num_seconds());
+        }
+        assert_eq!(&delays[..4], [5, 10, 20, 40]);
+        assert_eq!(delays[11], RETRY_MAX_SECONDS);
+        assert_eq!(task.attempts, 12);
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use sha2::{Digest, Sha256};
+
+use crate::domain::models::node::NodeId;
+use crate::domain::models::object::Object;
+use crate::domain::models::r#box::BoxId;
+use crate::domain::models::scrub::{BoxDigest, Divergence};
+
+/// Buckets of a box digest, a divergence is narrowed down to one of them
+const DIGEST_BUCKETS: usize = 16;
+
+pub struct ScrubService { }
+
+impl ScrubService {
+
+    /// Digests of the boxes of the objects, ordered by box id
+    ///
+    /// An object is covered by its id, blob hash and the time of the last change,
+    /// so the same object on two nodes gives the same digest.
+    pub fn box_digests(&self, objects: &[Object]) -> Vec<BoxDigest> {
+        let mut boxes: BTreeMap<&BoxId, Vec<Vec<&Object>>> = BTreeMap::new();
+        for object in objects {
+            let buckets = boxes.entry(&object.box_id).or_insert_with(|| vec![vec![]; DIGEST_BUCKETS]);
+            buckets[bucket(&object.id)].push(object);
+        }
+
+        boxes.into_iter().map(|(box_id, buckets)| {
+            let buckets: Vec<String> = buckets.into_iter().map(|mut objects| {
+                objects.sort_by(|a, b| a.id.cmp(&b.id));
+                let mut hasher = Sha256::new();
+                for object in objects {
+                    hasher.update(Sha256::digest(format!(
+                        "{}\n{}\n{}",
+                        object.id,
+                        object.hash,
+                        object.updated_at.unwrap_or(object.created_at).timestamp_millis()
+                    )));
+                }
+                format!("{:x}", hasher.finalize())
+            }).collect();
+            BoxDigest {
+                box_id: box_id.clone(),
+                root: format!("{:x}", Sha256::digest(buckets.concat())),
+                buckets,
+            }
+        }).collect()
+    }
+
+    /// Boxes of this node and a peer that differ, with the buckets that differ
+    pub fn compare(
+        &self,
+        node_id: &NodeId,
+        local: &[BoxDigest],
+        remote: &[BoxDigest]
+    ) -> Vec<Divergence> {
+        let remote: BTreeMap<&BoxId, &BoxDigest> = remote.iter()
+            .map(|digest| (&digest.box_id, digest))
+            .collect();
+        let mut divergences = vec![];
+        for digest in local {
+            let buckets: Vec<usize> = match remote.get(&digest.box_id) {
+                Some(other) if other.root == digest.root => continue,
+                Some(other) => (0..DIGEST_BUCKETS)
+                    .filter(|index| digest.buckets.get(*index) != other.buckets.get(*index))
+                    .collect(),
+                None => (0..DIGEST_BUCKETS).collect()
+            };
+            divergences.push(Divergence {
+                node_id: node_id.clone(),
+                box_id: digest.box_id.clone(),
+                buckets
+            });
+        }
+        let local: BTreeSet<&BoxId> = local.iter().map(|digest| &digest.box_id).collect();
+        for box_id in remote.keys().filter(|box_id| !local.contains(*box_id)) {
+            divergences.push(Divergence {
+                node_id: node_id.clone(),
+                box_id: (*box_id).clone(),
+                buckets: (0..DIGEST_BUCKETS).collect()
+            });
+        }
+        divergences
+    }
+}
+
+fn bucket(object_id: &str) -> usize {
+    Sha256::digest(object_id.as_bytes())[0] as usize % DIGEST_BUCKETS
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::Utc;
+
+    use super::*;
+
+    fn object(id: &str, box_id: &str, hash: &str) -> Object {
+        Object {
+            id: id.to_string(),
+            name: None,
+            path: None,
+            hash: hash.to_string(),
+            size: 1,
+            content_type: "text/plain".to_string(),
+            metadata: HashMap::new(),
+            box_id: box_id.to_string(),
+            created_at: Utc::now(),
+            updated_at: None
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let service = ScrubService { };
+        let objects = vec![
+            object("a", "photos", "1"),
+            object("b", "photos", "2"),
+            object("c", "docs", "3"),
+        ];
+        let local = service.box_digests(&objects);
+        assert_eq!(local.len(), 2);
+        assert_eq!(local[0].box_id, "docs");
+
+        // Order of objects does not matter
+        let reversed: Vec<Object> = objects.iter().rev().cloned().collect();
+        assert_eq!(service.box_digests(&reversed), local);
+        assert!(service.compare(&"peer".to_string(), &local, &local).is_empty());
+
+        let mut changed = objects.clone();
+        changed[1].hash = "changed".to_string();
+        let divergences = service.compare(&"peer".to_string(), &local, &service.box_digests(&changed));
+        assert_eq!(divergences.len(), 1);
+        assert_eq!(divergences[0].box_id, "photos");
+        assert_eq!(divergences[0].buckets, vec![bucket("b")]);
+
+        let divergences = service.compare(&"peer".to_string(), &local, &local[..1]);
+        assert_eq!(divergences.len(), 1);
+        assert_eq!(divergences[0].box_id, "photos");
+        assert_eq!(divergences[0].buckets.len(), DIGEST_BUCKETS);
+        assert_eq!(service.compare(&"peer".to_string(), &local[..1], &local).len(), 1);
+    }
+}
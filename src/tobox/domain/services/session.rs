@@ -1,6 +1,8 @@
+use chrono::{DateTime, TimeDelta, Utc};
 use rand::random;
 use crate::domain::models::permission::PermissionTag;
-use crate::domain::models::session::Session;
+use crate::domain::id_generator::generate_id;
+use crate::domain::models::session::{Session, SessionOrigin};
 use crate::domain::models::user::UserId;
 
 pub struct SessionService {
@@ -15,18 +17,166 @@ impl SessionService {
             token_ttl,
         }
     }
+    
+    pub fn generate_token(&self) -> String {
+        (0..64).map(|_| format!("{:02x}", random::<u8>())).collect()
+    }
+    
     pub fn create_session(
         &self,
+        token_hash: String,
         user_id: UserId,
         permissions: Vec<PermissionTag>,
+        origin: SessionOrigin,
+        now: &DateTime<Utc>
     ) -> Session {
         Session {
-            token: (0..64).map(
-                |_| format!("{:02x}", random::<u8>())
-            ).collect::<Vec<_>>().join("").to_string(),
+            id: generate_id(16),
+            token_hash,
             user_id,
             permissions,
-            expires_at: chrono::Utc::now() + chrono::Duration::seconds(self.token_ttl as i64)
+            origin,
+            created_at: *now,
+            expires_at: *now + self.ttl()
+        }
+    }
+    
+    /// A session is valid strictly before its expiry time
+    pub fn is_expired(&self, session: &Session, now: &DateTime<Utc>) -> bool {
+        session.expires_at <= *now
+    }
+    
+    /// Sliding expiration: a session used in the second half of its lifetime gets a full one again,
+    /// so a session in use does not expire and is not written on every request
+    ///
+    /// * return: whether the expiry time changed
+    pub fn renew(&self, session: &mut Session, now: &DateTime<Utc>) -> bool {
+        if self.is_expired(session, now) || session.expires_at - *now >= self.ttl() / 2 {
+            return false
         }
+        session.expires_at = *now + self.ttl();
+        true
+    }
+    
+    /// Recognize the client by the `User-Agent` header, fields not found are left empty
+    pub fn parse_origin(&self, ip: Option<String>, user_agent: Option<&str>) -> SessionOrigin {
+        let user_agent = match user_agent {
+            Some(user_agent) => user_agent,
+            None => return SessionOrigin { ip, ..SessionOrigin::default() }
+        };
+        let is_browser = user_agent.starts_with("Mozilla/");
+        let contains = |pattern: &str| user_agent.contains(pattern);
+        
+        // Browsers name the engines they are compatible with as well, the order matters
+        let client = [
+            ("Edg/", "Edge"),
+            ("OPR/", "Opera"),
+            ("Firefox/", "Firefox"),
+            ("Chrome/", "Chrome"),
+            ("Safari/", "Safari")
+        ].iter()
+            .find(|(pattern, _)| contains(pattern))
+            .map(|(_, name)| name.to_string())
+            .or_else(|| match is_browser {
+                true => None,
+                // Programs start with their name: `curl/8.5.0`, `aws-cli/2.15.0 ...`
+                false => user_agent.split(['/', ' '])
+                    .next()
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string)
+            });
+        
+        let os = [
+            ("Windows", "Windows"),
+            ("Android", "Android"),
+            ("iPhone", "iOS"),
+            ("iPad", "iOS"),
+            ("Mac OS X", "macOS"),
+            ("Linux", "Linux")
+        ].iter()
+            .find(|(pattern, _)| contains(pattern))
+            .map(|(_, name)| name.to_string());
+        
+        let device = match is_browser {
+            false => None,
+            true if contains("iPad") || (contains("Android") && !contains("Mobile")) => Some("Tablet"),
+            true if contains("Mobile") || contains("iPhone") => Some("Mobile"),
+            true => Some("Desktop")
+        }.map(str::to_string);
+        
+        SessionOrigin { ip, client, os, device }
+    }
+    
+    fn ttl(&self) -> TimeDelta {
+        TimeDelta::seconds(self.token_ttl as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expiry() {
+        let service = SessionService::new(3600);
+        let now = Utc::now();
+        let mut session = service.create_session(
+            "hash".to_string(),
+            "user".to_string(),
+            vec![],
+            SessionOrigin::default(),
+            &now
+        );
+        assert!(!service.is_expired(&session, &now));
+        assert!(service.is_expired(&session, &(now + TimeDelta::hours(1))));
+        assert_eq!(service.generate_token().len(), 128);
+
+        // Renewed only in the second half of the lifetime
+        assert!(!service.renew(&mut session, &(now + TimeDelta::minutes(20))));
+        let later = now + TimeDelta::minutes(40);
+        assert!(service.renew(&mut session, &later));
+        assert_eq!(session.expires_at, later + TimeDelta::hours(1));
+
+        // An expired session stays expired
+        let expired = session.expires_at;
+        assert!(!service.renew(&mut session, &expired));
+        assert_eq!(session.expires_at, expired);
+    }
+    
+    #[test]
+    fn test_parse_origin() {
+        let service = SessionService::new(3600);
+        let origin = |user_agent: &str| service.parse_origin(None, Some(user_agent));
+        
+        assert_eq!(
+            origin("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36 Edg/124.0.0.0"),
+            SessionOrigin {
+                ip: None,
+                client: Some("Edge".to_string()),
+                os: Some("Windows".to_string()),
+                device: Some("Desktop".to_string())
+            }
+        );
+        let iphone = origin("Mozilla/5.0 (iPhone; CPU iPhone OS 17_4 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1");
+        assert_eq!(iphone.client.as_deref(), Some("Safari"));
+        assert_eq!(iphone.os.as_deref(), Some("iOS"));
+        assert_eq!(iphone.device.as_deref(), Some("Mobile"));
+        let tablet = origin("Mozilla/5.0 (Linux; Android 14; SM-X710) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36");
+        assert_eq!(tablet.os.as_deref(), Some("Android"));
+        assert_eq!(tablet.device.as_deref(), Some("Tablet"));
+        
+        assert_eq!(
+            origin("aws-cli/2.15.0 Python/3.11.6 Linux/6.5.0 exe/x86_64.ubuntu.22"),
+            SessionOrigin {
+                ip: None,
+                client: Some("aws-cli".to_string()),
+                os: Some("Linux".to_string()),
+                device: None
+            }
+        );
+        assert_eq!(
+            service.parse_origin(Some("10.0.0.1".to_string()), None),
+            SessionOrigin { ip: Some("10.0.0.1".to_string()), ..SessionOrigin::default() }
+        );
     }
 }
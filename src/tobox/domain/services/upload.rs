@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+use chrono::{TimeDelta, Utc};
+use crate::domain::id_generator::generate_id;
+use crate::domain::models::object::ObjectId;
+use crate::domain::models::r#box::BoxId;
+use crate::domain::models::upload::{Upload, UploadId, UploadPart};
+use crate::domain::models::user::UserId;
+
+/// Sessions not completed within this time are removed with their parts
+const UPLOAD_TTL_HOURS: i64 = 24;
+
+pub struct UploadService { }
+
+impl UploadService {
+
+    pub fn create_upload(
+        &self,
+        box_id: BoxId,
+        name: Option<String>,
+        path: Option<String>,
+        metadata: HashMap<String, String>,
+        user_id: Option<UserId>,
+        object_id: Option<ObjectId>
+    ) -> Upload {
+        let now = Utc::now();
+        Upload {
+            id: generate_id(32),
+            box_id,
+            name,
+            path,
+            metadata,
+            user_id,
+            object_id,
+            created_at: now,
+            expires_at: now + TimeDelta::hours(UPLOAD_TTL_HOURS),
+        }
+    }
+
+    pub fn create_part(
+        &self,
+        upload_id: UploadId,
+        number: u32,
+        hash: String,
+        size: u64
+    ) -> UploadPart {
+        UploadPart {
+            upload_id,
+            number,
+            hash,
+            size,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn is_expired(&self, upload: &Upload) -> bool {
+        upload.expires_at < Utc::now()
+    }
+}
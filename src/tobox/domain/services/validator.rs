@@ -1,13 +1,20 @@
 use std::collections::HashMap;
 
 pub struct ValidatorService {
+    box_id_max_length: usize,
+    box_id_min_length: usize,
+    box_id_regex: regex::Regex,
+    
     object_name_max_length: usize,
     object_name_min_length: usize,
     object_name_regex: regex::Regex,
+    object_path_max_length: usize,
     
     metadata_key_max_length: usize,
     metadata_value_max_length: usize,
     
+    upload_part_number_max: u32,
+    
     username_max_length: usize,
     username_min_length: usize,
     username_regex: regex::Regex,
@@ -19,12 +26,20 @@ pub struct ValidatorService {
     role_description_max_length: usize,
     role_description_min_length: usize,
     
+    api_key_name_max_length: usize,
+    api_key_name_min_length: usize,
+    
 }
 
 impl ValidatorService {
 
     pub fn new() -> Self {
         
+        // Box id, compatible with s3 bucket names
+        let box_id_max_length = 63;
+        let box_id_min_length = 3;
+        let box_id_regex = regex::Regex::new(r"^[a-zA-Z0-9][a-zA-Z0-9._-]*[a-zA-Z0-9]$").unwrap();
+        
         // User - - - - - - - - - - - - - - - - - - - - - - - - - - -
         
         // Object name
@@ -32,10 +47,16 @@ impl ValidatorService {
         let object_name_min_length = 1;
         let object_name_regex = regex::Regex::new(r"^[^/\\:?]+$").unwrap();
         
+        // Object path, segments are checked as names
+        let object_path_max_length = 1024;
+        
         // Object metadata
         let metadata_key_max_length = 64;
         let metadata_value_max_length = 256;
         
+        // Upload part number
+        let upload_part_number_max = 10000;
+        
         // Username
         let username_max_length = 32;
         let username_min_length = 4;
@@ -53,12 +74,22 @@ impl ValidatorService {
         let role_description_max_length = 255;
         let role_description_min_length = 4;
         
+        // Api key - - - - - - - - - - - - - - - - - - - - - - - - - -
+        
+        let api_key_name_max_length = 64;
+        let api_key_name_min_length = 1;
+        
         ValidatorService {
+            box_id_max_length,
+            box_id_min_length,
+            box_id_regex,
             object_name_max_length,
             object_name_min_length,
             object_name_regex,
+            object_path_max_length,
             metadata_key_max_length,
             metadata_value_max_length,
+            upload_part_number_max,
             username_max_length,
             username_min_length,
             username_regex,
@@ -68,6 +99,8 @@ impl ValidatorService {
             role_title_min_length,
             role_description_max_length,
             role_description_min_length,
+            api_key_name_max_length,
+            api_key_name_min_length,
         }
     }
 
@@ -119,6 +152,24 @@ impl ValidatorService {
         Ok(())
     }
 
+    pub fn validate_box_id(&self, box_id: &str) -> Result<(), String> {
+        if box_id.len() > self.box_id_max_length || box_id.len() < self.box_id_min_length {
+            return Err(format!(
+                "Box id should be between {} and {} characters",
+                self.box_id_min_length,
+                self.box_id_max_length
+            ));
+        }
+
+        if !self.box_id_regex.is_match(box_id) {
+            return Err(
+                "Box id should contain only letters, numbers, dots, hyphens and underscores".to_string()
+            );
+        }
+
+        Ok(())
+    }
+
     pub fn validate_object_name(&self, last_name: &str) -> Result<(), String> {
         if last_name.len() > self.object_name_max_length  || last_name.len() < self.object_name_min_length {
             return Err(format!(
@@ -136,6 +187,34 @@ impl ValidatorService {
     }
     
     
+    pub fn validate_object_path(&self, path: &str) -> Result<(), String> {
+        if path.len() > self.object_path_max_length {
+            return Err(format!(
+                "Path should be less than {} characters",
+                self.object_path_max_length
+            ));
+        }
+        
+        for segment in path.split('/') {
+            if segment.is_empty() || segment == "." || segment == ".." {
+                return Err("Path should not contain empty, . or .. segments".to_string());
+            }
+            self.validate_object_name(segment).map_err(|e| format!("Path segment: {}", e))?;
+        }
+        
+        Ok(())
+    }
+    
+    pub fn validate_upload_part_number(&self, number: &u32) -> Result<(), String> {
+        if *number < 1 || *number > self.upload_part_number_max {
+            return Err(format!(
+                "Part number should be between 1 and {}",
+                self.upload_part_number_max
+            ));
+        }
+        Ok(())
+    }
+    
     pub fn validate_object_metadata(&self, metadata: &HashMap<String, String>) -> Result<(), String> {
         for (key, value) in metadata.iter() {
             if key.len() > self.metadata_key_max_length {
@@ -154,6 +233,29 @@ impl ValidatorService {
         Ok(())
     }
     
+    /// Blobs are addressed by the lowercase hex of their sha256
+    pub fn validate_blob_hash(&self, hash: &str) -> Result<(), String> {
+        if hash.len() != 64 || !hash.bytes().all(|c| c.is_ascii_digit() || (b'a'..=b'f').contains(&c)) {
+            return Err("Hash should be 64 lowercase hex characters".to_string());
+        }
+        Ok(())
+    }
+    
+    /// Node addresses are base urls, e.g. `http://10.0.0.2:3030`
+    pub fn validate_node_address(&self, address: &str) -> Result<(), String> {
+        let host = match address.strip_prefix("http://").or(address.strip_prefix("https://")) {
+            Some(host) => host,
+            None => return Err("Node address should start with http:// or https://".to_string())
+        };
+        if host.is_empty() || address.len() > 255 {
+            return Err("Node address should be between 1 and 255 characters".to_string());
+        }
+        if host.contains(['/', '?', '#']) || host.contains(char::is_whitespace) {
+            return Err("Node address should contain only a host and a port".to_string());
+        }
+        Ok(())
+    }
+    
     pub fn validate_role_title(&self, title: &str) -> Result<(), String> {
         if title.len() < self.role_title_min_length || title.len() > self.role_title_max_length {
             return Err(format!(
@@ -176,6 +278,17 @@ impl ValidatorService {
         Ok(())
     }
     
+    pub fn validate_api_key_name(&self, name: &str) -> Result<(), String> {
+        if name.len() < self.api_key_name_min_length || name.len() > self.api_key_name_max_length {
+            return Err(format!(
+                "Api key name should be between {} and {} characters",
+                self.api_key_name_min_length,
+                self.api_key_name_max_length
+            ));
+        }
+        Ok(())
+    }
+    
 
     pub fn validate_page(&self, page: &u64) -> Result<(), String> {
         if *page == 0 {
@@ -184,6 +297,13 @@ impl ValidatorService {
         Ok(())
     }
     
+    pub fn validate_list_limit(&self, limit: &u64) -> Result<(), String> {
+        if *limit == 0 || *limit > 1000 {
+            return Err("Limit should be between 1 and 1000".to_string());
+        }
+        Ok(())
+    }
+    
     pub fn validate_per_page(&self, per_page: &u64) -> Result<(), String> {
         if *per_page == 0 {
             return Err("Number of elements per page should be greater than 0".to_string());
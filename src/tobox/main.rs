@@ -12,6 +12,7 @@ mod application;
 mod adapters;
 mod node;
 mod panel;
+mod migrate;
 
 
 fn main() -> std::io::Result<()> {
@@ -21,7 +22,12 @@ fn main() -> std::io::Result<()> {
             .filter_level(log::LevelFilter::from_str(log_level).unwrap())
             .init();
     }
-    
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|command| command == "migrate") {
+        std::process::exit(migrate::run(&config, &args[1..]));
+    }
+
     thread::scope(|scope| {
         if let Some(panel_config) = &config.get().panel {
             let mut server = PanelServer::new();
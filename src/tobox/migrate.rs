@@ -0,0 +1,133 @@
+use crate::adapters::database::migration::{
+    self,
+    Direction,
+    EMPTY_SCHEMA,
+    MigrationStep
+};
+use crate::adapters::database::sqlite::{self, DEFAULT_DATABASE};
+use crate::config::ConfigManager;
+
+const USAGE: &str = "Usage: tobox migrate [--to <version>] [--dry-run]
+
+Takes the database schema of the node to the version, the latest one known to this release when not set.
+Version 0 reverts every migration. With --dry-run the migrations and the planned steps are only listed.";
+
+#[derive(Debug, PartialEq)]
+struct MigrateArgs {
+    target: Option<i64>,
+    dry_run: bool
+}
+
+fn parse_args(args: &[String]) -> Result<MigrateArgs, String> {
+    let mut parsed = MigrateArgs { target: None, dry_run: false };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dry-run" => parsed.dry_run = true,
+            "--to" => {
+                let version = args.next().ok_or("--to requires a version")?;
+                parsed.target = Some(version.parse().map_err(|_| format!("Invalid version: {}", version))?);
+            },
+            other => return Err(format!("Unknown argument: {}", other))
+        }
+    }
+    Ok(parsed)
+}
+
+fn describe(step: &MigrationStep, dry_run: bool) -> String {
+    let action = match (step.direction, dry_run) {
+        (Direction::Up, true) => "Would apply",
+        (Direction::Up, false) => "Applied",
+        (Direction::Down, true) => "Would revert",
+        (Direction::Down, false) => "Reverted"
+    };
+    format!("{} {} {}", action, step.version, step.description)
+}
+
+/// `tobox migrate`: upgrade or downgrade the schema explicitly
+///
+/// * return: exit code of the process
+pub fn run(config_manager: &ConfigManager, args: &[String]) -> i32 {
+    let args = match parse_args(args) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, USAGE);
+            return 2
+        }
+    };
+    let database = config_manager.get().node
+        .and_then(|node| node.database)
+        .unwrap_or_else(|| DEFAULT_DATABASE.to_string());
+
+    let rt = actix_web::rt::Runtime::new().unwrap();
+    rt.block_on(async {
+        let db = match sqlite::open(&database).await {
+            Ok(db) => db,
+            Err(error) => {
+                eprintln!("Failed to open the database {}: {}", database, error);
+                return 1
+            }
+        };
+
+        if args.dry_run {
+            let status = match migration::status(&db).await {
+                Ok(status) => status,
+                Err(error) => {
+                    eprintln!("{}", error);
+                    return 1
+                }
+            };
+            let version = status.iter()
+                .filter(|migration| migration.applied)
+                .map(|migration| migration.version)
+                .max()
+                .unwrap_or(EMPTY_SCHEMA);
+            println!("Schema version {}, latest known {}", version, migration::latest_version());
+            for migration in &status {
+                println!(
+                    "  [{}] {} {}",
+                    if migration.applied { "x" } else { " " },
+                    migration.version,
+                    migration.description
+                );
+            }
+        }
+
+        let result = match args.dry_run {
+            true => migration::plan(&db, args.target).await,
+            false => migration::migrate(&db, args.target).await
+        };
+        match result {
+            Ok(steps) if steps.is_empty() => println!("Nothing to migrate"),
+            Ok(steps) => for step in &steps {
+                println!("{}", describe(step, args.dry_run));
+            },
+            Err(error) => {
+                eprintln!("{}", error);
+                return 1
+            }
+        }
+        0
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse_args(&args(&[])), Ok(MigrateArgs { target: None, dry_run: false }));
+        assert_eq!(
+            parse_args(&args(&["--to", "0", "--dry-run"])),
+            Ok(MigrateArgs { target: Some(0), dry_run: true })
+        );
+        assert!(parse_args(&args(&["--to"])).is_err());
+        assert!(parse_args(&args(&["--to", "latest"])).is_err());
+        assert!(parse_args(&args(&["--force"])).is_err());
+    }
+}
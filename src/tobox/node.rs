@@ -9,13 +9,35 @@ use actix_web::{App, HttpServer as ActixHttpServer, web};
 use actix_web::http::KeepAlive;
 use actix_web::middleware::Logger;
 
+use crate::adapters::auth::token::TokenProcessor;
+use crate::adapters::database::api_key_db::ApiKeyGateway;
+use crate::adapters::database::disk;
+use crate::adapters::database::permission_db::PermissionGateway;
+use crate::adapters::database::session_db::SessionGateway;
+use crate::adapters::database::sqlite::{self, DEFAULT_DATABASE};
+use crate::adapters::hmac_signer::HmacSigner;
+use crate::adapters::sha256_session_hasher::Sha256SessionHasher;
+use crate::application::cluster::heartbeat::heartbeat_nodes;
+use crate::application::initial;
+use crate::application::common::interactor::Interactor;
 use crate::application::common::server::{ConnectionConfig, Server};
 use crate::config::ConfigManager;
+use crate::domain::models::node::NodeId;
 use crate::domain::models::service::ServiceTextId;
+use crate::domain::services::session::SessionService;
 use crate::ioc::IoC;
 use crate::presentation;
+use crate::presentation::node::id_provider::ClusterSecret;
 use crate::presentation::node::interactor_factory::InteractorFactory;
 
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+const METADATA_TICK_INTERVAL: Duration = Duration::from_millis(200);
+const REPLICATION_INTERVAL: Duration = Duration::from_secs(10);
+const REBALANCE_INTERVAL: Duration = Duration::from_secs(30);
+const SCRUB_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+const SESSION_SWEEP_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// Seconds a session lives without being used
+const SESSION_TTL: u32 = 7 * 24 * 60 * 60;
 
 pub struct NodeServer {
     connection_config: Arc<Mutex<ConnectionConfig>>,
@@ -37,6 +59,25 @@ impl NodeServer {
             config_manager
         }
     }
+    
+    /// Id of this node from the config, generated and saved when not set
+    fn node_id(&self) -> NodeId {
+        let mut config = self.config_manager.get();
+        let node_config = config.node.as_mut().expect("Node config is not set");
+        if let Some(id) = &node_config.id {
+            return id.clone()
+        }
+        
+        let id = self.ioc.node_service().generate_node_id();
+        node_config.id = Some(id.clone());
+        self.config_manager.set(config);
+        if let Err(error) = self.config_manager.save() {
+            log::error!("Failed to save the node id: {}", error);
+            std::process::exit(1);
+        }
+        log::info!("Node id {} generated", id);
+        id
+    }
 }
 
 impl Server for NodeServer {
@@ -60,8 +101,145 @@ impl Server for NodeServer {
     fn run(self) -> io::Result<()> {
         let rt = actix_web::rt::Runtime::new().unwrap();
         rt.block_on(async {
+            let database = self.config_manager.get().node
+                .and_then(|node| node.database)
+                .unwrap_or_else(|| DEFAULT_DATABASE.to_string());
+            // Gateways of the IoC share the pool, the schema is migrated before anything reads it
+            let db = match sqlite::connect(&database).await {
+                Ok(db) => db,
+                Err(error) => {
+                    log::error!("Failed to open the database {}: {}", database, error);
+                    std::process::exit(1);
+                }
+            };
             let ioc = self.ioc.clone();
             let app_config_provider = self.app_config_provider.clone();
+            let token_processor = web::Data::new(TokenProcessor::new(
+                Arc::new(SessionGateway::new(db.clone())),
+                Arc::new(ApiKeyGateway::new(db.clone())),
+                Arc::new(PermissionGateway::new(db.clone())),
+                Arc::new(Sha256SessionHasher {}),
+                SessionService::new(SESSION_TTL)
+            ));
+            let s3_config = self.config_manager.get().node.and_then(|node| node.s3);
+            let node_id = self.node_id();
+            let cluster_config = self.config_manager.get().node.and_then(|node| node.cluster);
+            let seeds = cluster_config.as_ref()
+                .map(|cluster| cluster.nodes.clone())
+                .unwrap_or_default();
+            // Derived from the cluster secret, a url presigned by one node is accepted by the others
+            let secret = cluster_config.as_ref().and_then(|cluster| cluster.secret.as_deref());
+            let url_signer = web::Data::new(match secret {
+                Some(secret) => HmacSigner::new(secret, "presign"),
+                None => {
+                    log::warn!("Cluster secret is not set, presigned urls are valid until the node restarts");
+                    HmacSigner::random()
+                }
+            });
+            let cluster_secret = web::Data::new(ClusterSecret(
+                cluster_config.and_then(|cluster| cluster.secret)
+            ));
+            // Intermediate nodes keep no objects and pass object requests to storage nodes
+            let is_intermediate = self.is_intermediate;
+            
+            // Leftovers of an unclean shutdown are removed before anything uses the storage
+            if !is_intermediate {
+                let disk_config = self.config_manager.get().node
+                    .expect("Node config is not set")
+                    .disk;
+                let file_storage = match disk::open(&disk_config) {
+                    Ok(file_storage) => file_storage,
+                    Err(error) => {
+                        log::error!("Failed to open the storage volumes: {}", error);
+                        std::process::exit(1);
+                    }
+                };
+                initial::file_storage(
+                    ioc.object_reader(),
+                    ioc.upload_gateway(),
+                    file_storage.as_ref(),
+                    ioc.blob_locks()
+                ).await;
+            }
+            
+            let heartbeat_ioc = ioc.clone();
+            actix_web::rt::spawn(async move {
+                let mut interval = actix_web::rt::time::interval(HEARTBEAT_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    heartbeat_nodes(
+                        heartbeat_ioc.node_gateway(),
+                        heartbeat_ioc.node_client(),
+                        heartbeat_ioc.node_service(),
+                        &node_id,
+                        &seeds
+                    ).await;
+                }
+            });
+            
+            let metadata_ioc = ioc.clone();
+            actix_web::rt::spawn(async move {
+                let requests = metadata_ioc.metadata_proposer().take_requests()
+                    .expect("Metadata proposals are taken twice");
+                metadata_ioc.metadata_consensus().run(requests, METADATA_TICK_INTERVAL).await;
+            });
+            
+            let session_ioc = ioc.clone();
+            actix_web::rt::spawn(async move {
+                let mut interval = actix_web::rt::time::interval(SESSION_SWEEP_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    match session_ioc.sweep_sessions().execute(()).await {
+                        Ok(0) => (),
+                        Ok(count) => log::debug!("Removed {} expired sessions", count),
+                        Err(error) => log::error!("Session sweep failed: {}", error)
+                    }
+                }
+            });
+            
+            if !is_intermediate {
+                let replication_ioc = ioc.clone();
+                actix_web::rt::spawn(async move {
+                    let mut interval = actix_web::rt::time::interval(REPLICATION_INTERVAL);
+                    loop {
+                        interval.tick().await;
+                        if let Err(error) = replication_ioc.replicate_objects().execute(()).await {
+                            log::error!("Object replication failed: {}", error);
+                        }
+                    }
+                });
+                
+                let rebalance_ioc = ioc.clone();
+                actix_web::rt::spawn(async move {
+                    let mut interval = actix_web::rt::time::interval(REBALANCE_INTERVAL);
+                    loop {
+                        interval.tick().await;
+                        if let Err(error) = rebalance_ioc.rebalance_objects().execute(()).await {
+                            log::error!("Object rebalancing failed: {}", error);
+                        }
+                    }
+                });
+                
+                let scrub_ioc = ioc.clone();
+                actix_web::rt::spawn(async move {
+                    let mut interval = actix_web::rt::time::interval(SCRUB_INTERVAL);
+                    // The first tick is immediate, a restarted node is not scrubbed right away
+                    interval.tick().await;
+                    loop {
+                        interval.tick().await;
+                        match scrub_ioc.scrub_blobs().execute(()).await {
+                            Ok(report) => log::info!(
+                                "Scrub checked {} blobs: {} repaired, {} restored, {} damaged",
+                                report.blobs_checked,
+                                report.blobs_repaired,
+                                report.blobs_restored,
+                                report.blobs_damaged.len()
+                            ),
+                            Err(error) => log::error!("Scrub failed: {}", error)
+                        }
+                    }
+                });
+            }
 
             let app_builder = move || {
                 let ioc_arc: Arc<dyn InteractorFactory> = ioc.clone();
@@ -70,17 +248,28 @@ impl Server for NodeServer {
                 App::new()
                     .service(web::scope("/node")
                         .configure(presentation::panel::rest::user::router)
-                        .configure(presentation::panel::rest::session::router)
                         .configure(presentation::panel::rest::access_log::router)
                         .configure(presentation::panel::rest::role::router)
                         .configure(presentation::panel::rest::stats::router)
                         .configure(presentation::panel::rest::permission::router)
                         .configure(presentation::panel::rest::service::router)
+                        .configure(|cfg| presentation::node::router(cfg, is_intermediate))
                     )
+                    .configure(|cfg| if let Some(s3_config) = &s3_config {
+                        cfg.app_data(web::Data::new(s3_config.clone()));
+                        if is_intermediate {
+                            presentation::node::proxy::s3_router(cfg);
+                        } else {
+                            presentation::node::s3::router(cfg);
+                        }
+                    })
                     .app_data(web::Data::new(
                         app_config_provider.clone()
                     ))
                     .app_data(ioc_data)
+                    .app_data(token_processor.clone())
+                    .app_data(cluster_secret.clone())
+                    .app_data(url_signer.clone())
                     .default_service(web::route().to(presentation::panel::exception::not_found))
                     .wrap(Logger::default())
             };
@@ -21,6 +21,7 @@ impl ApplicationError {
             ApplicationError::Conflict(ref content) => (StatusCode::CONFLICT, content.clone()),
             ApplicationError::Forbidden(ref content) => (StatusCode::FORBIDDEN, content.clone()),
             ApplicationError::Unauthorized(ref content) => (StatusCode::UNAUTHORIZED, content.clone()),
+            ApplicationError::Unavailable(ref content) => (StatusCode::SERVICE_UNAVAILABLE, content.clone()),
         }
     }
     
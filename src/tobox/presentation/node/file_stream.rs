@@ -0,0 +1,57 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use tokio::io;
+use tokio::sync::mpsc;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::domain::models::file_stream::FileStream;
+
+const CHANNEL_CAPACITY: usize = 8;
+
+/// File stream fed by the request handler
+///
+/// Multipart fields are bound to the worker thread, so their chunks are passed
+/// through a bounded channel, which also slows the client down when storage
+/// can not keep up.
+pub struct ChannelFileStream {
+    receiver: mpsc::Receiver<io::Result<Bytes>>,
+}
+
+impl Stream for ChannelFileStream {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>
+    ) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl FileStream for ChannelFileStream {}
+
+pub fn channel_file_stream() -> (mpsc::Sender<io::Result<Bytes>>, ChannelFileStream) {
+    let (sender, receiver) = mpsc::channel(CHANNEL_CAPACITY);
+    (sender, ChannelFileStream { receiver })
+}
+
+/// Pass request body chunks (a multipart field or a raw payload) to the channel
+/// until the body ends or the reader is gone
+pub async fn forward_stream<S, E>(stream: S, sender: mpsc::Sender<io::Result<Bytes>>)
+where
+    S: Stream<Item = Result<Bytes, E>>,
+    E: ToString
+{
+    tokio::pin!(stream);
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(
+            |error| io::Error::new(io::ErrorKind::Interrupted, error.to_string())
+        );
+        let is_err = chunk.is_err();
+        if sender.send(chunk).await.is_err() || is_err {
+            break;
+        }
+    }
+}
@@ -1,16 +1,203 @@
-use actix_web::HttpRequest;
+use actix_web::{HttpRequest, web};
+use actix_web::http::header::{AUTHORIZATION, CONTENT_LENGTH};
 
+use crate::adapters::auth::api_key::IdApiKeyProvider;
+use crate::adapters::auth::forwarded::{
+    FORWARDED_OBJECT_ID_HEADER,
+    FORWARDED_PERMISSIONS_HEADER,
+    FORWARDED_USER_HEADER,
+    IdForwardedProvider
+};
+use crate::adapters::auth::presign::{
+    IdPresignedProvider,
+    PRESIGN_SIGNATURE_PARAM,
+    PresignedQuery,
+    PresignedRequest
+};
+use crate::adapters::auth::sigv4::{IdSigV4Provider, SignedRequest};
 use crate::adapters::auth::token::{IdTokenProvider, TokenProcessor};
+use crate::adapters::hmac_signer::HmacSigner;
+use crate::adapters::http_node_client::CLUSTER_SECRET_HEADER;
+use crate::application::common::access_key_gateway::AccessKeyReader;
 use crate::application::common::exceptions::{ApplicationError, ErrorContent};
 use crate::application::common::id_provider::IdProvider;
+use crate::application::common::permission_gateway::PermissionReader;
+use crate::domain::models::object::ObjectId;
+use crate::domain::models::presign::PresignedAction;
+use crate::domain::services::access::AccessService;
+use crate::domain::services::api_key::API_KEY_PREFIX;
+use crate::domain::services::presign::PresignService;
 
-pub fn make_token_provider(
+/// Cookie holding the session token
+pub const TOKEN_COOKIE: &str = "token";
+const BEARER_PREFIX: &str = "Bearer ";
+
+/// Path of object requests, the only ones presigned urls are made for
+pub const OBJECT_PATH: &str = "/node/object";
+
+/// Secret from the cluster config, shared by the nodes
+pub struct ClusterSecret(pub Option<String>);
+
+/// The token is taken from `Authorization: Bearer <token>` or the cookie,
+/// api keys are told from session tokens by their prefix.
+/// A request with a presigned url is authorized by the url alone.
+pub async fn make_token_provider(
     req: &HttpRequest,
     token_processor: &TokenProcessor
 ) -> Result<Box<dyn IdProvider>, ApplicationError> {
-    let token = req.cookie("token").map(|cookie| cookie.value().to_string());
-    match IdTokenProvider::new(token, token_processor) {
+    if let Some(provider) = make_forwarded_provider(req)? {
+        return Ok(provider)
+    }
+    if let Some(provider) = make_presigned_provider(req)? {
+        return Ok(provider)
+    }
+    
+    let token = match req.headers().get(AUTHORIZATION) {
+        Some(value) => {
+            let value = value.to_str().map_err(
+                |error| ApplicationError::Unauthorized(ErrorContent::from(error.to_string()))
+            )?;
+            match value.strip_prefix(BEARER_PREFIX) {
+                Some(token) => Some(token.trim().to_string()),
+                None => return Err(ApplicationError::Unauthorized(
+                    ErrorContent::from("Only Bearer authorization is supported")
+                ))
+            }
+        },
+        None => req.cookie(TOKEN_COOKIE).map(|cookie| cookie.value().to_string())
+    };
+    
+    if let Some(key) = token.as_deref().filter(|token| token.starts_with(API_KEY_PREFIX)) {
+        return match IdApiKeyProvider::new(key, token_processor).await {
+            Ok(provider) => Ok(Box::new(provider)),
+            Err(error) => Err(ApplicationError::Unauthorized(ErrorContent::from(error)))
+        }
+    }
+    match IdTokenProvider::new(token, token_processor).await {
+        Ok(provider) => Ok(Box::new(provider)),
+        Err(error) => Err(ApplicationError::Unauthorized(ErrorContent::from(error)))
+    }
+}
+
+/// Requests without the `Authorization` header are served as anonymous,
+/// `Bearer` authorization is passed to `make_token_provider`
+pub async fn make_sigv4_provider(
+    req: &HttpRequest,
+    region: &str,
+    token_processor: &TokenProcessor,
+    access_key_reader: &dyn AccessKeyReader,
+    permission_reader: &dyn PermissionReader
+) -> Result<Box<dyn IdProvider>, ApplicationError> {
+    if let Some(provider) = make_forwarded_provider(req)? {
+        return Ok(provider)
+    }
+    
+    let authorization = match req.headers().get(AUTHORIZATION) {
+        Some(value) => value.to_str().map_err(
+            |error| ApplicationError::Unauthorized(ErrorContent::from(error.to_string()))
+        )?,
+        None => return make_token_provider(req, token_processor).await
+    };
+    if authorization.starts_with(BEARER_PREFIX) {
+        return make_token_provider(req, token_processor).await
+    }
+
+    let request = SignedRequest {
+        method: req.method().to_string(),
+        path: req.uri().path().to_string(),
+        query: req.query_string().to_string(),
+        headers: req.headers().iter()
+            .filter_map(|(name, value)| Some((
+                name.as_str().to_lowercase(),
+                value.to_str().ok()?.to_string()
+            )))
+            .collect()
+    };
+
+    match IdSigV4Provider::new(
+        &request,
+        authorization,
+        region,
+        access_key_reader,
+        permission_reader
+    ).await {
         Ok(provider) => Ok(Box::new(provider)),
         Err(error) => Err(ApplicationError::Unauthorized(ErrorContent::from(error)))
     }
 }
+
+/// Identity of a request proxied by an intermediate node, which has already
+/// authenticated the caller
+fn make_forwarded_provider(
+    req: &HttpRequest
+) -> Result<Option<Box<dyn IdProvider>>, ApplicationError> {
+    let header = |name: &str| req.headers().get(name).and_then(|value| value.to_str().ok());
+    let permissions = match header(FORWARDED_PERMISSIONS_HEADER) {
+        Some(permissions) => permissions,
+        None => return Ok(None)
+    };
+    
+    let cluster_secret = req.app_data::<web::Data<ClusterSecret>>()
+        .and_then(|secret| secret.0.as_deref());
+    AccessService {}.ensure_is_peer(header(CLUSTER_SECRET_HEADER), cluster_secret).map_err(
+        |error| ApplicationError::Unauthorized(ErrorContent::from(error))
+    )?;
+    
+    match IdForwardedProvider::new(header(FORWARDED_USER_HEADER), permissions) {
+        Ok(provider) => Ok(Some(Box::new(provider))),
+        Err(error) => Err(ApplicationError::Unauthorized(ErrorContent::from(error)))
+    }
+}
+
+/// Id of a new object placed by an intermediate node, trusted only together with the cluster secret
+pub fn forwarded_object_id(req: &HttpRequest) -> Result<Option<ObjectId>, ApplicationError> {
+    let header = |name: &str| req.headers().get(name).and_then(|value| value.to_str().ok());
+    let object_id = match header(FORWARDED_OBJECT_ID_HEADER) {
+        Some(object_id) => object_id,
+        None => return Ok(None)
+    };
+    
+    let cluster_secret = req.app_data::<web::Data<ClusterSecret>>()
+        .and_then(|secret| secret.0.as_deref());
+    AccessService {}.ensure_is_peer(header(CLUSTER_SECRET_HEADER), cluster_secret).map_err(
+        |error| ApplicationError::Unauthorized(ErrorContent::from(error))
+    )?;
+    Ok(Some(object_id.to_string()))
+}
+
+/// Requests with the signature in the query are made with a presigned url
+fn make_presigned_provider(
+    req: &HttpRequest
+) -> Result<Option<Box<dyn IdProvider>>, ApplicationError> {
+    if !req.query_string().contains(PRESIGN_SIGNATURE_PARAM) {
+        return Ok(None)
+    }
+    
+    let query = web::Query::<PresignedQuery>::from_query(req.query_string()).map_err(
+        |error| ApplicationError::Unauthorized(ErrorContent::from(error.to_string()))
+    )?.into_inner();
+    let action = match req.path().strip_prefix(OBJECT_PATH) {
+        Some("") | Some("/") => Some(PresignedAction::CreateObject),
+        Some(path) => path.strip_prefix('/')
+            .filter(|id| !id.contains('/'))
+            .map(|id| PresignedAction::GetObject(id.to_string())),
+        None => None
+    }.ok_or(ApplicationError::Unauthorized(
+        ErrorContent::from("Presigned urls are valid for object downloads and uploads only")
+    ))?;
+    let request = PresignedRequest {
+        method: req.method().as_str(),
+        action,
+        content_length: req.headers().get(CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse().ok())
+    };
+    
+    let signer = req.app_data::<web::Data<HmacSigner>>().ok_or(
+        ApplicationError::Unauthorized(ErrorContent::from("Presigned urls are not supported"))
+    )?;
+    match IdPresignedProvider::new(query, &request, signer.get_ref(), &PresignService {}) {
+        Ok(provider) => Ok(Some(Box::new(provider))),
+        Err(error) => Err(ApplicationError::Unauthorized(ErrorContent::from(error)))
+    }
+}
@@ -1,7 +1,35 @@
+use crate::application::access_key::create::CreateAccessKey;
+use crate::application::access_key::delete::DeleteAccessKey;
+use crate::application::access_key::get_self::GetSelfAccessKeys;
+use crate::application::api_key::create::CreateApiKey;
+use crate::application::api_key::delete::DeleteApiKey;
+use crate::application::api_key::get_self::GetSelfApiKeys;
+use crate::application::cluster::add_node::AddNode;
+use crate::application::cluster::get_info::GetNodeInfo;
+use crate::application::cluster::get_nodes::GetNodes;
+use crate::application::cluster::get_scrub_status::GetScrubStatus;
+use crate::application::cluster::remove_node::RemoveNode;
+use crate::application::common::access_key_gateway::AccessKeyReader;
+use crate::application::common::blob_lock::BlobLocks;
 use crate::application::common::id_provider::IdProvider;
+use crate::application::common::node_client::NodeClient;
+use crate::application::common::node_gateway::NodeGateway;
+use crate::application::common::object_gateway::ObjectReader;
+use crate::application::common::permission_gateway::PermissionReader;
+use crate::application::common::upload_gateway::UploadGateway;
+use crate::application::metadata::append_entries::AppendEntries;
+use crate::application::metadata::consensus::MetadataConsensus;
+use crate::application::metadata::proposer::MetadataProposer;
+use crate::application::metadata::receive_proposal::ReceiveProposal;
+use crate::application::metadata::request_vote::RequestVote;
 use crate::application::object::create::CreateObject;
+use crate::application::object::delete::DeleteObject;
+use crate::application::object::find::FindObject;
 use crate::application::object::get::GetObject;
 use crate::application::object::get_info::GetObjectInfo;
+use crate::application::object::list::ListObjects;
+use crate::application::object::presign::PresignObject;
+use crate::application::object::update::UpdateObject;
 use crate::application::permission::get_by_role::GetRolePermissions;
 use crate::application::permission::get_by_user::GetUserPermissions;
 use crate::application::permission::get_range::GetPermissionRange;
@@ -9,6 +37,7 @@ use crate::application::permission::link::LinkRolePermission;
 use crate::application::permission::unlink::UnlinkRolePermission;
 use crate::application::r#box::create::CreateBox;
 use crate::application::r#box::delete::DeleteBox;
+use crate::application::r#box::update::UpdateBox;
 use crate::application::r#box::get_range::GetBoxRange;
 use crate::application::role::create::CreateRole;
 use crate::application::role::delete::DeleteRole;
@@ -22,11 +51,32 @@ use crate::application::role::set_default::SetDefaultRole;
 use crate::application::role::unlink::UnlinkRoleUser;
 use crate::application::role::update::UpdateRole;
 use crate::application::session::create::CreateSession;
+use crate::application::session::delete::DeleteSession;
+use crate::application::session::delete_all_self::DeleteAllSelfSessions;
+use crate::application::session::delete_self::DeleteSelfSession;
+use crate::application::session::get_by_user::GetUserSessions;
+use crate::application::session::get_self::GetSelfSessions;
+use crate::application::session::sweep::SweepSessions;
+use crate::application::sync::check_blob::CheckBlob;
+use crate::application::sync::get_digests::GetDigests;
+use crate::application::sync::receive_blob::ReceiveBlob;
+use crate::application::sync::rebalance::RebalanceObjects;
+use crate::application::sync::receive_object::ReceiveObject;
+use crate::application::sync::replicate::ReplicateObjects;
+use crate::application::sync::scrub::ScrubBlobs;
+use crate::application::sync::send_blob::SendBlob;
+use crate::application::upload::abort::AbortUpload;
+use crate::application::upload::complete::CompleteUpload;
+use crate::application::upload::get_parts::GetUploadParts;
+use crate::application::upload::initiate::InitiateUpload;
+use crate::application::upload::put_part::PutUploadPart;
 use crate::application::user::create::CreateUser;
 use crate::application::user::get_by_id::GetUserById;
 use crate::application::user::get_range::GetUserRange;
 use crate::application::user::get_self::GetUserSelf;
 use crate::application::user::delete::DeleteUser;
+use crate::domain::services::node::NodeService;
+use crate::domain::services::placement::PlacementService;
 
 
 pub trait InteractorFactory {
@@ -37,6 +87,19 @@ pub trait InteractorFactory {
     fn delete_user(&self, id_provider: Box<dyn IdProvider>) -> DeleteUser;
     
     fn create_session(&self, id_provider: Box<dyn IdProvider>) -> CreateSession;
+    fn get_self_sessions(&self, id_provider: Box<dyn IdProvider>) -> GetSelfSessions;
+    fn get_user_sessions(&self, id_provider: Box<dyn IdProvider>) -> GetUserSessions;
+    fn delete_session(&self, id_provider: Box<dyn IdProvider>) -> DeleteSession;
+    fn delete_self_session(&self, id_provider: Box<dyn IdProvider>) -> DeleteSelfSession;
+    fn delete_all_self_sessions(&self, id_provider: Box<dyn IdProvider>) -> DeleteAllSelfSessions;
+    
+    fn create_access_key(&self, id_provider: Box<dyn IdProvider>) -> CreateAccessKey;
+    fn get_self_access_keys(&self, id_provider: Box<dyn IdProvider>) -> GetSelfAccessKeys;
+    fn delete_access_key(&self, id_provider: Box<dyn IdProvider>) -> DeleteAccessKey;
+    
+    fn create_api_key(&self, id_provider: Box<dyn IdProvider>) -> CreateApiKey;
+    fn get_self_api_keys(&self, id_provider: Box<dyn IdProvider>) -> GetSelfApiKeys;
+    fn delete_api_key(&self, id_provider: Box<dyn IdProvider>) -> DeleteApiKey;
     
     fn create_role(&self, id_provider: Box<dyn IdProvider>) -> CreateRole;
     fn get_role_by_id(&self, id_provider: Box<dyn IdProvider>) -> GetRoleById;
@@ -57,11 +120,64 @@ pub trait InteractorFactory {
     fn unlink_role_permission(&self, id_provider: Box<dyn IdProvider>) -> UnlinkRolePermission;
     
     fn create_box(&self, id_provider: Box<dyn IdProvider>) -> CreateBox;
+    fn update_box(&self, id_provider: Box<dyn IdProvider>) -> UpdateBox;
     fn delete_box(&self, id_provider: Box<dyn IdProvider>) -> DeleteBox;
     fn get_box_range(&self, id_provider: Box<dyn IdProvider>) -> GetBoxRange;
     
     fn create_object(&self, id_provider: Box<dyn IdProvider>) -> CreateObject;
     fn get_object(&self, id_provider: Box<dyn IdProvider>) -> GetObject;
     fn get_object_info(&self, id_provider: Box<dyn IdProvider>) -> GetObjectInfo;
+    fn presign_object(&self, id_provider: Box<dyn IdProvider>) -> PresignObject;
+    fn update_object(&self, id_provider: Box<dyn IdProvider>) -> UpdateObject;
+    fn delete_object(&self, id_provider: Box<dyn IdProvider>) -> DeleteObject;
+    fn find_object(&self, id_provider: Box<dyn IdProvider>) -> FindObject;
+    fn list_objects(&self, id_provider: Box<dyn IdProvider>) -> ListObjects;
+    
+    fn initiate_upload(&self, id_provider: Box<dyn IdProvider>) -> InitiateUpload;
+    fn put_upload_part(&self, id_provider: Box<dyn IdProvider>) -> PutUploadPart;
+    fn get_upload_parts(&self, id_provider: Box<dyn IdProvider>) -> GetUploadParts;
+    fn complete_upload(&self, id_provider: Box<dyn IdProvider>) -> CompleteUpload;
+    fn abort_upload(&self, id_provider: Box<dyn IdProvider>) -> AbortUpload;
+    
+    fn get_node_info(&self) -> GetNodeInfo;
+    fn get_nodes(&self, id_provider: Box<dyn IdProvider>) -> GetNodes;
+    fn add_node(&self, id_provider: Box<dyn IdProvider>) -> AddNode;
+    fn remove_node(&self, id_provider: Box<dyn IdProvider>) -> RemoveNode;
+    fn get_scrub_status(&self, id_provider: Box<dyn IdProvider>) -> GetScrubStatus;
+    
+    // Requested by peers, authenticated by the cluster secret
+    fn check_blob(&self) -> CheckBlob;
+    fn send_blob(&self) -> SendBlob;
+    fn receive_blob(&self) -> ReceiveBlob;
+    fn receive_object(&self) -> ReceiveObject;
+    fn get_digests(&self) -> GetDigests;
+    fn replicate_objects(&self) -> ReplicateObjects;
+    fn rebalance_objects(&self) -> RebalanceObjects;
+    fn scrub_blobs(&self) -> ScrubBlobs;
+    fn sweep_sessions(&self) -> SweepSessions;
+    fn request_vote(&self) -> RequestVote;
+    fn append_entries(&self) -> AppendEntries;
+    fn receive_proposal(&self) -> ReceiveProposal;
+    
+    // Gateways used to authenticate signed requests
+    fn access_key_reader(&self) -> &dyn AccessKeyReader;
+    fn permission_reader(&self) -> &dyn PermissionReader;
+    
+    // Used by the heartbeat of cluster nodes
+    fn node_gateway(&self) -> &dyn NodeGateway;
+    fn node_client(&self) -> &dyn NodeClient;
+    fn node_service(&self) -> &NodeService;
+    
+    // Used by intermediate nodes to stream new objects to their owners
+    fn placement_service(&self) -> &PlacementService;
+    
+    // Used by the recovery of the file storage on startup
+    fn object_reader(&self) -> &dyn ObjectReader;
+    fn upload_gateway(&self) -> &dyn UploadGateway;
+    fn blob_locks(&self) -> &BlobLocks;
+    
+    // Used by the metadata consensus loop, the replicated gateways pass changes through the proposer
+    fn metadata_consensus(&self) -> MetadataConsensus;
+    fn metadata_proposer(&self) -> &MetadataProposer;
     
 }
@@ -1,5 +1,30 @@
+use actix_web::web;
+
 pub mod rest;
 pub mod exception;
 mod deserializers;
 pub mod interactor_factory;
 pub mod id_provider;
+pub mod file_stream;
+pub mod s3;
+pub mod proxy;
+#[cfg(test)]
+pub mod test_doubles;
+
+/// Routes of a node under `/node`
+///
+/// Intermediate nodes keep no objects and pass object requests to storage nodes.
+pub fn router(cfg: &mut web::ServiceConfig, is_intermediate: bool) {
+    cfg
+        .configure(rest::session::router)
+        .configure(rest::access_key::router)
+        .configure(rest::api_key::router)
+        .configure(rest::cluster::router);
+    if is_intermediate {
+        proxy::router(cfg);
+    } else {
+        rest::object::router(cfg);
+        rest::upload::router(cfg);
+        rest::r#box::router(cfg);
+    }
+}
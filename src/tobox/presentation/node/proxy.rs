@@ -0,0 +1,558 @@
+//! Request routing of intermediate nodes.
+//!
+//! An intermediate node keeps no objects: the caller is authenticated here and
+//! the request is passed to storage nodes along with the caller identity.
+
+use std::collections::HashMap;
+
+use actix_web::{HttpRequest, HttpResponse, web};
+use actix_web::http::{Method, StatusCode};
+use bytes::{Bytes, BytesMut};
+use serde::Deserialize;
+use tokio_stream::StreamExt;
+
+use crate::adapters::auth::forwarded::{
+    FORWARDED_OBJECT_ID_HEADER,
+    FORWARDED_PERMISSIONS_HEADER,
+    FORWARDED_USER_HEADER,
+    IdForwardedProvider
+};
+use crate::adapters::auth::sigv4::uri_encode;
+use crate::adapters::auth::token::TokenProcessor;
+use crate::adapters::http_node_client::CLUSTER_SECRET_HEADER;
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::node_client::{ForwardBody, ForwardRequest, ForwardResponse};
+use crate::application::object::list::{ListObjectsDTO, ListObjectsResultDTO, merge_pages};
+use crate::config::S3Config;
+use crate::domain::models::node::Node;
+use crate::domain::models::object::ObjectId;
+use crate::domain::services::object::ObjectService;
+use crate::domain::services::placement::PlacementService;
+use crate::domain::services::r#box::BoxService;
+use crate::presentation::node::file_stream::{channel_file_stream, forward_stream};
+use crate::presentation::node::id_provider::{make_sigv4_provider, make_token_provider};
+use crate::presentation::node::interactor_factory::InteractorFactory;
+use crate::presentation::node::s3::bucket::{
+    ListObjectsQuery,
+    list_objects_dto,
+    list_objects_response
+};
+use crate::presentation::node::s3::exception::S3Error;
+
+/// Bodies of requests sent to several nodes are kept in memory
+const MAX_BUFFERED_BODY: usize = 1024 * 1024;
+
+/// Not passed between the client and a storage node
+const SKIPPED_HEADERS: [&str; 15] = [
+    "connection", "keep-alive", "transfer-encoding", "te", "trailer", "upgrade",
+    "proxy-authorization", "host", "cookie", "authorization", "content-length",
+    CLUSTER_SECRET_HEADER, FORWARDED_USER_HEADER, FORWARDED_PERMISSIONS_HEADER,
+    FORWARDED_OBJECT_ID_HEADER,
+];
+
+/// How a request reaches storage nodes
+#[derive(Debug, PartialEq)]
+enum Route {
+    /// Reads: nodes are tried in turn until one has the resource,
+    /// so a missing or failed node is replaced by a replica
+    Failover,
+    /// Changes of existing resources: sent to every node, which may hold a copy
+    Broadcast,
+    /// New content: the object id is chosen here and the content is streamed to its owner
+    Placement,
+    /// Multipart upload: sent to the node holding the upload session
+    Upload(String),
+    /// Object listings: every node holds a part of the box, their pages are merged
+    List,
+}
+
+fn route(method: &Method, path: &str) -> Route {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match (method, segments.as_slice()) {
+        (_, ["node", "upload", id, ..]) => Route::Upload(id.to_string()),
+        (&Method::POST, ["node", "upload"] | ["node", "object"]) => Route::Placement,
+        (&Method::PUT, ["s3", _bucket, key, ..]) if !key.is_empty() => Route::Placement,
+        // Signing a url changes nothing, any node having the object can do it
+        (&Method::POST, ["node", "object", "presign"]) => Route::Failover,
+        (&Method::GET, ["node", "object"] | ["s3", _]) => Route::List,
+        (&Method::GET | &Method::HEAD, _) => Route::Failover,
+        _ => Route::Broadcast
+    }
+}
+
+pub fn router(cfg: &mut web::ServiceConfig) {
+    for scope in ["/object", "/box", "/upload"] {
+        cfg.service(web::scope(scope).default_service(web::to(proxy)));
+    }
+}
+
+pub fn s3_router(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::scope("/s3").default_service(web::to(proxy_s3)));
+}
+
+async fn proxy(
+    payload: web::Payload,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    forward(&req, payload, ioc.get_ref(), id_provider.as_ref()).await
+}
+
+async fn proxy_s3(
+    payload: web::Payload,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    s3_config: web::Data<S3Config>,
+    req: HttpRequest
+) -> Result<HttpResponse, S3Error> {
+    let id_provider = make_sigv4_provider(
+        &req,
+        &s3_config.region,
+        &token_processor,
+        ioc.access_key_reader(),
+        ioc.permission_reader()
+    ).await.map_err(S3Error::bucket)?;
+    if route(req.method(), req.path()) == Route::List {
+        return list_bucket(&req, ioc.get_ref(), id_provider.as_ref()).await
+    }
+    forward(&req, payload, ioc.get_ref(), id_provider.as_ref()).await.map_err(S3Error::bucket)
+}
+
+/// ListObjectsV2: storage nodes are listed by the object api, the merged page is rendered here
+async fn list_bucket(
+    req: &HttpRequest,
+    ioc: &dyn InteractorFactory,
+    id_provider: &dyn IdProvider
+) -> Result<HttpResponse, S3Error> {
+    let bucket = req.path().trim_matches('/').split('/').nth(1).unwrap_or_default().to_string();
+    let query = web::Query::<ListObjectsQuery>::from_query(req.query_string()).map_err(
+        |error| S3Error::bucket(ApplicationError::InvalidData(ErrorContent::from(error.to_string())))
+    )?.into_inner();
+    let data = list_objects_dto(bucket.clone(), &query);
+
+    let nodes = storage_nodes(ioc).await.map_err(S3Error::bucket)?;
+    let headers: Vec<(String, String)> = IdForwardedProvider::headers(id_provider).into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+    let request = || ForwardRequest {
+        method: Method::GET.to_string(),
+        path: format!("/node/object?{}", list_query(&data)),
+        headers: headers.clone(),
+        body: ForwardBody::Buffered(Bytes::new())
+    };
+    let page = list_nodes(&nodes, ioc, request, data.limit).await.map_err(S3Error::bucket)?;
+    Ok(list_objects_response(&bucket, &query, page))
+}
+
+async fn storage_nodes(ioc: &dyn InteractorFactory) -> Result<Vec<Node>, ApplicationError> {
+    let nodes = ioc.node_service().available_nodes(ioc.node_gateway().get_nodes().await);
+    if nodes.is_empty() {
+        return Err(ApplicationError::Unavailable(
+            ErrorContent::from("No storage nodes available")
+        ))
+    }
+    Ok(nodes)
+}
+
+async fn forward(
+    req: &HttpRequest,
+    payload: web::Payload,
+    ioc: &dyn InteractorFactory,
+    id_provider: &dyn IdProvider
+) -> Result<HttpResponse, ApplicationError> {
+    let nodes = storage_nodes(ioc).await?;
+
+    let path = req.uri().path_and_query().map_or(req.path(), |value| value.as_str());
+    let mut headers: Vec<(String, String)> = req.headers().iter()
+        .filter(|(name, _)| !SKIPPED_HEADERS.iter().any(|skipped| name.as_str().eq_ignore_ascii_case(skipped)))
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+    headers.extend(IdForwardedProvider::headers(id_provider).into_iter().map(
+        |(name, value)| (name.to_string(), value)
+    ));
+    let request = |body| ForwardRequest {
+        method: req.method().to_string(),
+        path: path.to_string(),
+        headers: headers.clone(),
+        body
+    };
+
+    match route(req.method(), req.path()) {
+        Route::Placement => {
+            let object_id = ObjectService {}.generate_object_id();
+            let node = primary_owner(
+                ioc.placement_service(),
+                ioc.node_gateway().get_nodes().await,
+                &nodes,
+                &object_id
+            );
+            let request = |body| {
+                let mut request = request(body);
+                request.headers.push((FORWARDED_OBJECT_ID_HEADER.to_string(), object_id.clone()));
+                request
+            };
+            stream_to(node, req, payload, ioc, request).await
+        }
+        Route::Upload(id) => {
+            // The upload session is on the node that lists its parts
+            let probe = || ForwardRequest {
+                method: Method::GET.to_string(),
+                path: format!("/node/upload/{}/parts", id),
+                ..request(ForwardBody::Buffered(Bytes::new()))
+            };
+            let mut owner = None;
+            for node in &nodes {
+                match ioc.node_client().forward(&node.address, probe()).await {
+                    Ok(response) if response.status == StatusCode::OK.as_u16() => {
+                        owner = Some(node);
+                        break
+                    }
+                    Ok(_) => (),
+                    Err(error) => log::warn!("Node {} is not reachable: {}", node.id, error)
+                }
+            }
+            match owner {
+                Some(node) => stream_to(node, req, payload, ioc, request).await,
+                None => Err(ApplicationError::NotFound(ErrorContent::from("Upload not found")))
+            }
+        }
+        Route::Failover => {
+            let body = read_body(payload).await?;
+            let mut missing = None;
+            for node in &nodes {
+                match ioc.node_client().forward(
+                    &node.address,
+                    request(ForwardBody::Buffered(body.clone()))
+                ).await {
+                    Ok(response) if response.status == StatusCode::NOT_FOUND.as_u16()
+                        || response.status >= 500 => missing = Some(response),
+                    Ok(response) => return Ok(into_response(response)),
+                    Err(error) => log::warn!("Node {} is not reachable: {}", node.id, error)
+                }
+            }
+            missing.map(into_response).ok_or_else(|| ApplicationError::Unavailable(
+                ErrorContent::from("No storage node answered")
+            ))
+        }
+        Route::List => {
+            let limit = web::Query::<ListLimit>::from_query(req.query_string())
+                .ok()
+                .and_then(|query| query.limit);
+            let page = list_nodes(
+                &nodes,
+                ioc,
+                || request(ForwardBody::Buffered(Bytes::new())),
+                limit
+            ).await?;
+            Ok(HttpResponse::Ok().json(page))
+        }
+        Route::Broadcast => {
+            let mut body = read_body(payload).await?;
+            // Every node must create the box with the same id
+            if req.method() == Method::POST && req.path().trim_end_matches('/') == "/node/box" {
+                body = with_box_id(body)?;
+            }
+            let mut responses = vec![];
+            for node in &nodes {
+                match ioc.node_client().forward(
+                    &node.address,
+                    request(ForwardBody::Buffered(body.clone()))
+                ).await {
+                    Ok(response) => responses.push(response),
+                    Err(error) => log::warn!("Node {} is not reachable: {}", node.id, error)
+                }
+            }
+            // A success on any node, otherwise the most specific error
+            responses.sort_by_key(|response| match response.status {
+                200..=299 => 0,
+                404 => 2,
+                _ => 1
+            });
+            responses.into_iter().next().map(into_response).ok_or_else(
+                || ApplicationError::Unavailable(ErrorContent::from("No storage node answered"))
+            )
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ListLimit {
+    limit: Option<u64>
+}
+
+/// Every node is listed with the same request, pages of the nodes that answered are merged
+///
+/// A node that can not be listed is skipped, its objects are still listed by their replicas.
+async fn list_nodes(
+    nodes: &[Node],
+    ioc: &dyn InteractorFactory,
+    request: impl Fn() -> ForwardRequest,
+    limit: Option<u64>
+) -> Result<ListObjectsResultDTO, ApplicationError> {
+    let mut pages = vec![];
+    let mut errors = vec![];
+    for node in nodes {
+        let response = match ioc.node_client().forward(&node.address, request()).await {
+            Ok(response) => response,
+            Err(error) => {
+                log::warn!("Node {} is not reachable: {}", node.id, error);
+                continue
+            }
+        };
+        let status = response.status;
+        let body = match read_response(response).await {
+            Ok(body) => body,
+            Err(error) => {
+                log::warn!("Listing of node {} is interrupted: {}", node.id, error);
+                continue
+            }
+        };
+        if status != StatusCode::OK.as_u16() {
+            errors.push((status, body));
+            continue
+        }
+        match serde_json::from_slice::<ListObjectsResultDTO>(&body) {
+            Ok(page) => pages.push(page),
+            Err(error) => log::warn!("Node {} answered an invalid listing: {}", node.id, error)
+        }
+    }
+
+    if pages.is_empty() {
+        // The most specific error, as for changes sent to every node
+        errors.sort_by_key(|(status, _)| *status == StatusCode::NOT_FOUND.as_u16());
+        return Err(errors.first().map_or_else(
+            || ApplicationError::Unavailable(ErrorContent::from("No storage node answered")),
+            |(status, body)| into_error(*status, body)
+        ))
+    }
+    Ok(merge_pages(pages, limit))
+}
+
+/// Query of the object listing of storage nodes
+fn list_query(data: &ListObjectsDTO) -> String {
+    [
+        ("box_id", Some(data.box_id.clone())),
+        ("prefix", data.prefix.clone()),
+        ("delimiter", data.delimiter.clone()),
+        ("cursor", data.cursor.clone()),
+        ("start_after", data.start_after.clone()),
+        ("limit", data.limit.map(|limit| limit.to_string())),
+    ].into_iter()
+        .filter_map(|(name, value)| Some(format!("{}={}", name, uri_encode(&value?))))
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+async fn read_response(mut response: ForwardResponse) -> Result<Bytes, String> {
+    let mut body = BytesMut::new();
+    while let Some(chunk) = response.body.next().await {
+        body.extend_from_slice(&chunk.map_err(|error| error.to_string())?);
+    }
+    Ok(body.freeze())
+}
+
+/// Error answered by a storage node, for responses made by this node
+fn into_error(status: u16, body: &[u8]) -> ApplicationError {
+    let data: serde_json::Value = serde_json::from_slice(body).unwrap_or_default();
+    let content = match (data["error"].as_str(), data["errors"].as_array()) {
+        (Some(message), _) => ErrorContent::from(message),
+        (None, Some(errors)) => ErrorContent::from(errors.iter()
+            .filter_map(|error| Some((
+                error["field"].as_str()?.to_string(),
+                error["message"].as_str()?.to_string()
+            )))
+            .collect::<HashMap<String, String>>()),
+        (None, None) => ErrorContent::from("Storage node failed")
+    };
+    match status {
+        400 => ApplicationError::InvalidData(content),
+        401 => ApplicationError::Unauthorized(content),
+        403 => ApplicationError::Forbidden(content),
+        404 => ApplicationError::NotFound(content),
+        409 => ApplicationError::Conflict(content),
+        _ => ApplicationError::Unavailable(content)
+    }
+}
+
+/// The first owner of the object that is up, the object is replicated from it to the others
+///
+/// The ring is the one of the storage nodes, suspect nodes keep their place in it.
+///
+/// * registered: every storage node
+/// * nodes: storage nodes that are up
+fn primary_owner<'a>(
+    placement_service: &PlacementService,
+    registered: Vec<Node>,
+    nodes: &'a [Node],
+    object_id: &ObjectId
+) -> &'a Node {
+    let ring = placement_service.build_ring(placement_service.storage_members(registered));
+    placement_service.owners(&ring, object_id, ring.members.len()).iter()
+        .find_map(|owner| nodes.iter().find(|node| node.id == *owner))
+        .unwrap_or(&nodes[0])
+}
+
+/// Streamed bodies can be sent only once, so there is no failover
+async fn stream_to(
+    node: &Node,
+    req: &HttpRequest,
+    payload: web::Payload,
+    ioc: &dyn InteractorFactory,
+    request: impl Fn(ForwardBody) -> ForwardRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let (sender, body) = channel_file_stream();
+    let mut request = request(ForwardBody::Stream(Box::new(body)));
+    if let Some(length) = req.headers().get("content-length").and_then(|value| value.to_str().ok()) {
+        request.headers.push(("content-length".to_string(), length.to_string()));
+    }
+    let (response, _) = tokio::join!(
+        ioc.node_client().forward(&node.address, request),
+        forward_stream(payload, sender)
+    );
+    response.map(into_response).map_err(|error| {
+        log::warn!("Node {} is not reachable: {}", node.id, error);
+        ApplicationError::Unavailable(ErrorContent::from("Storage node is not reachable"))
+    })
+}
+
+async fn read_body(payload: web::Payload) -> Result<Bytes, ApplicationError> {
+    match payload.to_bytes_limited(MAX_BUFFERED_BODY).await {
+        Ok(Ok(body)) => Ok(body),
+        Ok(Err(error)) => Err(ApplicationError::InvalidData(ErrorContent::from(error.to_string()))),
+        Err(_) => Err(ApplicationError::InvalidData(ErrorContent::from(format!(
+            "Request body should be less than {} bytes", MAX_BUFFERED_BODY
+        ))))
+    }
+}
+
+fn with_box_id(body: Bytes) -> Result<Bytes, ApplicationError> {
+    let mut data: serde_json::Value = serde_json::from_slice(&body).map_err(
+        |error| ApplicationError::InvalidData(ErrorContent::from(error.to_string()))
+    )?;
+    if let Some(fields) = data.as_object_mut() {
+        if fields.get("id").is_none_or(|id| id.is_null()) {
+            fields.insert("id".to_string(), BoxService {}.generate_box_id().into());
+        }
+    }
+    Ok(Bytes::from(serde_json::to_vec(&data).unwrap()))
+}
+
+fn into_response(response: ForwardResponse) -> HttpResponse {
+    let mut builder = HttpResponse::build(
+        StatusCode::from_u16(response.status).unwrap_or(StatusCode::BAD_GATEWAY)
+    );
+    let mut length = None;
+    for (name, value) in response.headers {
+        if name.eq_ignore_ascii_case("content-length") {
+            length = value.parse::<u64>().ok();
+        } else if !SKIPPED_HEADERS.iter().any(|skipped| name.eq_ignore_ascii_case(skipped)) {
+            builder.append_header((name, value));
+        }
+    }
+    if let Some(length) = length {
+        builder.no_chunking(length);
+    }
+    builder.streaming(response.body)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use crate::domain::models::node::NodeStatus;
+    use crate::domain::services::node::NodeService;
+
+    use super::*;
+
+    #[test]
+    fn test_route() {
+        assert_eq!(route(&Method::GET, "/node/object/abc"), Route::Failover);
+        assert_eq!(route(&Method::GET, "/node/object"), Route::List);
+        assert_eq!(route(&Method::POST, "/node/object"), Route::Placement);
+        assert_eq!(route(&Method::POST, "/node/object/presign"), Route::Failover);
+        assert_eq!(route(&Method::PATCH, "/node/object/abc"), Route::Broadcast);
+        assert_eq!(route(&Method::DELETE, "/node/object/abc"), Route::Broadcast);
+        assert_eq!(route(&Method::POST, "/node/box"), Route::Broadcast);
+        assert_eq!(route(&Method::POST, "/node/upload"), Route::Placement);
+        assert_eq!(route(&Method::PUT, "/node/upload/u1/parts/2"), Route::Upload("u1".to_string()));
+        assert_eq!(route(&Method::POST, "/node/upload/u1/complete"), Route::Upload("u1".to_string()));
+        assert_eq!(route(&Method::PUT, "/s3/bucket/dir/key"), Route::Placement);
+        assert_eq!(route(&Method::PUT, "/s3/bucket"), Route::Broadcast);
+        assert_eq!(route(&Method::PUT, "/s3/bucket/"), Route::Broadcast);
+        assert_eq!(route(&Method::HEAD, "/s3/bucket/key"), Route::Failover);
+        assert_eq!(route(&Method::GET, "/s3/bucket"), Route::List);
+        assert_eq!(route(&Method::GET, "/s3/bucket/"), Route::List);
+        assert_eq!(route(&Method::HEAD, "/s3/bucket"), Route::Failover);
+        assert_eq!(route(&Method::GET, "/s3"), Route::Failover);
+    }
+
+    fn node(id: &str, status: NodeStatus) -> Node {
+        Node {
+            id: id.to_string(),
+            address: format!("http://{}", id),
+            version: String::new(),
+            capacity: 0,
+            status,
+            last_heartbeat: None,
+            created_at: Utc::now()
+        }
+    }
+
+    #[test]
+    fn test_primary_owner() {
+        let service = PlacementService {};
+        let registered = vec![
+            node("a", NodeStatus::Up),
+            node("b", NodeStatus::Suspect),
+            node("c", NodeStatus::Up),
+            node("d", NodeStatus::Down)
+        ];
+        let ring = service.build_ring(service.storage_members(registered.clone()));
+        let available = NodeService {}.available_nodes(registered.clone());
+
+        for index in 0..20 {
+            let object_id = format!("object{}", index);
+            let owners = service.owners(&ring, &object_id, 3);
+            assert!(!owners.contains(&"d".to_string()));
+            // A suspect owner is passed over for the next one
+            let expected = owners.iter().find(|owner| *owner != "b").unwrap();
+            let owner = primary_owner(&service, registered.clone(), &available, &object_id);
+            assert_eq!(&owner.id, expected);
+        }
+    }
+
+    #[test]
+    fn test_list_query() {
+        let data = ListObjectsDTO {
+            box_id: "photos".to_string(),
+            prefix: Some("2024/a b".to_string()),
+            delimiter: Some("/".to_string()),
+            limit: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(list_query(&data), "box_id=photos&prefix=2024%2Fa%20b&delimiter=%2F&limit=10");
+    }
+
+    #[test]
+    fn test_into_error() {
+        let error = into_error(404, b"{\"error\":\"Box not found\"}");
+        assert!(matches!(error, ApplicationError::NotFound(ErrorContent::Message(message)) if message == "Box not found"));
+        let error = into_error(400, b"{\"errors\":[{\"field\":\"limit\",\"message\":\"too big\"}]}");
+        assert!(matches!(error, ApplicationError::InvalidData(ErrorContent::Map(map)) if map["limit"] == "too big"));
+        assert!(matches!(into_error(502, b"bad gateway"), ApplicationError::Unavailable(_)));
+    }
+
+    #[test]
+    fn test_with_box_id() {
+        let body = with_box_id(Bytes::from_static(b"{\"duplicate_names\":\"warn\"}")).unwrap();
+        let data: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(data["id"].as_str().unwrap().len(), 16);
+        assert_eq!(data["duplicate_names"], "warn");
+
+        let body = with_box_id(Bytes::from_static(b"{\"id\":\"photos\"}")).unwrap();
+        assert_eq!(&body[..], b"{\"id\":\"photos\"}");
+    }
+}
@@ -0,0 +1,52 @@
+use actix_web::{delete, get, HttpRequest, HttpResponse, post, Result, web};
+
+use crate::adapters::auth::token::TokenProcessor;
+use crate::application::access_key::delete::DeleteAccessKeyDTO;
+use crate::application::common::exceptions::ApplicationError;
+use crate::application::common::interactor::Interactor;
+use crate::presentation::node::id_provider::make_token_provider;
+use crate::presentation::node::interactor_factory::InteractorFactory;
+
+pub fn router(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/access_keys")
+            .service(create_access_key)
+            .service(get_self_access_keys)
+            .service(delete_access_key)
+    );
+}
+
+/// The secret of the created key is returned only in this response
+#[post("")]
+async fn create_access_key(
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = ioc.create_access_key(id_provider).execute(()).await?;
+    Ok(HttpResponse::Created().json(data))
+}
+
+#[get("self")]
+async fn get_self_access_keys(
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = ioc.get_self_access_keys(id_provider).execute(()).await?;
+    Ok(HttpResponse::Ok().json(data))
+}
+
+#[delete("{id}")]
+async fn delete_access_key(
+    data: web::Path<DeleteAccessKeyDTO>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    ioc.delete_access_key(id_provider).execute(data.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,54 @@
+use actix_web::{delete, get, HttpRequest, HttpResponse, post, Result, web};
+
+use crate::adapters::auth::token::TokenProcessor;
+use crate::application::api_key::create::CreateApiKeyDTO;
+use crate::application::api_key::delete::DeleteApiKeyDTO;
+use crate::application::common::exceptions::ApplicationError;
+use crate::application::common::interactor::Interactor;
+use crate::presentation::node::id_provider::make_token_provider;
+use crate::presentation::node::interactor_factory::InteractorFactory;
+
+pub fn router(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api_keys")
+            .service(create_api_key)
+            .service(get_self_api_keys)
+            .service(delete_api_key)
+    );
+}
+
+/// The key is returned only in this response
+#[post("")]
+async fn create_api_key(
+    data: web::Json<CreateApiKeyDTO>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = ioc.create_api_key(id_provider).execute(data.into_inner()).await?;
+    Ok(HttpResponse::Created().json(data))
+}
+
+#[get("self")]
+async fn get_self_api_keys(
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = ioc.get_self_api_keys(id_provider).execute(()).await?;
+    Ok(HttpResponse::Ok().json(data))
+}
+
+#[delete("{id}")]
+async fn delete_api_key(
+    data: web::Path<DeleteApiKeyDTO>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    ioc.delete_api_key(id_provider).execute(data.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,84 @@
+use actix_web::{delete, get, HttpRequest, HttpResponse, post, put, Result, web};
+use serde::Deserialize;
+
+use crate::adapters::auth::token::TokenProcessor;
+use crate::application::common::exceptions::ApplicationError;
+use crate::application::common::interactor::Interactor;
+use crate::application::r#box::create::CreateBoxDTO;
+use crate::application::r#box::delete::DeleteBoxDTO;
+use crate::application::r#box::get_range::GetBoxRangeDTO;
+use crate::application::r#box::update::UpdateBoxDTO;
+use crate::domain::models::r#box::{BoxAccessPolicy, BoxId, DuplicateNamePolicy};
+use crate::presentation::node::id_provider::make_token_provider;
+use crate::presentation::node::interactor_factory::InteractorFactory;
+
+pub fn router(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/box")
+            .service(create_box)
+            .service(get_box_range)
+            .service(update_box)
+            .service(delete_box)
+    );
+}
+
+#[post("")]
+async fn create_box(
+    data: web::Json<CreateBoxDTO>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = ioc.create_box(id_provider).execute(data.into_inner()).await?;
+    Ok(HttpResponse::Created().json(data))
+}
+
+#[get("")]
+async fn get_box_range(
+    data: web::Query<GetBoxRangeDTO>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = ioc.get_box_range(id_provider).execute(data.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(data))
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateBoxBody {
+    duplicate_names: Option<DuplicateNamePolicy>,
+    /// `private`, `public_read` or `public_list`
+    access: Option<BoxAccessPolicy>
+}
+
+#[put("{id}")]
+async fn update_box(
+    id: web::Path<BoxId>,
+    data: web::Json<UpdateBoxBody>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = data.into_inner();
+    let data = ioc.update_box(id_provider).execute(UpdateBoxDTO {
+        id: id.into_inner(),
+        duplicate_names: data.duplicate_names,
+        access: data.access
+    }).await?;
+    Ok(HttpResponse::Ok().json(data))
+}
+
+#[delete("{id}")]
+async fn delete_box(
+    data: web::Path<DeleteBoxDTO>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    ioc.delete_box(id_provider).execute(data.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,352 @@
+use actix_web::{delete, get, head, HttpRequest, HttpResponse, post, put, Result, web};
+
+use crate::adapters::auth::token::TokenProcessor;
+use crate::adapters::http_node_client::CLUSTER_SECRET_HEADER;
+use crate::application::cluster::add_node::AddNodeDTO;
+use crate::application::cluster::get_nodes::GetNodesDTO;
+use crate::application::cluster::remove_node::RemoveNodeDTO;
+use crate::application::common::exceptions::ApplicationError;
+use crate::application::common::interactor::Interactor;
+use crate::application::metadata::append_entries::AppendEntriesDTO;
+use crate::application::metadata::receive_proposal::ReceiveProposalDTO;
+use crate::application::metadata::request_vote::RequestVoteDTO;
+use crate::application::sync::check_blob::CheckBlobDTO;
+use crate::application::sync::get_digests::GetDigestsDTO;
+use crate::application::sync::receive_blob::ReceiveBlobDTO;
+use crate::application::sync::receive_object::ReceiveObjectDTO;
+use crate::application::sync::send_blob::SendBlobDTO;
+use crate::domain::models::metadata::{AppendRequest, MetadataCommand, VoteRequest};
+use crate::domain::models::replication::Replica;
+use crate::presentation::node::file_stream::{channel_file_stream, forward_stream};
+use crate::presentation::node::id_provider::make_token_provider;
+use crate::presentation::node::interactor_factory::InteractorFactory;
+
+pub fn router(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/cluster")
+            .service(get_node_info)
+            .service(get_nodes)
+            .service(add_node)
+            .service(remove_node)
+            .service(get_scrub_status)
+            .service(check_blob)
+            .service(send_blob)
+            .service(receive_blob)
+            .service(receive_object)
+            .service(get_digests)
+            .service(request_vote)
+            .service(append_entries)
+            .service(receive_proposal)
+    );
+}
+
+fn cluster_secret(req: &HttpRequest) -> Option<String> {
+    req.headers().get(CLUSTER_SECRET_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Requested by peers on every heartbeat
+#[get("info")]
+async fn get_node_info(
+    ioc: web::Data<dyn InteractorFactory>,
+) -> Result<HttpResponse, ApplicationError> {
+    let data = ioc.get_node_info().execute(()).await?;
+    Ok(HttpResponse::Ok().json(data))
+}
+
+#[get("nodes")]
+async fn get_nodes(
+    data: web::Query<GetNodesDTO>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = ioc.get_nodes(id_provider).execute(data.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(data))
+}
+
+#[post("nodes")]
+async fn add_node(
+    data: web::Json<AddNodeDTO>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = ioc.add_node(id_provider).execute(data.into_inner()).await?;
+    Ok(HttpResponse::Created().json(data))
+}
+
+#[delete("nodes/{id}")]
+async fn remove_node(
+    data: web::Path<RemoveNodeDTO>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    ioc.remove_node(id_provider).execute(data.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[get("scrub")]
+async fn get_scrub_status(
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = ioc.get_scrub_status(id_provider).execute(()).await?;
+    Ok(HttpResponse::Ok().json(data))
+}
+
+#[head("blobs/{hash}")]
+async fn check_blob(
+    hash: web::Path<String>,
+    ioc: web::Data<dyn InteractorFactory>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let exists = ioc.check_blob().execute(CheckBlobDTO {
+        hash: hash.into_inner(),
+        secret: cluster_secret(&req)
+    }).await?;
+    Ok(match exists {
+        true => HttpResponse::Ok().finish(),
+        false => HttpResponse::NotFound().finish()
+    })
+}
+
+#[get("blobs/{hash}")]
+async fn send_blob(
+    hash: web::Path<String>,
+    ioc: web::Data<dyn InteractorFactory>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let file = ioc.send_blob().execute(SendBlobDTO {
+        hash: hash.into_inner(),
+        secret: cluster_secret(&req)
+    }).await?;
+    Ok(HttpResponse::Ok()
+        .content_type("application/octet-stream")
+        .streaming(file))
+}
+
+/// The blob content is the raw request body
+#[put("blobs/{hash}")]
+async fn receive_blob(
+    hash: web::Path<String>,
+    payload: web::Payload,
+    ioc: web::Data<dyn InteractorFactory>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let (sender, file) = channel_file_stream();
+    // Borrowed by the future below, the interactor must outlive it
+    let interactor = ioc.receive_blob();
+    let (data, _) = tokio::join!(
+        interactor.execute(ReceiveBlobDTO {
+            hash: hash.into_inner(),
+            secret: cluster_secret(&req),
+            file: Box::new(file)
+        }),
+        forward_stream(payload, sender)
+    );
+    data?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[put("objects")]
+async fn receive_object(
+    data: web::Json<Replica>,
+    ioc: web::Data<dyn InteractorFactory>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    ioc.receive_object().execute(ReceiveObjectDTO {
+        replica: data.into_inner(),
+        secret: cluster_secret(&req)
+    }).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[get("digests/{node_id}")]
+async fn get_digests(
+    node_id: web::Path<String>,
+    ioc: web::Data<dyn InteractorFactory>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let data = ioc.get_digests().execute(GetDigestsDTO {
+        node_id: node_id.into_inner(),
+        secret: cluster_secret(&req)
+    }).await?;
+    Ok(HttpResponse::Ok().json(data))
+}
+
+#[post("metadata/vote")]
+async fn request_vote(
+    data: web::Json<VoteRequest>,
+    ioc: web::Data<dyn InteractorFactory>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let data = ioc.request_vote().execute(RequestVoteDTO {
+        request: data.into_inner(),
+        secret: cluster_secret(&req)
+    }).await?;
+    Ok(HttpResponse::Ok().json(data))
+}
+
+#[post("metadata/append")]
+async fn append_entries(
+    data: web::Json<AppendRequest>,
+    ioc: web::Data<dyn InteractorFactory>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let data = ioc.append_entries().execute(AppendEntriesDTO {
+        request: data.into_inner(),
+        secret: cluster_secret(&req)
+    }).await?;
+    Ok(HttpResponse::Ok().json(data))
+}
+
+#[post("metadata/proposals")]
+async fn receive_proposal(
+    data: web::Json<MetadataCommand>,
+    ioc: web::Data<dyn InteractorFactory>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let data = ioc.receive_proposal().execute(ReceiveProposalDTO {
+        command: data.into_inner(),
+        secret: cluster_secret(&req)
+    }).await?;
+    Ok(HttpResponse::Ok().json(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use actix_web::{App, HttpServer};
+    use actix_web::dev::ServerHandle;
+    use chrono::{TimeDelta, Utc};
+    use reqwest::StatusCode;
+    use serde_json::{json, Value};
+
+    use crate::adapters::hmac_signer::HmacSigner;
+    use crate::adapters::http_node_client::HttpNodeClient;
+    use crate::application::cluster::heartbeat::heartbeat_nodes;
+    use crate::application::common::node_client::NodeClient;
+    use crate::application::common::node_gateway::{NodeReader, NodeWriter};
+    use crate::domain::models::node::NodeId;
+    use crate::domain::models::permission::PermissionTag;
+    use crate::presentation::node::id_provider::ClusterSecret;
+    use crate::presentation::node::router;
+    use crate::presentation::node::test_doubles::TestFactory;
+
+    use super::*;
+
+    const SECRET: &str = "secret";
+
+    /// Storage node on localhost with the routes and app data of a node server
+    struct TestNode {
+        id: NodeId,
+        address: String,
+        ioc: Arc<TestFactory>,
+        handle: ServerHandle
+    }
+
+    async fn start(id: &str) -> TestNode {
+        let ioc = Arc::new(TestFactory::new(id, Some(SECRET)).await);
+        let token_processor = web::Data::new(ioc.token_processor());
+        let app_ioc = ioc.clone();
+        let server = HttpServer::new(move || {
+            let ioc: Arc<dyn InteractorFactory> = app_ioc.clone();
+            App::new()
+                .service(web::scope("/node").configure(|cfg| router(cfg, false)))
+                .app_data(web::Data::from(ioc))
+                .app_data(token_processor.clone())
+                .app_data(web::Data::new(ClusterSecret(Some(SECRET.to_string()))))
+                .app_data(web::Data::new(HmacSigner::new(SECRET, "presign")))
+        }).workers(1).bind(("127.0.0.1", 0)).unwrap();
+        let address = format!("http://{}", server.addrs()[0]);
+        let server = server.run();
+        let handle = server.handle();
+        actix_web::rt::spawn(server);
+        TestNode { id: id.to_string(), address, ioc, handle }
+    }
+
+    async fn heartbeat(node: &TestNode) {
+        heartbeat_nodes(
+            node.ioc.node_gateway(),
+            node.ioc.node_client(),
+            node.ioc.node_service(),
+            &node.id,
+            &[]
+        ).await;
+    }
+
+    /// Statuses of the nodes listed by the api, ordered by id
+    async fn statuses(client: &reqwest::Client, url: &str, token: &str) -> Vec<(String, String)> {
+        let nodes: Vec<Value> = client.get(url).bearer_auth(token).send().await.unwrap()
+            .json().await.unwrap();
+        let mut statuses: Vec<(String, String)> = nodes.iter()
+            .map(|node| (
+                node["id"].as_str().unwrap().to_string(),
+                node["status"].as_str().unwrap().to_string()
+            ))
+            .collect();
+        statuses.sort();
+        statuses
+    }
+
+    #[actix_web::test]
+    async fn test_cluster_membership() {
+        let (a, b, c) = (start("a").await, start("b").await, start("c").await);
+        a.ioc.add_user("admin", vec![PermissionTag::GetNode, PermissionTag::AddNode]).await;
+        a.ioc.add_user("viewer", vec![PermissionTag::GetNode]).await;
+        let (admin, viewer) = (a.ioc.login("admin").await, a.ioc.login("viewer").await);
+        let client = reqwest::Client::new();
+        let nodes = format!("{}/node/cluster/nodes", a.address);
+
+        // Registration asks the node about itself and needs the permission
+        let response = client.post(&nodes).json(&json!({ "address": b.address })).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = client.post(&nodes).bearer_auth(&viewer)
+            .json(&json!({ "address": b.address }))
+            .send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        for node in [&b, &c] {
+            let response = client.post(&nodes).bearer_auth(&admin)
+                .json(&json!({ "address": node.address }))
+                .send().await.unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+        }
+        let response = client.post(&nodes).bearer_auth(&admin)
+            .json(&json!({ "address": a.address }))
+            .send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(statuses(&client, &nodes, &viewer).await, [
+            ("b".to_string(), "up".to_string()),
+            ("c".to_string(), "up".to_string())
+        ]);
+
+        // Peer requests are served only with the cluster secret
+        let hash = "0".repeat(64);
+        assert_eq!(a.ioc.node_client().has_blob(&b.address, &hash).await, Ok(false));
+        assert!(HttpNodeClient::new(Some("other".to_string())).has_blob(&b.address, &hash).await.is_err());
+        assert!(HttpNodeClient::new(None).has_blob(&b.address, &hash).await.is_err());
+
+        heartbeat(&a).await;
+        let mut node_c = a.ioc.node_gateway().get_node(&"c".to_string()).await.unwrap();
+        assert!(node_c.last_heartbeat.is_some());
+
+        // c stops and stays silent long enough to be considered down
+        c.handle.stop(true).await;
+        node_c.last_heartbeat = Some(Utc::now() - TimeDelta::minutes(5));
+        a.ioc.node_gateway().save_node(&node_c).await;
+        heartbeat(&a).await;
+        assert_eq!(statuses(&client, &nodes, &viewer).await, [
+            ("b".to_string(), "up".to_string()),
+            ("c".to_string(), "down".to_string())
+        ]);
+    }
+}
@@ -4,4 +4,11 @@ pub mod stats;
 pub mod user;
 pub mod access_log;
 pub mod permission;
-pub mod service;
\ No newline at end of file
+pub mod service;
+pub mod object;
+pub mod access_key;
+pub mod api_key;
+pub mod upload;
+pub mod r#box;
+
+pub mod cluster;
\ No newline at end of file
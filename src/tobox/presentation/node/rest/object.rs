@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use actix_multipart::{Field, Multipart};
+use actix_web::{delete, get, HttpMessage, HttpRequest, HttpResponse, post, put, Result, route, web};
+use actix_web::http::header::{
+    self,
+    ByteRangeSpec,
+    ContentDisposition,
+    ContentRange,
+    ContentRangeSpec,
+    DispositionParam,
+    DispositionType,
+    EntityTag,
+    ETag,
+    IfModifiedSince,
+    IfNoneMatch,
+    IfRange,
+    LastModified,
+    Range
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use tokio_stream::StreamExt;
+
+use crate::adapters::auth::presign::PresignedQuery;
+use crate::adapters::auth::token::TokenProcessor;
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::application::common::interactor::Interactor;
+use crate::application::object::create::CreateObjectDTO;
+use crate::application::object::delete::DeleteObjectDTO;
+use crate::application::object::get::{GetObjectDTO, ObjectContent, ObjectRange, RangeCondition};
+use crate::application::object::get_info::GetObjectInfoDTO;
+use crate::application::object::list::ListObjectsDTO;
+use crate::application::object::presign::PresignObjectDTO;
+use crate::application::object::update::UpdateObjectDTO;
+use crate::domain::models::object::ObjectId;
+use crate::domain::models::r#box::BoxId;
+use crate::presentation::node::file_stream::{channel_file_stream, forward_stream};
+use crate::presentation::node::id_provider::{forwarded_object_id, make_token_provider, OBJECT_PATH};
+use crate::presentation::node::interactor_factory::InteractorFactory;
+
+const TEXT_FIELD_MAX_SIZE: usize = 64 * 1024;
+
+pub fn router(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/object")
+            .service(list_objects)
+            .service(create_object)
+            .service(presign_object)
+            .service(get_object_info)
+            .service(get_object)
+            .service(update_object)
+            .service(delete_object)
+    );
+}
+
+async fn read_text_field(field: &mut Field) -> Result<String, ApplicationError> {
+    let mut buf = Vec::new();
+    while let Some(chunk) = field.next().await {
+        let chunk = chunk.map_err(
+            |error| ApplicationError::InvalidData(ErrorContent::from(error.to_string()))
+        )?;
+        if buf.len() + chunk.len() > TEXT_FIELD_MAX_SIZE {
+            return Err(ApplicationError::InvalidData(
+                ErrorContent::from(format!("Field {} is too large", field.name().unwrap_or_default()))
+            ))
+        }
+        buf.extend_from_slice(&chunk);
+    }
+    String::from_utf8(buf).map_err(
+        |error| ApplicationError::InvalidData(ErrorContent::from(error.to_string()))
+    )
+}
+
+/// Upload an object as multipart/form-data
+///
+/// Fields `box_id`, `name`, `path` and `metadata` (json object) must precede the `file` field,
+/// the file is streamed to the storage as it arrives.
+#[post("")]
+async fn create_object(
+    mut payload: Multipart,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+
+    let mut fields: HashMap<String, String> = HashMap::new();
+    while let Some(field) = payload.next().await {
+        let mut field = field.map_err(
+            |error| ApplicationError::InvalidData(ErrorContent::from(error.to_string()))
+        )?;
+        let field_name = field.name().unwrap_or_default().to_string();
+
+        if field_name != "file" {
+            let value = read_text_field(&mut field).await?;
+            fields.insert(field_name, value);
+            continue;
+        }
+
+        let mut validator_err_map: HashMap<String, String> = HashMap::new();
+        let box_id = fields.remove("box_id").unwrap_or_else(|| {
+            validator_err_map.insert("box_id".to_string(), "is required".to_string());
+            String::new()
+        });
+        let metadata = match fields.remove("metadata") {
+            Some(metadata) => serde_json::from_str(&metadata).unwrap_or_else(|_| {
+                validator_err_map.insert("metadata".to_string(), "should be a json object of strings".to_string());
+                HashMap::new()
+            }),
+            None => HashMap::new()
+        };
+        if !validator_err_map.is_empty() {
+            return Err(ApplicationError::InvalidData(ErrorContent::from(validator_err_map)))
+        }
+
+        let id = forwarded_object_id(&req)?;
+        let (sender, file) = channel_file_stream();
+        // Borrowed by the future below, the interactor must outlive it
+        let interactor = ioc.create_object(id_provider);
+        let (data, _) = tokio::join!(
+            interactor.execute(CreateObjectDTO {
+                id,
+                box_id,
+                name: fields.remove("name"),
+                path: fields.remove("path"),
+                file: Box::new(file),
+                metadata,
+                expected_hash: None,
+                replace: false
+            }),
+            forward_stream(field, sender)
+        );
+        return Ok(HttpResponse::Created().json(data?))
+    }
+
+    Err(ApplicationError::InvalidData(ErrorContent::from("Field file is required")))
+}
+
+/// Sign a url to download the object or upload a new object to the box without credentials
+///
+/// The url is relative to the node address, e.g. `/node/object/<id>?X-Tobox-Signature=...`.
+#[post("presign")]
+async fn presign_object(
+    data: web::Json<PresignObjectDTO>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = ioc.presign_object(id_provider).execute(data.into_inner()).await?;
+    let url = format!(
+        "{}{}?{}",
+        OBJECT_PATH,
+        data.object_id.as_ref().map(|id| format!("/{}", id)).unwrap_or_default(),
+        PresignedQuery::encode(&data.grant, &data.signature)
+    );
+    Ok(HttpResponse::Ok().json(json!({
+        "url": url,
+        "method": data.method,
+        "object_id": data.object_id,
+        "box_id": data.box_id,
+        "expires_at": data.expires_at,
+        "max_length": data.max_length
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+struct ListObjectsQuery {
+    box_id: BoxId,
+    prefix: Option<String>,
+    /// `/` lists a directory
+    delimiter: Option<String>,
+    /// Json object of strings
+    metadata: Option<String>,
+    content_type: Option<String>,
+    created_from: Option<DateTime<Utc>>,
+    created_to: Option<DateTime<Utc>>,
+    updated_from: Option<DateTime<Utc>>,
+    updated_to: Option<DateTime<Utc>>,
+    cursor: Option<String>,
+    /// List only keys greater than this one, ignored when the cursor is set
+    start_after: Option<String>,
+    limit: Option<u64>
+}
+
+#[get("")]
+async fn list_objects(
+    query: web::Query<ListObjectsQuery>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let query = query.into_inner();
+    let metadata = match query.metadata {
+        Some(metadata) => serde_json::from_str(&metadata).map_err(|_| {
+            let mut validator_err_map: HashMap<String, String> = HashMap::new();
+            validator_err_map.insert("metadata".to_string(), "should be a json object of strings".to_string());
+            ApplicationError::InvalidData(ErrorContent::from(validator_err_map))
+        })?,
+        None => HashMap::new()
+    };
+    let data = ioc.list_objects(id_provider).execute(ListObjectsDTO {
+        box_id: query.box_id,
+        prefix: query.prefix,
+        delimiter: query.delimiter,
+        metadata,
+        content_type: query.content_type,
+        created_from: query.created_from,
+        created_to: query.created_to,
+        updated_from: query.updated_from,
+        updated_to: query.updated_to,
+        cursor: query.cursor,
+        start_after: query.start_after,
+        limit: query.limit
+    }).await?;
+    Ok(HttpResponse::Ok().json(data))
+}
+
+/// Only a single byte range is supported, requests for several ranges are served in full
+pub fn parse_range(req: &HttpRequest) -> Option<ObjectRange> {
+    match req.get_header::<Range>()? {
+        Range::Bytes(ranges) if ranges.len() == 1 => Some(match ranges[0] {
+            ByteRangeSpec::FromTo(first, last) => ObjectRange::FromTo(first, last),
+            ByteRangeSpec::From(first) => ObjectRange::From(first),
+            ByteRangeSpec::Last(length) => ObjectRange::Suffix(length),
+        }),
+        _ => None
+    }
+}
+
+pub fn parse_if_range(req: &HttpRequest) -> Option<RangeCondition> {
+    match req.get_header::<IfRange>()? {
+        // Weak tags can not be used to combine ranges
+        IfRange::EntityTag(tag) if !tag.weak => Some(RangeCondition::Hash(tag.tag().to_string())),
+        IfRange::EntityTag(_) => Some(RangeCondition::Hash(String::new())),
+        IfRange::Date(date) => Some(RangeCondition::Date(DateTime::<Utc>::from(SystemTime::from(date)))),
+    }
+}
+
+pub fn parse_if_none_match(req: &HttpRequest) -> Option<Vec<String>> {
+    match req.get_header::<IfNoneMatch>()? {
+        IfNoneMatch::Any => Some(vec!["*".to_string()]),
+        IfNoneMatch::Items(tags) => Some(tags.iter().map(|tag| tag.tag().to_string()).collect())
+    }
+}
+
+/// HEAD is served as well, a presigned download url is valid for both
+#[route("{id}", method = "GET", method = "HEAD")]
+async fn get_object(
+    id: web::Path<ObjectId>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = ioc.get_object(id_provider).execute(GetObjectDTO {
+        id: id.into_inner(),
+        range: parse_range(&req),
+        if_range: parse_if_range(&req),
+        if_none_match: parse_if_none_match(&req),
+        if_modified_since: req.get_header::<IfModifiedSince>().map(
+            |date| DateTime::<Utc>::from(SystemTime::from(date.0))
+        )
+    }).await?;
+
+    let mut response = match data.content {
+        ObjectContent::NotModified => HttpResponse::NotModified(),
+        ObjectContent::RangeNotSatisfiable => HttpResponse::RangeNotSatisfiable(),
+        ObjectContent::Full(_) => HttpResponse::Ok(),
+        ObjectContent::Partial(first, last, _) => {
+            let mut response = HttpResponse::PartialContent();
+            response.insert_header(ContentRange(ContentRangeSpec::Bytes {
+                range: Some((first, last)),
+                instance_length: Some(data.size),
+            }));
+            response
+        }
+    };
+    response
+        .insert_header(ETag(EntityTag::new_strong(data.hash)))
+        .insert_header(LastModified(SystemTime::from(data.last_modified).into()))
+        .insert_header((header::ACCEPT_RANGES, "bytes"));
+
+    Ok(match data.content {
+        ObjectContent::NotModified => response.finish(),
+        ObjectContent::RangeNotSatisfiable => response
+            .insert_header(ContentRange(ContentRangeSpec::Bytes {
+                range: None,
+                instance_length: Some(data.size),
+            }))
+            .finish(),
+        ObjectContent::Full(file) => response
+            .content_type(data.content_type)
+            .insert_header(ContentDisposition {
+                disposition: DispositionType::Inline,
+                parameters: vec![DispositionParam::Filename(data.name)],
+            })
+            .no_chunking(data.size)
+            .streaming(file),
+        ObjectContent::Partial(first, last, file) => response
+            .content_type(data.content_type)
+            .no_chunking(last - first + 1)
+            .streaming(file),
+    })
+}
+
+#[get("{id}/info")]
+async fn get_object_info(
+    data: web::Path<GetObjectInfoDTO>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = ioc.get_object_info(id_provider).execute(data.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(data))
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateObjectBody {
+    name: Option<String>,
+    path: Option<String>,
+    metadata: HashMap<String, String>
+}
+
+#[put("{id}")]
+async fn update_object(
+    id: web::Path<ObjectId>,
+    data: web::Json<UpdateObjectBody>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = data.into_inner();
+    let data = ioc.update_object(id_provider).execute(UpdateObjectDTO {
+        id: id.into_inner(),
+        name: data.name,
+        path: data.path,
+        metadata: data.metadata
+    }).await?;
+    Ok(HttpResponse::Ok().json(data))
+}
+
+#[delete("{id}")]
+async fn delete_object(
+    data: web::Path<DeleteObjectDTO>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    ioc.delete_object(id_provider).execute(data.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use actix_web::{App, test};
+    use actix_web::http::{Method, StatusCode};
+    use chrono::TimeDelta;
+
+    use crate::adapters::hmac_signer::HmacSigner;
+    use crate::application::common::signer::Signer;
+    use crate::domain::models::presign::{PresignedAction, PresignedGrant};
+    use crate::domain::services::presign::PresignService;
+    use crate::presentation::node::test_doubles::TestFactory;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_presigned_download() {
+        let factory = TestFactory::new("node", None).await;
+        factory.add_box("photos").await;
+        let id = factory.add_object("photos", "cat.txt", b"meow").await;
+        let token_processor = web::Data::new(factory.token_processor());
+        let signer = HmacSigner::new("secret", "presign");
+        let grant = PresignedGrant {
+            action: PresignedAction::GetObject(id.clone()),
+            box_id: "photos".to_string(),
+            user_id: "alice".to_string(),
+            expires_at: DateTime::from_timestamp((Utc::now() + TimeDelta::minutes(5)).timestamp(), 0).unwrap(),
+            max_length: None
+        };
+        let signature = signer.sign(&PresignService {}.string_to_sign(&grant));
+        let url = format!("{}/{}?{}", OBJECT_PATH, id, PresignedQuery::encode(&grant, &signature));
+        let ioc: Arc<dyn InteractorFactory> = Arc::new(factory);
+        let app = test::init_service(
+            App::new()
+                .service(web::scope("/node").configure(router))
+                .app_data(web::Data::from(ioc))
+                .app_data(token_processor)
+                .app_data(web::Data::new(signer))
+        ).await;
+
+        let request = |method: Method, url: &str| test::TestRequest::default()
+            .method(method)
+            .uri(url)
+            .to_request();
+        let response = test::call_service(&app, request(Method::HEAD, &url)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(header::ACCEPT_RANGES).unwrap(), "bytes");
+        let response = test::call_service(&app, request(Method::GET, &url)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(test::read_body(response).await, "meow");
+
+        // The url is not valid for other requests or without the signature
+        let response = test::call_service(&app, request(Method::DELETE, &url)).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = test::call_service(&app, request(Method::HEAD, &format!("{}/{}", OBJECT_PATH, id))).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
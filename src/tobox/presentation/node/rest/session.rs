@@ -1,142 +1,117 @@
 use actix_web::{delete, get, HttpRequest, HttpResponse, post, Result, web};
 use actix_web::cookie::Cookie;
-use serde::Deserialize;
+use actix_web::http::header::USER_AGENT;
 
-use crate::AppConfigProvider;
-use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::adapters::auth::token::TokenProcessor;
+use crate::application::common::exceptions::ApplicationError;
 use crate::application::common::interactor::Interactor;
 use crate::application::session::create::CreateSessionDTO;
 use crate::application::session::delete::DeleteSessionDTO;
-use crate::domain::models::session::SessionId;
-use crate::domain::models::user::UserId;
-use crate::presentation::node::id_provider::make_id_provider_from_request;
+use crate::application::session::get_by_user::GetUserSessionsDTO;
+use crate::presentation::node::id_provider::{make_token_provider, TOKEN_COOKIE};
 use crate::presentation::node::interactor_factory::InteractorFactory;
 
 pub fn router(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/sessions")
-            .service(sessions_self)
             .service(create_session)
-            .service(sessions_by)
-            .service(delete_session)
+            .service(get_self_sessions)
+            .service(get_user_sessions)
+            .service(delete_all_self_sessions)
             .service(delete_self_session)
+            .service(delete_session)
     );
 }
 
+/// Log in, the token is set as a cookie and returned only in this response
 #[post("")]
 async fn create_session(
     data: web::Json<CreateSessionDTO>,
     ioc: web::Data<dyn InteractorFactory>,
-    app_config_provider: web::Data<AppConfigProvider>,
+    token_processor: web::Data<TokenProcessor>,
     req: HttpRequest
 ) -> Result<HttpResponse, ApplicationError> {
-    let id_provider = make_id_provider_from_request(
-        &app_config_provider.service_name,
-        app_config_provider.is_intermediate,
-        &req
-    );
-    let (data, session_token) = ioc.create_session(id_provider).execute(
-        data.into_inner()
-    ).await?;
-    
-    let mut response = HttpResponse::Ok().json(data);
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = CreateSessionDTO {
+        ip: req.peer_addr().map(|addr| addr.ip().to_string()),
+        user_agent: req.headers().get(USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+        ..data.into_inner()
+    };
+    let data = ioc.create_session(id_provider).execute(data).await?;
+
+    let mut response = HttpResponse::Created().json(&data);
     response.add_cookie(
-        &Cookie::build("session_token", session_token.to_string())
+        &Cookie::build(TOKEN_COOKIE, data.token)
             .path("/")
             .http_only(true)
             .finish()
     ).unwrap();
-    
     Ok(response)
 }
 
-#[delete("{id}")]
-async fn delete_session(
-    id: web::Path<DeleteSessionDTO>,
+#[get("self")]
+async fn get_self_sessions(
     ioc: web::Data<dyn InteractorFactory>,
-    app_config_provider: web::Data<AppConfigProvider>,
+    token_processor: web::Data<TokenProcessor>,
     req: HttpRequest
 ) -> Result<HttpResponse, ApplicationError> {
-    let id_provider = make_id_provider_from_request(
-        &app_config_provider.service_name,
-        app_config_provider.is_intermediate,
-        &req
-    );
-    ioc.delete_session(id_provider).execute(
-        id.into_inner()
-    ).await?;
-    Ok(HttpResponse::NoContent().finish())
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = ioc.get_self_sessions(id_provider).execute(()).await?;
+    Ok(HttpResponse::Ok().json(data))
 }
 
-#[delete("self")]
-async fn delete_self_session(
+#[get("")]
+async fn get_user_sessions(
+    data: web::Query<GetUserSessionsDTO>,
     ioc: web::Data<dyn InteractorFactory>,
-    app_config_provider: web::Data<AppConfigProvider>,
+    token_processor: web::Data<TokenProcessor>,
     req: HttpRequest
 ) -> Result<HttpResponse, ApplicationError> {
-    let id_provider = make_id_provider_from_request(
-        &app_config_provider.service_name,
-        app_config_provider.is_intermediate,
-        &req
-    );
-    ioc.delete_self_session(id_provider).execute(()).await?;
-    Ok(HttpResponse::NoContent().finish())
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = ioc.get_user_sessions(id_provider).execute(data.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(data))
 }
 
-#[derive(Debug, Deserialize)]
-struct SessionsQueryParams {
-    user_id: Option<UserId>,
-    id: Option<SessionId>
-}
+/// Log out everywhere
+#[delete("self/all")]
+async fn delete_all_self_sessions(
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = ioc.delete_all_self_sessions(id_provider).execute(()).await?;
 
+    let mut response = HttpResponse::Ok().json(data);
+    response.add_removal_cookie(&Cookie::build(TOKEN_COOKIE, "").path("/").finish()).unwrap();
+    Ok(response)
+}
 
-#[get("")]
-async fn sessions_by(
-    data: web::Query<SessionsQueryParams>,
-    app_config_provider: web::Data<AppConfigProvider>,
+/// Log out
+#[delete("self")]
+async fn delete_self_session(
     ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
     req: HttpRequest
 ) -> Result<HttpResponse, ApplicationError> {
-    let id_provider = make_id_provider_from_request(
-        &app_config_provider.service_name,
-        app_config_provider.is_intermediate,
-        &req
-    );
-    
-    if let Some(id) = data.id {
-        let data = ioc.get_session_by_id(id_provider).execute(
-            id
-        ).await?;
-        return Ok(HttpResponse::Ok().json(data));
-    }
-    
-    if let Some(user_id) = data.user_id {
-        let data = ioc.get_sessions_by_user_id(id_provider).execute(
-            user_id
-        ).await?;
-        return Ok(HttpResponse::Ok().json(data));
-    }
-    
-    Err(
-        ApplicationError::InvalidData(
-            ErrorContent::Message(
-                "Необходимо указать user_id или id сессии".to_string()
-            )
-        )
-    )
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    ioc.delete_self_session(id_provider).execute(()).await?;
+
+    let mut response = HttpResponse::NoContent().finish();
+    response.add_removal_cookie(&Cookie::build(TOKEN_COOKIE, "").path("/").finish()).unwrap();
+    Ok(response)
 }
 
-#[get("self")]
-async fn sessions_self(
+#[delete("{id}")]
+async fn delete_session(
+    data: web::Path<DeleteSessionDTO>,
     ioc: web::Data<dyn InteractorFactory>,
-    app_config_provider: web::Data<AppConfigProvider>,
+    token_processor: web::Data<TokenProcessor>,
     req: HttpRequest
-) -> Result<HttpResponse, ApplicationError>{
-    let id_provider = make_id_provider_from_request(
-        &app_config_provider.service_name,
-        app_config_provider.is_intermediate,
-        &req
-    );
-    let data = ioc.get_sessions_self(id_provider).execute(()).await?;
-    Ok(HttpResponse::Ok().json(data))
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    ioc.delete_session(id_provider).execute(data.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
 }
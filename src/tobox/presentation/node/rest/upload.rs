@@ -0,0 +1,112 @@
+use actix_web::{delete, get, HttpRequest, HttpResponse, post, put, Result, web};
+use serde::Deserialize;
+
+use crate::adapters::auth::token::TokenProcessor;
+use crate::application::common::exceptions::ApplicationError;
+use crate::application::common::interactor::Interactor;
+use crate::application::upload::abort::AbortUploadDTO;
+use crate::application::upload::complete::{CompletePart, CompleteUploadDTO};
+use crate::application::upload::get_parts::GetUploadPartsDTO;
+use crate::application::upload::initiate::InitiateUploadDTO;
+use crate::application::upload::put_part::PutUploadPartDTO;
+use crate::domain::models::upload::UploadId;
+use crate::presentation::node::file_stream::{channel_file_stream, forward_stream};
+use crate::presentation::node::id_provider::{forwarded_object_id, make_token_provider};
+use crate::presentation::node::interactor_factory::InteractorFactory;
+
+pub fn router(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/upload")
+            .service(initiate_upload)
+            .service(put_upload_part)
+            .service(get_upload_parts)
+            .service(complete_upload)
+            .service(abort_upload)
+    );
+}
+
+#[post("")]
+async fn initiate_upload(
+    data: web::Json<InitiateUploadDTO>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = ioc.initiate_upload(id_provider).execute(InitiateUploadDTO {
+        object_id: forwarded_object_id(&req)?,
+        ..data.into_inner()
+    }).await?;
+    Ok(HttpResponse::Created().json(data))
+}
+
+/// The part content is the raw request body
+#[put("{id}/parts/{number}")]
+async fn put_upload_part(
+    path: web::Path<(UploadId, u32)>,
+    payload: web::Payload,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let (upload_id, number) = path.into_inner();
+
+    let (sender, file) = channel_file_stream();
+    // Borrowed by the future below, the interactor must outlive it
+    let interactor = ioc.put_upload_part(id_provider);
+    let (data, _) = tokio::join!(
+        interactor.execute(PutUploadPartDTO {
+            upload_id,
+            number,
+            file: Box::new(file)
+        }),
+        forward_stream(payload, sender)
+    );
+    Ok(HttpResponse::Ok().json(data?))
+}
+
+#[get("{id}/parts")]
+async fn get_upload_parts(
+    data: web::Path<GetUploadPartsDTO>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = ioc.get_upload_parts(id_provider).execute(data.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(data))
+}
+
+#[derive(Debug, Deserialize)]
+struct CompleteUploadBody {
+    parts: Option<Vec<CompletePart>>
+}
+
+#[post("{id}/complete")]
+async fn complete_upload(
+    id: web::Path<UploadId>,
+    data: Option<web::Json<CompleteUploadBody>>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    let data = ioc.complete_upload(id_provider).execute(CompleteUploadDTO {
+        id: id.into_inner(),
+        parts: data.and_then(|data| data.into_inner().parts)
+    }).await?;
+    Ok(HttpResponse::Created().json(data))
+}
+
+#[delete("{id}")]
+async fn abort_upload(
+    data: web::Path<AbortUploadDTO>,
+    ioc: web::Data<dyn InteractorFactory>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, ApplicationError> {
+    let id_provider = make_token_provider(&req, &token_processor).await?;
+    ioc.abort_upload(id_provider).execute(data.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
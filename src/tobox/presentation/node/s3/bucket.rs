@@ -0,0 +1,196 @@
+use actix_web::{HttpRequest, HttpResponse, web};
+use actix_web::http::header::{ContentType, LOCATION};
+use serde::Deserialize;
+
+use crate::adapters::auth::sigv4::uri_encode;
+use crate::adapters::auth::token::TokenProcessor;
+use crate::application::common::interactor::Interactor;
+use crate::application::object::list::{ListObjectsDTO, ListObjectsResultDTO};
+use crate::application::r#box::create::CreateBoxDTO;
+use crate::application::r#box::delete::DeleteBoxDTO;
+use crate::application::r#box::get_range::GetBoxRangeDTO;
+use crate::config::S3Config;
+use crate::domain::models::r#box::BoxId;
+use crate::presentation::node::interactor_factory::InteractorFactory;
+use crate::presentation::node::s3::exception::{BUCKET_NOT_EMPTY, NO_SUCH_BUCKET, S3Error};
+use crate::presentation::node::s3::make_id_provider;
+use crate::presentation::node::s3::xml::{format_date, XmlWriter};
+
+const MAX_KEYS: usize = 1000;
+
+pub async fn list_buckets(
+    ioc: web::Data<dyn InteractorFactory>,
+    s3_config: web::Data<S3Config>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, S3Error> {
+    let id_provider = make_id_provider(&req, &s3_config, &token_processor, ioc.as_ref()).await?;
+    let owner = id_provider.user_id().cloned().unwrap_or_default();
+    let boxes = ioc.get_box_range(id_provider).execute(GetBoxRangeDTO {
+        page: None,
+        per_page: None
+    }).await.map_err(S3Error::bucket)?;
+
+    let mut xml = XmlWriter::new();
+    xml.open_root("ListAllMyBucketsResult")
+        .open("Owner")
+        .element("ID", &owner)
+        .element("DisplayName", &owner)
+        .close("Owner")
+        .open("Buckets");
+    for r#box in boxes {
+        xml.open("Bucket")
+            .element("Name", &r#box.id)
+            .element("CreationDate", &format_date(&r#box.created_at))
+            .close("Bucket");
+    }
+    xml.close("Buckets").close("ListAllMyBucketsResult");
+
+    Ok(HttpResponse::Ok().insert_header(ContentType::xml()).body(xml.finish()))
+}
+
+/// The location constraint in the body, if any, is ignored
+pub async fn create_bucket(
+    bucket: web::Path<BoxId>,
+    ioc: web::Data<dyn InteractorFactory>,
+    s3_config: web::Data<S3Config>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, S3Error> {
+    let id_provider = make_id_provider(&req, &s3_config, &token_processor, ioc.as_ref()).await?;
+    let data = ioc.create_box(id_provider).execute(CreateBoxDTO {
+        id: Some(bucket.into_inner()),
+        ..Default::default()
+    }).await.map_err(S3Error::bucket)?;
+    Ok(HttpResponse::Ok().insert_header((LOCATION, format!("/{}", data.id))).finish())
+}
+
+pub async fn head_bucket(
+    bucket: web::Path<BoxId>,
+    ioc: web::Data<dyn InteractorFactory>,
+    s3_config: web::Data<S3Config>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, S3Error> {
+    let id_provider = make_id_provider(&req, &s3_config, &token_processor, ioc.as_ref()).await?;
+    ioc.list_objects(id_provider).execute(ListObjectsDTO {
+        box_id: bucket.into_inner(),
+        limit: Some(1),
+        ..Default::default()
+    }).await.map_err(S3Error::bucket)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub async fn delete_bucket(
+    bucket: web::Path<BoxId>,
+    ioc: web::Data<dyn InteractorFactory>,
+    s3_config: web::Data<S3Config>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, S3Error> {
+    let id_provider = make_id_provider(&req, &s3_config, &token_processor, ioc.as_ref()).await?;
+    ioc.delete_box(id_provider).execute(DeleteBoxDTO {
+        id: bucket.into_inner()
+    }).await.map_err(
+        |error| S3Error::from_application(error, NO_SUCH_BUCKET, BUCKET_NOT_EMPTY)
+    )?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ListObjectsQuery {
+    prefix: Option<String>,
+    delimiter: Option<String>,
+    max_keys: Option<usize>,
+    continuation_token: Option<String>,
+    start_after: Option<String>,
+    encoding_type: Option<String>,
+}
+
+/// ListObjectsV2, the continuation token is the cursor of the object listing
+pub async fn list_objects(
+    bucket: web::Path<BoxId>,
+    query: web::Query<ListObjectsQuery>,
+    ioc: web::Data<dyn InteractorFactory>,
+    s3_config: web::Data<S3Config>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, S3Error> {
+    let bucket = bucket.into_inner();
+    let query = query.into_inner();
+
+    let id_provider = make_id_provider(&req, &s3_config, &token_processor, ioc.as_ref()).await?;
+    let data = ioc.list_objects(id_provider).execute(
+        list_objects_dto(bucket.clone(), &query)
+    ).await.map_err(S3Error::bucket)?;
+
+    Ok(list_objects_response(&bucket, &query, data))
+}
+
+/// Also used by intermediate nodes, which list the storage nodes
+pub fn list_objects_dto(bucket: BoxId, query: &ListObjectsQuery) -> ListObjectsDTO {
+    ListObjectsDTO {
+        box_id: bucket,
+        prefix: query.prefix.clone().filter(|prefix| !prefix.is_empty()),
+        delimiter: query.delimiter.clone().filter(|delimiter| !delimiter.is_empty()),
+        cursor: query.continuation_token.clone(),
+        start_after: query.start_after.clone(),
+        limit: Some(query.max_keys.unwrap_or(MAX_KEYS).min(MAX_KEYS) as u64),
+        ..Default::default()
+    }
+}
+
+pub fn list_objects_response(
+    bucket: &str,
+    query: &ListObjectsQuery,
+    data: ListObjectsResultDTO
+) -> HttpResponse {
+    let prefix = query.prefix.clone().unwrap_or_default();
+    let delimiter = query.delimiter.as_ref().filter(|delimiter| !delimiter.is_empty());
+    let max_keys = query.max_keys.unwrap_or(MAX_KEYS).min(MAX_KEYS);
+
+    // Clients ask to encode keys which may contain characters not allowed in xml
+    let is_url_encoded = query.encoding_type.as_deref() == Some("url");
+    let encode = |value: &str| if is_url_encoded { uri_encode(value) } else { value.to_string() };
+
+    let mut xml = XmlWriter::new();
+    xml.open_root("ListBucketResult")
+        .element("Name", bucket)
+        .element("Prefix", &encode(&prefix))
+        .element("MaxKeys", &max_keys.to_string())
+        .element("KeyCount", &(data.objects.len() + data.common_prefixes.len()).to_string())
+        .element("IsTruncated", &data.next_cursor.is_some().to_string());
+    if let Some(delimiter) = delimiter {
+        xml.element("Delimiter", &encode(delimiter));
+    }
+    if let Some(token) = &query.continuation_token {
+        xml.element("ContinuationToken", token);
+    }
+    if let Some(token) = &data.next_cursor {
+        xml.element("NextContinuationToken", token);
+    }
+    if let Some(start_after) = &query.start_after {
+        xml.element("StartAfter", &encode(start_after));
+    }
+    if is_url_encoded {
+        xml.element("EncodingType", "url");
+    }
+    for object in data.objects {
+        xml.open("Contents")
+            .element("Key", &encode(&object.key))
+            .element("LastModified", &format_date(&object.updated_at.unwrap_or(object.created_at)))
+            .element("ETag", &format!("\"{}\"", object.hash))
+            .element("Size", &object.size.to_string())
+            .element("StorageClass", "STANDARD")
+            .close("Contents");
+    }
+    for common_prefix in data.common_prefixes {
+        xml.open("CommonPrefixes")
+            .element("Prefix", &encode(&common_prefix))
+            .close("CommonPrefixes");
+    }
+    xml.close("ListBucketResult");
+
+    HttpResponse::Ok().insert_header(ContentType::xml()).body(xml.finish())
+}
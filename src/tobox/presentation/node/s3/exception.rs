@@ -0,0 +1,83 @@
+use std::fmt::{Display, Formatter};
+
+use actix_web::{error, HttpResponse};
+use actix_web::http::header::ContentType;
+use actix_web::http::StatusCode;
+
+use crate::application::common::exceptions::{ApplicationError, ErrorContent};
+use crate::presentation::node::s3::xml::XmlWriter;
+
+pub const NO_SUCH_BUCKET: &str = "NoSuchBucket";
+pub const NO_SUCH_KEY: &str = "NoSuchKey";
+pub const BUCKET_ALREADY_EXISTS: &str = "BucketAlreadyExists";
+pub const BUCKET_NOT_EMPTY: &str = "BucketNotEmpty";
+
+/// Error rendered as an s3 `<Error>` document
+#[derive(Debug)]
+pub struct S3Error {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+}
+
+impl S3Error {
+    pub fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        Self { status, code, message: message.into() }
+    }
+
+    /// Codes of not found and conflict errors depend on the operation
+    pub fn from_application(
+        error: ApplicationError,
+        not_found_code: &'static str,
+        conflict_code: &'static str
+    ) -> Self {
+        let (status, code, content) = match error {
+            ApplicationError::InvalidData(content) => (StatusCode::BAD_REQUEST, "InvalidArgument", content),
+            ApplicationError::NotFound(content) => (StatusCode::NOT_FOUND, not_found_code, content),
+            ApplicationError::Conflict(content) => (StatusCode::CONFLICT, conflict_code, content),
+            // S3 does not use 401, failed authentication is a denied access
+            ApplicationError::Unauthorized(content) => (StatusCode::FORBIDDEN, "AccessDenied", content),
+            ApplicationError::Forbidden(content) => (StatusCode::FORBIDDEN, "AccessDenied", content),
+            ApplicationError::Unavailable(content) => (StatusCode::SERVICE_UNAVAILABLE, "ServiceUnavailable", content),
+        };
+        let message = match content {
+            ErrorContent::Message(message) => message,
+            ErrorContent::Map(map) => map.iter()
+                .map(|(field, message)| format!("{}: {}", field, message))
+                .collect::<Vec<_>>()
+                .join("; "),
+        };
+        Self::new(status, code, message)
+    }
+
+    pub fn bucket(error: ApplicationError) -> Self {
+        Self::from_application(error, NO_SUCH_BUCKET, BUCKET_ALREADY_EXISTS)
+    }
+
+    pub fn key(error: ApplicationError) -> Self {
+        Self::from_application(error, NO_SUCH_KEY, "OperationAborted")
+    }
+}
+
+impl Display for S3Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl error::ResponseError for S3Error {
+    fn status_code(&self) -> StatusCode {
+        self.status
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status)
+            .insert_header(ContentType::xml())
+            .body(XmlWriter::new()
+                .open("Error")
+                .element("Code", self.code)
+                .element("Message", &self.message)
+                .close("Error")
+                .finish())
+    }
+}
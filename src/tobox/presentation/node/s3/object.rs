@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::SystemTime;
+
+use actix_web::{HttpMessage, HttpRequest, HttpResponse, HttpResponseBuilder, web};
+use actix_web::http::header::{
+    ACCEPT_RANGES,
+    CONTENT_ENCODING,
+    ContentRange,
+    ContentRangeSpec,
+    CONTENT_TYPE,
+    EntityTag,
+    ETag,
+    IfModifiedSince,
+    LastModified
+};
+use actix_web::http::StatusCode;
+use chrono::{DateTime, Utc};
+
+use crate::adapters::auth::token::TokenProcessor;
+use crate::application::common::exceptions::ApplicationError;
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::object::create::CreateObjectDTO;
+use crate::application::object::delete::DeleteObjectDTO;
+use crate::application::object::find::{FindObjectDTO, FindObjectResultDTO};
+use crate::application::object::get::{GetObjectDTO, ObjectContent};
+use crate::config::S3Config;
+use crate::domain::models::object::split_key;
+use crate::domain::models::r#box::BoxId;
+use crate::presentation::node::file_stream::{channel_file_stream, forward_stream};
+use crate::presentation::node::id_provider::forwarded_object_id;
+use crate::presentation::node::interactor_factory::InteractorFactory;
+use crate::presentation::node::rest::object::{parse_if_none_match, parse_if_range, parse_range};
+use crate::presentation::node::s3::exception::S3Error;
+use crate::presentation::node::s3::make_id_provider;
+
+const META_HEADER_PREFIX: &str = "x-amz-meta-";
+const CONTENT_SHA256_HEADER: &str = "x-amz-content-sha256";
+
+/// Metadata is passed in `x-amz-meta-*` headers
+fn parse_metadata(req: &HttpRequest) -> HashMap<String, String> {
+    req.headers().iter()
+        .filter_map(|(name, value)| Some((
+            name.as_str().strip_prefix(META_HEADER_PREFIX)?.to_string(),
+            value.to_str().ok()?.to_string()
+        )))
+        .collect()
+}
+
+/// Only an actual hash can be checked, not `UNSIGNED-PAYLOAD` and the like
+fn parse_content_sha256(req: &HttpRequest) -> Option<String> {
+    req.headers().get(CONTENT_SHA256_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| value.len() == 64 && value.bytes().all(|c| c.is_ascii_hexdigit()))
+        .map(|value| value.to_lowercase())
+}
+
+fn object_headers(response: &mut HttpResponseBuilder, object: &FindObjectResultDTO) {
+    response
+        .insert_header(ETag(EntityTag::new_strong(object.hash.clone())))
+        .insert_header(LastModified(
+            SystemTime::from(object.updated_at.unwrap_or(object.created_at)).into()
+        ))
+        .insert_header((ACCEPT_RANGES, "bytes"));
+    for (key, value) in &object.metadata {
+        response.insert_header((format!("{}{}", META_HEADER_PREFIX, key), value.as_str()));
+    }
+}
+
+/// The object replaces any other object with the same key
+pub async fn put_object(
+    path: web::Path<(BoxId, String)>,
+    payload: web::Payload,
+    ioc: web::Data<dyn InteractorFactory>,
+    s3_config: web::Data<S3Config>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, S3Error> {
+    let id_provider = make_id_provider(&req, &s3_config, &token_processor, ioc.as_ref()).await?;
+    let (bucket, key) = path.into_inner();
+
+    let is_aws_chunked = req.headers().get(CONTENT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains("aws-chunked"));
+    if is_aws_chunked {
+        return Err(S3Error::new(
+            StatusCode::NOT_IMPLEMENTED,
+            "NotImplemented",
+            "aws-chunked content encoding is not supported"
+        ))
+    }
+
+    // Keys are stored as a path and a name, so directories can be listed
+    let (path, name) = split_key(&key);
+    let id = forwarded_object_id(&req).map_err(S3Error::key)?;
+    let (sender, file) = channel_file_stream();
+    // Borrowed by the future below, the interactor must outlive it
+    let interactor = ioc.create_object(id_provider);
+    let (data, _) = tokio::join!(
+        interactor.execute(CreateObjectDTO {
+            id,
+            box_id: bucket,
+            name: Some(name.to_string()),
+            path: path.map(str::to_string),
+            file: Box::new(file),
+            metadata: parse_metadata(&req),
+            expected_hash: parse_content_sha256(&req),
+            replace: true
+        }),
+        forward_stream(payload, sender)
+    );
+    let data = data.map_err(S3Error::key)?;
+
+    Ok(HttpResponse::Ok().insert_header(ETag(EntityTag::new_strong(data.hash))).finish())
+}
+
+pub async fn get_object(
+    path: web::Path<(BoxId, String)>,
+    ioc: web::Data<dyn InteractorFactory>,
+    s3_config: web::Data<S3Config>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, S3Error> {
+    let id_provider: Rc<dyn IdProvider> = Rc::from(
+        make_id_provider(&req, &s3_config, &token_processor, ioc.as_ref()).await?
+    );
+    let (box_id, key) = path.into_inner();
+    let object = ioc.find_object(Box::new(id_provider.clone()))
+        .execute(FindObjectDTO { box_id, key }).await
+        .map_err(S3Error::key)?;
+
+    let data = ioc.get_object(Box::new(id_provider)).execute(GetObjectDTO {
+        id: object.id.clone(),
+        range: parse_range(&req),
+        if_range: parse_if_range(&req),
+        if_none_match: parse_if_none_match(&req),
+        if_modified_since: req.get_header::<IfModifiedSince>().map(
+            |date| DateTime::<Utc>::from(SystemTime::from(date.0))
+        )
+    }).await.map_err(S3Error::key)?;
+
+    Ok(match data.content {
+        ObjectContent::NotModified => {
+            let mut response = HttpResponse::NotModified();
+            object_headers(&mut response, &object);
+            response.finish()
+        },
+        ObjectContent::RangeNotSatisfiable => return Err(S3Error::new(
+            StatusCode::RANGE_NOT_SATISFIABLE,
+            "InvalidRange",
+            "The requested range is not satisfiable"
+        )),
+        ObjectContent::Full(file) => {
+            let mut response = HttpResponse::Ok();
+            object_headers(&mut response, &object);
+            response
+                .content_type(data.content_type)
+                .no_chunking(data.size)
+                .streaming(file)
+        },
+        ObjectContent::Partial(first, last, file) => {
+            let mut response = HttpResponse::PartialContent();
+            object_headers(&mut response, &object);
+            response
+                .insert_header(ContentRange(ContentRangeSpec::Bytes {
+                    range: Some((first, last)),
+                    instance_length: Some(data.size),
+                }))
+                .content_type(data.content_type)
+                .no_chunking(last - first + 1)
+                .streaming(file)
+        }
+    })
+}
+
+pub async fn head_object(
+    path: web::Path<(BoxId, String)>,
+    ioc: web::Data<dyn InteractorFactory>,
+    s3_config: web::Data<S3Config>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, S3Error> {
+    let id_provider = make_id_provider(&req, &s3_config, &token_processor, ioc.as_ref()).await?;
+    let (box_id, key) = path.into_inner();
+    let object = ioc.find_object(id_provider).execute(FindObjectDTO { box_id, key }).await
+        .map_err(S3Error::key)?;
+
+    let mut response = HttpResponse::Ok();
+    object_headers(&mut response, &object);
+    Ok(response
+        .insert_header((CONTENT_TYPE, object.content_type.as_str()))
+        .no_chunking(object.size)
+        .finish())
+}
+
+/// Deleting a missing key is not an error in s3
+pub async fn delete_object(
+    path: web::Path<(BoxId, String)>,
+    ioc: web::Data<dyn InteractorFactory>,
+    s3_config: web::Data<S3Config>,
+    token_processor: web::Data<TokenProcessor>,
+    req: HttpRequest
+) -> Result<HttpResponse, S3Error> {
+    let id_provider: Rc<dyn IdProvider> = Rc::from(
+        make_id_provider(&req, &s3_config, &token_processor, ioc.as_ref()).await?
+    );
+    let (box_id, key) = path.into_inner();
+    let found = ioc.find_object(Box::new(id_provider.clone()))
+        .execute(FindObjectDTO { box_id, key }).await;
+    let object = match found {
+        Ok(object) => object,
+        Err(ApplicationError::NotFound(_)) => return Ok(HttpResponse::NoContent().finish()),
+        Err(error) => return Err(S3Error::key(error))
+    };
+
+    match ioc.delete_object(Box::new(id_provider)).execute(DeleteObjectDTO { id: object.id }).await {
+        Ok(_) | Err(ApplicationError::NotFound(_)) => Ok(HttpResponse::NoContent().finish()),
+        Err(error) => Err(S3Error::key(error))
+    }
+}
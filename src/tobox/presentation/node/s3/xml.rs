@@ -0,0 +1,83 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+
+pub const S3_NAMESPACE: &str = "http://s3.amazonaws.com/doc/2006-03-01/";
+
+/// Minimal writer for s3 responses, elements are written in document order
+pub struct XmlWriter {
+    buf: String,
+}
+
+impl XmlWriter {
+    pub fn new() -> Self {
+        Self {
+            buf: String::from(r#"<?xml version="1.0" encoding="UTF-8"?>"#),
+        }
+    }
+
+    pub fn open_root(&mut self, tag: &str) -> &mut Self {
+        self.buf.push_str(&format!(r#"<{} xmlns="{}">"#, tag, S3_NAMESPACE));
+        self
+    }
+
+    pub fn open(&mut self, tag: &str) -> &mut Self {
+        self.buf.push_str(&format!("<{}>", tag));
+        self
+    }
+
+    pub fn close(&mut self, tag: &str) -> &mut Self {
+        self.buf.push_str(&format!("</{}>", tag));
+        self
+    }
+
+    pub fn element(&mut self, tag: &str, text: &str) -> &mut Self {
+        self.buf.push_str(&format!("<{}>{}</{}>", tag, escape(text), tag));
+        self
+    }
+
+    pub fn finish(&mut self) -> String {
+        std::mem::take(&mut self.buf)
+    }
+}
+
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Timestamps in listings use ISO 8601 with milliseconds
+pub fn format_date(date: &DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xml_writer() {
+        let xml = XmlWriter::new()
+            .open_root("Error")
+            .element("Code", "NoSuchKey")
+            .element("Key", "a<b>&'c\"")
+            .close("Error")
+            .finish();
+        assert_eq!(
+            xml,
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?><Error xmlns="{}"><Code>NoSuchKey</Code><Key>a&lt;b&gt;&amp;&apos;c&quot;</Key></Error>"#,
+                S3_NAMESPACE
+            )
+        );
+    }
+}
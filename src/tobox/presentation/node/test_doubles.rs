@@ -0,0 +1,548 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use chrono::Utc;
+use sqlx::SqlitePool;
+use tempfile::TempDir;
+
+use crate::adapters::auth::forwarded::IdForwardedProvider;
+use crate::adapters::auth::token::TokenProcessor;
+use crate::adapters::database::access_key_db::AccessKeyGateway;
+use crate::adapters::database::api_key_db::ApiKeyGateway;
+use crate::adapters::database::box_db::BoxGateway;
+use crate::adapters::database::file_storage::FileStorage;
+use crate::adapters::database::node_db::NodeGateway;
+use crate::adapters::database::object_db::ObjectGateway;
+use crate::adapters::database::permission_db::PermissionGateway;
+use crate::adapters::database::replication_db::ReplicationGateway;
+use crate::adapters::database::role_db::RoleGateway;
+use crate::adapters::database::session_db::SessionGateway;
+use crate::adapters::database::sqlite::connect_in_memory;
+use crate::adapters::http_node_client::HttpNodeClient;
+use crate::adapters::sha256_session_hasher::Sha256SessionHasher;
+use crate::application::access_key::create::CreateAccessKey;
+use crate::application::access_key::delete::DeleteAccessKey;
+use crate::application::access_key::get_self::GetSelfAccessKeys;
+use crate::application::api_key::create::CreateApiKey;
+use crate::application::api_key::delete::DeleteApiKey;
+use crate::application::api_key::get_self::GetSelfApiKeys;
+use crate::application::r#box::create::CreateBox;
+use crate::application::r#box::delete::DeleteBox;
+use crate::application::r#box::get_range::GetBoxRange;
+use crate::application::r#box::update::UpdateBox;
+use crate::application::cluster::add_node::AddNode;
+use crate::application::cluster::get_info::GetNodeInfo;
+use crate::application::cluster::get_nodes::GetNodes;
+use crate::application::cluster::get_scrub_status::GetScrubStatus;
+use crate::application::cluster::remove_node::RemoveNode;
+use crate::application::common::access_key_gateway::{AccessKeyReader, AccessKeyWriter};
+use crate::application::common::blob_lock::BlobLocks;
+use crate::application::common::box_gateway::BoxWriter;
+use crate::application::common::hasher::Hasher;
+use crate::application::common::id_provider::IdProvider;
+use crate::application::common::interactor::Interactor;
+use crate::application::common::node_client::NodeClient;
+use crate::application::common::node_gateway::NodeGateway as NodeGatewayTrait;
+use crate::application::common::object_gateway::ObjectReader;
+use crate::application::common::permission_gateway::{
+    PermissionLinker,
+    PermissionReader,
+    PermissionWriter
+};
+use crate::application::common::role_gateway::{RoleLinker, RoleWriter};
+use crate::application::common::session_gateway::SessionWriter;
+use crate::application::common::upload_gateway::UploadGateway;
+use crate::application::metadata::append_entries::AppendEntries;
+use crate::application::metadata::consensus::MetadataConsensus;
+use crate::application::metadata::proposer::MetadataProposer;
+use crate::application::metadata::receive_proposal::ReceiveProposal;
+use crate::application::metadata::request_vote::RequestVote;
+use crate::application::object::create::CreateObject;
+use crate::application::object::create::CreateObjectDTO;
+use crate::application::object::delete::DeleteObject;
+use crate::application::object::find::FindObject;
+use crate::application::object::get::GetObject;
+use crate::application::object::get_info::GetObjectInfo;
+use crate::application::object::list::ListObjects;
+use crate::application::object::presign::PresignObject;
+use crate::application::object::update::UpdateObject;
+use crate::application::permission::get_by_role::GetRolePermissions;
+use crate::application::permission::get_by_user::GetUserPermissions;
+use crate::application::permission::get_range::GetPermissionRange;
+use crate::application::permission::link::LinkRolePermission;
+use crate::application::permission::unlink::UnlinkRolePermission;
+use crate::application::role::create::CreateRole;
+use crate::application::role::delete::DeleteRole;
+use crate::application::role::get_by_id::GetRoleById;
+use crate::application::role::get_by_ids::GetRolesByIds;
+use crate::application::role::get_by_user::GetUserRoles;
+use crate::application::role::get_default::GetDefaultRole;
+use crate::application::role::get_range::GetRoleRange;
+use crate::application::role::link::LinkRoleUser;
+use crate::application::role::set_default::SetDefaultRole;
+use crate::application::role::unlink::UnlinkRoleUser;
+use crate::application::role::update::UpdateRole;
+use crate::application::session::create::CreateSession;
+use crate::application::session::delete::DeleteSession;
+use crate::application::session::delete_all_self::DeleteAllSelfSessions;
+use crate::application::session::delete_self::DeleteSelfSession;
+use crate::application::session::get_by_user::GetUserSessions;
+use crate::application::session::get_self::GetSelfSessions;
+use crate::application::session::sweep::SweepSessions;
+use crate::application::sync::check_blob::CheckBlob;
+use crate::application::sync::get_digests::GetDigests;
+use crate::application::sync::rebalance::RebalanceObjects;
+use crate::application::sync::receive_blob::ReceiveBlob;
+use crate::application::sync::receive_object::ReceiveObject;
+use crate::application::sync::replicate::ReplicateObjects;
+use crate::application::sync::scrub::ScrubBlobs;
+use crate::application::sync::send_blob::SendBlob;
+use crate::application::upload::abort::AbortUpload;
+use crate::application::upload::complete::CompleteUpload;
+use crate::application::upload::get_parts::GetUploadParts;
+use crate::application::upload::initiate::InitiateUpload;
+use crate::application::upload::put_part::PutUploadPart;
+use crate::application::user::create::CreateUser;
+use crate::application::user::delete::DeleteUser;
+use crate::application::user::get_by_id::GetUserById;
+use crate::application::user::get_range::GetUserRange;
+use crate::application::user::get_self::GetUserSelf;
+use crate::domain::models::access_key::AccessKey;
+use crate::domain::models::r#box::{Box as BoxDomain, BoxAccessPolicy, DuplicateNamePolicy};
+use crate::domain::models::node::NodeId;
+use crate::domain::models::object::ObjectId;
+use crate::domain::models::permission::{Permission, PermissionTag};
+use crate::domain::models::role::Role;
+use crate::domain::models::session::SessionOrigin;
+use crate::domain::services::access::AccessService;
+use crate::domain::services::node::NodeService;
+use crate::domain::services::object::ObjectService;
+use crate::domain::services::placement::PlacementService;
+use crate::domain::services::replication::ReplicationService;
+use crate::domain::services::session::SessionService;
+use crate::domain::services::validator::ValidatorService;
+use crate::presentation::node::file_stream::channel_file_stream;
+use crate::presentation::node::interactor_factory::InteractorFactory;
+
+const VERSION: &str = "0.1.0";
+
+/// Storage node over a database in memory and a temporary directory, shared by the route tests
+///
+/// Only the interactors reachable from the tested routes are built.
+pub struct TestFactory {
+    pub db: SqlitePool,
+    node_id: NodeId,
+    cluster_secret: Option<String>,
+    file_storage: FileStorage,
+    box_gateway: BoxGateway,
+    object_gateway: ObjectGateway,
+    replication_gateway: ReplicationGateway,
+    access_key_gateway: AccessKeyGateway,
+    permission_gateway: PermissionGateway,
+    node_gateway: NodeGateway,
+    node_client: HttpNodeClient,
+    validator: ValidatorService,
+    blob_locks: BlobLocks,
+    _dir: TempDir
+}
+
+impl TestFactory {
+    pub async fn new(node_id: &str, cluster_secret: Option<&str>) -> Self {
+        let db = connect_in_memory().await;
+        let dir = tempfile::tempdir().unwrap();
+        Self {
+            node_id: node_id.to_string(),
+            cluster_secret: cluster_secret.map(str::to_string),
+            file_storage: FileStorage::new(dir.path()),
+            box_gateway: BoxGateway::new(db.clone()),
+            object_gateway: ObjectGateway::new(db.clone()),
+            replication_gateway: ReplicationGateway::new(db.clone()),
+            access_key_gateway: AccessKeyGateway::new(db.clone()),
+            permission_gateway: PermissionGateway::new(db.clone()),
+            node_gateway: NodeGateway::new(db.clone()),
+            node_client: HttpNodeClient::new(cluster_secret.map(str::to_string)),
+            validator: ValidatorService::new(),
+            blob_locks: BlobLocks::default(),
+            db,
+            _dir: dir
+        }
+    }
+
+    pub fn token_processor(&self) -> TokenProcessor {
+        TokenProcessor::new(
+            Arc::new(SessionGateway::new(self.db.clone())),
+            Arc::new(ApiKeyGateway::new(self.db.clone())),
+            Arc::new(PermissionGateway::new(self.db.clone())),
+            Arc::new(Sha256SessionHasher {}),
+            SessionService::new(3600)
+        )
+    }
+
+    /// User with a role granting the permissions
+    pub async fn add_user(&self, user_id: &str, tags: Vec<PermissionTag>) {
+        sqlx::query("INSERT INTO users (id, username, hashed_password, created_at) VALUES (?, ?, '', ?)")
+            .bind(user_id)
+            .bind(user_id)
+            .bind(Utc::now())
+            .execute(&self.db)
+            .await
+            .unwrap();
+        let roles = RoleGateway::new(self.db.clone());
+        let role_id = format!("{}-role", user_id);
+        roles.save_role(&Role {
+            id: role_id.clone(),
+            title: role_id.clone(),
+            description: None,
+            created_at: Utc::now(),
+            updated_at: None
+        }).await;
+        roles.link_role_to_user(&role_id, &user_id.to_string()).await;
+        let permissions: Vec<Permission> = tags.into_iter().map(|tag| Permission {
+            id: format!("{}-{}", user_id, tag),
+            tag
+        }).collect();
+        self.permission_gateway.save_permissions(&permissions).await;
+        self.permission_gateway.link_permissions_to_role(
+            &role_id,
+            &permissions.into_iter().map(|permission| permission.id).collect()
+        ).await;
+    }
+
+    /// Token of a new session of the user
+    pub async fn login(&self, user_id: &str) -> String {
+        let service = SessionService::new(3600);
+        let token = service.generate_token();
+        let session = service.create_session(
+            Sha256SessionHasher {}.hash(&token).await,
+            user_id.to_string(),
+            vec![],
+            SessionOrigin::default(),
+            &Utc::now()
+        );
+        SessionGateway::new(self.db.clone()).save_session(&session).await;
+        token
+    }
+
+    pub async fn add_access_key(&self, user_id: &str, id: &str, secret: &str) {
+        self.access_key_gateway.save_access_key(&AccessKey {
+            id: id.to_string(),
+            secret: secret.to_string(),
+            user_id: user_id.to_string(),
+            created_at: Utc::now()
+        }).await;
+    }
+
+    pub async fn add_box(&self, box_id: &str) {
+        self.box_gateway.save_box(&BoxDomain {
+            id: box_id.to_string(),
+            duplicate_names: DuplicateNamePolicy::Allow,
+            access: BoxAccessPolicy::Private,
+            created_at: Utc::now(),
+        }).await;
+    }
+
+    /// Object stored in the box as if it was uploaded
+    pub async fn add_object(&self, box_id: &str, name: &str, content: &'static [u8]) -> ObjectId {
+        let (sender, file) = channel_file_stream();
+        sender.send(Ok(Bytes::from_static(content))).await.unwrap();
+        drop(sender);
+        let id_provider = IdForwardedProvider::new(Some("uploader"), r#"["CreateObject"]"#).unwrap();
+        self.create_object(Box::new(id_provider)).execute(CreateObjectDTO {
+            id: None,
+            box_id: box_id.to_string(),
+            name: Some(name.to_string()),
+            path: None,
+            file: Box::new(file),
+            metadata: Default::default(),
+            expected_hash: None,
+            replace: false
+        }).await.unwrap().id
+    }
+}
+
+impl InteractorFactory for TestFactory {
+    fn get_user_by_id(&self, _: Box<dyn IdProvider>) -> GetUserById<'_> {
+        unimplemented!()
+    }
+    fn get_user_range(&self, _: Box<dyn IdProvider>) -> GetUserRange<'_> {
+        unimplemented!()
+    }
+    fn get_user_self(&self, _: Box<dyn IdProvider>) -> GetUserSelf<'_> {
+        unimplemented!()
+    }
+    fn create_user(&self, _: Box<dyn IdProvider>) -> CreateUser<'_> {
+        unimplemented!()
+    }
+    fn delete_user(&self, _: Box<dyn IdProvider>) -> DeleteUser<'_> {
+        unimplemented!()
+    }
+    fn create_session(&self, _: Box<dyn IdProvider>) -> CreateSession<'_> {
+        unimplemented!()
+    }
+    fn get_self_sessions(&self, _: Box<dyn IdProvider>) -> GetSelfSessions<'_> {
+        unimplemented!()
+    }
+    fn get_user_sessions(&self, _: Box<dyn IdProvider>) -> GetUserSessions<'_> {
+        unimplemented!()
+    }
+    fn delete_session(&self, _: Box<dyn IdProvider>) -> DeleteSession<'_> {
+        unimplemented!()
+    }
+    fn delete_self_session(&self, _: Box<dyn IdProvider>) -> DeleteSelfSession<'_> {
+        unimplemented!()
+    }
+    fn delete_all_self_sessions(&self, _: Box<dyn IdProvider>) -> DeleteAllSelfSessions<'_> {
+        unimplemented!()
+    }
+    fn create_access_key(&self, _: Box<dyn IdProvider>) -> CreateAccessKey<'_> {
+        unimplemented!()
+    }
+    fn get_self_access_keys(&self, _: Box<dyn IdProvider>) -> GetSelfAccessKeys<'_> {
+        unimplemented!()
+    }
+    fn delete_access_key(&self, _: Box<dyn IdProvider>) -> DeleteAccessKey<'_> {
+        unimplemented!()
+    }
+    fn create_api_key(&self, _: Box<dyn IdProvider>) -> CreateApiKey<'_> {
+        unimplemented!()
+    }
+    fn get_self_api_keys(&self, _: Box<dyn IdProvider>) -> GetSelfApiKeys<'_> {
+        unimplemented!()
+    }
+    fn delete_api_key(&self, _: Box<dyn IdProvider>) -> DeleteApiKey<'_> {
+        unimplemented!()
+    }
+    fn create_role(&self, _: Box<dyn IdProvider>) -> CreateRole<'_> {
+        unimplemented!()
+    }
+    fn get_role_by_id(&self, _: Box<dyn IdProvider>) -> GetRoleById<'_> {
+        unimplemented!()
+    }
+    fn get_roles_by_ids(&self, _: Box<dyn IdProvider>) -> GetRolesByIds<'_> {
+        unimplemented!()
+    }
+    fn get_role_by_user(&self, _: Box<dyn IdProvider>) -> GetUserRoles<'_> {
+        unimplemented!()
+    }
+    fn get_role_range(&self, _: Box<dyn IdProvider>) -> GetRoleRange<'_> {
+        unimplemented!()
+    }
+    fn set_default_role(&self, _: Box<dyn IdProvider>) -> SetDefaultRole<'_> {
+        unimplemented!()
+    }
+    fn get_default_role(&self, _: Box<dyn IdProvider>) -> GetDefaultRole<'_> {
+        unimplemented!()
+    }
+    fn link_role_user(&self, _: Box<dyn IdProvider>) -> LinkRoleUser<'_> {
+        unimplemented!()
+    }
+    fn unlink_role_user(&self, _: Box<dyn IdProvider>) -> UnlinkRoleUser<'_> {
+        unimplemented!()
+    }
+    fn update_role(&self, _: Box<dyn IdProvider>) -> UpdateRole<'_> {
+        unimplemented!()
+    }
+    fn delete_role(&self, _: Box<dyn IdProvider>) -> DeleteRole<'_> {
+        unimplemented!()
+    }
+    fn get_permission_range(&self, _: Box<dyn IdProvider>) -> GetPermissionRange<'_> {
+        unimplemented!()
+    }
+    fn get_role_permissions(&self, _: Box<dyn IdProvider>) -> GetRolePermissions<'_> {
+        unimplemented!()
+    }
+    fn get_user_permissions(&self, _: Box<dyn IdProvider>) -> GetUserPermissions<'_> {
+        unimplemented!()
+    }
+    fn link_role_permission(&self, _: Box<dyn IdProvider>) -> LinkRolePermission<'_> {
+        unimplemented!()
+    }
+    fn unlink_role_permission(&self, _: Box<dyn IdProvider>) -> UnlinkRolePermission<'_> {
+        unimplemented!()
+    }
+    fn create_box(&self, _: Box<dyn IdProvider>) -> CreateBox<'_> {
+        unimplemented!()
+    }
+    fn update_box(&self, _: Box<dyn IdProvider>) -> UpdateBox<'_> {
+        unimplemented!()
+    }
+    fn delete_box(&self, _: Box<dyn IdProvider>) -> DeleteBox<'_> {
+        unimplemented!()
+    }
+    fn get_box_range(&self, _: Box<dyn IdProvider>) -> GetBoxRange<'_> {
+        unimplemented!()
+    }
+    fn create_object(&self, id_provider: Box<dyn IdProvider>) -> CreateObject<'_> {
+        CreateObject {
+            box_reader: &self.box_gateway,
+            file_storage_writer: &self.file_storage,
+            file_storage_remover: &self.file_storage,
+            object_gateway: &self.object_gateway,
+            replication_writer: &self.replication_gateway,
+            object_service: &ObjectService { },
+            replication_service: &ReplicationService { },
+            validator: &self.validator,
+            access_service: &AccessService { },
+            blob_locks: &self.blob_locks,
+            id_provider,
+        }
+    }
+    fn get_object(&self, id_provider: Box<dyn IdProvider>) -> GetObject<'_> {
+        GetObject {
+            box_reader: &self.box_gateway,
+            file_storage_reader: &self.file_storage,
+            object_reader: &self.object_gateway,
+            access_service: &AccessService { },
+            id_provider
+        }
+    }
+    fn get_object_info(&self, _: Box<dyn IdProvider>) -> GetObjectInfo<'_> {
+        unimplemented!()
+    }
+    fn presign_object(&self, _: Box<dyn IdProvider>) -> PresignObject<'_> {
+        unimplemented!()
+    }
+    fn update_object(&self, _: Box<dyn IdProvider>) -> UpdateObject<'_> {
+        unimplemented!()
+    }
+    fn delete_object(&self, id_provider: Box<dyn IdProvider>) -> DeleteObject<'_> {
+        DeleteObject {
+            object_gateway: &self.object_gateway,
+            file_storage_remover: &self.file_storage,
+            access_service: &AccessService { },
+            blob_locks: &self.blob_locks,
+            id_provider,
+        }
+    }
+    fn find_object(&self, id_provider: Box<dyn IdProvider>) -> FindObject<'_> {
+        FindObject {
+            box_reader: &self.box_gateway,
+            object_reader: &self.object_gateway,
+            access_service: &AccessService { },
+            id_provider
+        }
+    }
+    fn list_objects(&self, _: Box<dyn IdProvider>) -> ListObjects<'_> {
+        unimplemented!()
+    }
+    fn initiate_upload(&self, _: Box<dyn IdProvider>) -> InitiateUpload<'_> {
+        unimplemented!()
+    }
+    fn put_upload_part(&self, _: Box<dyn IdProvider>) -> PutUploadPart<'_> {
+        unimplemented!()
+    }
+    fn get_upload_parts(&self, _: Box<dyn IdProvider>) -> GetUploadParts<'_> {
+        unimplemented!()
+    }
+    fn complete_upload(&self, _: Box<dyn IdProvider>) -> CompleteUpload<'_> {
+        unimplemented!()
+    }
+    fn abort_upload(&self, _: Box<dyn IdProvider>) -> AbortUpload<'_> {
+        unimplemented!()
+    }
+    fn get_node_info(&self) -> GetNodeInfo<'_> {
+        GetNodeInfo {
+            file_storage_reader: &self.file_storage,
+            node_id: &self.node_id,
+            version: VERSION,
+        }
+    }
+    fn get_nodes(&self, id_provider: Box<dyn IdProvider>) -> GetNodes<'_> {
+        GetNodes {
+            node_reader: &self.node_gateway,
+            access_service: &AccessService { },
+            id_provider,
+        }
+    }
+    fn add_node(&self, id_provider: Box<dyn IdProvider>) -> AddNode<'_> {
+        AddNode {
+            node_gateway: &self.node_gateway,
+            node_client: &self.node_client,
+            node_service: &NodeService { },
+            validator: &self.validator,
+            access_service: &AccessService { },
+            node_id: &self.node_id,
+            id_provider,
+        }
+    }
+    fn remove_node(&self, _: Box<dyn IdProvider>) -> RemoveNode<'_> {
+        unimplemented!()
+    }
+    fn get_scrub_status(&self, _: Box<dyn IdProvider>) -> GetScrubStatus<'_> {
+        unimplemented!()
+    }
+    fn check_blob(&self) -> CheckBlob<'_> {
+        CheckBlob {
+            file_storage_reader: &self.file_storage,
+            validator: &self.validator,
+            access_service: &AccessService { },
+            cluster_secret: self.cluster_secret.as_deref(),
+        }
+    }
+    fn send_blob(&self) -> SendBlob<'_> {
+        unimplemented!()
+    }
+    fn receive_blob(&self) -> ReceiveBlob<'_> {
+        unimplemented!()
+    }
+    fn receive_object(&self) -> ReceiveObject<'_> {
+        unimplemented!()
+    }
+    fn get_digests(&self) -> GetDigests<'_> {
+        unimplemented!()
+    }
+    fn replicate_objects(&self) -> ReplicateObjects<'_> {
+        unimplemented!()
+    }
+    fn rebalance_objects(&self) -> RebalanceObjects<'_> {
+        unimplemented!()
+    }
+    fn scrub_blobs(&self) -> ScrubBlobs<'_> {
+        unimplemented!()
+    }
+    fn sweep_sessions(&self) -> SweepSessions<'_> {
+        unimplemented!()
+    }
+    fn request_vote(&self) -> RequestVote<'_> {
+        unimplemented!()
+    }
+    fn append_entries(&self) -> AppendEntries<'_> {
+        unimplemented!()
+    }
+    fn receive_proposal(&self) -> ReceiveProposal<'_> {
+        unimplemented!()
+    }
+    fn metadata_consensus(&self) -> MetadataConsensus<'_> {
+        unimplemented!()
+    }
+    fn access_key_reader(&self) -> &dyn AccessKeyReader {
+        &self.access_key_gateway
+    }
+    fn permission_reader(&self) -> &dyn PermissionReader {
+        &self.permission_gateway
+    }
+    fn node_gateway(&self) -> &dyn NodeGatewayTrait {
+        &self.node_gateway
+    }
+    fn node_client(&self) -> &dyn NodeClient {
+        &self.node_client
+    }
+    fn node_service(&self) -> &NodeService {
+        &NodeService { }
+    }
+    fn placement_service(&self) -> &PlacementService {
+        unimplemented!()
+    }
+    fn object_reader(&self) -> &dyn ObjectReader {
+        &self.object_gateway
+    }
+    fn upload_gateway(&self) -> &dyn UploadGateway {
+        unimplemented!()
+    }
+    fn blob_locks(&self) -> &BlobLocks {
+        &self.blob_locks
+    }
+    fn metadata_proposer(&self) -> &MetadataProposer {
+        unimplemented!()
+    }
+}